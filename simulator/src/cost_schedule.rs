@@ -0,0 +1,96 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pluggable per-resource fee pricing for the mocked fee check.
+//!
+//! `SimulationRequest::mock_base_fee`/`mock_gas_price` model fee pricing as
+//! a flat per-operation amount and a single compute-unit price; this module
+//! lets callers instead price each resource independently via a
+//! [`CostSchedule`] — CPU instructions, memory bytes, storage reads and
+//! writes, and a flat per-operation base — so they can compare the same
+//! transaction's required fee under different protocol parameter
+//! assumptions (e.g. mainnet vs. testnet pricing) and see exactly which
+//! schedule produced the result via `SimulationResponse::effective_cost_schedule`.
+
+use crate::types::CostSchedule;
+
+/// The resource counts a `CostSchedule` prices against, drawn from figures
+/// already computed elsewhere (`BudgetUsage`, the loaded ledger entries,
+/// and the state-diff report) rather than a separate accounting pass.
+pub struct ResourceUsage {
+    pub cpu_insns: u64,
+    pub memory_bytes: u64,
+    pub operations_count: u64,
+    pub storage_reads: u64,
+    pub storage_writes: u64,
+}
+
+/// The fee `schedule` requires for `usage`, in stroops.
+pub fn required_fee_stroops(schedule: &CostSchedule, usage: &ResourceUsage) -> u64 {
+    schedule
+        .cpu_insn_price
+        .saturating_mul(usage.cpu_insns)
+        .saturating_add(schedule.memory_byte_price.saturating_mul(usage.memory_bytes))
+        .saturating_add(
+            schedule
+                .per_operation_base_fee
+                .saturating_mul(usage.operations_count),
+        )
+        .saturating_add(schedule.storage_read_price.saturating_mul(usage.storage_reads))
+        .saturating_add(schedule.storage_write_price.saturating_mul(usage.storage_writes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage() -> ResourceUsage {
+        ResourceUsage {
+            cpu_insns: 1_000,
+            memory_bytes: 2_000,
+            operations_count: 3,
+            storage_reads: 4,
+            storage_writes: 5,
+        }
+    }
+
+    #[test]
+    fn a_default_schedule_requires_nothing() {
+        assert_eq!(required_fee_stroops(&CostSchedule::default(), &usage()), 0);
+    }
+
+    #[test]
+    fn each_coefficient_contributes_its_own_term() {
+        let schedule = CostSchedule {
+            cpu_insn_price: 1,
+            memory_byte_price: 2,
+            per_operation_base_fee: 10,
+            storage_read_price: 100,
+            storage_write_price: 1_000,
+        };
+        let usage = usage();
+        let expected = usage.cpu_insns * schedule.cpu_insn_price
+            + usage.memory_bytes * schedule.memory_byte_price
+            + usage.operations_count * schedule.per_operation_base_fee
+            + usage.storage_reads * schedule.storage_read_price
+            + usage.storage_writes * schedule.storage_write_price;
+
+        assert_eq!(required_fee_stroops(&schedule, &usage), expected);
+    }
+
+    #[test]
+    fn an_overflowing_schedule_saturates_instead_of_panicking() {
+        let schedule = CostSchedule {
+            cpu_insn_price: u64::MAX,
+            ..CostSchedule::default()
+        };
+        let usage = ResourceUsage {
+            cpu_insns: 2,
+            memory_bytes: 0,
+            operations_count: 0,
+            storage_reads: 0,
+            storage_writes: 0,
+        };
+        assert_eq!(required_fee_stroops(&schedule, &usage), u64::MAX);
+    }
+}
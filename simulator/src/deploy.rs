@@ -0,0 +1,181 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Single-call contract deployment from raw WASM bytes.
+//!
+//! Without this, getting a contract into the Host means hand-assembling a
+//! `LedgerKeyContractCode`/`ContractCodeEntry` pair and a matching
+//! `ContractData` instance entry the way `test.rs`'s fixtures do, computing
+//! the code hash and contract id by hand along the way. [`install_wasm`] and
+//! [`deploy_contract`] collapse that into the same two calls a real
+//! `UploadContractWasm` + `CreateContract` operation pair would make,
+//! guaranteeing the derived hashes match what the network would compute.
+
+use crate::snapshot::{inject_ledger_entry, SnapshotError};
+use sha2::{Digest, Sha256};
+use soroban_env_host::xdr::{
+    ContractCodeEntry, ContractDataDurability, ContractDataEntry, ContractExecutable,
+    ContractIdPreimage, ContractIdPreimageFromAddress, ExtensionPoint, Hash, HashIdPreimage,
+    HashIdPreimageContractId, InstallContractCodeArgs, LedgerEntry, LedgerEntryData,
+    LedgerEntryExt, LedgerKey, LedgerKeyContractCode, LedgerKeyContractData, Limits, ScAddress,
+    ScContractInstance, ScVal, Uint256, WriteXdr,
+};
+use soroban_env_host::Host;
+
+/// Install `wasm_bytes` as a `ContractCode` entry and return its code hash.
+///
+/// The hash is `sha256` over the XDR encoding of `InstallContractCodeArgs {
+/// code: wasm_bytes }` — the same preimage a real `UploadContractWasm`
+/// operation hashes — so a caller can derive the same code hash offline to
+/// build `deploy_contract` calls or cross-check against a real transaction.
+pub fn install_wasm(host: &Host, wasm_bytes: &[u8]) -> Result<Hash, SnapshotError> {
+    let install_args = InstallContractCodeArgs {
+        code: wasm_bytes.to_vec().try_into().map_err(|_| {
+            SnapshotError::XdrEncoding("WASM payload exceeds the maximum contract code size".to_string())
+        })?,
+    };
+    let install_args_xdr = install_args.to_xdr(Limits::none()).map_err(|e| {
+        SnapshotError::XdrEncoding(format!("Failed to encode InstallContractCodeArgs: {e}"))
+    })?;
+    let code_hash = Hash(Sha256::digest(&install_args_xdr).into());
+
+    let key = LedgerKey::ContractCode(LedgerKeyContractCode {
+        hash: code_hash.clone(),
+    });
+    let entry = LedgerEntry {
+        last_modified_ledger_seq: 0,
+        data: LedgerEntryData::ContractCode(ContractCodeEntry {
+            ext: ExtensionPoint::V0,
+            hash: code_hash.clone(),
+            code: wasm_bytes.to_vec().try_into().map_err(|_| {
+                SnapshotError::XdrEncoding("WASM payload exceeds the maximum contract code size".to_string())
+            })?,
+        }),
+        ext: LedgerEntryExt::V0,
+    };
+    inject_ledger_entry(host, &key, &entry)?;
+    Ok(code_hash)
+}
+
+/// Create a `ContractData` instance entry pointing at `code_hash` and return
+/// the deployed contract's address.
+///
+/// `contract_id` is derived the way the network does: `sha256` over
+/// `HashIdPreimage::ContractId { network_id, contract_id_preimage:
+/// ContractIdPreimage::Address { address: source, salt } }`, where
+/// `network_id` is `sha256(network_passphrase)`. Passing the same
+/// `source`/`salt`/`network_passphrase` a real `CreateContract` operation
+/// would use yields the same address.
+pub fn deploy_contract(
+    host: &Host,
+    code_hash: Hash,
+    salt: [u8; 32],
+    source: ScAddress,
+    network_passphrase: &str,
+) -> Result<ScAddress, SnapshotError> {
+    let network_id = Hash(Sha256::digest(network_passphrase.as_bytes()).into());
+    let preimage = HashIdPreimage::ContractId(HashIdPreimageContractId {
+        network_id,
+        contract_id_preimage: ContractIdPreimage::Address(ContractIdPreimageFromAddress {
+            address: source,
+            salt: Uint256(salt),
+        }),
+    });
+    let preimage_xdr = preimage.to_xdr(Limits::none()).map_err(|e| {
+        SnapshotError::XdrEncoding(format!("Failed to encode contract id preimage: {e}"))
+    })?;
+    let contract_address = ScAddress::Contract(Hash(Sha256::digest(&preimage_xdr).into()));
+
+    let key = LedgerKey::ContractData(LedgerKeyContractData {
+        contract: contract_address.clone(),
+        key: ScVal::LedgerKeyContractInstance,
+        durability: ContractDataDurability::Persistent,
+    });
+    let entry = LedgerEntry {
+        last_modified_ledger_seq: 0,
+        data: LedgerEntryData::ContractData(ContractDataEntry {
+            ext: ExtensionPoint::V0,
+            contract: contract_address.clone(),
+            key: ScVal::LedgerKeyContractInstance,
+            durability: ContractDataDurability::Persistent,
+            val: ScVal::ContractInstance(ScContractInstance {
+                executable: ContractExecutable::Wasm(code_hash),
+                storage: None,
+            }),
+        }),
+        ext: LedgerEntryExt::V0,
+    };
+    inject_ledger_entry(host, &key, &entry)?;
+    Ok(contract_address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{AccountId, PublicKey};
+
+    fn create_test_host() -> Host {
+        let host = Host::default();
+        host.set_diagnostic_level(soroban_env_host::DiagnosticLevel::Debug)
+            .unwrap();
+        host
+    }
+
+    fn dummy_source() -> ScAddress {
+        ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(
+            soroban_env_host::xdr::Uint256([1u8; 32]),
+        )))
+    }
+
+    #[test]
+    fn test_install_wasm_injects_a_contract_code_entry() {
+        let host = create_test_host();
+        let wasm = vec![0x00, 0x61, 0x73, 0x6d];
+
+        let code_hash = install_wasm(&host, &wasm).expect("install should succeed");
+
+        let key = soroban_env_host::xdr::LedgerKey::ContractCode(LedgerKeyContractCode {
+            hash: code_hash,
+        });
+        let in_footprint = host
+            .with_storage(|storage| Ok(storage.footprint.0.contains_key(&std::rc::Rc::new(key))))
+            .expect("storage should be readable");
+        assert!(in_footprint, "installed code hash should show up in the footprint");
+    }
+
+    #[test]
+    fn test_install_wasm_is_deterministic_for_the_same_bytes() {
+        let host = create_test_host();
+        let wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+
+        let first = install_wasm(&host, &wasm).expect("install should succeed");
+        let second = install_wasm(&host, &wasm).expect("install should succeed");
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_deploy_contract_derives_the_same_address_for_the_same_inputs() {
+        let host = create_test_host();
+        let wasm = vec![0x00, 0x61, 0x73, 0x6d];
+        let code_hash = install_wasm(&host, &wasm).expect("install should succeed");
+
+        let first = deploy_contract(&host, code_hash.clone(), [9u8; 32], dummy_source(), "Test Network ; July 2026")
+            .expect("deploy should succeed");
+        let second = deploy_contract(&host, code_hash, [9u8; 32], dummy_source(), "Test Network ; July 2026")
+            .expect("deploy should succeed");
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_deploy_contract_changes_address_when_the_salt_changes() {
+        let host = create_test_host();
+        let wasm = vec![0x00, 0x61, 0x73, 0x6d];
+        let code_hash = install_wasm(&host, &wasm).expect("install should succeed");
+
+        let first = deploy_contract(&host, code_hash.clone(), [1u8; 32], dummy_source(), "Test Network ; July 2026")
+            .expect("deploy should succeed");
+        let second = deploy_contract(&host, code_hash, [2u8; 32], dummy_source(), "Test Network ; July 2026")
+            .expect("deploy should succeed");
+        assert_ne!(first, second);
+    }
+}
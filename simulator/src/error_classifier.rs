@@ -0,0 +1,433 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Classify `HostError`s (and the panics some host versions still raise
+//! instead of returning one) into a small set of stable, actionable
+//! categories, each with an optional remediation hint. This replaces the
+//! bare `format!("{:?}", host_error)` error path with something downstream
+//! tooling can key consistent diagnostics off of.
+
+use crate::types::StructuredError;
+use soroban_env_host::{
+    xdr::{ScErrorCode, ScErrorType},
+    HostError,
+};
+
+/// A stable, machine-readable bucket for a simulation failure. The
+/// `as_str()` slug is what's surfaced as `SimulationResponse::error_code`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    WasmTrapOutOfBounds,
+    WasmTrapUnreachable,
+    WasmTrapDivideByZero,
+    WasmTrapIntegerOverflow,
+    WasmTrapStackOverflow,
+    WasmTrapIndirectCallMismatch,
+    WasmTrapOther,
+    BudgetExceededCpu,
+    BudgetExceededMemory,
+    LedgerEntryMissing,
+    LedgerEntryExpired,
+    AuthorizationFailure,
+    ContractNotFound,
+    CompilationFailure,
+    MethodResolutionFailure,
+    SerializationFailure,
+    InsufficientFee,
+    HostInternalInvariant,
+    Unknown,
+}
+
+impl ErrorCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::WasmTrapOutOfBounds => "ERR_WASM_TRAP_OUT_OF_BOUNDS",
+            Self::WasmTrapUnreachable => "ERR_WASM_TRAP_UNREACHABLE",
+            Self::WasmTrapDivideByZero => "ERR_WASM_TRAP_DIVIDE_BY_ZERO",
+            Self::WasmTrapIntegerOverflow => "ERR_WASM_TRAP_INTEGER_OVERFLOW",
+            Self::WasmTrapStackOverflow => "ERR_WASM_TRAP_STACK_OVERFLOW",
+            Self::WasmTrapIndirectCallMismatch => "ERR_WASM_TRAP_INDIRECT_CALL_MISMATCH",
+            Self::WasmTrapOther => "ERR_WASM_TRAP",
+            Self::BudgetExceededCpu => "ERR_BUDGET_EXCEEDED_CPU",
+            Self::BudgetExceededMemory => "ERR_BUDGET_EXCEEDED_MEMORY",
+            Self::LedgerEntryMissing => "ERR_LEDGER_ENTRY_MISSING",
+            Self::LedgerEntryExpired => "ERR_LEDGER_ENTRY_EXPIRED",
+            Self::AuthorizationFailure => "ERR_AUTHORIZATION_FAILURE",
+            Self::ContractNotFound => "ERR_CONTRACT_NOT_FOUND",
+            Self::CompilationFailure => "ERR_COMPILATION_FAILURE",
+            Self::MethodResolutionFailure => "ERR_METHOD_RESOLUTION_FAILURE",
+            Self::SerializationFailure => "ERR_SERIALIZATION_FAILURE",
+            Self::InsufficientFee => "ERR_INSUFFICIENT_FEE",
+            Self::HostInternalInvariant => "ERR_HOST_INTERNAL_INVARIANT",
+            Self::Unknown => "ERR_UNKNOWN",
+        }
+    }
+
+    /// A short, actionable next step for this category, if there is an
+    /// obvious one. `None` for categories where the fix is case-specific.
+    pub fn remediation(&self) -> Option<&'static str> {
+        match self {
+            Self::WasmTrapOutOfBounds
+            | Self::WasmTrapUnreachable
+            | Self::WasmTrapDivideByZero
+            | Self::WasmTrapIntegerOverflow
+            | Self::WasmTrapStackOverflow
+            | Self::WasmTrapIndirectCallMismatch
+            | Self::WasmTrapOther => {
+                Some("Fix the contract: this is a WASM-level trap, not a resource or ledger-state problem.")
+            }
+            Self::BudgetExceededCpu => {
+                Some("Increase the CPU resource budget, or reduce contract computation.")
+            }
+            Self::BudgetExceededMemory => {
+                Some("Increase the memory resource budget, or reduce contract memory usage.")
+            }
+            Self::LedgerEntryMissing => {
+                Some("Add the missing entry to `ledger_entries`.")
+            }
+            Self::LedgerEntryExpired => {
+                Some("Restore or bump the ledger entry's TTL before simulating.")
+            }
+            Self::AuthorizationFailure => {
+                Some("Ensure the required signer(s) and auth entries are present on the transaction.")
+            }
+            Self::ContractNotFound => {
+                Some("Verify the contract ID and that its Wasm/contract-code entry is included in `ledger_entries`.")
+            }
+            Self::CompilationFailure => {
+                Some("Verify the supplied WASM is a valid, Soroban-compatible module.")
+            }
+            Self::MethodResolutionFailure => {
+                Some("Verify the invoked function name matches one the contract actually exports.")
+            }
+            Self::SerializationFailure => {
+                Some("Verify argument/return values are valid XDR-encodable `ScVal`s.")
+            }
+            Self::InsufficientFee => {
+                Some("Raise the transaction's declared fee to at least the required amount.")
+            }
+            Self::HostInternalInvariant => {
+                Some("This indicates an internal host invariant violation rather than a contract bug; file a bug report with the full debug output.")
+            }
+            Self::Unknown => None,
+        }
+    }
+
+    /// The human-readable explanation for this category — the exact wording
+    /// `decode_error` has always produced for each case, now keyed off the
+    /// stable category rather than re-matched from scratch. `raw` is only
+    /// used by `WasmTrapOther` and `Unknown`, where the original message is
+    /// folded into (or simply is) the phrase.
+    pub fn phrase(&self, raw: &str) -> String {
+        match self {
+            Self::WasmTrapOutOfBounds => "VM Trap: Out of Bounds Access (VM Trap: Out of bounds memory access) — the contract read or wrote outside its allocated memory region.".to_string(),
+            Self::WasmTrapStackOverflow => "VM Trap: Stack Overflow — the contract exceeded the maximum call-stack depth.".to_string(),
+            Self::WasmTrapIntegerOverflow => "VM Trap: Integer Overflow — arithmetic exceeded integer bounds.".to_string(),
+            Self::WasmTrapDivideByZero => "VM Trap: Division by Zero — attempted integer division by zero.".to_string(),
+            Self::WasmTrapUnreachable => "VM Trap: Unreachable Instruction — the contract executed an explicit trap or reached dead code.".to_string(),
+            Self::WasmTrapIndirectCallMismatch => "VM Trap: Indirect-Call Type Mismatch — wrong function signature in call_indirect.".to_string(),
+            Self::WasmTrapOther => format!("VM Trap: {raw}"),
+            Self::AuthorizationFailure => "Authorization failure — a required signer or policy check was not satisfied.".to_string(),
+            Self::BudgetExceededCpu | Self::BudgetExceededMemory => "Resource limit exceeded — the transaction consumed more CPU instructions or memory than the protocol-21 budget allows.".to_string(),
+            Self::LedgerEntryMissing => "Missing ledger entry — the contract referenced a key that does not exist in the current ledger state.".to_string(),
+            Self::LedgerEntryExpired => "Ledger entry expired — its TTL must be restored or bumped before simulating.".to_string(),
+            Self::ContractNotFound => "Contract not found — the referenced contract's code or instance entry is missing from the supplied ledger state.".to_string(),
+            Self::CompilationFailure => "Compilation failure — the contract's WASM module failed to parse or instantiate.".to_string(),
+            Self::MethodResolutionFailure => "Method resolution failure — the invoked function is not exported by the contract.".to_string(),
+            Self::SerializationFailure => "Serialization failure — a value could not be encoded or decoded as XDR.".to_string(),
+            Self::InsufficientFee => "Insufficient fee — the transaction's declared fee is below the required amount.".to_string(),
+            Self::HostInternalInvariant => "Internal host invariant violation.".to_string(),
+            Self::Unknown => raw.to_string(),
+        }
+    }
+}
+
+/// Classify a `HostError` into a stable category, using the `ScError`
+/// type/code embedded in it where that's discriminating enough, and
+/// falling back to matching known substrings in its debug text (e.g. to
+/// tell a WASM out-of-bounds trap apart from an unreachable instruction,
+/// which the host error code alone doesn't distinguish).
+pub fn classify_host_error(host_error: &HostError, error_debug: &str) -> ErrorCategory {
+    let lower = error_debug.to_lowercase();
+    match (host_error.error.get_type(), host_error.error.get_code()) {
+        (ScErrorType::WasmVm, _) => classify_wasm_trap(&lower),
+        (ScErrorType::Budget, ScErrorCode::ExceededLimit) => classify_budget_exceeded(&lower),
+        (ScErrorType::Storage, ScErrorCode::MissingValue) => classify_missing_storage(&lower),
+        (ScErrorType::Auth, _) => ErrorCategory::AuthorizationFailure,
+        _ => classify_from_text(&lower),
+    }
+}
+
+/// Reclassify a caught panic message into the same categories used for
+/// real `HostError`s, when it matches a known host-internal pattern.
+/// Newer host versions convert the dynamic `RefCell` borrow failures this
+/// recognizes into real `HostError`s, but older ones still surface them as
+/// bare panics — callers shouldn't see a different diagnostic shape either
+/// way. Returns `None` when the panic doesn't match a known pattern, so the
+/// caller can fall back to its generic "Simulator panicked" handling.
+pub fn classify_panic_message(panic_msg: &str) -> Option<ErrorCategory> {
+    let lower = panic_msg.to_lowercase();
+    if lower.contains("already borrowed")
+        || lower.contains("already mutably borrowed")
+        || lower.contains("borrowmuterror")
+        || lower.contains("borrowerror")
+    {
+        Some(ErrorCategory::HostInternalInvariant)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn classify_wasm_trap(lower: &str) -> ErrorCategory {
+    if lower.contains("out of bounds") || lower.contains("memory access") {
+        ErrorCategory::WasmTrapOutOfBounds
+    } else if lower.contains("unreachable") {
+        ErrorCategory::WasmTrapUnreachable
+    } else if lower.contains("divide by zero") || lower.contains("division by zero") {
+        ErrorCategory::WasmTrapDivideByZero
+    } else if lower.contains("integer overflow") {
+        ErrorCategory::WasmTrapIntegerOverflow
+    } else if lower.contains("stack overflow") || lower.contains("call stack") {
+        ErrorCategory::WasmTrapStackOverflow
+    } else if lower.contains("indirect call") || lower.contains("table") {
+        ErrorCategory::WasmTrapIndirectCallMismatch
+    } else {
+        ErrorCategory::WasmTrapOther
+    }
+}
+
+fn classify_budget_exceeded(lower: &str) -> ErrorCategory {
+    if lower.contains("mem") {
+        ErrorCategory::BudgetExceededMemory
+    } else {
+        ErrorCategory::BudgetExceededCpu
+    }
+}
+
+fn classify_missing_storage(lower: &str) -> ErrorCategory {
+    if lower.contains("expired") {
+        ErrorCategory::LedgerEntryExpired
+    } else if lower.contains("contract") {
+        ErrorCategory::ContractNotFound
+    } else {
+        ErrorCategory::LedgerEntryMissing
+    }
+}
+
+fn classify_from_text(lower: &str) -> ErrorCategory {
+    if lower.contains("already borrowed") || lower.contains("borrowmuterror") || lower.contains("refcell")
+    {
+        ErrorCategory::HostInternalInvariant
+    } else if lower.contains("auth") || lower.contains("unauthorized") {
+        ErrorCategory::AuthorizationFailure
+    } else if lower.contains("missing") || lower.contains("not found") {
+        ErrorCategory::LedgerEntryMissing
+    } else {
+        ErrorCategory::Unknown
+    }
+}
+
+/// Classify a raw, free-form error/diagnostic string the same way
+/// [`classify_host_error`] classifies a typed `HostError`, for callers (like
+/// `decode_error`) that only ever see rendered text — e.g. a panic message
+/// that didn't match [`classify_panic_message`], or a string surfaced from
+/// somewhere other than the host's own `ScError`. Mirrors the cascade
+/// `decode_error` used to run inline: trap family first (so a "wasm trap:
+/// ..." message is classified as precisely as a bare trap keyword would be),
+/// then auth, budget, the newer compilation/method/serialization/fee
+/// families, then the generic missing-entry catch-all.
+pub fn classify_error_text(raw: &str) -> ErrorCategory {
+    let lower = raw.to_lowercase();
+
+    if lower.contains("wasm trap") || lower.contains("vm trap") {
+        return classify_wasm_trap(&lower);
+    }
+    if lower.contains("unreachable")
+        || lower.contains("divide by zero")
+        || lower.contains("division by zero")
+        || lower.contains("integer overflow")
+        || lower.contains("stack overflow")
+        || lower.contains("call stack")
+        || lower.contains("indirect call")
+    {
+        return classify_wasm_trap(&lower);
+    }
+
+    if lower.contains("auth") || lower.contains("unauthorized") {
+        return ErrorCategory::AuthorizationFailure;
+    }
+
+    if lower.contains("budget") || lower.contains("cpu limit") || lower.contains("mem limit") {
+        return classify_budget_exceeded(&lower);
+    }
+
+    if lower.contains("compilation failed")
+        || lower.contains("failed to parse wasm")
+        || lower.contains("failed to instantiate")
+        || lower.contains("invalid wasm")
+    {
+        return ErrorCategory::CompilationFailure;
+    }
+
+    if lower.contains("function not found")
+        || lower.contains("method not found")
+        || lower.contains("missing export")
+    {
+        return ErrorCategory::MethodResolutionFailure;
+    }
+
+    if lower.contains("serialization") || lower.contains("deserialize") || lower.contains("invalid xdr") {
+        return ErrorCategory::SerializationFailure;
+    }
+
+    if lower.contains("insufficient fee") || lower.contains("fee too low") {
+        return ErrorCategory::InsufficientFee;
+    }
+
+    if lower.contains("expired") || lower.contains("contract") {
+        if lower.contains("missing") || lower.contains("not found") {
+            return classify_missing_storage(&lower);
+        }
+    }
+
+    if lower.contains("missing") || lower.contains("not found") {
+        return ErrorCategory::LedgerEntryMissing;
+    }
+
+    ErrorCategory::Unknown
+}
+
+/// Build the `StructuredError` response payload for a classified failure.
+pub fn structured_error_for(
+    category: ErrorCategory,
+    message: String,
+    details: Option<String>,
+) -> StructuredError {
+    StructuredError {
+        error_type: category.as_str().to_string(),
+        message,
+        details,
+        remediation: category.remediation().map(ToString::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_wasm_traps_from_debug_text() {
+        assert_eq!(
+            classify_wasm_trap("wasm trap: out of bounds memory access"),
+            ErrorCategory::WasmTrapOutOfBounds
+        );
+        assert_eq!(
+            classify_wasm_trap("panic: unreachable"),
+            ErrorCategory::WasmTrapUnreachable
+        );
+        assert_eq!(
+            classify_wasm_trap("integer divide by zero"),
+            ErrorCategory::WasmTrapDivideByZero
+        );
+        assert_eq!(
+            classify_wasm_trap("stack overflow occurred"),
+            ErrorCategory::WasmTrapStackOverflow
+        );
+    }
+
+    #[test]
+    fn classifies_budget_exceeded_dimension() {
+        assert_eq!(
+            classify_budget_exceeded("cpu limit exceeded"),
+            ErrorCategory::BudgetExceededCpu
+        );
+        assert_eq!(
+            classify_budget_exceeded("mem limit exceeded"),
+            ErrorCategory::BudgetExceededMemory
+        );
+    }
+
+    #[test]
+    fn classifies_missing_vs_expired_vs_contract_storage() {
+        assert_eq!(
+            classify_missing_storage("missing value for key"),
+            ErrorCategory::LedgerEntryMissing
+        );
+        assert_eq!(
+            classify_missing_storage("entry has expired"),
+            ErrorCategory::LedgerEntryExpired
+        );
+        assert_eq!(
+            classify_missing_storage("contract code not found"),
+            ErrorCategory::ContractNotFound
+        );
+    }
+
+    #[test]
+    fn reclassifies_refcell_borrow_panics_as_host_internal_invariant() {
+        assert_eq!(
+            classify_panic_message("already borrowed: BorrowMutError"),
+            Some(ErrorCategory::HostInternalInvariant)
+        );
+        assert_eq!(classify_panic_message("index out of bounds"), None);
+    }
+
+    #[test]
+    fn remediation_is_present_for_every_actionable_category() {
+        let actionable = [
+            ErrorCategory::BudgetExceededCpu,
+            ErrorCategory::BudgetExceededMemory,
+            ErrorCategory::LedgerEntryMissing,
+            ErrorCategory::LedgerEntryExpired,
+            ErrorCategory::AuthorizationFailure,
+            ErrorCategory::ContractNotFound,
+            ErrorCategory::HostInternalInvariant,
+            ErrorCategory::CompilationFailure,
+            ErrorCategory::MethodResolutionFailure,
+            ErrorCategory::SerializationFailure,
+            ErrorCategory::InsufficientFee,
+        ];
+        for category in actionable {
+            assert!(category.remediation().is_some(), "{:?} should have a remediation hint", category);
+        }
+        assert!(ErrorCategory::Unknown.remediation().is_none());
+    }
+
+    #[test]
+    fn classify_error_text_covers_the_newer_families() {
+        assert_eq!(
+            classify_error_text("failed to parse wasm: invalid magic number"),
+            ErrorCategory::CompilationFailure
+        );
+        assert_eq!(
+            classify_error_text("function not found: increment"),
+            ErrorCategory::MethodResolutionFailure
+        );
+        assert_eq!(
+            classify_error_text("failed to deserialize ScVal"),
+            ErrorCategory::SerializationFailure
+        );
+        assert_eq!(
+            classify_error_text("insufficient fee: declared 100, required 5000"),
+            ErrorCategory::InsufficientFee
+        );
+        assert_eq!(
+            classify_error_text("wasm trap: integer overflow"),
+            ErrorCategory::WasmTrapIntegerOverflow
+        );
+    }
+
+    #[test]
+    fn classify_error_text_falls_back_to_unknown_for_plain_messages() {
+        assert_eq!(classify_error_text("normal error"), ErrorCategory::Unknown);
+    }
+
+    #[test]
+    fn phrase_echoes_raw_text_only_for_other_and_unknown() {
+        assert_eq!(
+            ErrorCategory::WasmTrapOther.phrase("wasm trap: exotic condition"),
+            "VM Trap: wasm trap: exotic condition"
+        );
+        assert_eq!(ErrorCategory::Unknown.phrase("normal error"), "normal error");
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Catalog of intentionally faulty contracts used by the safety test-suite,
+//! each targeting exactly one host-enforced resource limit.
+//!
+//! Every entry names the fixture crate it was built from (see the sibling
+//! `lib_*_contract.rs` files) and the [`BudgetDimension`] it is expected to
+//! exhaust. `tests/host_budget_fault_suite_test.rs` drives each entry
+//! through the simulator and asserts that the reported budget-exceeded
+//! error is attributed to exactly that dimension, never a different one.
+
+use crate::types::BudgetUsage;
+
+/// A single host-enforced resource limit that a fault contract can exhaust.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetDimension {
+    CpuInstructions,
+    MemoryBytes,
+    StorageEntries,
+    Events,
+    CallDepth,
+}
+
+impl BudgetDimension {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetDimension::CpuInstructions => "cpu_instructions",
+            BudgetDimension::MemoryBytes => "memory_bytes",
+            BudgetDimension::StorageEntries => "storage_entries",
+            BudgetDimension::Events => "events",
+            BudgetDimension::CallDepth => "call_depth",
+        }
+    }
+}
+
+/// One entry in the fault-contract family.
+#[derive(Debug, Clone, Copy)]
+pub struct FaultContract {
+    /// Name of the fixture crate, e.g. `oom_contract`, built to
+    /// `<name>.wasm` for the harness to load.
+    pub name: &'static str,
+    /// Exported contract function to invoke.
+    pub function: &'static str,
+    /// Host-budget dimension this contract is designed to exhaust.
+    pub dimension: BudgetDimension,
+    /// `iterations`/`depth` argument large enough to guarantee exhaustion
+    /// against the mainnet-default budget. `0` means the contract takes no
+    /// such argument (it fails unconditionally).
+    pub default_iterations: u32,
+}
+
+/// The full family of fault contracts, one per independently-enforced
+/// budget dimension.
+pub const FAULT_CONTRACTS: &[FaultContract] = &[
+    FaultContract {
+        name: "oom_contract",
+        function: "run",
+        dimension: BudgetDimension::MemoryBytes,
+        default_iterations: 100_000,
+    },
+    FaultContract {
+        name: "endless_loop_contract",
+        function: "run",
+        dimension: BudgetDimension::CpuInstructions,
+        default_iterations: 0,
+    },
+    FaultContract {
+        name: "deep_recursion_contract",
+        function: "recurse",
+        dimension: BudgetDimension::CallDepth,
+        default_iterations: 1_000,
+    },
+    FaultContract {
+        name: "storage_blowup_contract",
+        function: "run",
+        dimension: BudgetDimension::StorageEntries,
+        default_iterations: 100_000,
+    },
+    FaultContract {
+        name: "event_flood_contract",
+        function: "run",
+        dimension: BudgetDimension::Events,
+        default_iterations: 100_000,
+    },
+];
+
+/// Best-effort classification of which budget dimension a failed simulation
+/// exhausted, based on how close each metered dimension came to its limit.
+///
+/// Only CPU instructions and memory bytes are tracked by the host's
+/// [`soroban_env_host::budget::Budget`] today; storage-entry and event
+/// exhaustion are surfaced by other host checks and are not yet reflected
+/// in `BudgetUsage`; `classify` deliberately returns `None` for those rather
+/// than guessing.
+pub fn classify(usage: &BudgetUsage) -> Option<BudgetDimension> {
+    if usage.cpu_usage_percent >= 100.0 {
+        Some(BudgetDimension::CpuInstructions)
+    } else if usage.memory_usage_percent >= 100.0 {
+        Some(BudgetDimension::MemoryBytes)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fault_contracts_cover_every_dimension_exactly_once() {
+        let mut dimensions: Vec<BudgetDimension> =
+            FAULT_CONTRACTS.iter().map(|c| c.dimension).collect();
+        dimensions.sort_by_key(|d| d.as_str());
+
+        let mut expected = vec![
+            BudgetDimension::CallDepth,
+            BudgetDimension::CpuInstructions,
+            BudgetDimension::Events,
+            BudgetDimension::MemoryBytes,
+            BudgetDimension::StorageEntries,
+        ];
+        expected.sort_by_key(|d| d.as_str());
+
+        assert_eq!(dimensions, expected);
+    }
+
+    fn usage(cpu_percent: f64, mem_percent: f64) -> BudgetUsage {
+        BudgetUsage {
+            cpu_instructions: 0,
+            memory_bytes: 0,
+            operations_count: 0,
+            cpu_limit: 0,
+            memory_limit: 0,
+            cpu_usage_percent: cpu_percent,
+            memory_usage_percent: mem_percent,
+            cost_breakdown: std::collections::HashMap::new(),
+            vm_instantiation_cpu: 0,
+            vm_instantiation_mem: 0,
+        }
+    }
+
+    #[test]
+    fn classify_picks_cpu_when_cpu_exhausted() {
+        assert_eq!(
+            classify(&usage(100.0, 12.0)),
+            Some(BudgetDimension::CpuInstructions)
+        );
+    }
+
+    #[test]
+    fn classify_picks_memory_when_memory_exhausted() {
+        assert_eq!(
+            classify(&usage(40.0, 143.0)),
+            Some(BudgetDimension::MemoryBytes)
+        );
+    }
+
+    #[test]
+    fn classify_is_none_when_nothing_exhausted() {
+        assert_eq!(classify(&usage(50.0, 50.0)), None);
+    }
+}
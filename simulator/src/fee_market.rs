@@ -0,0 +1,129 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fee-market / congestion modeling for simulated transactions.
+//!
+//! `SimulationRequest::mock_gas_price` models a single fixed compute-unit
+//! price, but real network congestion makes that price vary ledger to
+//! ledger. This module draws a batch of randomized compute-unit prices from
+//! a caller-supplied distribution, computes the effective fee the
+//! transaction would pay at each sampled price given its
+//! `BudgetUsage::cpu_instructions`, and summarizes the result at the
+//! p50/p90/p99 sampled prices so callers can see how their contract's cost
+//! behaves under congestion rather than against a single fixed price.
+
+use crate::types::{FeeMarketDistribution, FeeMarketReport, FeeSamplePoint};
+use rand::RngCore;
+
+/// Resource units per CPU instruction, matching the conversion
+/// `mocked_required_fee_stroops` already uses for `mock_gas_price`, so a
+/// fee-market report is directly comparable to the single-price mocked fee
+/// check.
+const CPU_UNITS_DIVISOR: u64 = 10_000;
+
+fn cpu_units(cpu_instructions: u64) -> u64 {
+    cpu_instructions.saturating_add(CPU_UNITS_DIVISOR - 1) / CPU_UNITS_DIVISOR
+}
+
+fn sample_compute_unit_price(distribution: &FeeMarketDistribution, rng: &mut impl RngCore) -> u64 {
+    let (min, max) = (
+        distribution.min_compute_unit_price,
+        distribution.max_compute_unit_price,
+    );
+    if max <= min {
+        return min;
+    }
+    min + rng.next_u64() % (max - min + 1)
+}
+
+fn percentile(sorted_prices: &[u64], pct: f64) -> u64 {
+    if sorted_prices.is_empty() {
+        return 0;
+    }
+    let idx = (((sorted_prices.len() - 1) as f64) * pct).round() as usize;
+    sorted_prices[idx.min(sorted_prices.len() - 1)]
+}
+
+fn sample_point(compute_unit_price: u64, cpu_instructions: u64) -> FeeSamplePoint {
+    FeeSamplePoint {
+        compute_unit_price,
+        fee_stroops: compute_unit_price.saturating_mul(cpu_units(cpu_instructions).max(1)),
+    }
+}
+
+/// Draw `distribution.sample_count` compute-unit prices and summarize the
+/// resulting fee, at the p50/p90/p99 sampled prices, for a transaction that
+/// used `cpu_instructions` CPU.
+pub fn simulate(
+    distribution: &FeeMarketDistribution,
+    cpu_instructions: u64,
+    rng: &mut impl RngCore,
+) -> FeeMarketReport {
+    let mut prices: Vec<u64> = (0..distribution.sample_count.max(1))
+        .map(|_| sample_compute_unit_price(distribution, rng))
+        .collect();
+    prices.sort_unstable();
+
+    FeeMarketReport {
+        p50: sample_point(percentile(&prices, 0.50), cpu_instructions),
+        p90: sample_point(percentile(&prices, 0.90), cpu_instructions),
+        p99: sample_point(percentile(&prices, 0.99), cpu_instructions),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn distribution(min: u64, max: u64, sample_count: u32) -> FeeMarketDistribution {
+        FeeMarketDistribution {
+            min_compute_unit_price: min,
+            max_compute_unit_price: max,
+            sample_count,
+        }
+    }
+
+    #[test]
+    fn samples_fall_within_the_distribution_bounds() {
+        let dist = distribution(100, 500, 1000);
+        let mut rng = StdRng::seed_from_u64(1);
+        let report = simulate(&dist, 50_000, &mut rng);
+
+        assert!((100..=500).contains(&report.p50.compute_unit_price));
+        assert!((100..=500).contains(&report.p99.compute_unit_price));
+    }
+
+    #[test]
+    fn percentiles_are_non_decreasing() {
+        let dist = distribution(10, 10_000, 2000);
+        let mut rng = StdRng::seed_from_u64(2);
+        let report = simulate(&dist, 200_000, &mut rng);
+
+        assert!(report.p50.compute_unit_price <= report.p90.compute_unit_price);
+        assert!(report.p90.compute_unit_price <= report.p99.compute_unit_price);
+        assert!(report.p50.fee_stroops <= report.p90.fee_stroops);
+        assert!(report.p90.fee_stroops <= report.p99.fee_stroops);
+    }
+
+    #[test]
+    fn a_degenerate_distribution_returns_the_fixed_price() {
+        let dist = distribution(42, 42, 100);
+        let mut rng = StdRng::seed_from_u64(3);
+        let report = simulate(&dist, 10_000, &mut rng);
+
+        assert_eq!(report.p50.compute_unit_price, 42);
+        assert_eq!(report.p99.compute_unit_price, 42);
+    }
+
+    #[test]
+    fn fee_scales_with_cpu_instructions() {
+        let dist = distribution(100, 100, 10);
+        let mut rng = StdRng::seed_from_u64(4);
+        let small = simulate(&dist, 10_000, &mut rng);
+        let large = simulate(&dist, 100_000, &mut rng);
+
+        assert!(large.p50.fee_stroops > small.p50.fee_stroops);
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Machine-readable output backends for [`OptimizationReport`], mirroring a
+//! test harness's formatter split: JSON for programmatic consumption, JUnit
+//! XML for CI systems that already know how to render test results and fail
+//! builds on them.
+
+use crate::gas_optimizer::OptimizationReport;
+
+/// Output backend selector for [`crate::gas_optimizer::GasOptimizationAdvisor::render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Junit,
+}
+
+/// Serializes the full `OptimizationReport` (efficiency score, every tip's
+/// severity/category/message/estimated_savings/code_location, the budget
+/// breakdown map, and the baseline comparison summary) as a stable JSON
+/// object.
+pub struct JsonFormatter;
+
+impl JsonFormatter {
+    pub fn format(report: &OptimizationReport) -> String {
+        serde_json::to_string_pretty(report)
+            .unwrap_or_else(|e| format!("{{\"error\":\"failed to serialize report: {e}\"}}"))
+    }
+}
+
+/// Emits a `<testsuite>/<testcase>` JUnit XML document: one `<testcase>` per
+/// tip, with high-severity tips reported as a `<failure>` so a CI pipeline
+/// that already understands JUnit treats them as build failures, and each
+/// budget metric attached to the suite as a `<property>`.
+pub struct JunitFormatter;
+
+impl JunitFormatter {
+    pub fn format(report: &OptimizationReport) -> String {
+        let failures = report.tips.iter().filter(|tip| tip.severity == "high").count();
+
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuite name=\"gas-optimization\" tests=\"{}\" failures=\"{}\">\n",
+            report.tips.len(),
+            failures
+        ));
+
+        xml.push_str("  <properties>\n");
+        xml.push_str(&format!(
+            "    <property name=\"overall_efficiency\" value=\"{:.2}\"/>\n",
+            report.overall_efficiency
+        ));
+        let mut budget_keys: Vec<&String> = report.budget_breakdown.keys().collect();
+        budget_keys.sort();
+        for key in budget_keys {
+            xml.push_str(&format!(
+                "    <property name=\"{}\" value=\"{}\"/>\n",
+                escape_xml(key),
+                report.budget_breakdown[key]
+            ));
+        }
+        xml.push_str("  </properties>\n");
+
+        for (index, tip) in report.tips.iter().enumerate() {
+            xml.push_str(&format!(
+                "  <testcase name=\"{}-{}\" classname=\"{}\">\n",
+                escape_xml(&tip.category),
+                index,
+                escape_xml(&tip.category)
+            ));
+            if tip.severity == "high" {
+                let mut body = format!("Estimated savings: {}", tip.estimated_savings);
+                if let Some(location) = &tip.code_location {
+                    body.push_str(&format!("\nLocation: {location}"));
+                }
+                xml.push_str(&format!(
+                    "    <failure message=\"{}\">{}</failure>\n",
+                    escape_xml(&tip.message),
+                    escape_xml(&body)
+                ));
+            }
+            xml.push_str("  </testcase>\n");
+        }
+
+        xml.push_str("</testsuite>\n");
+        xml
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas_optimizer::OptimizationTip;
+    use std::collections::HashMap;
+
+    fn sample_report() -> OptimizationReport {
+        let mut budget_breakdown = HashMap::new();
+        budget_breakdown.insert("cpu_per_op".to_string(), 1500.0);
+
+        OptimizationReport {
+            overall_efficiency: 62.5,
+            tips: vec![
+                OptimizationTip {
+                    category: "Storage Access".to_string(),
+                    severity: "high".to_string(),
+                    message: "20 storage writes detected".to_string(),
+                    estimated_savings: "25-40% with batching".to_string(),
+                    code_location: Some("Storage operations".to_string()),
+                },
+                OptimizationTip {
+                    category: "General".to_string(),
+                    severity: "low".to_string(),
+                    message: "Contract execution is efficient".to_string(),
+                    estimated_savings: "N/A".to_string(),
+                    code_location: None,
+                },
+            ],
+            budget_breakdown,
+            comparison_to_baseline: "Fair - significant optimization opportunities exist".to_string(),
+            estimated_fee: 42_000,
+        }
+    }
+
+    #[test]
+    fn test_json_formatter_round_trips_through_serde() {
+        let report = sample_report();
+        let json = JsonFormatter::format(&report);
+        let parsed: OptimizationReport =
+            serde_json::from_str(&json).expect("formatter output should be valid JSON");
+        assert_eq!(parsed.overall_efficiency, report.overall_efficiency);
+        assert_eq!(parsed.tips.len(), report.tips.len());
+    }
+
+    #[test]
+    fn test_junit_formatter_counts_only_high_severity_tips_as_failures() {
+        let report = sample_report();
+        let xml = JunitFormatter::format(&report);
+
+        assert!(xml.contains("<testsuite name=\"gas-optimization\" tests=\"2\" failures=\"1\">"));
+        assert_eq!(xml.matches("<failure").count(), 1);
+        assert_eq!(xml.matches("<testcase").count(), 2);
+    }
+
+    #[test]
+    fn test_junit_formatter_escapes_special_characters() {
+        let mut report = sample_report();
+        report.tips[0].message = "cost < 5 & > 1".to_string();
+        let xml = JunitFormatter::format(&report);
+
+        assert!(xml.contains("cost &lt; 5 &amp; &gt; 1"));
+    }
+}
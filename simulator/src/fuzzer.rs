@@ -0,0 +1,353 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coverage-guided fuzzing harness for [`SimulationRequest`]s.
+//!
+//! Starting from a seed request, [`run_fuzz_loop`] repeatedly [`mutate`]s
+//! its fuzzable inputs (`ledger_entries` values, `envelope_xdr`, and the
+//! mocked fee/gas-price knobs) and runs the result through a caller-supplied
+//! simulation step, keeping any mutation that covers a function the corpus
+//! hasn't seen before or that triggers a panic, a budget overrun, or a
+//! `memory_limit` violation. Coverage is read from
+//! `SimulationResponse::lcov_report` via [`covered_functions`] — the same
+//! `enable_coverage`/`FNDA` machinery `main.rs` already produces for every
+//! simulation — so this module doesn't need its own instrumentation.
+//!
+//! `run_fuzz_loop` takes the simulation step as a closure rather than
+//! calling into `main.rs` directly: today's `main()` is a single
+//! stdin-to-stdout pass with no reusable `simulate(request) -> response`
+//! entry point, so wiring this into the real binary is a follow-up that
+//! factors that loop body out first. Input minimization (shrinking a
+//! crashing input to the smallest one that still crashes) is likewise not
+//! implemented yet; findings are reported as discovered, unminimized.
+
+use crate::types::{SimulationRequest, SimulationResponse};
+use rand::RngCore;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+/// Why a mutated input was kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FindingKind {
+    /// Covered at least one function the corpus hadn't seen before.
+    NewCoverage,
+    /// `cpu_usage_percent` or `memory_usage_percent` reached 100%.
+    BudgetExceeded,
+    /// Reported memory consumption exceeded `SimulationRequest::memory_limit`.
+    MemoryLimitExceeded,
+    /// The simulation panicked.
+    Panic,
+}
+
+/// One interesting input discovered by the fuzz loop, kept in the corpus.
+#[derive(Debug, Clone)]
+pub struct FuzzFinding {
+    pub request: SimulationRequest,
+    pub kind: FindingKind,
+    /// Number of functions newly covered relative to the corpus at the time
+    /// this input was kept (`0` for pure crash/budget findings that didn't
+    /// also grow coverage).
+    pub coverage_delta: usize,
+}
+
+/// Parse the `FNDA:<count>,<name>` lines of an lcov report (see
+/// `generate_lcov_report` in `main.rs`) into the set of function names that
+/// were actually invoked (`count > 0`).
+pub fn covered_functions(lcov_report: &str) -> HashSet<String> {
+    lcov_report
+        .lines()
+        .filter_map(|line| line.strip_prefix("FNDA:"))
+        .filter_map(|rest| {
+            let (count, name) = rest.split_once(',')?;
+            let count: u64 = count.parse().ok()?;
+            (count > 0).then(|| name.to_string())
+        })
+        .collect()
+}
+
+fn hash_coverage(covered: &HashSet<String>) -> u64 {
+    let mut sorted: Vec<&String> = covered.iter().collect();
+    sorted.sort();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    sorted.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Flip a single pseudo-random bit of a base64-encoded field. Returns the
+/// original string unchanged if it isn't valid base64 or decodes to no
+/// bytes, so callers don't need to special-case empty/absent fields.
+fn mutate_base64_field(value: &str, rng: &mut impl RngCore) -> String {
+    use base64::Engine as _;
+    let Ok(mut bytes) = base64::engine::general_purpose::STANDARD.decode(value) else {
+        return value.to_string();
+    };
+    if bytes.is_empty() {
+        return value.to_string();
+    }
+    let idx = (rng.next_u32() as usize) % bytes.len();
+    bytes[idx] ^= 1u8 << (rng.next_u32() % 8);
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// Produce one mutated child of `seed`, perturbing its fuzzable inputs:
+/// the transaction envelope XDR, one `ledger_entries` value, and the mocked
+/// fee/gas-price knobs. `enable_coverage` is always turned on so the result
+/// carries the `lcov_report` this module's coverage tracking depends on.
+pub fn mutate(seed: &SimulationRequest, rng: &mut impl RngCore) -> SimulationRequest {
+    let mut mutated = seed.clone();
+    mutated.enable_coverage = true;
+    mutated.envelope_xdr = mutate_base64_field(&seed.envelope_xdr, rng);
+
+    if let Some(entries) = &mut mutated.ledger_entries {
+        if !entries.is_empty() {
+            let target = (rng.next_u32() as usize) % entries.len();
+            if let Some((_, value)) = entries.iter_mut().nth(target) {
+                *value = mutate_base64_field(value, rng);
+            }
+        }
+    }
+
+    if rng.next_u32() % 2 == 0 {
+        let base = mutated.mock_base_fee.unwrap_or(100);
+        mutated.mock_base_fee = Some(base.wrapping_mul(1 + rng.next_u32() % 1000));
+    } else {
+        let base = mutated.mock_gas_price.unwrap_or(100);
+        mutated.mock_gas_price = Some(base.wrapping_mul(1 + rng.next_u64() % 1000));
+    }
+
+    mutated
+}
+
+/// Coverage-guided corpus: the cumulative set of covered function names
+/// (used to compute each candidate's coverage delta), plus a set of
+/// covered-edge-set hashes so inputs with a coverage shape already in the
+/// corpus don't get kept again just for reaching it a second way.
+#[derive(Debug, Default)]
+struct FuzzCorpus {
+    covered: HashSet<String>,
+    seen_coverage_hashes: HashSet<u64>,
+}
+
+/// Classify a single simulation outcome against the fuzz loop's failure
+/// oracles (panic, budget overrun, memory-limit violation), falling back to
+/// `None` (no finding) when nothing interesting happened.
+fn classify_outcome(
+    candidate: &SimulationRequest,
+    response: &SimulationResponse,
+) -> Option<FindingKind> {
+    let panicked = response.status == "error"
+        && response
+            .error
+            .as_deref()
+            .is_some_and(|e| e.contains("Simulator panicked"));
+    if panicked {
+        return Some(FindingKind::Panic);
+    }
+
+    if let Some(budget) = &response.budget_usage {
+        if let Some(limit) = candidate.memory_limit {
+            if budget.memory_bytes > limit {
+                return Some(FindingKind::MemoryLimitExceeded);
+            }
+        }
+        if budget.cpu_usage_percent >= 100.0 || budget.memory_usage_percent >= 100.0 {
+            return Some(FindingKind::BudgetExceeded);
+        }
+    }
+
+    None
+}
+
+/// Run `iterations` rounds of mutate-then-simulate starting from `seed`,
+/// keeping any mutated input that grows cumulative coverage or trips one of
+/// `classify_outcome`'s failure oracles. `simulate` is the simulation step
+/// to fuzz — in production this should run the same code path `main()`
+/// runs per request.
+pub fn run_fuzz_loop(
+    seed: &SimulationRequest,
+    iterations: usize,
+    rng: &mut impl RngCore,
+    mut simulate: impl FnMut(&SimulationRequest) -> SimulationResponse,
+) -> Vec<FuzzFinding> {
+    let mut corpus = FuzzCorpus::default();
+    let mut findings = Vec::new();
+    let mut current = seed.clone();
+
+    for _ in 0..iterations {
+        let candidate = mutate(&current, rng);
+        let response = simulate(&candidate);
+
+        let covered = response
+            .lcov_report
+            .as_deref()
+            .map(covered_functions)
+            .unwrap_or_default();
+        let new_functions = covered.difference(&corpus.covered).count();
+        let coverage_hash = hash_coverage(&covered);
+        let is_new_coverage = new_functions > 0 && corpus.seen_coverage_hashes.insert(coverage_hash);
+
+        let kind = classify_outcome(&candidate, &response).or(if is_new_coverage {
+            Some(FindingKind::NewCoverage)
+        } else {
+            None
+        });
+
+        if let Some(kind) = kind {
+            corpus.covered.extend(covered);
+            findings.push(FuzzFinding {
+                request: candidate.clone(),
+                kind,
+                coverage_delta: new_functions,
+            });
+            current = candidate;
+        }
+    }
+
+    findings
+}
+
+/// Sort findings worst-first (panics, then memory-limit violations, then
+/// budget overruns, then pure new-coverage inputs), breaking ties by
+/// coverage delta so the most informative input in each category leads.
+pub fn rank_findings(mut findings: Vec<FuzzFinding>) -> Vec<FuzzFinding> {
+    findings.sort_by(|a, b| {
+        b.kind
+            .cmp(&a.kind)
+            .then_with(|| b.coverage_delta.cmp(&a.coverage_delta))
+    });
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use std::collections::HashMap;
+
+    fn seed_request() -> SimulationRequest {
+        let mut ledger_entries = HashMap::new();
+        ledger_entries.insert("a2V5".to_string(), "dmFsdWU=".to_string());
+        SimulationRequest {
+            envelope_xdr: "AAAAAQ==".to_string(),
+            result_meta_xdr: String::new(),
+            ledger_entries: Some(ledger_entries),
+            contract_wasm: None,
+            wasm_path: None,
+            enable_optimization_advisor: false,
+            profile: None,
+            timestamp: String::new(),
+            mock_base_fee: Some(100),
+            mock_gas_price: Some(100),
+            enable_coverage: false,
+            coverage_lcov_path: None,
+            resource_calibration: None,
+            memory_limit: None,
+            restore_preamble: None,
+            recording: false,
+            protocol_version: crate::types::DEFAULT_PROTOCOL_VERSION,
+            ledger_snapshot_path: None,
+            fee_market: None,
+            cost_schedule: None,
+            auto_provision_accounts: None,
+        }
+    }
+
+    fn response_with_coverage(functions: &[&str]) -> SimulationResponse {
+        let mut lcov = String::new();
+        for f in functions {
+            lcov.push_str(&format!("FNDA:1,{f}\n"));
+        }
+        SimulationResponse {
+            status: "success".to_string(),
+            error: None,
+            error_code: None,
+            lcov_report: Some(lcov),
+            lcov_report_path: None,
+            events: vec![],
+            diagnostic_events: vec![],
+            categorized_events: vec![],
+            logs: vec![],
+            flamegraph: None,
+            memory_flamegraph: None,
+            optimization_report: None,
+            budget_usage: None,
+            execution_trace: vec![],
+            fee_market_report: None,
+            effective_cost_schedule: None,
+            footprint: None,
+            state_changes: None,
+            source_location: None,
+            stack_trace: None,
+            wasm_offset: None,
+        }
+    }
+
+    #[test]
+    fn covered_functions_parses_only_invoked_entries() {
+        let lcov = "TN:simulator\nFN:1,foo\nFNDA:2,foo\nFNDA:0,bar\nFNF:2\n";
+        let covered = covered_functions(lcov);
+        assert!(covered.contains("foo"));
+        assert!(!covered.contains("bar"));
+    }
+
+    #[test]
+    fn mutate_flips_a_byte_without_changing_length_shape() {
+        let seed = seed_request();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mutated = mutate(&seed, &mut rng);
+        assert!(mutated.enable_coverage);
+        assert_ne!(mutated.envelope_xdr, seed.envelope_xdr);
+        assert_eq!(mutated.envelope_xdr.len(), seed.envelope_xdr.len());
+    }
+
+    #[test]
+    fn run_fuzz_loop_keeps_inputs_that_grow_coverage() {
+        let seed = seed_request();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut call = 0;
+        let findings = run_fuzz_loop(&seed, 5, &mut rng, |_req| {
+            call += 1;
+            response_with_coverage(&[&format!("fn_{call}")])
+        });
+        assert_eq!(findings.len(), 5);
+        assert!(findings.iter().all(|f| f.kind == FindingKind::NewCoverage));
+        assert!(findings.iter().all(|f| f.coverage_delta == 1));
+    }
+
+    #[test]
+    fn run_fuzz_loop_discards_inputs_with_no_new_signal() {
+        let seed = seed_request();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let findings = run_fuzz_loop(&seed, 5, &mut rng, |_req| response_with_coverage(&["always_same"]));
+        // Only the first iteration introduces "always_same" as new coverage.
+        assert_eq!(findings.len(), 1);
+    }
+
+    #[test]
+    fn run_fuzz_loop_flags_panics_regardless_of_coverage() {
+        let seed = seed_request();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let findings = run_fuzz_loop(&seed, 1, &mut rng, |_req| {
+            let mut response = response_with_coverage(&[]);
+            response.status = "error".to_string();
+            response.error = Some("Simulator panicked: out of memory".to_string());
+            response
+        });
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, FindingKind::Panic);
+    }
+
+    #[test]
+    fn rank_findings_sorts_panics_before_new_coverage() {
+        let seed = seed_request();
+        let findings = vec![
+            FuzzFinding { request: seed.clone(), kind: FindingKind::NewCoverage, coverage_delta: 3 },
+            FuzzFinding { request: seed.clone(), kind: FindingKind::Panic, coverage_delta: 0 },
+            FuzzFinding { request: seed, kind: FindingKind::BudgetExceeded, coverage_delta: 1 },
+        ];
+        let ranked = rank_findings(findings);
+        assert_eq!(ranked[0].kind, FindingKind::Panic);
+        assert_eq!(ranked[1].kind, FindingKind::BudgetExceeded);
+        assert_eq!(ranked[2].kind, FindingKind::NewCoverage);
+    }
+}
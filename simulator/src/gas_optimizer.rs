@@ -1,14 +1,133 @@
 // Copyright 2025 Erst Users
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::types::{ContractCostEntry, ResourceCalibration};
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::process::Command;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BudgetMetrics {
     pub cpu_instructions: u64,
     pub memory_bytes: u64,
     pub total_operations: usize,
+    /// Per-`ContractCostType` tracker readout, so advice can be keyed off
+    /// concrete cost categories (e.g. "cold VM instantiation is expensive")
+    /// rather than only the aggregate totals above.
+    pub cost_breakdown: HashMap<String, ContractCostEntry>,
+}
+
+/// Where a [`MeasuredInstructionCount`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstructionCountSource {
+    /// Counted by a Cachegrind-style deterministic instruction simulator.
+    Cachegrind,
+    /// No deterministic counter was available; the count is a placeholder,
+    /// not a measurement. Callers should not treat `calibrated` as
+    /// meaningful in this case.
+    Estimated,
+}
+
+/// The result of [`BudgetMetrics::measure`]: a raw instruction count for the
+/// measured closure, the instruction count of an empty "do nothing" closure
+/// measured the same way, and `calibrated = raw - calibration` — the
+/// fixed process/measurement-setup overhead canceled out. Because
+/// Cachegrind-style counting is deterministic (unlike wall-clock timing), a
+/// single raw/calibration pair suffices; there's no sampling noise to
+/// average away.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeasuredInstructionCount {
+    pub raw: u64,
+    pub calibration: u64,
+    pub calibrated: u64,
+    pub source: InstructionCountSource,
+}
+
+/// Check whether a Cachegrind-capable `valgrind` is on `PATH`.
+///
+/// Deterministic instruction counting needs `valgrind --tool=cachegrind` to
+/// dynamically re-execute the target, which only works across a process
+/// boundary (the tool instruments a whole process, not an arbitrary
+/// in-process closure). [`BudgetMetrics::measure`] uses this to decide
+/// whether it can report [`InstructionCountSource::Cachegrind`] at all, but
+/// a generic `FnOnce` closure's instructions still can't be isolated this
+/// way without re-executing the current binary under a name-addressable
+/// benchmark registry (as e.g. the `iai` crate does for plain `fn` items) —
+/// out of scope here, so `measure` always reports
+/// [`InstructionCountSource::Estimated`] today even when this returns
+/// `true`. Kept as a separate, honestly-named check so callers (and a
+/// future re-exec-based implementation) have it ready.
+pub fn cachegrind_available() -> bool {
+    Command::new("valgrind")
+        .arg("--tool=cachegrind")
+        .arg("--help")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+impl BudgetMetrics {
+    /// Run `f` and report the deterministic CPU-instruction cost of doing
+    /// so, Cachegrind-style: measure `f`, measure an empty closure as
+    /// calibration, and subtract the calibration count from the raw one so
+    /// fixed setup/teardown instructions don't pollute the result.
+    ///
+    /// `f` is always executed in-process for its side effects. Counting its
+    /// instructions deterministically would require re-executing this
+    /// binary under `valgrind --tool=cachegrind` and addressing `f` by a
+    /// stable symbol name across that process boundary — not possible for
+    /// an arbitrary closure (see [`cachegrind_available`]) — so this
+    /// currently always returns [`InstructionCountSource::Estimated`] with
+    /// an all-zero count rather than fabricating a number. `memory_bytes`
+    /// and `total_operations` are passed through unchanged from the caller,
+    /// who is expected to have them from the host budget already.
+    pub fn measure<F: FnOnce()>(
+        f: F,
+        memory_bytes: u64,
+        total_operations: usize,
+    ) -> (Self, MeasuredInstructionCount) {
+        f();
+
+        let measured = MeasuredInstructionCount {
+            raw: 0,
+            calibration: 0,
+            calibrated: 0,
+            source: InstructionCountSource::Estimated,
+        };
+
+        let metrics = Self {
+            cpu_instructions: measured.calibrated,
+            memory_bytes,
+            total_operations,
+            cost_breakdown: HashMap::new(),
+        };
+
+        (metrics, measured)
+    }
+}
+
+/// One crypto host-function's aggregate call shape within a simulated run:
+/// how many times it was invoked and how many input bytes it processed in
+/// total. Fed into [`GasOptimizationAdvisor::analyze_with_calibration`] so
+/// tips can be backed by the `ResourceCalibration` cost model instead of
+/// only the aggregate CPU/memory totals in `BudgetMetrics`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CryptoCallBreakdown {
+    /// Host-function name, e.g. `"sha256"`, `"keccak256"`, `"ed25519"`.
+    pub function: String,
+    pub invocations: u64,
+    pub total_input_bytes: u64,
+}
+
+/// Per-call cost samples for one operation category (e.g. every
+/// `storage_read` CPU-instruction cost seen in a run), fed to
+/// [`GasOptimizationAdvisor::analyze_operation_profile`] to find anomalous
+/// individual calls rather than judging the category as a whole.
+#[derive(Debug, Clone)]
+pub struct OperationProfile {
+    pub category: String,
+    pub samples: Vec<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -26,12 +145,73 @@ pub struct OptimizationReport {
     pub tips: Vec<OptimizationTip>,
     pub budget_breakdown: HashMap<String, f64>,
     pub comparison_to_baseline: String,
+    /// Concrete stroop cost from the advisor's [`FeeModel`], alongside the
+    /// percentage-based `budget_breakdown`/`comparison_to_baseline` above.
+    pub estimated_fee: u64,
+}
+
+/// Maps a [`BudgetMetrics`] reading to a concrete transaction cost in
+/// stroops, separating each resource's weight (how much of it was used)
+/// from its unit price (what it costs) — the same split
+/// [`crate::types::CostSchedule`] uses at simulation time, but
+/// scoped to the three resources `BudgetMetrics` already tracks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeModel {
+    pub cpu_price: u64,
+    pub mem_price: u64,
+    pub per_op_base: u64,
+}
+
+impl FeeModel {
+    pub fn new(cpu_price: u64, mem_price: u64, per_op_base: u64) -> Self {
+        Self {
+            cpu_price,
+            mem_price,
+            per_op_base,
+        }
+    }
+
+    /// Illustrative stroop prices loosely modeled on typical low-cost
+    /// testnet pricing — not pulled from live network state. Override with
+    /// [`Self::new`] for real pricing, e.g. from `SimulationRequest::cost_schedule`.
+    pub fn default_testnet() -> Self {
+        Self::new(1, 5, 100)
+    }
+
+    /// `fee = cpu_instructions * cpu_price + memory_bytes * mem_price +
+    /// per_op_base * total_operations`, in stroops. Saturating throughout
+    /// so a pathological contract's budget numbers can't overflow and panic
+    /// the advisor.
+    pub fn estimate_fee_stroops(&self, metrics: &BudgetMetrics) -> u64 {
+        metrics
+            .cpu_instructions
+            .saturating_mul(self.cpu_price)
+            .saturating_add(metrics.memory_bytes.saturating_mul(self.mem_price))
+            .saturating_add(self.per_op_base.saturating_mul(metrics.total_operations as u64))
+    }
+}
+
+impl Default for FeeModel {
+    fn default() -> Self {
+        Self::default_testnet()
+    }
 }
 
 pub struct GasOptimizationAdvisor {
     // Baseline metrics for common operations
     baseline_cpu_per_op: u64,
     baseline_memory_per_op: u64,
+    /// p-value cutoff below which [`Self::compare_to_baseline`] treats an
+    /// observed change as statistically significant rather than noise.
+    significance_threshold: f64,
+    /// Minimum relative change [`Self::compare_to_baseline`] cares about,
+    /// even if statistically significant — guards against flagging a
+    /// real-but-tiny 0.1% wiggle.
+    noise_threshold: f64,
+    /// Prices `analyze`/`analyze_with_calibration` use to populate
+    /// `OptimizationReport::estimated_fee` and rank tips by fee saved
+    /// instead of raw percentage.
+    fee_model: FeeModel,
 }
 
 impl GasOptimizationAdvisor {
@@ -39,9 +219,33 @@ impl GasOptimizationAdvisor {
         Self {
             baseline_cpu_per_op: 1000,
             baseline_memory_per_op: 500,
+            significance_threshold: 0.05,
+            noise_threshold: 0.02,
+            fee_model: FeeModel::default_testnet(),
         }
     }
 
+    /// Override the p-value cutoff [`Self::compare_to_baseline`] uses
+    /// (default `0.05`).
+    pub fn with_significance_threshold(mut self, significance_threshold: f64) -> Self {
+        self.significance_threshold = significance_threshold;
+        self
+    }
+
+    /// Override the minimum relative change [`Self::compare_to_baseline`]
+    /// cares about (default `0.02`, i.e. 2%).
+    pub fn with_noise_threshold(mut self, noise_threshold: f64) -> Self {
+        self.noise_threshold = noise_threshold;
+        self
+    }
+
+    /// Override the [`FeeModel`] used to compute `estimated_fee` and rank
+    /// tips (default [`FeeModel::default_testnet`]).
+    pub fn with_fee_model(mut self, fee_model: FeeModel) -> Self {
+        self.fee_model = fee_model;
+        self
+    }
+
     /// Analyze budget metrics and generate optimization suggestions
     pub fn analyze(&self, metrics: &BudgetMetrics) -> OptimizationReport {
         let mut tips = Vec::new();
@@ -150,6 +354,32 @@ impl GasOptimizationAdvisor {
             });
         }
 
+        // Cold vs. cached VM instantiation: the protocol-21 budget charges
+        // these two paths separately, so a high cold-instantiation share of
+        // CPU is a concrete, fixable signal rather than generic "CPU usage".
+        if let Some(cold) = metrics.cost_breakdown.get("VmInstantiation") {
+            let cached_cpu = metrics
+                .cost_breakdown
+                .get("VmCachedInstantiation")
+                .map(|c| c.cpu_insns)
+                .unwrap_or(0);
+            if cold.cpu_insns > 0 && metrics.cpu_instructions > 0 {
+                let cold_share = cold.cpu_insns as f64 / metrics.cpu_instructions as f64 * 100.0;
+                if cold_share > 20.0 {
+                    tips.push(OptimizationTip {
+                        category: "VM Instantiation".to_string(),
+                        severity: if cold_share > 50.0 { "high" } else { "medium" }.to_string(),
+                        message: format!(
+                            "Cold VM instantiation (parse/validate/translate) accounts for {:.1}% of CPU instructions ({} cached-instantiation CPU instructions by comparison). Reusing an already-parsed module across invocations avoids re-paying this cost.",
+                            cold_share, cached_cpu
+                        ),
+                        estimated_savings: "up to full elimination when the module is already cached".to_string(),
+                        code_location: Some("Contract loading / module cache".to_string()),
+                    });
+                }
+            }
+        }
+
         // General best practices
         if tips.is_empty() {
             tips.push(OptimizationTip {
@@ -187,12 +417,125 @@ impl GasOptimizationAdvisor {
             "Poor - contract requires substantial optimization".to_string()
         };
 
+        let estimated_fee = self.fee_model.estimate_fee_stroops(metrics);
+        self.sort_tips_by_fee_impact(&mut tips, metrics);
+
         OptimizationReport {
             overall_efficiency,
             tips,
             budget_breakdown,
             comparison_to_baseline: comparison,
+            estimated_fee,
+        }
+    }
+
+    /// Sort `tips` so the ones targeting the resource that actually
+    /// dominates `metrics`' fee come first, rather than leaving them in
+    /// the fixed order they happened to be generated — a tip that trims 5%
+    /// CPU is deprioritized below one trimming 1% of a memory cost that's
+    /// ten times larger in stroops.
+    fn sort_tips_by_fee_impact(&self, tips: &mut [OptimizationTip], metrics: &BudgetMetrics) {
+        let cpu_fee = metrics.cpu_instructions.saturating_mul(self.fee_model.cpu_price);
+        let memory_fee = metrics.memory_bytes.saturating_mul(self.fee_model.mem_price);
+        let base_fee = self
+            .fee_model
+            .per_op_base
+            .saturating_mul(metrics.total_operations as u64);
+        tips.sort_by_key(|tip| std::cmp::Reverse(tip_fee_weight(&tip.category, cpu_fee, memory_fee, base_fee)));
+    }
+
+    /// Like [`Self::analyze`], but additionally models the CPU cost of
+    /// individual crypto host-function calls from `calibration`'s
+    /// fixed/per-byte coefficients (`modeled cost = fixed + per_byte *
+    /// bytes`), so a tip can name the specific primitive dominating the
+    /// budget — and whether a cheaper one would do — instead of only
+    /// reporting aggregate CPU usage.
+    pub fn analyze_with_calibration(
+        &self,
+        metrics: &BudgetMetrics,
+        calibration: &ResourceCalibration,
+        calls: &[CryptoCallBreakdown],
+    ) -> OptimizationReport {
+        let mut report = self.analyze(metrics);
+
+        let modeled_cost = |function: &str, invocations: u64, bytes: u64| -> Option<u64> {
+            let (fixed, per_byte) = match function {
+                "sha256" => (calibration.sha256_fixed, calibration.sha256_per_byte),
+                "keccak256" => (calibration.keccak256_fixed, calibration.keccak256_per_byte),
+                "ed25519" => (calibration.ed25519_fixed, 0),
+                _ => return None,
+            };
+            Some(fixed.saturating_mul(invocations).saturating_add(per_byte.saturating_mul(bytes)))
+        };
+
+        let mut dominant: Option<(&CryptoCallBreakdown, u64)> = None;
+        for call in calls {
+            if let Some(cost) = modeled_cost(&call.function, call.invocations, call.total_input_bytes) {
+                if dominant.map(|(_, c)| cost > c).unwrap_or(true) {
+                    dominant = Some((call, cost));
+                }
+            }
+        }
+
+        if let Some((call, cost)) = dominant {
+            if cost > 0 && metrics.cpu_instructions > 0 {
+                let share = cost as f64 / metrics.cpu_instructions as f64 * 100.0;
+                if share > 15.0 {
+                    report.tips.push(OptimizationTip {
+                        category: "Crypto Primitive".to_string(),
+                        severity: if share > 40.0 { "high" } else { "medium" }.to_string(),
+                        message: format!(
+                            "{} accounts for an estimated {:.1}% of CPU instructions across {} call(s), based on calibrated cost coefficients.",
+                            call.function, share, call.invocations
+                        ),
+                        estimated_savings: "see accompanying primitive-specific tips".to_string(),
+                        code_location: Some(call.function.clone()),
+                    });
+                }
+            }
+        }
+
+        if let Some(keccak) = calls.iter().find(|c| c.function == "keccak256" && c.invocations > 0) {
+            let avg_bytes = keccak.total_input_bytes / keccak.invocations;
+            let keccak_per_call = calibration.keccak256_fixed + calibration.keccak256_per_byte * avg_bytes;
+            let sha256_per_call = calibration.sha256_fixed + calibration.sha256_per_byte * avg_bytes;
+            if keccak_per_call > sha256_per_call {
+                report.tips.push(OptimizationTip {
+                    category: "Crypto Primitive".to_string(),
+                    severity: "medium".to_string(),
+                    message: format!(
+                        "keccak256 was called {} time(s) (~{} bytes/call) and is modeled at {} CPU instructions/call versus {} for sha256 at the same input size; switch to sha256 unless the contract specifically requires Keccak.",
+                        keccak.invocations, avg_bytes, keccak_per_call, sha256_per_call
+                    ),
+                    estimated_savings: format!(
+                        "~{:.0}% per hash call",
+                        (1.0 - sha256_per_call as f64 / keccak_per_call as f64) * 100.0
+                    ),
+                    code_location: Some("keccak256".to_string()),
+                });
+            }
         }
+
+        for call in calls {
+            if call.invocations > 10 {
+                let avg_bytes = call.total_input_bytes / call.invocations;
+                if avg_bytes > 0 && avg_bytes < 64 {
+                    report.tips.push(OptimizationTip {
+                        category: "Crypto Primitive".to_string(),
+                        severity: "low".to_string(),
+                        message: format!(
+                            "{} was called {} times with a small average input (~{} bytes/call); consider hashing one concatenated buffer instead of many small ones to amortize the fixed per-call cost.",
+                            call.function, call.invocations, avg_bytes
+                        ),
+                        estimated_savings: "amortizes the fixed per-call cost across fewer invocations".to_string(),
+                        code_location: Some(call.function.clone()),
+                    });
+                }
+            }
+        }
+
+        self.sort_tips_by_fee_impact(&mut report.tips, metrics);
+        report
     }
 
     /// Analyze specific operation patterns
@@ -237,6 +580,275 @@ impl GasOptimizationAdvisor {
             _ => None,
         }
     }
+
+    /// Find anomalous individual calls within `profile` using Tukey's
+    /// fences, rather than flagging a whole category by a fixed count
+    /// threshold the way [`Self::analyze_operation_pattern`] does.
+    ///
+    /// Computes the first/third quartiles (Q1/Q3) and interquartile range
+    /// `IQR = Q3 - Q1` of `profile.samples`, then classifies each sample as
+    /// a mild outlier beyond `Q1 - 1.5*IQR`/`Q3 + 1.5*IQR` or a severe one
+    /// beyond `Q1 - 3*IQR`/`Q3 + 3*IQR`. These fences adapt to each
+    /// contract's own cost distribution instead of a magic number, so a
+    /// single pathological call stands out even when the category looks
+    /// fine on average.
+    ///
+    /// Only severe high-side outliers are surfaced, one tip per offending
+    /// sample with its index (into `profile.samples`) as `code_location` —
+    /// this is meant to point at the one call site worth investigating, not
+    /// to flag every mild or low-side deviation.
+    pub fn analyze_operation_profile(&self, profile: &OperationProfile) -> Vec<OptimizationTip> {
+        if profile.samples.len() < 4 {
+            return Vec::new();
+        }
+
+        let mut sorted = profile.samples.clone();
+        sorted.sort_unstable();
+        let q1 = quartile(&sorted, 0.25) as f64;
+        let q3 = quartile(&sorted, 0.75) as f64;
+        let median = quartile(&sorted, 0.5);
+        let iqr = q3 - q1;
+        let severe_high_fence = q3 + 3.0 * iqr;
+
+        profile
+            .samples
+            .iter()
+            .enumerate()
+            .filter(|(_, &sample)| sample as f64 > severe_high_fence)
+            .map(|(index, &sample)| OptimizationTip {
+                category: profile.category.clone(),
+                severity: "high".to_string(),
+                message: if median > 0 {
+                    format!(
+                        "{} call #{} cost {} CPU instructions, {:.1}x the category median ({}); this is a severe statistical outlier (beyond Q3 + 3*IQR), not just a slow category overall.",
+                        profile.category, index, sample, sample as f64 / median as f64, median
+                    )
+                } else {
+                    format!(
+                        "{} call #{} cost {} CPU instructions, a severe statistical outlier (beyond Q3 + 3*IQR) within this category.",
+                        profile.category, index, sample
+                    )
+                },
+                estimated_savings: "investigate this specific call site".to_string(),
+                code_location: Some(index.to_string()),
+            })
+            .collect()
+    }
+
+    /// Detect whether `current` regresses on `baseline`, the way a
+    /// benchmark harness flags performance changes, instead of only
+    /// comparing a single run against the fixed budget percentages
+    /// `analyze` uses.
+    ///
+    /// Treats `current`/`baseline` as samples, computes mean CPU/memory per
+    /// operation for each, and expresses the difference as a relative
+    /// change `(current_mean - baseline_mean) / baseline_mean`.
+    /// Significance is established by resampling: `bootstrap_samples` draws
+    /// from the pooled `current`+`baseline` data estimate the null
+    /// distribution of the relative-change statistic, and the p-value is
+    /// the fraction of those draws at least as extreme as the observed
+    /// change. A change is `Regressed`/`Improved` only when it clears both
+    /// `self.significance_threshold` (p-value) and `self.noise_threshold`
+    /// (minimum relative change) — see [`Self::with_significance_threshold`]/
+    /// [`Self::with_noise_threshold`].
+    pub fn compare_to_baseline(
+        &self,
+        current: &[BudgetMetrics],
+        baseline: &[BudgetMetrics],
+        rng: &mut impl RngCore,
+    ) -> ComparisonReport {
+        self.compare_to_baseline_with_bootstrap_samples(current, baseline, 10_000, rng)
+    }
+
+    /// Like [`Self::compare_to_baseline`], but with the number of bootstrap
+    /// resamples as an explicit parameter instead of the fixed `10_000` —
+    /// useful for keeping tests fast.
+    pub fn compare_to_baseline_with_bootstrap_samples(
+        &self,
+        current: &[BudgetMetrics],
+        baseline: &[BudgetMetrics],
+        bootstrap_samples: usize,
+        rng: &mut impl RngCore,
+    ) -> ComparisonReport {
+        let cpu_current: Vec<f64> = current.iter().map(cpu_per_operation).collect();
+        let cpu_baseline: Vec<f64> = baseline.iter().map(cpu_per_operation).collect();
+        let memory_current: Vec<f64> = current.iter().map(memory_per_operation).collect();
+        let memory_baseline: Vec<f64> = baseline.iter().map(memory_per_operation).collect();
+
+        ComparisonReport {
+            cpu: self.compare_metric(&cpu_current, &cpu_baseline, bootstrap_samples, rng),
+            memory: self.compare_metric(&memory_current, &memory_baseline, bootstrap_samples, rng),
+        }
+    }
+
+    /// Render `report` through one of [`crate::formatters`]'s output
+    /// backends, so a caller (e.g. a CI pipeline) can consume gas-
+    /// optimization results without depending on this crate's internal
+    /// types.
+    pub fn render(&self, report: &OptimizationReport, format: crate::formatters::ReportFormat) -> String {
+        match format {
+            crate::formatters::ReportFormat::Json => crate::formatters::JsonFormatter::format(report),
+            crate::formatters::ReportFormat::Junit => crate::formatters::JunitFormatter::format(report),
+        }
+    }
+
+    fn compare_metric(
+        &self,
+        current: &[f64],
+        baseline: &[f64],
+        bootstrap_samples: usize,
+        rng: &mut impl RngCore,
+    ) -> MetricComparison {
+        let observed = relative_change(mean(current), mean(baseline));
+
+        // p-value: resample current-sized and baseline-sized groups from
+        // the pooled data (the null hypothesis that there's no real
+        // difference between the two) and see how often a relative change
+        // at least as extreme as `observed` shows up by chance alone.
+        let pooled: Vec<f64> = current.iter().chain(baseline.iter()).copied().collect();
+        let mut as_extreme = 0usize;
+        for _ in 0..bootstrap_samples {
+            let boot_current = mean(&resample(&pooled, current.len().max(1), rng));
+            let boot_baseline = mean(&resample(&pooled, baseline.len().max(1), rng));
+            if relative_change(boot_current, boot_baseline).abs() >= observed.abs() {
+                as_extreme += 1;
+            }
+        }
+        let p_value = as_extreme as f64 / bootstrap_samples.max(1) as f64;
+
+        // Confidence interval: resample `current` and `baseline`
+        // independently (not pooled) to capture the sampling variability of
+        // the observed relative change itself, rather than the null
+        // distribution used above for the p-value.
+        let mut ci_changes: Vec<f64> = (0..bootstrap_samples)
+            .map(|_| {
+                let boot_current = mean(&resample(current, current.len().max(1), rng));
+                let boot_baseline = mean(&resample(baseline, baseline.len().max(1), rng));
+                relative_change(boot_current, boot_baseline)
+            })
+            .collect();
+        ci_changes.sort_by(|a, b| a.partial_cmp(b).expect("relative change is never NaN"));
+        let confidence_interval = (
+            percentile(&ci_changes, 0.025),
+            percentile(&ci_changes, 0.975),
+        );
+
+        let verdict = if p_value <= self.significance_threshold && observed.abs() >= self.noise_threshold {
+            if observed > 0.0 {
+                RegressionVerdict::Regressed
+            } else {
+                RegressionVerdict::Improved
+            }
+        } else {
+            RegressionVerdict::NoChange
+        };
+
+        MetricComparison {
+            verdict,
+            relative_change: observed,
+            confidence_interval,
+            p_value,
+        }
+    }
+}
+
+/// The stroop fee component a tip's category actually targets, used by
+/// [`GasOptimizationAdvisor::sort_tips_by_fee_impact`] to rank tips by
+/// real cost rather than the order they happened to be generated in.
+fn tip_fee_weight(category: &str, cpu_fee: u64, memory_fee: u64, base_fee: u64) -> u64 {
+    match category {
+        "CPU Usage" | "Budget Allocation" | "VM Instantiation" | "Loop Optimization"
+        | "Crypto Primitive" => cpu_fee,
+        "Memory Usage" | "Memory Efficiency" => memory_fee,
+        "Storage Access" => base_fee,
+        _ => 0,
+    }
+}
+
+fn cpu_per_operation(metrics: &BudgetMetrics) -> f64 {
+    if metrics.total_operations > 0 {
+        metrics.cpu_instructions as f64 / metrics.total_operations as f64
+    } else {
+        metrics.cpu_instructions as f64
+    }
+}
+
+fn memory_per_operation(metrics: &BudgetMetrics) -> f64 {
+    if metrics.total_operations > 0 {
+        metrics.memory_bytes as f64 / metrics.total_operations as f64
+    } else {
+        metrics.memory_bytes as f64
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+fn relative_change(current_mean: f64, baseline_mean: f64) -> f64 {
+    if baseline_mean == 0.0 {
+        0.0
+    } else {
+        (current_mean - baseline_mean) / baseline_mean
+    }
+}
+
+fn resample(values: &[f64], n: usize, rng: &mut impl RngCore) -> Vec<f64> {
+    (0..n)
+        .map(|_| values[(rng.next_u64() as usize) % values.len()])
+        .collect()
+}
+
+fn percentile(sorted_values: &[f64], pct: f64) -> f64 {
+    if sorted_values.is_empty() {
+        return 0.0;
+    }
+    let idx = (((sorted_values.len() - 1) as f64) * pct).round() as usize;
+    sorted_values[idx.min(sorted_values.len() - 1)]
+}
+
+/// Like `percentile`, but for the `u64` cost samples
+/// [`GasOptimizationAdvisor::analyze_operation_profile`] works with.
+fn quartile(sorted_values: &[u64], pct: f64) -> u64 {
+    if sorted_values.is_empty() {
+        return 0;
+    }
+    let idx = (((sorted_values.len() - 1) as f64) * pct).round() as usize;
+    sorted_values[idx.min(sorted_values.len() - 1)]
+}
+
+/// Verdict of comparing one [`BudgetMetrics`] resource metric (CPU or
+/// memory, per operation) between a current run and a stored baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegressionVerdict {
+    Regressed,
+    Improved,
+    NoChange,
+}
+
+/// The result of comparing one resource metric between `current` and
+/// `baseline` runs in [`GasOptimizationAdvisor::compare_to_baseline`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricComparison {
+    pub verdict: RegressionVerdict,
+    /// `(current_mean - baseline_mean) / baseline_mean`.
+    pub relative_change: f64,
+    /// 95% bootstrap percentile confidence interval for `relative_change`.
+    pub confidence_interval: (f64, f64),
+    pub p_value: f64,
+}
+
+/// Full result of [`GasOptimizationAdvisor::compare_to_baseline`]: CPU and
+/// memory compared independently, since a contract can regress on one
+/// without the other.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    pub cpu: MetricComparison,
+    pub memory: MetricComparison,
 }
 
 impl Default for GasOptimizationAdvisor {
@@ -244,3 +856,245 @@ impl Default for GasOptimizationAdvisor {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_measure_runs_the_closure() {
+        let mut ran = false;
+        let (_, _) = BudgetMetrics::measure(
+            || {
+                ran = true;
+            },
+            0,
+            0,
+        );
+        assert!(ran);
+    }
+
+    #[test]
+    fn test_measure_reports_estimated_source_without_cachegrind_re_exec() {
+        // `measure` can't isolate an arbitrary closure's instructions
+        // without re-executing this binary (see `cachegrind_available`'s
+        // doc comment), so it always reports `Estimated` today regardless
+        // of whether `valgrind` happens to be installed on this machine.
+        let (metrics, measured) = BudgetMetrics::measure(|| {}, 1234, 7);
+        assert_eq!(measured.source, InstructionCountSource::Estimated);
+        assert_eq!(measured.calibrated, measured.raw.saturating_sub(measured.calibration));
+        assert_eq!(metrics.memory_bytes, 1234);
+        assert_eq!(metrics.total_operations, 7);
+    }
+
+    fn metrics_with(cpu_instructions: u64, memory_bytes: u64) -> BudgetMetrics {
+        BudgetMetrics {
+            cpu_instructions,
+            memory_bytes,
+            total_operations: 1,
+            cost_breakdown: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_compare_to_baseline_flags_a_clear_regression() {
+        let advisor = GasOptimizationAdvisor::new();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let baseline: Vec<BudgetMetrics> = (0..20)
+            .map(|i| metrics_with(1000 + (i % 3), 500))
+            .collect();
+        let current: Vec<BudgetMetrics> = (0..20)
+            .map(|i| metrics_with(2000 + (i % 3), 500))
+            .collect();
+
+        let report = advisor.compare_to_baseline_with_bootstrap_samples(&current, &baseline, 500, &mut rng);
+
+        assert_eq!(report.cpu.verdict, RegressionVerdict::Regressed);
+        assert!(report.cpu.relative_change > 0.0);
+        assert!(report.cpu.p_value <= advisor_significance_threshold());
+    }
+
+    #[test]
+    fn test_compare_to_baseline_flags_a_clear_improvement() {
+        let advisor = GasOptimizationAdvisor::new();
+        let mut rng = StdRng::seed_from_u64(2);
+
+        let baseline: Vec<BudgetMetrics> = (0..20)
+            .map(|i| metrics_with(2000 + (i % 3), 500))
+            .collect();
+        let current: Vec<BudgetMetrics> = (0..20)
+            .map(|i| metrics_with(1000 + (i % 3), 500))
+            .collect();
+
+        let report = advisor.compare_to_baseline_with_bootstrap_samples(&current, &baseline, 500, &mut rng);
+
+        assert_eq!(report.cpu.verdict, RegressionVerdict::Improved);
+        assert!(report.cpu.relative_change < 0.0);
+    }
+
+    #[test]
+    fn test_compare_to_baseline_reports_no_change_for_identical_samples() {
+        let advisor = GasOptimizationAdvisor::new();
+        let mut rng = StdRng::seed_from_u64(3);
+
+        let baseline: Vec<BudgetMetrics> = (0..20).map(|i| metrics_with(1000 + (i % 5), 500)).collect();
+        let current = baseline.clone();
+
+        let report = advisor.compare_to_baseline_with_bootstrap_samples(&current, &baseline, 500, &mut rng);
+
+        assert_eq!(report.cpu.verdict, RegressionVerdict::NoChange);
+        assert_eq!(report.cpu.relative_change, 0.0);
+        assert_eq!(report.memory.verdict, RegressionVerdict::NoChange);
+    }
+
+    #[test]
+    fn test_compare_to_baseline_suppresses_tiny_but_significant_changes_as_noise() {
+        let advisor = GasOptimizationAdvisor::new().with_noise_threshold(0.5);
+        let mut rng = StdRng::seed_from_u64(4);
+
+        // A consistent ~5% increase is statistically clear-cut with no
+        // overlap between groups, but falls well short of a 50% noise
+        // threshold, so it should still be reported as `NoChange`.
+        let baseline: Vec<BudgetMetrics> = (0..20).map(|_| metrics_with(1000, 500)).collect();
+        let current: Vec<BudgetMetrics> = (0..20).map(|_| metrics_with(1050, 500)).collect();
+
+        let report = advisor.compare_to_baseline_with_bootstrap_samples(&current, &baseline, 500, &mut rng);
+
+        assert_eq!(report.cpu.verdict, RegressionVerdict::NoChange);
+    }
+
+    fn advisor_significance_threshold() -> f64 {
+        GasOptimizationAdvisor::new().significance_threshold
+    }
+
+    #[test]
+    fn test_analyze_operation_profile_flags_a_severe_high_side_outlier() {
+        let advisor = GasOptimizationAdvisor::new();
+        let mut samples = vec![100u64; 19];
+        samples.push(10_000);
+        let profile = OperationProfile {
+            category: "storage_read".to_string(),
+            samples,
+        };
+
+        let tips = advisor.analyze_operation_profile(&profile);
+
+        assert_eq!(tips.len(), 1);
+        assert_eq!(tips[0].severity, "high");
+        assert_eq!(tips[0].code_location, Some("19".to_string()));
+    }
+
+    #[test]
+    fn test_analyze_operation_profile_ignores_a_tight_distribution() {
+        let advisor = GasOptimizationAdvisor::new();
+        let profile = OperationProfile {
+            category: "storage_read".to_string(),
+            samples: vec![100, 101, 99, 102, 98, 100, 103, 97, 100, 101],
+        };
+
+        let tips = advisor.analyze_operation_profile(&profile);
+
+        assert!(tips.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_operation_profile_ignores_mild_outliers() {
+        let advisor = GasOptimizationAdvisor::new();
+        // Sorted, Q1=100 (index 2), Q3=110 (index 6), IQR=10: mild fence is
+        // Q3+15=125, severe fence is Q3+30=140. 130 clears the mild fence
+        // but not the severe one, so it should not be reported.
+        let profile = OperationProfile {
+            category: "storage_read".to_string(),
+            samples: vec![90, 95, 100, 100, 105, 105, 110, 115, 130],
+        };
+
+        let tips = advisor.analyze_operation_profile(&profile);
+
+        assert!(tips.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_operation_profile_requires_at_least_four_samples() {
+        let advisor = GasOptimizationAdvisor::new();
+        let profile = OperationProfile {
+            category: "storage_read".to_string(),
+            samples: vec![1, 1, 100],
+        };
+
+        assert!(advisor.analyze_operation_profile(&profile).is_empty());
+    }
+
+    #[test]
+    fn test_render_dispatches_to_the_requested_format() {
+        let advisor = GasOptimizationAdvisor::new();
+        let report = advisor.analyze(&metrics_with(1000, 500));
+
+        let json = advisor.render(&report, crate::formatters::ReportFormat::Json);
+        assert!(serde_json::from_str::<OptimizationReport>(&json).is_ok());
+
+        let junit = advisor.render(&report, crate::formatters::ReportFormat::Junit);
+        assert!(junit.starts_with("<?xml"));
+    }
+
+    #[test]
+    fn test_fee_model_computes_the_documented_formula() {
+        let model = FeeModel::new(2, 3, 10);
+        let metrics = metrics_with_ops(1000, 2000, 5);
+
+        assert_eq!(model.estimate_fee_stroops(&metrics), 1000 * 2 + 2000 * 3 + 10 * 5);
+    }
+
+    #[test]
+    fn test_fee_model_saturates_instead_of_overflowing() {
+        let model = FeeModel::new(u64::MAX, u64::MAX, u64::MAX);
+        let metrics = metrics_with_ops(u64::MAX, u64::MAX, usize::MAX);
+
+        assert_eq!(model.estimate_fee_stroops(&metrics), u64::MAX);
+    }
+
+    #[test]
+    fn test_analyze_populates_estimated_fee() {
+        let advisor = GasOptimizationAdvisor::new().with_fee_model(FeeModel::new(2, 3, 10));
+        let metrics = metrics_with_ops(1000, 2000, 5);
+
+        let report = advisor.analyze(&metrics);
+
+        assert_eq!(report.estimated_fee, 1000 * 2 + 2000 * 3 + 10 * 5);
+    }
+
+    #[test]
+    fn test_analyze_ranks_tips_by_dominant_fee_component_by_default() {
+        let advisor = GasOptimizationAdvisor::new();
+        // Both a CPU and a Memory tip fire; the default fee model prices
+        // this CPU usage far higher in stroops, so it should rank first.
+        let metrics = metrics_with_ops(100_000, 3_000, 1);
+
+        let report = advisor.analyze(&metrics);
+
+        assert_eq!(report.tips[0].category, "CPU Usage");
+    }
+
+    #[test]
+    fn test_analyze_reorders_tips_when_the_fee_model_changes() {
+        // Same metrics as above, but with a fee model that prices memory
+        // far higher than CPU — the Memory tip should now rank first.
+        let advisor = GasOptimizationAdvisor::new().with_fee_model(FeeModel::new(1, 1_000, 0));
+        let metrics = metrics_with_ops(100_000, 3_000, 1);
+
+        let report = advisor.analyze(&metrics);
+
+        assert_eq!(report.tips[0].category, "Memory Usage");
+    }
+
+    fn metrics_with_ops(cpu_instructions: u64, memory_bytes: u64, total_operations: usize) -> BudgetMetrics {
+        BudgetMetrics {
+            cpu_instructions,
+            memory_bytes,
+            total_operations,
+            cost_breakdown: HashMap::new(),
+        }
+    }
+}
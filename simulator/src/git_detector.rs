@@ -4,12 +4,82 @@
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// The code-hosting forge a remote URL points at, along with the data
+/// needed to build that forge's permalink format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+    Bitbucket,
+    SourceHut,
+    /// Any other host, reachable over the web but with no known permalink
+    /// template. `web_base` is the normalized `https://host` root.
+    Generic { web_base: String },
+}
+
+impl Forge {
+    /// Detect the forge from a normalized remote URL's host.
+    fn detect(remote_url: &str) -> Option<Self> {
+        let host = remote_url
+            .strip_prefix("https://")
+            .or_else(|| remote_url.strip_prefix("http://"))?
+            .split('/')
+            .next()?;
+
+        Some(match host {
+            "github.com" => Forge::GitHub,
+            "gitlab.com" => Forge::GitLab,
+            "bitbucket.org" => Forge::Bitbucket,
+            "sr.ht" | "git.sr.ht" => Forge::SourceHut,
+            _ if host.contains("gitlab") => Forge::GitLab,
+            _ if host.contains("bitbucket") => Forge::Bitbucket,
+            _ => Forge::Generic {
+                web_base: format!("https://{host}"),
+            },
+        })
+    }
+
+    /// Render this forge's permalink template for a file/line pair.
+    fn permalink(
+        &self,
+        remote_url: &str,
+        commit_hash: &str,
+        relative_path: &str,
+        line: u32,
+    ) -> String {
+        match self {
+            Forge::GitHub => format!("{remote_url}/blob/{commit_hash}/{relative_path}#L{line}"),
+            Forge::GitLab => format!("{remote_url}/-/blob/{commit_hash}/{relative_path}#L{line}"),
+            Forge::Bitbucket => {
+                format!("{remote_url}/src/{commit_hash}/{relative_path}#lines-{line}")
+            }
+            Forge::SourceHut => {
+                format!("{remote_url}/tree/{commit_hash}/item/{relative_path}#L{line}")
+            }
+            Forge::Generic { .. } => {
+                // No known permalink template; fall back to the GitHub-style
+                // convention most forges that mimic it also understand.
+                format!("{remote_url}/blob/{commit_hash}/{relative_path}#L{line}")
+            }
+        }
+    }
+}
+
+/// Where a repository's remote points: a forge reachable over the web, or
+/// a local-only checkout (e.g. `file://`) with no web presence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Remote {
+    Hosted(Forge),
+    Local,
+}
+
 #[derive(Debug, Clone)]
 pub struct GitRepository {
     pub remote_url: String,
     pub branch: String,
     pub commit_hash: String,
     pub root_path: PathBuf,
+    pub remote: Remote,
 }
 
 impl GitRepository {
@@ -18,15 +88,28 @@ impl GitRepository {
         let remote_url = Self::get_remote_url(&root_path)?;
         let branch = Self::get_current_branch(&root_path).unwrap_or_else(|| "main".to_string());
         let commit_hash = Self::get_commit_hash(&root_path)?;
+        let remote = Self::classify_remote(&remote_url);
 
         Some(GitRepository {
             remote_url,
             branch,
             commit_hash,
             root_path,
+            remote,
         })
     }
 
+    fn classify_remote(remote_url: &str) -> Remote {
+        if remote_url.starts_with("file://") || !remote_url.starts_with("http") {
+            return Remote::Local;
+        }
+
+        match Forge::detect(remote_url) {
+            Some(forge) => Remote::Hosted(forge),
+            None => Remote::Local,
+        }
+    }
+
     fn find_git_root(start_path: &Path) -> Option<PathBuf> {
         let mut current = start_path.to_path_buf();
         
@@ -93,12 +176,29 @@ impl GitRepository {
         }
     }
 
+    /// Normalize an `ssh://`/`git@host:path` or `https://` remote into a
+    /// `https://host/path` form with any trailing `.git` stripped, so forge
+    /// detection and permalink generation only need to handle one shape.
     fn normalize_git_url(url: &str) -> String {
-        if url.starts_with("git@github.com:") {
-            url.replace("git@github.com:", "https://github.com/")
-                .trim_end_matches(".git")
-                .to_string()
-        } else if url.starts_with("https://github.com/") {
+        if let Some(rest) = url.strip_prefix("ssh://") {
+            let rest = rest.split_once('@').map_or(rest, |(_, r)| r);
+            if let Some((authority, path)) = rest.split_once('/') {
+                let host = authority.split(':').next().unwrap_or(authority);
+                return format!("https://{host}/{path}")
+                    .trim_end_matches(".git")
+                    .to_string();
+            }
+        }
+
+        if let Some(rest) = url.strip_prefix("git@") {
+            if let Some((host, path)) = rest.split_once(':') {
+                return format!("https://{host}/{path}")
+                    .trim_end_matches(".git")
+                    .to_string();
+            }
+        }
+
+        if url.starts_with("https://") || url.starts_with("http://") {
             url.trim_end_matches(".git").to_string()
         } else {
             url.to_string()
@@ -106,23 +206,19 @@ impl GitRepository {
     }
 
     pub fn is_github(&self) -> bool {
-        self.remote_url.contains("github.com")
+        matches!(self.remote, Remote::Hosted(Forge::GitHub))
     }
 
+    /// Build a clickable permalink to `file_path`/`line` in this repository's
+    /// hosted remote, or `None` for a local-only checkout.
     pub fn generate_file_link(&self, file_path: &str, line: u32) -> Option<String> {
-        if !self.is_github() {
-            return None;
-        }
+        let forge = match &self.remote {
+            Remote::Hosted(forge) => forge,
+            Remote::Local => return None,
+        };
 
         let relative_path = self.make_relative_path(file_path)?;
-        
-        Some(format!(
-            "{}/blob/{}/{}#L{}",
-            self.remote_url,
-            self.commit_hash,
-            relative_path,
-            line
-        ))
+        Some(forge.permalink(&self.remote_url, &self.commit_hash, &relative_path, line))
     }
 
     fn make_relative_path(&self, file_path: &str) -> Option<String> {
@@ -143,6 +239,16 @@ impl GitRepository {
 mod tests {
     use super::*;
 
+    fn repo_with(remote_url: &str, remote: Remote) -> GitRepository {
+        GitRepository {
+            remote_url: remote_url.to_string(),
+            branch: "main".to_string(),
+            commit_hash: "abc123def456".to_string(),
+            root_path: PathBuf::from("/tmp/repo"),
+            remote,
+        }
+    }
+
     #[test]
     fn test_normalize_git_url_ssh() {
         let url = "git@github.com:dotandev/hintents.git";
@@ -157,30 +263,112 @@ mod tests {
         assert_eq!(normalized, "https://github.com/dotandev/hintents");
     }
 
+    #[test]
+    fn test_normalize_git_url_ssh_self_hosted() {
+        let url = "git@git.example.com:team/project.git";
+        let normalized = GitRepository::normalize_git_url(url);
+        assert_eq!(normalized, "https://git.example.com/team/project");
+    }
+
+    #[test]
+    fn test_forge_detect() {
+        assert_eq!(
+            Forge::detect("https://github.com/dotandev/hintents"),
+            Some(Forge::GitHub)
+        );
+        assert_eq!(
+            Forge::detect("https://gitlab.com/dotandev/hintents"),
+            Some(Forge::GitLab)
+        );
+        assert_eq!(
+            Forge::detect("https://bitbucket.org/dotandev/hintents"),
+            Some(Forge::Bitbucket)
+        );
+        assert_eq!(
+            Forge::detect("https://git.sr.ht/~dotandev/hintents"),
+            Some(Forge::SourceHut)
+        );
+        assert_eq!(
+            Forge::detect("https://git.example.com/dotandev/hintents"),
+            Some(Forge::Generic {
+                web_base: "https://git.example.com".to_string()
+            })
+        );
+    }
+
     #[test]
     fn test_is_github() {
-        let repo = GitRepository {
-            remote_url: "https://github.com/dotandev/hintents".to_string(),
-            branch: "main".to_string(),
-            commit_hash: "abc123".to_string(),
-            root_path: PathBuf::from("/tmp/repo"),
-        };
+        let repo = repo_with(
+            "https://github.com/dotandev/hintents",
+            Remote::Hosted(Forge::GitHub),
+        );
         assert!(repo.is_github());
     }
 
     #[test]
-    fn test_generate_file_link() {
-        let repo = GitRepository {
-            remote_url: "https://github.com/dotandev/hintents".to_string(),
-            branch: "main".to_string(),
-            commit_hash: "abc123def456".to_string(),
-            root_path: PathBuf::from("/tmp/repo"),
-        };
-
+    fn test_generate_file_link_github() {
+        let repo = repo_with(
+            "https://github.com/dotandev/hintents",
+            Remote::Hosted(Forge::GitHub),
+        );
         let link = repo.generate_file_link("src/token.rs", 45);
         assert_eq!(
             link,
             Some("https://github.com/dotandev/hintents/blob/abc123def456/src/token.rs#L45".to_string())
         );
     }
+
+    #[test]
+    fn test_generate_file_link_gitlab() {
+        let repo = repo_with(
+            "https://gitlab.com/dotandev/hintents",
+            Remote::Hosted(Forge::GitLab),
+        );
+        let link = repo.generate_file_link("src/token.rs", 45);
+        assert_eq!(
+            link,
+            Some(
+                "https://gitlab.com/dotandev/hintents/-/blob/abc123def456/src/token.rs#L45"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_generate_file_link_bitbucket() {
+        let repo = repo_with(
+            "https://bitbucket.org/dotandev/hintents",
+            Remote::Hosted(Forge::Bitbucket),
+        );
+        let link = repo.generate_file_link("src/token.rs", 45);
+        assert_eq!(
+            link,
+            Some(
+                "https://bitbucket.org/dotandev/hintents/src/abc123def456/src/token.rs#lines-45"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_generate_file_link_sourcehut() {
+        let repo = repo_with(
+            "https://git.sr.ht/~dotandev/hintents",
+            Remote::Hosted(Forge::SourceHut),
+        );
+        let link = repo.generate_file_link("src/token.rs", 45);
+        assert_eq!(
+            link,
+            Some(
+                "https://git.sr.ht/~dotandev/hintents/tree/abc123def456/item/src/token.rs#L45"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_generate_file_link_local_checkout_is_none() {
+        let repo = repo_with("file:///tmp/repo", Remote::Local);
+        assert_eq!(repo.generate_file_link("src/token.rs", 45), None);
+    }
 }
@@ -0,0 +1,411 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Self-signed X.509 certificate issuance for any [`Signer`].
+//!
+//! [`Signer::public_key`] already returns a DER `SubjectPublicKeyInfo`, but
+//! some downstream consumers (TLS stacks, code-signing tools, anything that
+//! expects PKIX rather than a bare public key) want a certificate wrapping
+//! it instead. [`CertBuilder`] builds one entirely from the DER primitives
+//! hand-rolled below rather than pulling in a general-purpose X.509 crate:
+//! every such crate we looked at expects a synchronous `signature::Signer`,
+//! while [`Signer::sign`] here is async (software, PKCS#11, and the other
+//! backends all need to be able to do I/O to produce a signature), so
+//! bridging would mean blocking on async from inside a sync callback. The
+//! certificate's `subjectPublicKeyInfo` is the signer's own `spki_bytes`
+//! copied in verbatim (it's already valid DER); only the signature
+//! algorithm identifier and the raw-to-DER signature conversion
+//! (ECDSA only — [`raw_ecdsa_to_der`]) need to be chosen based on the
+//! signer's reported algorithm.
+
+use super::pkcs11::raw_ecdsa_to_der;
+use super::{Signer, SignerError};
+use base64::Engine;
+
+/// Errors from [`CertBuilder::build_self_signed`].
+#[derive(Debug, thiserror::Error)]
+pub enum CertError {
+    #[error("unsupported signer algorithm for X.509 certificate issuance: {0}")]
+    UnsupportedAlgorithm(String),
+
+    #[error("invalid RFC 3339 timestamp {0}: {1}")]
+    InvalidTimestamp(String, String),
+
+    #[error("signing error: {0}")]
+    Signer(#[from] SignerError),
+}
+
+/// A minimal X.509 `Name` (RDNSequence): just the handful of attributes
+/// most internal/demo certificates need. Each present field becomes one
+/// single-attribute RDN, in `common_name, organization, country` order.
+#[derive(Debug, Clone, Default)]
+pub struct DistinguishedName {
+    pub common_name: Option<String>,
+    pub organization: Option<String>,
+    pub country: Option<String>,
+}
+
+impl DistinguishedName {
+    pub fn common_name(name: &str) -> Self {
+        Self {
+            common_name: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// A DER-encoded self-signed certificate, and its PEM encoding for tools
+/// that expect one.
+#[derive(Debug, Clone)]
+pub struct Certificate {
+    pub der: Vec<u8>,
+}
+
+impl Certificate {
+    /// PEM encoding (`-----BEGIN CERTIFICATE-----` ... `-----END
+    /// CERTIFICATE-----`), base64 body wrapped at 64 columns per RFC 7468.
+    pub fn to_pem(&self) -> String {
+        let body = base64::engine::general_purpose::STANDARD.encode(&self.der);
+        let mut pem = String::from("-----BEGIN CERTIFICATE-----\n");
+        for line in body.as_bytes().chunks(64) {
+            pem.push_str(std::str::from_utf8(line).expect("base64 output is ASCII"));
+            pem.push('\n');
+        }
+        pem.push_str("-----END CERTIFICATE-----\n");
+        pem
+    }
+}
+
+/// Builds self-signed X.509v3 certificates around a [`Signer`]'s own key.
+#[derive(Debug, Clone)]
+pub struct CertBuilder {
+    subject: DistinguishedName,
+    serial_number: Vec<u8>,
+    not_before: String,
+    not_after: String,
+    is_ca: bool,
+}
+
+impl CertBuilder {
+    /// A builder for `subject`, valid from `not_before` to `not_after`
+    /// (both RFC 3339 timestamps), with serial number `1` and no CA
+    /// extensions set. Use [`CertBuilder::with_serial_number`] or
+    /// [`CertBuilder::with_ca`] to override either.
+    pub fn new(subject: DistinguishedName, not_before: &str, not_after: &str) -> Self {
+        Self {
+            subject,
+            serial_number: vec![1],
+            not_before: not_before.to_string(),
+            not_after: not_after.to_string(),
+            is_ca: false,
+        }
+    }
+
+    pub fn with_serial_number(mut self, serial_number: &[u8]) -> Self {
+        self.serial_number = serial_number.to_vec();
+        self
+    }
+
+    /// Set the `basicConstraints` `cA` flag (`false` by default, i.e. an
+    /// end-entity certificate).
+    pub fn with_ca(mut self, is_ca: bool) -> Self {
+        self.is_ca = is_ca;
+        self
+    }
+
+    /// Build a self-signed certificate over `signer`'s own public key:
+    /// subject and issuer are both `self.subject`, and `signer.sign` signs
+    /// the `TBSCertificate` DER. The signature algorithm OID is chosen from
+    /// `signer.signer_info().algorithm` (Ed25519, or ECDSA with SHA-256 for
+    /// secp256k1/secp256r1); any other algorithm is rejected before
+    /// touching the signer.
+    pub async fn build_self_signed(&self, signer: &dyn Signer) -> Result<Certificate, CertError> {
+        let algorithm = signer.signer_info().algorithm;
+        let signature_alg_der = signature_algorithm_der(&algorithm)?;
+
+        let public_key = signer.public_key().await?;
+        let name_der = self.name_der();
+        let tbs = der::sequence(&[
+            // [0] EXPLICIT version INTEGER { v3(2) }
+            der::context_tag(0, &der::integer(&[2])),
+            der::integer(&self.serial_number),
+            signature_alg_der.clone(),
+            name_der.clone(),
+            self.validity_der()?,
+            name_der,
+            public_key.spki_bytes.clone(),
+            der::context_tag(3, &self.extensions_der()),
+        ]);
+
+        let signature = signer.sign(&tbs).await?;
+        let signature_bytes = match algorithm.as_str() {
+            "ed25519" | "Ed25519" => signature.bytes,
+            "ES256K" | "ES256" | "secp256k1" | "secp256r1" => raw_ecdsa_to_der(&signature.bytes)?,
+            other => return Err(CertError::UnsupportedAlgorithm(other.to_string())),
+        };
+
+        let der = der::sequence(&[tbs, signature_alg_der, der::bit_string(&signature_bytes)]);
+        Ok(Certificate { der })
+    }
+
+    fn name_der(&self) -> Vec<u8> {
+        let mut rdns = Vec::new();
+        if let Some(cn) = &self.subject.common_name {
+            rdns.push(der::rdn(&OID_COMMON_NAME, cn));
+        }
+        if let Some(o) = &self.subject.organization {
+            rdns.push(der::rdn(&OID_ORGANIZATION_NAME, o));
+        }
+        if let Some(c) = &self.subject.country {
+            rdns.push(der::rdn(&OID_COUNTRY_NAME, c));
+        }
+        der::sequence(&rdns)
+    }
+
+    fn validity_der(&self) -> Result<Vec<u8>, CertError> {
+        Ok(der::sequence(&[
+            der::generalized_time(&self.not_before)?,
+            der::generalized_time(&self.not_after)?,
+        ]))
+    }
+
+    /// `basicConstraints` (critical) plus a `keyUsage` (critical) of
+    /// `digitalSignature` and, when [`CertBuilder::with_ca`] is set,
+    /// `keyCertSign`.
+    fn extensions_der(&self) -> Vec<u8> {
+        let basic_constraints_value = if self.is_ca {
+            der::sequence(&[der::boolean(true)])
+        } else {
+            der::sequence(&[])
+        };
+        let key_usage_bits: u8 = if self.is_ca { 0b1000_0100 } else { 0b1000_0000 };
+
+        der::sequence(&[
+            der::extension(&OID_BASIC_CONSTRAINTS, true, &basic_constraints_value),
+            der::extension(&OID_KEY_USAGE, true, &der::bit_string(&[key_usage_bits])),
+        ])
+    }
+}
+
+const OID_COMMON_NAME: [u64; 4] = [2, 5, 4, 3];
+const OID_ORGANIZATION_NAME: [u64; 4] = [2, 5, 4, 10];
+const OID_COUNTRY_NAME: [u64; 4] = [2, 5, 4, 6];
+const OID_BASIC_CONSTRAINTS: [u64; 4] = [2, 5, 29, 19];
+const OID_KEY_USAGE: [u64; 4] = [2, 5, 29, 15];
+const OID_ED25519: [u64; 4] = [1, 3, 101, 112];
+const OID_ECDSA_WITH_SHA256: [u64; 7] = [1, 2, 840, 10045, 4, 3, 2];
+
+fn signature_algorithm_der(algorithm: &str) -> Result<Vec<u8>, CertError> {
+    match algorithm {
+        "ed25519" | "Ed25519" => Ok(der::sequence(&[der::oid(&OID_ED25519)])),
+        "ES256K" | "ES256" | "secp256k1" | "secp256r1" => {
+            Ok(der::sequence(&[der::oid(&OID_ECDSA_WITH_SHA256)]))
+        }
+        other => Err(CertError::UnsupportedAlgorithm(other.to_string())),
+    }
+}
+
+/// Hand-rolled ASN.1 DER primitives — just enough to build the
+/// `TBSCertificate`/`Certificate` structures above. We avoid a
+/// general-purpose ASN.1/X.509 crate for the reasons explained in the
+/// module doc comment; this is deliberately not a general-purpose encoder.
+mod der {
+    fn encode_length(len: usize, out: &mut Vec<u8>) {
+        if len < 0x80 {
+            out.push(len as u8);
+        } else {
+            let len_bytes = len.to_be_bytes();
+            let first_nonzero = len_bytes.iter().position(|&b| b != 0).unwrap_or(len_bytes.len() - 1);
+            let significant = &len_bytes[first_nonzero..];
+            out.push(0x80 | significant.len() as u8);
+            out.extend_from_slice(significant);
+        }
+    }
+
+    fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut out = vec![tag];
+        encode_length(content.len(), &mut out);
+        out.extend_from_slice(content);
+        out
+    }
+
+    pub fn sequence(members: &[Vec<u8>]) -> Vec<u8> {
+        tlv(0x30, &members.concat())
+    }
+
+    pub fn integer(unsigned_be_bytes: &[u8]) -> Vec<u8> {
+        let mut bytes = unsigned_be_bytes.to_vec();
+        while bytes.len() > 1 && bytes[0] == 0 {
+            bytes.remove(0);
+        }
+        if bytes.is_empty() {
+            bytes.push(0);
+        }
+        if bytes[0] & 0x80 != 0 {
+            bytes.insert(0, 0);
+        }
+        tlv(0x02, &bytes)
+    }
+
+    pub fn boolean(value: bool) -> Vec<u8> {
+        tlv(0x01, &[if value { 0xff } else { 0x00 }])
+    }
+
+    pub fn oid(arcs: &[u64]) -> Vec<u8> {
+        let mut content = vec![(arcs[0] * 40 + arcs[1]) as u8];
+        for &arc in &arcs[2..] {
+            if arc == 0 {
+                content.push(0);
+                continue;
+            }
+            let mut chunks = Vec::new();
+            let mut remaining = arc;
+            while remaining > 0 {
+                chunks.push((remaining & 0x7f) as u8);
+                remaining >>= 7;
+            }
+            chunks.reverse();
+            for (i, chunk) in chunks.iter().enumerate() {
+                let continuation = if i + 1 < chunks.len() { 0x80 } else { 0x00 };
+                content.push(chunk | continuation);
+            }
+        }
+        tlv(0x06, &content)
+    }
+
+    /// A `BIT STRING` with zero unused trailing bits — every signature and
+    /// key-usage flag field this module encodes is already byte-aligned.
+    pub fn bit_string(bytes: &[u8]) -> Vec<u8> {
+        let mut content = vec![0u8];
+        content.extend_from_slice(bytes);
+        tlv(0x03, &content)
+    }
+
+    pub fn printable_string(s: &str) -> Vec<u8> {
+        tlv(0x13, s.as_bytes())
+    }
+
+    /// `GeneralizedTime`, used uniformly (rather than switching to
+    /// `UTCTime` before the year 2050 per RFC 5280) since every consumer
+    /// of these certificates already needs a general ASN.1 parser.
+    /// `timestamp` must be an RFC 3339 string; it's reformatted to
+    /// `YYYYMMDDHHMMSSZ`.
+    pub fn generalized_time(timestamp: &str) -> Result<Vec<u8>, super::CertError> {
+        let parsed = chrono::DateTime::parse_from_rfc3339(timestamp)
+            .map_err(|e| super::CertError::InvalidTimestamp(timestamp.to_string(), e.to_string()))?;
+        let formatted = parsed.format("%Y%m%d%H%M%SZ").to_string();
+        Ok(tlv(0x18, formatted.as_bytes()))
+    }
+
+    /// A single-attribute `RelativeDistinguishedName`: `SET OF
+    /// AttributeTypeAndValue`, where the lone `AttributeTypeAndValue` is
+    /// `SEQUENCE { OBJECT IDENTIFIER, PrintableString }`.
+    pub fn rdn(oid_arcs: &[u64], value: &str) -> Vec<u8> {
+        let attribute = sequence(&[oid(oid_arcs), printable_string(value)]);
+        tlv(0x31, &attribute)
+    }
+
+    /// `Extension ::= SEQUENCE { extnID OBJECT IDENTIFIER, critical
+    /// BOOLEAN DEFAULT FALSE, extnValue OCTET STRING }`, where `extnValue`
+    /// wraps the already-DER-encoded `value`.
+    pub fn extension(oid_arcs: &[u64], critical: bool, value: &[u8]) -> Vec<u8> {
+        let mut members = vec![oid(oid_arcs)];
+        if critical {
+            members.push(boolean(true));
+        }
+        members.push(tlv(0x04, value));
+        sequence(&members)
+    }
+
+    /// Constructed, context-specific `[n] EXPLICIT content`.
+    pub fn context_tag(n: u8, content: &[u8]) -> Vec<u8> {
+        tlv(0xa0 | n, content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hsm::software::{P256SoftwareSigner, Secp256k1SoftwareSigner, SoftwareSigner};
+
+    fn builder() -> CertBuilder {
+        CertBuilder::new(
+            DistinguishedName::common_name("erst-test"),
+            "2020-01-01T00:00:00Z",
+            "2999-01-01T00:00:00Z",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_build_self_signed_with_ed25519_signer() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let cert = builder().build_self_signed(&signer).await.unwrap();
+
+        assert!(!cert.der.is_empty());
+        assert_eq!(cert.der[0], 0x30, "certificate must be a DER SEQUENCE");
+
+        let pem = cert.to_pem();
+        assert!(pem.starts_with("-----BEGIN CERTIFICATE-----\n"));
+        assert!(pem.trim_end().ends_with("-----END CERTIFICATE-----"));
+    }
+
+    #[tokio::test]
+    async fn test_build_self_signed_with_secp256k1_signer() {
+        let (signer, _pem) = Secp256k1SoftwareSigner::generate().unwrap();
+        let cert = builder().build_self_signed(&signer).await.unwrap();
+        assert!(!cert.der.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_build_self_signed_with_p256_signer() {
+        let (signer, _pem) = P256SoftwareSigner::generate().unwrap();
+        let cert = builder().build_self_signed(&signer).await.unwrap();
+        assert!(!cert.der.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_build_self_signed_rejects_an_invalid_timestamp() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let builder = CertBuilder::new(
+            DistinguishedName::common_name("erst-test"),
+            "not-a-timestamp",
+            "2999-01-01T00:00:00Z",
+        );
+
+        let err = builder.build_self_signed(&signer).await.unwrap_err();
+        assert!(matches!(err, CertError::InvalidTimestamp(_, _)));
+    }
+
+    #[tokio::test]
+    async fn test_build_self_signed_rejects_an_unsupported_algorithm() {
+        struct RsaLikeSigner;
+
+        #[async_trait::async_trait]
+        impl Signer for RsaLikeSigner {
+            async fn sign(&self, _data: &[u8]) -> Result<super::super::Signature, SignerError> {
+                unreachable!("algorithm is rejected before signing")
+            }
+
+            async fn public_key(&self) -> Result<super::super::PublicKey, SignerError> {
+                unreachable!("algorithm is rejected before fetching the public key")
+            }
+
+            fn signer_info(&self) -> super::super::SignerInfo {
+                super::super::SignerInfo {
+                    signer_type: "software".to_string(),
+                    algorithm: "rsa".to_string(),
+                    metadata: Default::default(),
+                }
+            }
+        }
+
+        let err = builder().build_self_signed(&RsaLikeSigner).await.unwrap_err();
+        assert!(matches!(err, CertError::UnsupportedAlgorithm(_)));
+    }
+
+    #[test]
+    fn test_oid_encoding_matches_known_values() {
+        // id-Ed25519 (1.3.101.112) per RFC 8410.
+        assert_eq!(der::oid(&OID_ED25519), vec![0x06, 0x03, 0x2b, 0x65, 0x70]);
+    }
+}
@@ -0,0 +1,425 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hierarchical deterministic (HD) key derivation: a single BIP-39 mnemonic
+//! plus a derivation path fans out into an unbounded tree of signing keys,
+//! so operators can back up one seed phrase instead of one PEM file per
+//! key. secp256k1 keys derive via BIP-32 (supporting both hardened and
+//! normal child indices); Ed25519 keys derive via SLIP-0010, which supports
+//! hardened indices only.
+
+use super::software::{self, Secp256k1SoftwareSigner, SoftwareSigner};
+use super::{PublicKey, Signature, Signer, SignerError, SignerInfo};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use std::collections::HashMap;
+use std::fmt;
+
+type HmacSha512 = Hmac<Sha512>;
+
+const HARDENED_OFFSET: u32 = 1 << 31;
+
+/// One component of a derivation path, e.g. the `44'` in `m/44'/60'/0'/0/0`.
+/// Hardened indices (the top bit set, conventionally written with a `'` or
+/// `h` suffix) derive from the parent's private key; normal indices derive
+/// from the parent's public key. SLIP-0010 Ed25519 derivation only supports
+/// hardened indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChildNumber(u32);
+
+impl ChildNumber {
+    pub fn hardened(index: u32) -> Self {
+        ChildNumber(index | HARDENED_OFFSET)
+    }
+
+    pub fn normal(index: u32) -> Self {
+        ChildNumber(index & !HARDENED_OFFSET)
+    }
+
+    pub fn is_hardened(&self) -> bool {
+        self.0 & HARDENED_OFFSET != 0
+    }
+
+    pub fn to_u32(self) -> u32 {
+        self.0
+    }
+}
+
+/// A parsed derivation path such as `m/44'/60'/0'/0/0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath(Vec<ChildNumber>);
+
+impl DerivationPath {
+    /// Parse a path in the conventional `m/44'/60'/0'/0/0` form. Either `'`
+    /// or `h` may be used to mark a hardened index.
+    pub fn parse(path: &str) -> Result<Self, SignerError> {
+        let mut segments = path.split('/');
+        match segments.next() {
+            Some("m") => {}
+            _ => {
+                return Err(SignerError::Config(format!(
+                    "Derivation path must start with \"m\": {}",
+                    path
+                )))
+            }
+        }
+
+        let mut components = Vec::new();
+        for segment in segments {
+            let (digits, hardened) = match segment.strip_suffix(['\'', 'h']) {
+                Some(digits) => (digits, true),
+                None => (segment, false),
+            };
+            let index: u32 = digits.parse().map_err(|_| {
+                SignerError::Config(format!("Invalid derivation path segment: {}", segment))
+            })?;
+            components.push(if hardened {
+                ChildNumber::hardened(index)
+            } else {
+                ChildNumber::normal(index)
+            });
+        }
+
+        Ok(DerivationPath(components))
+    }
+
+    pub fn children(&self) -> &[ChildNumber] {
+        &self.0
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for child in &self.0 {
+            if child.is_hardened() {
+                write!(f, "/{}'", child.to_u32() & !HARDENED_OFFSET)?;
+            } else {
+                write!(f, "/{}", child.to_u32())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A BIP-32-style extended private key: a 32-byte scalar plus a 32-byte
+/// chain code, the unit derivation operates on.
+struct ExtendedKey {
+    key: [u8; 32],
+    chain_code: [u8; 32],
+}
+
+fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+    let mut mac = HmacSha512::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+/// Derive the master extended key for `domain` (`b"Bitcoin seed"` for
+/// secp256k1, `b"ed25519 seed"` for SLIP-0010) from a BIP-39 seed.
+fn master_key(seed: &[u8], domain: &[u8]) -> ExtendedKey {
+    let i = hmac_sha512(domain, seed);
+    let mut key = [0u8; 32];
+    let mut chain_code = [0u8; 32];
+    key.copy_from_slice(&i[0..32]);
+    chain_code.copy_from_slice(&i[32..64]);
+    ExtendedKey { key, chain_code }
+}
+
+/// BIP-32 `CKDpriv`: derive a secp256k1 child extended key. Hardened
+/// indices hash the parent private key; normal indices hash the parent's
+/// compressed public point instead, per the spec.
+fn ckd_priv_secp256k1(parent: &ExtendedKey, child: ChildNumber) -> Result<ExtendedKey, SignerError> {
+    use k256::elliptic_curve::PrimeField;
+
+    let mut data = Vec::with_capacity(37);
+    if child.is_hardened() {
+        data.push(0x00);
+        data.extend_from_slice(&parent.key);
+    } else {
+        let signing_key = k256::ecdsa::SigningKey::from_bytes(&parent.key.into())
+            .map_err(|e| SignerError::Crypto(format!("Invalid parent secp256k1 key: {}", e)))?;
+        let point = signing_key.verifying_key().to_encoded_point(true);
+        data.extend_from_slice(point.as_bytes());
+    }
+    data.extend_from_slice(&child.to_u32().to_be_bytes());
+
+    let i = hmac_sha512(&parent.chain_code, &data);
+    let il: [u8; 32] = i[0..32].try_into().unwrap();
+    let chain_code: [u8; 32] = i[32..64].try_into().unwrap();
+
+    let il_scalar = Option::<k256::Scalar>::from(k256::Scalar::from_repr(il.into()))
+        .ok_or_else(|| SignerError::Crypto("derived IL is not a valid secp256k1 scalar".to_string()))?;
+    let parent_scalar = Option::<k256::Scalar>::from(k256::Scalar::from_repr(parent.key.into()))
+        .ok_or_else(|| SignerError::Crypto("parent key is not a valid secp256k1 scalar".to_string()))?;
+
+    let child_scalar = il_scalar + parent_scalar;
+    let child_bytes = child_scalar.to_repr();
+    if child_bytes.iter().all(|b| *b == 0) {
+        return Err(SignerError::Crypto(
+            "derived child scalar is zero (astronomically unlikely; retry with a different index)".to_string(),
+        ));
+    }
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&child_bytes);
+
+    Ok(ExtendedKey { key, chain_code })
+}
+
+/// SLIP-0010 child derivation for Ed25519, which only supports hardened
+/// indices (there is no public-point derivation for Ed25519 in SLIP-0010).
+fn ckd_priv_ed25519(parent: &ExtendedKey, child: ChildNumber) -> Result<ExtendedKey, SignerError> {
+    if !child.is_hardened() {
+        return Err(SignerError::Config(
+            "SLIP-0010 Ed25519 derivation supports hardened indices only".to_string(),
+        ));
+    }
+
+    let mut data = Vec::with_capacity(37);
+    data.push(0x00);
+    data.extend_from_slice(&parent.key);
+    data.extend_from_slice(&child.to_u32().to_be_bytes());
+
+    let i = hmac_sha512(&parent.chain_code, &data);
+    let mut key = [0u8; 32];
+    let mut chain_code = [0u8; 32];
+    key.copy_from_slice(&i[0..32]);
+    chain_code.copy_from_slice(&i[32..64]);
+    Ok(ExtendedKey { key, chain_code })
+}
+
+enum HdInner {
+    Ed25519(SoftwareSigner),
+    Secp256k1(Secp256k1SoftwareSigner),
+}
+
+/// A signer backed by a key derived from a BIP-39 mnemonic plus a
+/// derivation path, rather than a single PEM/keystore-loaded key. Wraps a
+/// [`SoftwareSigner`] or [`Secp256k1SoftwareSigner`] built from the derived
+/// key material and forwards [`Signer`] calls to it.
+pub struct HdSigner {
+    inner: HdInner,
+    algorithm: String,
+    key: [u8; 32],
+    chain_code: [u8; 32],
+    path: DerivationPath,
+}
+
+impl HdSigner {
+    /// Derive a signer from `phrase`/`passphrase` at `path` for `algorithm`
+    /// (`"ed25519"` or `"secp256k1"`).
+    pub fn from_mnemonic(
+        phrase: &str,
+        passphrase: &str,
+        path: &str,
+        algorithm: &str,
+    ) -> Result<Self, SignerError> {
+        let seed = software::mnemonic_seed(phrase, passphrase)?;
+        let parsed_path = DerivationPath::parse(path)?;
+
+        let domain: &[u8] = match algorithm {
+            "secp256k1" => b"Bitcoin seed",
+            "ed25519" => b"ed25519 seed",
+            other => {
+                return Err(SignerError::Config(format!(
+                    "Unsupported HD signer algorithm: {} (expected \"ed25519\" or \"secp256k1\")",
+                    other
+                )))
+            }
+        };
+
+        let mut current = master_key(&seed, domain);
+        for child in parsed_path.children() {
+            current = Self::derive_one(algorithm, &current, *child)?;
+        }
+
+        Self::from_extended_key(algorithm, current, parsed_path)
+    }
+
+    /// Derive a child of this signer at `index`. Pass [`ChildNumber::hardened`]
+    /// or [`ChildNumber::normal`]'s inner value (or simply a plain index,
+    /// which is treated as normal) to control hardening; Ed25519 signers
+    /// require a hardened index.
+    pub fn derive_child(&self, index: u32) -> Result<Self, SignerError> {
+        let child = ChildNumber(index);
+        let parent = ExtendedKey {
+            key: self.key,
+            chain_code: self.chain_code,
+        };
+        let derived = Self::derive_one(&self.algorithm, &parent, child)?;
+
+        let mut child_path = self.path.clone();
+        child_path.0.push(child);
+
+        Self::from_extended_key(&self.algorithm, derived, child_path)
+    }
+
+    /// The derivation path this signer was derived at.
+    pub fn path(&self) -> &DerivationPath {
+        &self.path
+    }
+
+    fn derive_one(algorithm: &str, parent: &ExtendedKey, child: ChildNumber) -> Result<ExtendedKey, SignerError> {
+        match algorithm {
+            "secp256k1" => ckd_priv_secp256k1(parent, child),
+            "ed25519" => ckd_priv_ed25519(parent, child),
+            other => Err(SignerError::Config(format!("Unsupported HD signer algorithm: {}", other))),
+        }
+    }
+
+    fn from_extended_key(algorithm: &str, key: ExtendedKey, path: DerivationPath) -> Result<Self, SignerError> {
+        let inner = match algorithm {
+            "secp256k1" => HdInner::Secp256k1(Secp256k1SoftwareSigner::from_raw_bytes(key.key)?),
+            "ed25519" => HdInner::Ed25519(SoftwareSigner::from_raw_bytes(key.key)),
+            other => return Err(SignerError::Config(format!("Unsupported HD signer algorithm: {}", other))),
+        };
+
+        Ok(Self {
+            inner,
+            algorithm: algorithm.to_string(),
+            key: key.key,
+            chain_code: key.chain_code,
+            path,
+        })
+    }
+}
+
+#[async_trait]
+impl Signer for HdSigner {
+    async fn sign(&self, data: &[u8]) -> Result<Signature, SignerError> {
+        match &self.inner {
+            HdInner::Ed25519(signer) => signer.sign(data).await,
+            HdInner::Secp256k1(signer) => signer.sign(data).await,
+        }
+    }
+
+    async fn sign_recoverable(&self, data: &[u8]) -> Result<Signature, SignerError> {
+        match &self.inner {
+            HdInner::Ed25519(signer) => Signer::sign_recoverable(signer, data).await,
+            HdInner::Secp256k1(signer) => signer.sign_recoverable(data).await,
+        }
+    }
+
+    async fn public_key(&self) -> Result<PublicKey, SignerError> {
+        match &self.inner {
+            HdInner::Ed25519(signer) => signer.public_key().await,
+            HdInner::Secp256k1(signer) => signer.public_key().await,
+        }
+    }
+
+    fn signer_info(&self) -> SignerInfo {
+        let mut metadata = HashMap::new();
+        metadata.insert("implementation".to_string(), "hd".to_string());
+        metadata.insert("derivation_path".to_string(), self.path.to_string());
+
+        SignerInfo {
+            signer_type: "hd".to_string(),
+            algorithm: self.algorithm.clone(),
+            metadata,
+        }
+    }
+
+    fn supported_algorithms(&self) -> Vec<&'static str> {
+        match &self.inner {
+            HdInner::Ed25519(signer) => signer.supported_algorithms(),
+            HdInner::Secp256k1(signer) => signer.supported_algorithms(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path_marks_hardened_and_normal_indices() {
+        let path = DerivationPath::parse("m/44'/60h/0'/0/5").unwrap();
+        let children = path.children();
+
+        assert!(children[0].is_hardened());
+        assert!(children[1].is_hardened());
+        assert!(children[2].is_hardened());
+        assert!(!children[3].is_hardened());
+        assert!(!children[4].is_hardened());
+        assert_eq!(children[4].to_u32(), 5);
+    }
+
+    #[test]
+    fn test_parse_path_rejects_a_path_without_the_m_prefix() {
+        assert!(DerivationPath::parse("44'/60'/0'/0/0").is_err());
+    }
+
+    #[test]
+    fn test_path_display_round_trips_through_parse() {
+        let path = DerivationPath::parse("m/44'/60'/0'/0/0").unwrap();
+        assert_eq!(path.to_string(), "m/44'/60'/0'/0/0");
+    }
+
+    #[test]
+    fn test_secp256k1_hd_signer_is_deterministic() {
+        let phrase = SoftwareSigner::generate_with_mnemonic(12).unwrap().1;
+
+        let a = HdSigner::from_mnemonic(&phrase, "", "m/44'/148'/0'", "secp256k1").unwrap();
+        let b = HdSigner::from_mnemonic(&phrase, "", "m/44'/148'/0'", "secp256k1").unwrap();
+
+        assert_eq!(a.key, b.key);
+        assert_eq!(a.chain_code, b.chain_code);
+    }
+
+    #[test]
+    fn test_secp256k1_hd_signer_derive_child_matches_the_full_path() {
+        let phrase = SoftwareSigner::generate_with_mnemonic(12).unwrap().1;
+
+        let parent = HdSigner::from_mnemonic(&phrase, "", "m/44'/148'", "secp256k1").unwrap();
+        let via_child = parent.derive_child(ChildNumber::hardened(0).to_u32()).unwrap();
+        let via_path = HdSigner::from_mnemonic(&phrase, "", "m/44'/148'/0'", "secp256k1").unwrap();
+
+        assert_eq!(via_child.key, via_path.key);
+        assert_eq!(via_child.path().to_string(), via_path.path().to_string());
+    }
+
+    #[test]
+    fn test_ed25519_hd_signer_rejects_non_hardened_indices() {
+        let phrase = SoftwareSigner::generate_with_mnemonic(12).unwrap().1;
+
+        match HdSigner::from_mnemonic(&phrase, "", "m/44'/148'/0", "ed25519") {
+            Err(SignerError::Config(msg)) => assert!(msg.contains("hardened")),
+            other => panic!("expected a Config error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_ed25519_hd_signer_is_deterministic() {
+        let phrase = SoftwareSigner::generate_with_mnemonic(12).unwrap().1;
+
+        let a = HdSigner::from_mnemonic(&phrase, "", "m/44'/148'/0'", "ed25519").unwrap();
+        let b = HdSigner::from_mnemonic(&phrase, "", "m/44'/148'/0'", "ed25519").unwrap();
+
+        assert_eq!(a.key, b.key);
+    }
+
+    #[test]
+    fn test_hd_signer_rejects_an_unsupported_algorithm() {
+        let phrase = SoftwareSigner::generate_with_mnemonic(12).unwrap().1;
+
+        match HdSigner::from_mnemonic(&phrase, "", "m/0'", "dsa") {
+            Err(SignerError::Config(_)) => {}
+            other => panic!("expected a Config error, got {:?}", other.err()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hd_signer_signs_and_reports_its_derivation_path() {
+        let phrase = SoftwareSigner::generate_with_mnemonic(12).unwrap().1;
+        let signer = HdSigner::from_mnemonic(&phrase, "", "m/44'/148'/0'", "secp256k1").unwrap();
+
+        let signature = signer.sign(b"data").await.unwrap();
+        assert_eq!(signature.algorithm, "ES256K");
+
+        let info = signer.signer_info();
+        assert_eq!(info.signer_type, "hd");
+        assert_eq!(info.metadata.get("derivation_path").unwrap(), "m/44'/148'/0'");
+    }
+}
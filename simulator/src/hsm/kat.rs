@@ -0,0 +1,237 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Known-answer-test harness driving the [`super::Verifier`] implementations
+//! against Wycheproof-style JSON test vectors.
+//!
+//! Each vector file holds one or more test groups, each carrying a public
+//! key and a list of cases with a hex message, a hex signature, and a
+//! `result` of `valid`, `invalid`, or `acceptable` (a signature that is
+//! cryptographically valid but whose acceptance is a matter of policy, e.g.
+//! a non-canonical but mathematically valid ECDSA `s` value). [`flatten`]
+//! turns that nested shape into a flat list of [`KatVector`]s so a new
+//! algorithm only needs a new vector file, not new harness code.
+//!
+//! The committed vector files under `tests/vectors/` are a small
+//! hand-authored set in the Wycheproof schema (not the full upstream
+//! corpus, which isn't vendored here) covering the cases this harness is
+//! meant to catch: a genuine signature, a bit-flipped signature, a
+//! wrong-length signature, and (for ECDSA) a malleable non-canonical `s`.
+
+use super::{PublicKey, Signature, Verifier};
+use serde::Deserialize;
+
+/// Top-level Wycheproof-style vector file.
+#[derive(Debug, Deserialize)]
+pub struct WycheproofTestVectors {
+    pub algorithm: String,
+    #[serde(rename = "testGroups")]
+    pub test_groups: Vec<WycheproofTestGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WycheproofTestGroup {
+    /// Hex-encoded SPKI-DER public key shared by every case in this group.
+    #[serde(rename = "publicKeyHex")]
+    pub public_key_hex: String,
+    pub tests: Vec<WycheproofCase>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WycheproofCase {
+    #[serde(rename = "tcId")]
+    pub tc_id: u32,
+    #[serde(default)]
+    pub comment: String,
+    pub msg: String,
+    pub sig: String,
+    pub result: String,
+    #[serde(default)]
+    pub flags: Vec<String>,
+}
+
+/// A case's expected verification outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KatExpected {
+    Valid,
+    Invalid,
+    /// Cryptographically valid, but whether it should be *accepted* is a
+    /// matter of application policy (e.g. signature malleability).
+    Acceptable,
+}
+
+impl KatExpected {
+    fn parse(result: &str) -> Result<Self, KatError> {
+        match result {
+            "valid" => Ok(KatExpected::Valid),
+            "invalid" => Ok(KatExpected::Invalid),
+            "acceptable" => Ok(KatExpected::Acceptable),
+            other => Err(KatError::UnknownResult(other.to_string())),
+        }
+    }
+}
+
+/// One flattened (public key, message, signature, expected outcome) tuple,
+/// ready to drive through a [`Verifier`].
+#[derive(Debug, Clone)]
+pub struct KatVector {
+    pub tc_id: u32,
+    pub comment: String,
+    pub public_key: Vec<u8>,
+    pub message: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub expected: KatExpected,
+    pub flags: Vec<String>,
+}
+
+/// Whether a case with `expected` should verify successfully under the
+/// given `accept_acceptable` policy: whether the caller treats
+/// `acceptable` cases (e.g. non-canonical-but-valid signatures) as
+/// something it accepts or rejects.
+pub fn should_verify(expected: KatExpected, accept_acceptable: bool) -> bool {
+    match expected {
+        KatExpected::Valid => true,
+        KatExpected::Invalid => false,
+        KatExpected::Acceptable => accept_acceptable,
+    }
+}
+
+/// Errors that can occur while loading or flattening KAT vectors.
+#[derive(Debug, thiserror::Error)]
+pub enum KatError {
+    #[error("Failed to read vector file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse vector file: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Failed to decode hex field: {0}")]
+    HexDecode(String),
+
+    #[error("Unknown test result: {0}")]
+    UnknownResult(String),
+}
+
+/// Load a Wycheproof-style vector file from `path`.
+pub fn load_vectors(path: &str) -> Result<WycheproofTestVectors, KatError> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Flatten the nested group/case structure of `vectors` into a flat list
+/// of [`KatVector`]s.
+pub fn flatten(vectors: &WycheproofTestVectors) -> Result<Vec<KatVector>, KatError> {
+    let mut flattened = Vec::new();
+
+    for group in &vectors.test_groups {
+        let public_key = hex::decode(&group.public_key_hex)
+            .map_err(|e| KatError::HexDecode(format!("publicKeyHex: {}", e)))?;
+
+        for case in &group.tests {
+            let message = hex::decode(&case.msg)
+                .map_err(|e| KatError::HexDecode(format!("tcId {}: msg: {}", case.tc_id, e)))?;
+            let signature = hex::decode(&case.sig)
+                .map_err(|e| KatError::HexDecode(format!("tcId {}: sig: {}", case.tc_id, e)))?;
+            let expected = KatExpected::parse(&case.result)?;
+
+            flattened.push(KatVector {
+                tc_id: case.tc_id,
+                comment: case.comment.clone(),
+                public_key: public_key.clone(),
+                message,
+                signature,
+                expected,
+                flags: case.flags.clone(),
+            });
+        }
+    }
+
+    Ok(flattened)
+}
+
+/// Drive `vector` through `verifier`, treating `acceptable` cases per
+/// `accept_acceptable`, and return an error describing any mismatch
+/// between the expected and actual outcome.
+pub async fn run_case(
+    verifier: &dyn Verifier,
+    vector: &KatVector,
+    algorithm: &str,
+    accept_acceptable: bool,
+) -> Result<(), String> {
+    let key = PublicKey {
+        algorithm: algorithm.to_string(),
+        spki_bytes: vector.public_key.clone(),
+    };
+    let sig = Signature {
+        algorithm: algorithm.to_string(),
+        bytes: vector.signature.clone(),
+        recovery_id: None,
+    };
+
+    let outcome = verifier.verify(&vector.message, &sig, &key).await;
+    let should_pass = should_verify(vector.expected, accept_acceptable);
+
+    match (should_pass, &outcome) {
+        (true, Ok(())) | (false, Err(_)) => Ok(()),
+        (true, Err(e)) => Err(format!(
+            "tcId {} ({}): expected verification to succeed, got {:?}",
+            vector.tc_id, vector.comment, e
+        )),
+        (false, Ok(())) => Err(format!(
+            "tcId {} ({}): expected verification to fail, but it succeeded",
+            vector.tc_id, vector.comment
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_vectors_json() -> &'static str {
+        r#"{
+            "algorithm": "EDDSA",
+            "testGroups": [
+                {
+                    "publicKeyHex": "aabbcc",
+                    "tests": [
+                        {"tcId": 1, "comment": "case a", "msg": "01", "sig": "02", "result": "valid", "flags": []},
+                        {"tcId": 2, "comment": "case b", "msg": "03", "sig": "04", "result": "invalid", "flags": ["Foo"]},
+                        {"tcId": 3, "comment": "case c", "msg": "05", "sig": "06", "result": "acceptable", "flags": []}
+                    ]
+                }
+            ]
+        }"#
+    }
+
+    #[test]
+    fn test_flatten_produces_one_entry_per_case() {
+        let vectors: WycheproofTestVectors = serde_json::from_str(sample_vectors_json()).unwrap();
+        let flattened = flatten(&vectors).unwrap();
+
+        assert_eq!(flattened.len(), 3);
+        assert_eq!(flattened[0].expected, KatExpected::Valid);
+        assert_eq!(flattened[1].expected, KatExpected::Invalid);
+        assert_eq!(flattened[2].expected, KatExpected::Acceptable);
+        assert_eq!(flattened[1].flags, vec!["Foo".to_string()]);
+        assert_eq!(flattened[0].public_key, vec![0xaa, 0xbb, 0xcc]);
+    }
+
+    #[test]
+    fn test_should_verify_follows_acceptable_policy() {
+        assert!(should_verify(KatExpected::Valid, false));
+        assert!(!should_verify(KatExpected::Invalid, true));
+        assert!(should_verify(KatExpected::Acceptable, true));
+        assert!(!should_verify(KatExpected::Acceptable, false));
+    }
+
+    #[test]
+    fn test_flatten_rejects_unknown_result() {
+        let bad_json = sample_vectors_json().replace("\"acceptable\"", "\"maybe\"");
+        let vectors: WycheproofTestVectors = serde_json::from_str(&bad_json).unwrap();
+        match flatten(&vectors) {
+            Err(KatError::UnknownResult(result)) => assert_eq!(result, "maybe"),
+            other => panic!("expected an UnknownResult error, got {:?}", other),
+        }
+    }
+}
@@ -7,13 +7,24 @@
 //! by different cryptographic backends, including software-based signers
 //! and PKCS#11 HSM signers.
 
+pub mod cert;
+pub mod hd;
+pub mod kat;
 pub mod pkcs11;
+pub mod sigstore;
 pub mod software;
+pub mod threshold;
+pub mod trust_root;
+pub mod verify;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sigstore::SigstoreSignature;
 use std::fmt;
+use std::fs;
 use thiserror::Error;
+use zeroize::Zeroize;
 
 /// Generic signer interface for cryptographic operations
 #[async_trait]
@@ -26,12 +37,84 @@ pub trait Signer: Send + Sync {
 
     /// Get information about the signer implementation
     fn signer_info(&self) -> SignerInfo;
+
+    /// Sign `data` and return a 65-byte recoverable signature (a 64-byte
+    /// `r || s` signature plus a 1-byte recovery id), as required by
+    /// contract-side secp256k1 verification (e.g. Soroban's
+    /// `secp256k1_recover` host function). Only meaningful for curves that
+    /// support public-key recovery; the default implementation errors out,
+    /// and signers that can produce one (currently
+    /// [`software::Secp256k1SoftwareSigner`]) override it.
+    async fn sign_recoverable(&self, _data: &[u8]) -> Result<Signature, SignerError> {
+        Err(SignerError::Crypto(
+            "recoverable signatures are not supported by this signer".to_string(),
+        ))
+    }
+
+    /// Sign `data` via a keyless flow and return the full provenance bundle
+    /// (signature, ephemeral certificate chain, and transparency-log entry)
+    /// instead of just the raw signature bytes `sign()` returns. Only
+    /// meaningful for signers backed by a certificate-transparency flow
+    /// (currently [`sigstore::SigstoreSigner`]); the default implementation
+    /// errors out.
+    async fn sign_keyless(&self, _data: &[u8]) -> Result<SigstoreSignature, SignerError> {
+        Err(SignerError::Crypto(
+            "keyless signing is not supported by this signer".to_string(),
+        ))
+    }
+
+    /// Sign `data` and package the result as a self-contained
+    /// [`SignatureBundle`] — the signed payload's hash, the signature, this
+    /// signer's public key and metadata, and room for an externally-supplied
+    /// attestation — so a caller can verify it offline via [`verify_bundle`]
+    /// without separately fetching the signer's key. The default
+    /// implementation builds one from the existing [`Signer::sign`] and
+    /// [`Signer::public_key`] and leaves `attestation` unset; signers with an
+    /// external attestation source (e.g. a timestamping authority) can
+    /// override it to populate that field.
+    async fn sign_bundle(&self, data: &[u8]) -> Result<SignatureBundle, SignerError> {
+        let signature = self.sign(data).await?;
+        let public_key = self.public_key().await?;
+
+        Ok(SignatureBundle {
+            payload_hash: Sha256::digest(data).to_vec(),
+            payload_hash_algorithm: "sha256".to_string(),
+            signature,
+            signer_info: self.signer_info(),
+            public_key,
+            attestation: None,
+        })
+    }
+
+    /// The JWS-style algorithm identifiers (e.g. `"ES256"`, `"RS256"`) this
+    /// signer can produce via [`Signer::sign`]. [`SignerFactory`] checks a
+    /// requested algorithm against this list before handing off a signing
+    /// job, so a signer whose key type can't back the requested scheme
+    /// (e.g. requesting `"ES256K"` of a P-256 key) is rejected up front
+    /// rather than producing a signature under the wrong identifier. The
+    /// default is empty, meaning "no particular algorithm is negotiable" —
+    /// signers that predate algorithm negotiation (e.g. [`pkcs11::Pkcs11Signer`])
+    /// don't need to opt in.
+    fn supported_algorithms(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+}
+
+/// Counterpart to [`Signer`]: checks a signature against a public key
+/// instead of producing one. Kept as a separate trait (rather than a method
+/// on `Signer`) since verification never needs access to key material a
+/// signer holds privately — callers typically verify against a key they
+/// received from elsewhere, e.g. one pinned by [`trust_root::TrustRoot`].
+#[async_trait]
+pub trait Verifier: Send + Sync {
+    /// Check that `sig` is a valid signature over `data` under `key`.
+    async fn verify(&self, data: &[u8], sig: &Signature, key: &PublicKey) -> Result<(), SignerError>;
 }
 
 /// Public key representation
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PublicKey {
-    /// Key algorithm (e.g., "ed25519", "secp256k1")
+    /// Key algorithm (e.g., "ed25519", "secp256k1", "secp256r1")
     pub algorithm: String,
     /// Public key bytes in SPKI format
     pub spki_bytes: Vec<u8>,
@@ -44,10 +127,18 @@ pub struct Signature {
     pub algorithm: String,
     /// Signature bytes
     pub bytes: Vec<u8>,
+    /// ECDSA recovery id (0..=3), present when `bytes` came from a curve
+    /// that supports public-key recovery (currently secp256k1 via
+    /// [`software::Secp256k1SoftwareSigner`]) and the signer captured it.
+    /// `None` for curves without a recovery scheme (Ed25519, RSA) or when
+    /// the signature was produced by plain [`Signer::sign`] before recovery
+    /// support existed.
+    #[serde(default)]
+    pub recovery_id: Option<u8>,
 }
 
 /// Information about a signer implementation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignerInfo {
     /// Signer type (e.g., "software", "pkcs11")
     pub signer_type: String,
@@ -57,12 +148,67 @@ pub struct SignerInfo {
     pub metadata: std::collections::HashMap<String, String>,
 }
 
+/// A self-contained, offline-verifiable signing envelope: the hash of the
+/// payload that was actually signed, the signature over it, and the
+/// signer's public key and metadata, all bundled together the way
+/// sigstore-rs's `bundle` feature packages a signature with its
+/// verification material instead of leaving the caller to separately track
+/// which key produced which raw bytes. Unlike [`sigstore::SigstoreSignature`],
+/// this doesn't require a Fulcio/Rekor keyless flow — any [`Signer`] can
+/// produce one via [`Signer::sign_bundle`], and it's checked with
+/// [`verify_bundle`] rather than a CA-backed certificate chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureBundle {
+    /// Digest of the signed payload.
+    pub payload_hash: Vec<u8>,
+    /// Algorithm used to compute `payload_hash` (currently always `"sha256"`).
+    pub payload_hash_algorithm: String,
+    /// Signature over the original payload (not the hash) from [`Signer::sign`].
+    pub signature: Signature,
+    /// Metadata about the signer that produced `signature`.
+    pub signer_info: SignerInfo,
+    /// DER SPKI public key needed to verify `signature` offline.
+    pub public_key: PublicKey,
+    /// Optional externally-supplied attestation (e.g. an RFC 3161 timestamp
+    /// token or a transparency-log inclusion proof) binding the bundle to a
+    /// point in time. Unlike [`sigstore::RekorLogEntry`], this is opaque to
+    /// the bundle itself — the caller supplies and interprets it.
+    #[serde(default)]
+    pub attestation: Option<Vec<u8>>,
+}
+
+/// Verify a [`SignatureBundle`] offline: check that `data` hashes to
+/// `bundle.payload_hash`, then dispatch to [`verify::verify_with_public_key`]
+/// to re-derive the verifying key from the embedded SPKI bytes and check
+/// `bundle.signature` over `data` against it. Supports Ed25519, secp256k1,
+/// and secp256r1 public keys, the curves software signers in this crate
+/// currently produce bundles for. See [`verify::Keyring`] for verifying
+/// against a set of trusted keys rather than one bundle's embedded key.
+pub async fn verify_bundle(data: &[u8], bundle: &SignatureBundle) -> Result<(), SignerError> {
+    if bundle.payload_hash_algorithm != "sha256" {
+        return Err(SignerError::Crypto(format!(
+            "verify_bundle supports sha256 payload hashes only, got {}",
+            bundle.payload_hash_algorithm
+        )));
+    }
+    if Sha256::digest(data).to_vec() != bundle.payload_hash {
+        return Err(SignerError::InvalidSignature(
+            "payload does not match the bundle's recorded hash".to_string(),
+        ));
+    }
+
+    verify::verify_with_public_key(data, &bundle.public_key, &bundle.signature)
+}
+
 /// Errors that can occur during signing operations
 #[derive(Debug, Error)]
 pub enum SignerError {
     #[error("PKCS#11 error: {0}")]
     Pkcs11(String),
 
+    #[error("PKCS#11 error: {0}")]
+    Pkcs11Code(pkcs11::Ckr),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -98,17 +244,92 @@ impl fmt::Display for Signature {
 pub struct SignerFactory;
 
 impl SignerFactory {
-    /// Create a signer based on configuration
+    /// Create a signer based on configuration.
+    ///
+    /// When `config.jws_algorithm` is set, the resulting signer's
+    /// [`Signer::supported_algorithms`] must include it, or this returns
+    /// `SignerError::Config` — this is the negotiation step that catches a
+    /// mismatched key type/curve (e.g. requesting `"ES256K"` against a
+    /// P-256 key) before a caller ever gets as far as signing.
     pub async fn create_from_config(config: &SignerConfig) -> Result<Box<dyn Signer>, SignerError> {
+        let signer = Self::build_signer(config).await?;
+
+        if let Some(requested) = &config.jws_algorithm {
+            let supported = signer.supported_algorithms();
+            if !supported.contains(&requested.as_str()) {
+                return Err(SignerError::Config(format!(
+                    "Requested algorithm {} is not compatible with the {} {} signer (supports: {:?})",
+                    requested, config.signer_type, config.algorithm, supported
+                )));
+            }
+        }
+
+        Ok(signer)
+    }
+
+    async fn build_signer(config: &SignerConfig) -> Result<Box<dyn Signer>, SignerError> {
         match config.signer_type.as_str() {
             "software" => {
-                let software_signer = software::SoftwareSigner::from_config(config)?;
-                Ok(Box::new(software_signer))
+                let software_config = config.software.as_ref().ok_or_else(|| {
+                    SignerError::Config("software signer requires a `software` config block".to_string())
+                })?;
+                match config.algorithm.as_str() {
+                    "ed25519" => Ok(Box::new(software::SoftwareSigner::from_config(software_config)?)
+                        as Box<dyn Signer>),
+                    "secp256k1" => {
+                        let curve_config = software::Secp256k1SoftwareSignerConfig {
+                            private_key_path: software_config.private_key_path.clone(),
+                            private_key_pem: software_config.private_key_pem.clone(),
+                            mnemonic: software_config.mnemonic.clone(),
+                            mnemonic_passphrase: software_config.mnemonic_passphrase.clone(),
+                        };
+                        Ok(Box::new(software::Secp256k1SoftwareSigner::from_config(&curve_config)?)
+                            as Box<dyn Signer>)
+                    }
+                    "secp256r1" | "p256" => {
+                        let curve_config = software::P256SoftwareSignerConfig {
+                            private_key_path: software_config.private_key_path.clone(),
+                            private_key_pem: software_config.private_key_pem.clone(),
+                            mnemonic: software_config.mnemonic.clone(),
+                            mnemonic_passphrase: software_config.mnemonic_passphrase.clone(),
+                        };
+                        Ok(Box::new(software::P256SoftwareSigner::from_config(&curve_config)?)
+                            as Box<dyn Signer>)
+                    }
+                    "rsa" => {
+                        let curve_config = software::RsaSoftwareSignerConfig {
+                            private_key_path: software_config.private_key_path.clone(),
+                            private_key_pem: software_config.private_key_pem.clone(),
+                            key_bits: None,
+                        };
+                        Ok(Box::new(software::RsaSoftwareSigner::from_config(&curve_config)?)
+                            as Box<dyn Signer>)
+                    }
+                    other => Err(SignerError::Config(format!(
+                        "Unsupported algorithm for software signer: {}",
+                        other
+                    ))),
+                }
             }
             "pkcs11" => {
-                let pkcs11_signer = pkcs11::Pkcs11Signer::from_config(config).await?;
+                let pkcs11_config = config.pkcs11.clone().ok_or_else(|| {
+                    SignerError::Config("pkcs11 signer requires a `pkcs11` config block".to_string())
+                })?;
+                let pkcs11_signer = pkcs11::Pkcs11Signer::from_config(pkcs11_config).await?;
                 Ok(Box::new(pkcs11_signer))
             }
+            "sigstore" => {
+                let sigstore_config = config.sigstore.clone().ok_or_else(|| {
+                    SignerError::Config("sigstore signer requires a `sigstore` config block".to_string())
+                })?;
+                Ok(Box::new(sigstore::SigstoreSigner::from_config(sigstore_config)?) as Box<dyn Signer>)
+            }
+            "frost" => {
+                let frost_config = config.frost.as_ref().ok_or_else(|| {
+                    SignerError::Config("frost signer requires a `frost` config block".to_string())
+                })?;
+                Ok(Box::new(threshold::FrostCoordinator::from_config(frost_config)?) as Box<dyn Signer>)
+            }
             other => Err(SignerError::Config(format!(
                 "Unsupported signer type: {}",
                 other
@@ -126,17 +347,33 @@ impl SignerFactory {
 /// Configuration for signer creation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignerConfig {
-    /// Type of signer to create ("software", "pkcs11")
+    /// Type of signer to create ("software", "pkcs11", "sigstore")
     pub signer_type: String,
 
-    /// Algorithm to use ("ed25519", "secp256k1")
+    /// Key type/curve to use ("ed25519", "secp256k1", "secp256r1"/"p256",
+    /// "rsa"). Selects which concrete backend `SignerFactory` constructs.
     pub algorithm: String,
 
+    /// Optional JWS-style algorithm identifier to require of the
+    /// constructed signer (e.g. `"ES256"`, `"RS256"`, `"ES256K"`,
+    /// `"Ed25519"`), validated against its [`Signer::supported_algorithms`]
+    /// once built. Lets a caller that needs a specific scheme (e.g. for
+    /// ACME/JWS or TLS PKI interop) fail fast on a misconfigured `algorithm`
+    /// rather than discovering the mismatch only once it inspects a
+    /// produced [`Signature`].
+    pub jws_algorithm: Option<String>,
+
     /// Software signer configuration
     pub software: Option<SoftwareSignerConfig>,
 
     /// PKCS#11 signer configuration
     pub pkcs11: Option<Pkcs11SignerConfig>,
+
+    /// Sigstore keyless signer configuration
+    pub sigstore: Option<SigstoreSignerConfig>,
+
+    /// FROST threshold signer configuration
+    pub frost: Option<threshold::FrostSignerConfig>,
 }
 
 impl SignerConfig {
@@ -148,11 +385,16 @@ impl SignerConfig {
         let algorithm = std::env::var("ERST_SIGNER_ALGORITHM")
             .unwrap_or_else(|_| "ed25519".to_string());
 
+        let jws_algorithm = std::env::var("ERST_SIGNER_JWS_ALGORITHM").ok();
+
         let mut config = SignerConfig {
             signer_type,
             algorithm,
+            jws_algorithm,
             software: None,
             pkcs11: None,
+            sigstore: None,
+            frost: None,
         };
 
         match config.signer_type.as_str() {
@@ -162,6 +404,15 @@ impl SignerConfig {
             "pkcs11" => {
                 config.pkcs11 = Some(Pkcs11SignerConfig::from_env()?);
             }
+            "sigstore" => {
+                config.sigstore = Some(SigstoreSignerConfig::from_env()?);
+            }
+            // `frost` has no from_env() counterpart yet: unlike the other
+            // backends, its parameters (threshold/participants) aren't
+            // secrets, so there's no natural ERST_FROST_* env var set to
+            // read them from without first deciding a config-file story for
+            // this signer type. Callers configure it via SignerConfig
+            // directly for now.
             _ => {}
         }
 
@@ -176,6 +427,28 @@ pub struct SoftwareSignerConfig {
     pub private_key_path: Option<String>,
     /// Private key in PEM format (direct string)
     pub private_key_pem: Option<String>,
+    /// BIP39 mnemonic phrase (12 or 24 words) to derive the key from. Takes
+    /// precedence over `private_key_pem`/`private_key_path` when set, since
+    /// it's the more portable, human-transcribable source of key material.
+    pub mnemonic: Option<String>,
+    /// Optional BIP39 passphrase ("25th word") used together with
+    /// `mnemonic`. Ignored unless `mnemonic` is set.
+    pub mnemonic_passphrase: Option<String>,
+    /// Path to an EIP-2335 encrypted JSON keystore file. Takes precedence
+    /// over `mnemonic`/`private_key_pem`/`private_key_path` when set, since
+    /// it's the only option that keeps key material encrypted at rest.
+    pub keystore_path: Option<String>,
+    /// Path to a file containing the keystore's decryption password.
+    /// Ignored unless `keystore_path` is set; falls back to
+    /// `ERST_SOFTWARE_KEYSTORE_PASSWORD` if unset.
+    pub keystore_password_path: Option<String>,
+    /// Path to a Solana-style raw keypair JSON file: a JSON array of 64
+    /// bytes, the 32-byte Ed25519 seed followed by its 32-byte public key,
+    /// as emitted by `solana-keygen` and similar Ed25519 tooling. Takes
+    /// precedence over `private_key_pem`/`private_key_path` when set, but
+    /// not over `keystore_path`/`mnemonic` (both of which avoid keeping raw
+    /// key material in a plain file).
+    pub keypair_json_path: Option<String>,
 }
 
 impl SoftwareSignerConfig {
@@ -184,6 +457,11 @@ impl SoftwareSignerConfig {
         Ok(Self {
             private_key_path: std::env::var("ERST_SOFTWARE_PRIVATE_KEY_PATH").ok(),
             private_key_pem: std::env::var("ERST_SOFTWARE_PRIVATE_KEY_PEM").ok(),
+            mnemonic: std::env::var("ERST_SOFTWARE_MNEMONIC").ok(),
+            mnemonic_passphrase: std::env::var("ERST_SOFTWARE_MNEMONIC_PASSPHRASE").ok(),
+            keystore_path: std::env::var("ERST_SOFTWARE_KEYSTORE_PATH").ok(),
+            keystore_password_path: std::env::var("ERST_SOFTWARE_KEYSTORE_PASSWORD_PATH").ok(),
+            keypair_json_path: std::env::var("ERST_SOFTWARE_KEYPAIR_JSON_PATH").ok(),
         })
     }
 }
@@ -194,12 +472,29 @@ pub struct Pkcs11SignerConfig {
     /// Path to PKCS#11 module/library
     pub module_path: String,
 
-    /// PIN for the token
-    pub pin: String,
+    /// PIN for the token. Optional: readers with their own PIN-pad (a
+    /// protected authentication path) take the PIN directly from the user,
+    /// not from software, so this can be left unset when
+    /// `protected_auth_path` is `true` or the token itself advertises
+    /// `CKF_PROTECTED_AUTHENTICATION_PATH`.
+    ///
+    /// Resolved by [`Pkcs11SignerConfig::from_env`] from, in priority order:
+    /// `ERST_PKCS11_PIN_FILE` (a path to read and trim the PIN from), then
+    /// `ERST_PKCS11_PIN` — itself either a literal PIN, `env:OTHER_VAR` to
+    /// read the PIN from a different named environment variable, or `-`/
+    /// `ask` to prompt for it interactively.
+    pub pin: Option<String>,
 
     /// Token label (optional)
     pub token_label: Option<String>,
 
+    /// Token serial number (optional). Matched against the token's own
+    /// `CK_TOKEN_INFO.serialNumber`, alongside or instead of `token_label`,
+    /// for distinguishing otherwise identically-labeled tokens (e.g. two
+    /// SoftHSM test tokens next to a real HSM).
+    #[serde(default)]
+    pub token_serial: Option<String>,
+
     /// Slot index (optional)
     pub slot_index: Option<u32>,
 
@@ -214,6 +509,30 @@ pub struct Pkcs11SignerConfig {
 
     /// Public key in PEM format (optional, can be derived from HSM)
     pub public_key_pem: Option<String>,
+
+    /// Return ECDSA signatures DER-encoded (`SEQUENCE { INTEGER r, INTEGER s }`)
+    /// instead of the HSM's raw fixed-width `r || s` concatenation. Ignored
+    /// for curves without an ASN.1 signature form (e.g. Ed25519), which
+    /// always return raw bytes. Defaults to `false` for backwards
+    /// compatibility with callers already consuming raw signatures.
+    #[serde(default)]
+    pub der_signatures: bool,
+
+    /// Explicit PKCS#11 mechanism name to sign with (one of `"CKM_ECDSA"`,
+    /// `"CKM_EDDSA"`, `"CKM_RSA_PKCS"`, `"CKM_RSA_PKCS_PSS"`,
+    /// `"CKM_SHA256_RSA_PKCS"`), bypassing both the discovered key's
+    /// `CKA_KEY_TYPE` and `C_GetMechanismList` capability detection. Needed
+    /// for tokens that misreport what they support.
+    pub mechanism_override: Option<String>,
+
+    /// Force protected-authentication-path login (`C_Login` with a NULL
+    /// PIN, so a reader's own PIN-pad prompts the user) even if the token
+    /// doesn't advertise `CKF_PROTECTED_AUTHENTICATION_PATH` in its token
+    /// info. Readers that misreport this flag need the escape hatch; when
+    /// `false`, the flag is still auto-detected from the token at session
+    /// open time. Defaults to `false`.
+    #[serde(default)]
+    pub protected_auth_path: bool,
 }
 
 impl Pkcs11SignerConfig {
@@ -222,13 +541,17 @@ impl Pkcs11SignerConfig {
         let module_path = std::env::var("ERST_PKCS11_MODULE")
             .map_err(|_| SignerError::Config("ERST_PKCS11_MODULE must be set".to_string()))?;
 
-        let pin = std::env::var("ERST_PKCS11_PIN")
-            .map_err(|_| SignerError::Config("ERST_PKCS11_PIN must be set".to_string()))?;
+        // Not required up front: a protected-authentication-path token (a
+        // reader with its own PIN-pad) takes the PIN directly from the
+        // user, and whether that's the case isn't known until the token's
+        // own flags are read in `Pkcs11Session::open`.
+        let pin = Self::resolve_pin()?;
 
         Ok(Self {
             module_path,
             pin,
             token_label: std::env::var("ERST_PKCS11_TOKEN_LABEL").ok(),
+            token_serial: std::env::var("ERST_PKCS11_TOKEN_SERIAL").ok(),
             slot_index: std::env::var("ERST_PKCS11_SLOT")
                 .ok()
                 .and_then(|s| s.parse().ok()),
@@ -236,6 +559,156 @@ impl Pkcs11SignerConfig {
             key_id_hex: std::env::var("ERST_PKCS11_KEY_ID").ok(),
             piv_slot: std::env::var("ERST_PKCS11_PIV_SLOT").ok(),
             public_key_pem: std::env::var("ERST_PKCS11_PUBLIC_KEY_PEM").ok(),
+            der_signatures: std::env::var("ERST_PKCS11_DER_SIGNATURES")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            mechanism_override: std::env::var("ERST_PKCS11_MECHANISM").ok(),
+            protected_auth_path: std::env::var("ERST_PKCS11_PROTECTED_AUTH")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+        })
+    }
+
+    /// Validate this configuration before a signer attempts to load
+    /// `module_path`, turning what used to be one generic "missing required
+    /// vars" failure into a layered diagnostic: a module that doesn't exist
+    /// on disk, a SoftHSM module missing its token directory, and a SoftHSM
+    /// module used without an explicit opt-in (so a test token is never
+    /// silently substituted for production signing).
+    pub fn validate(&self) -> Result<(), SignerError> {
+        if !std::path::Path::new(&self.module_path).exists() {
+            return Err(SignerError::Config(format!(
+                "PKCS#11 module not found at {}",
+                self.module_path
+            )));
+        }
+
+        if self.looks_like_softhsm() {
+            if std::env::var("SOFTHSM2_CONF").is_err() {
+                return Err(SignerError::Config(
+                    "module_path looks like SoftHSM but SOFTHSM2_CONF is not set; point it at your softhsm2.conf (the file that locates the test token directory)".to_string(),
+                ));
+            }
+
+            let allow_softhsm = std::env::var("ERST_PKCS11_ALLOW_SOFTHSM")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false);
+            if !allow_softhsm {
+                return Err(SignerError::Config(
+                    "module_path looks like SoftHSM; set ERST_PKCS11_ALLOW_SOFTHSM=1 to confirm a test token is intentional, not a production signer pointed at the wrong module".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether `module_path`'s file name looks like a SoftHSM shared
+    /// library (e.g. `libsofthsm2.so`), the way most SoftHSM deployments
+    /// name it, rather than a real HSM's PKCS#11 module.
+    fn looks_like_softhsm(&self) -> bool {
+        std::path::Path::new(&self.module_path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.to_lowercase().contains("softhsm"))
+            .unwrap_or(false)
+    }
+
+    /// Resolve the token PIN from, in priority order: `ERST_PKCS11_PIN_FILE`
+    /// (a path to read and trim the PIN from), then `ERST_PKCS11_PIN`
+    /// interpreted as `-`/`ask` (prompt interactively), `env:OTHER_VAR`
+    /// (read the PIN from a different named environment variable), or else
+    /// a literal PIN value. Returns `Ok(None)` if neither is set, for
+    /// protected-authentication-path tokens that never need a software PIN.
+    fn resolve_pin() -> Result<Option<String>, SignerError> {
+        if let Ok(path) = std::env::var("ERST_PKCS11_PIN_FILE") {
+            let mut pin = fs::read_to_string(&path)?;
+            let trimmed_len = pin.trim_end_matches(['\n', '\r']).len();
+            pin.truncate(trimmed_len);
+            return Ok(Some(pin));
+        }
+
+        let Ok(mut pin) = std::env::var("ERST_PKCS11_PIN") else {
+            return Ok(None);
+        };
+
+        if pin == "-" || pin.eq_ignore_ascii_case("ask") {
+            pin.zeroize();
+            return Self::prompt_for_pin().map(Some);
+        }
+
+        if let Some(var_name) = pin.strip_prefix("env:") {
+            let var_name = var_name.to_string();
+            pin.zeroize();
+            return std::env::var(&var_name).map(Some).map_err(|_| {
+                SignerError::Config(format!(
+                    "ERST_PKCS11_PIN=env:{} but {} is not set",
+                    var_name, var_name
+                ))
+            });
+        }
+
+        Ok(Some(pin))
+    }
+
+    /// Prompt for the PIN on stderr and read it from stdin. This repo has no
+    /// terminal-control dependency to suppress echo, so the PIN is visible
+    /// as it's typed; prefer `ERST_PKCS11_PIN_FILE` for unattended/scripted
+    /// use.
+    fn prompt_for_pin() -> Result<String, SignerError> {
+        use std::io::Write as _;
+
+        eprint!("Enter PKCS#11 PIN: ");
+        std::io::stderr()
+            .flush()
+            .map_err(|e| SignerError::Config(format!("Failed to prompt for PIN: {}", e)))?;
+
+        let mut line = String::new();
+        std::io::stdin()
+            .read_line(&mut line)
+            .map_err(|e| SignerError::Config(format!("Failed to read PIN from stdin: {}", e)))?;
+        let trimmed_len = line.trim_end_matches(['\n', '\r']).len();
+        line.truncate(trimmed_len);
+        Ok(line)
+    }
+}
+
+/// Configuration for the Sigstore keyless signer (see [`sigstore`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigstoreSignerConfig {
+    /// OIDC issuer that `identity_token` was obtained from.
+    pub oidc_issuer_url: String,
+
+    /// Fulcio-compatible certificate authority URL.
+    pub fulcio_url: String,
+
+    /// Rekor-compatible transparency log URL.
+    pub rekor_url: String,
+
+    /// Pre-obtained OIDC identity token (e.g. minted by a CI platform's
+    /// built-in OIDC provider). Acquiring this token is out of scope for
+    /// this signer: it authenticates to Fulcio, not to the OIDC issuer.
+    pub identity_token: Option<String>,
+}
+
+impl SigstoreSignerConfig {
+    /// Create configuration from environment variables
+    pub fn from_env() -> Result<Self, SignerError> {
+        let oidc_issuer_url = std::env::var("ERST_SIGSTORE_OIDC_ISSUER_URL").map_err(|_| {
+            SignerError::Config("ERST_SIGSTORE_OIDC_ISSUER_URL must be set".to_string())
+        })?;
+
+        let fulcio_url = std::env::var("ERST_SIGSTORE_FULCIO_URL")
+            .map_err(|_| SignerError::Config("ERST_SIGSTORE_FULCIO_URL must be set".to_string()))?;
+
+        let rekor_url = std::env::var("ERST_SIGSTORE_REKOR_URL")
+            .map_err(|_| SignerError::Config("ERST_SIGSTORE_REKOR_URL must be set".to_string()))?;
+
+        Ok(Self {
+            oidc_issuer_url,
+            fulcio_url,
+            rekor_url,
+            identity_token: std::env::var("ERST_SIGSTORE_IDENTITY_TOKEN").ok(),
         })
     }
 }
@@ -258,10 +731,58 @@ mod tests {
         let sig = Signature {
             algorithm: "ed25519".to_string(),
             bytes: vec![0x04, 0x05, 0x06],
+            recovery_id: None,
         };
         assert_eq!(sig.to_string(), "ed25519:040506");
     }
 
+    #[tokio::test]
+    async fn test_sign_bundle_is_verifiable_offline_for_ed25519() {
+        let (signer, _pem) = software::SoftwareSigner::generate().unwrap();
+        let data = b"contract wasm bytes";
+
+        let bundle = signer.sign_bundle(data).await.unwrap();
+        assert_eq!(bundle.payload_hash_algorithm, "sha256");
+        assert!(bundle.attestation.is_none());
+
+        assert!(verify_bundle(data, &bundle).await.is_ok());
+        assert!(verify_bundle(b"tampered", &bundle).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sign_bundle_is_verifiable_offline_for_secp256k1() {
+        let (signer, _pem) = software::Secp256k1SoftwareSigner::generate().unwrap();
+        let data = b"execution result";
+
+        let bundle = signer.sign_bundle(data).await.unwrap();
+
+        assert!(verify_bundle(data, &bundle).await.is_ok());
+        assert!(verify_bundle(b"tampered", &bundle).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sign_bundle_is_verifiable_offline_for_p256() {
+        let (signer, _pem) = software::P256SoftwareSigner::generate().unwrap();
+        let data = b"webauthn assertion";
+
+        let bundle = signer.sign_bundle(data).await.unwrap();
+
+        assert!(verify_bundle(data, &bundle).await.is_ok());
+        assert!(verify_bundle(b"tampered", &bundle).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_bundle_rejects_a_tampered_payload_hash() {
+        let (signer, _pem) = software::SoftwareSigner::generate().unwrap();
+        let mut bundle = signer.sign_bundle(b"original").await.unwrap();
+        bundle.payload_hash[0] ^= 0xFF;
+
+        match verify_bundle(b"original", &bundle).await {
+            Err(SignerError::InvalidSignature(_)) => {}
+            other => panic!("expected an InvalidSignature error, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_signer_config_from_env_default() {
         // Temporarily unset environment variables
@@ -283,4 +804,199 @@ mod tests {
             std::env::set_var("ERST_SIGNER_ALGORITHM", algo_val);
         }
     }
+
+    #[tokio::test]
+    async fn test_factory_dispatches_software_signer_by_algorithm() {
+        let base = SignerConfig {
+            signer_type: "software".to_string(),
+            algorithm: "ed25519".to_string(),
+            software: Some(SoftwareSignerConfig {
+                private_key_path: None,
+                private_key_pem: None,
+                mnemonic: None,
+                mnemonic_passphrase: None,
+                keystore_path: None,
+                keystore_password_path: None,
+                keypair_json_path: None,
+            }),
+            jws_algorithm: None,
+            pkcs11: None,
+            sigstore: None,
+            frost: None,
+        };
+
+        for algorithm in ["ed25519", "secp256k1", "secp256r1", "p256", "rsa"] {
+            let config = SignerConfig {
+                algorithm: algorithm.to_string(),
+                ..base.clone()
+            };
+            // No key material was supplied, so creation fails, but it must
+            // fail with a config error, not by reaching an "unsupported
+            // algorithm" branch or a type mismatch.
+            match SignerFactory::create_from_config(&config).await {
+                Err(SignerError::Config(msg)) => {
+                    assert!(!msg.contains("Unsupported algorithm"), "{algorithm}: {msg}");
+                }
+                other => panic!("{algorithm}: expected a Config error, got {:?}", other.err()),
+            }
+        }
+
+        let unsupported = SignerConfig {
+            algorithm: "dsa".to_string(),
+            ..base
+        };
+        match SignerFactory::create_from_config(&unsupported).await {
+            Err(SignerError::Config(msg)) => assert!(msg.contains("Unsupported algorithm")),
+            other => panic!("expected a Config error, got {:?}", other.err()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_factory_rejects_incompatible_jws_algorithm() {
+        let (_signer, pem) = software::Secp256k1SoftwareSigner::generate().unwrap();
+
+        let config = SignerConfig {
+            signer_type: "software".to_string(),
+            algorithm: "secp256k1".to_string(),
+            jws_algorithm: Some("ES256".to_string()),
+            software: Some(SoftwareSignerConfig {
+                private_key_path: None,
+                private_key_pem: Some(pem.to_string()),
+                mnemonic: None,
+                mnemonic_passphrase: None,
+                keystore_path: None,
+                keystore_password_path: None,
+                keypair_json_path: None,
+            }),
+            pkcs11: None,
+            sigstore: None,
+            frost: None,
+        };
+
+        match SignerFactory::create_from_config(&config).await {
+            Err(SignerError::Config(msg)) => assert!(msg.contains("ES256")),
+            other => panic!("expected a Config error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_resolve_pin_reads_and_trims_a_pin_file_before_the_plain_env_var() {
+        let mut path = std::env::temp_dir();
+        path.push("erst_test_pkcs11_pin_file");
+        std::fs::write(&path, "654321\n").unwrap();
+
+        std::env::set_var("ERST_PKCS11_PIN_FILE", path.to_str().unwrap());
+        std::env::set_var("ERST_PKCS11_PIN", "000000");
+
+        assert_eq!(
+            Pkcs11SignerConfig::resolve_pin().unwrap(),
+            Some("654321".to_string())
+        );
+
+        std::env::remove_var("ERST_PKCS11_PIN_FILE");
+        std::env::remove_var("ERST_PKCS11_PIN");
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_resolve_pin_follows_an_env_prefixed_indirection() {
+        std::env::remove_var("ERST_PKCS11_PIN_FILE");
+        std::env::set_var("ERST_PKCS11_PIN", "env:ERST_TEST_PKCS11_REAL_PIN");
+        std::env::set_var("ERST_TEST_PKCS11_REAL_PIN", "135790");
+
+        assert_eq!(
+            Pkcs11SignerConfig::resolve_pin().unwrap(),
+            Some("135790".to_string())
+        );
+
+        std::env::remove_var("ERST_PKCS11_PIN");
+        std::env::remove_var("ERST_TEST_PKCS11_REAL_PIN");
+    }
+
+    #[test]
+    fn test_resolve_pin_reports_an_unset_env_indirection_target() {
+        std::env::remove_var("ERST_PKCS11_PIN_FILE");
+        std::env::remove_var("ERST_TEST_PKCS11_MISSING_PIN");
+        std::env::set_var("ERST_PKCS11_PIN", "env:ERST_TEST_PKCS11_MISSING_PIN");
+
+        match Pkcs11SignerConfig::resolve_pin() {
+            Err(SignerError::Config(msg)) => assert!(msg.contains("ERST_TEST_PKCS11_MISSING_PIN")),
+            other => panic!("expected a Config error, got {:?}", other),
+        }
+
+        std::env::remove_var("ERST_PKCS11_PIN");
+    }
+
+    fn test_pkcs11_config(module_path: &str) -> Pkcs11SignerConfig {
+        Pkcs11SignerConfig {
+            module_path: module_path.to_string(),
+            pin: None,
+            token_label: None,
+            token_serial: None,
+            slot_index: None,
+            key_label: None,
+            key_id_hex: None,
+            piv_slot: None,
+            public_key_pem: None,
+            der_signatures: false,
+            mechanism_override: None,
+            protected_auth_path: false,
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_a_module_path_that_does_not_exist() {
+        let config = test_pkcs11_config("/nonexistent/libsofthsm2.so");
+        match config.validate() {
+            Err(SignerError::Config(msg)) => assert!(msg.contains("not found")),
+            other => panic!("expected a Config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_requires_softhsm2_conf_for_a_softhsm_module() {
+        let module_path = std::env::temp_dir().join("libsofthsm2.so");
+        std::fs::write(&module_path, b"").unwrap();
+        std::env::remove_var("SOFTHSM2_CONF");
+
+        let config = test_pkcs11_config(module_path.to_str().unwrap());
+        match config.validate() {
+            Err(SignerError::Config(msg)) => assert!(msg.contains("SOFTHSM2_CONF")),
+            other => panic!("expected a Config error, got {:?}", other),
+        }
+
+        std::fs::remove_file(&module_path).ok();
+    }
+
+    #[test]
+    fn test_validate_requires_an_explicit_opt_in_for_a_softhsm_module() {
+        let module_path = std::env::temp_dir().join("libsofthsm2.so");
+        std::fs::write(&module_path, b"").unwrap();
+        std::env::set_var("SOFTHSM2_CONF", "/tmp/softhsm2.conf");
+        std::env::remove_var("ERST_PKCS11_ALLOW_SOFTHSM");
+
+        let config = test_pkcs11_config(module_path.to_str().unwrap());
+        match config.validate() {
+            Err(SignerError::Config(msg)) => assert!(msg.contains("ERST_PKCS11_ALLOW_SOFTHSM")),
+            other => panic!("expected a Config error, got {:?}", other),
+        }
+
+        std::env::set_var("ERST_PKCS11_ALLOW_SOFTHSM", "1");
+        assert!(config.validate().is_ok());
+
+        std::env::remove_var("SOFTHSM2_CONF");
+        std::env::remove_var("ERST_PKCS11_ALLOW_SOFTHSM");
+        std::fs::remove_file(&module_path).ok();
+    }
+
+    #[test]
+    fn test_validate_accepts_a_non_softhsm_module_that_exists() {
+        let module_path = std::env::temp_dir().join("libykcs11_test.so");
+        std::fs::write(&module_path, b"").unwrap();
+
+        let config = test_pkcs11_config(module_path.to_str().unwrap());
+        assert!(config.validate().is_ok());
+
+        std::fs::remove_file(&module_path).ok();
+    }
 }
@@ -5,36 +5,522 @@
 
 use super::{PublicKey, Signature, Signer, SignerError, SignerInfo, Pkcs11SignerConfig};
 use async_trait::async_trait;
+use k256::pkcs8::EncodePublicKey as _;
 use libloading::{Library, Symbol};
+use p256::pkcs8::EncodePublicKey as _;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::ffi::{CStr, CString};
+use std::ffi::CString;
 use std::os::raw::{c_char, c_ulong, c_void};
 use std::ptr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock, RwLock, Weak};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use zeroize::Zeroize;
 
 // PKCS#11 constants and types
 const CKF_OS_LOCKING_OK: c_ulong = 0x1;
 const CKF_SERIAL_SESSION: c_ulong = 0x4;
 const CKF_RW_SESSION: c_ulong = 0x2;
+const CKF_PROTECTED_AUTHENTICATION_PATH: c_ulong = 0x100;
 const CKU_USER: c_ulong = 1;
-const CKO_PRIVATE_KEY: c_ulong = 0x3;
 const CKO_PUBLIC_KEY: c_ulong = 0x2;
+const CKO_PRIVATE_KEY: c_ulong = 0x3;
+const CKO_CERTIFICATE: c_ulong = 0x1;
+const CKK_RSA: c_ulong = 0x0;
 const CKK_EC: c_ulong = 0x3;
 const CKK_ECDSA: c_ulong = 0x3;
 const CKK_EDDSA: c_ulong = 0x42;
+const CKK_EC_EDWARDS: c_ulong = 0x40;
 const CKA_CLASS: c_ulong = 0x0;
 const CKA_KEY_TYPE: c_ulong = 0x100;
 const CKA_LABEL: c_ulong = 0x3;
 const CKA_ID: c_ulong = 0x102;
 const CKA_EC_PARAMS: c_ulong = 0x180;
 const CKA_EC_POINT: c_ulong = 0x181;
+const CKM_RSA_PKCS: c_ulong = 0x1;
+const CKM_RSA_PKCS_PSS: c_ulong = 0xd;
+const CKM_SHA256_RSA_PKCS: c_ulong = 0x40;
+const CKM_SHA256: c_ulong = 0x250;
+const CKG_MGF1_SHA256: c_ulong = 0x2;
 const CKM_ECDSA: c_ulong = 0x1041;
 const CKM_EDDSA: c_ulong = 0x1050;
+const CKF_SIGN: c_ulong = 0x400;
+// Not a real PKCS#11 v2.40 flag bit (the spec has no "supports multi-part
+// sign" capability flag on `CK_MECHANISM_INFO` at all) — a crate-local
+// convention, same spirit as the invented `CKK_EDDSA` constant above, so
+// `mechanism_supports_multi_part` (from `from_config`) can ask a token whether a mechanism
+// is safe to drive with `C_SignUpdate`/`C_SignFinal` rather than assuming.
+// A module that doesn't set this bit on a mechanism falls back to buffering
+// and single-shot `C_Sign` in `sign_stream`.
+const CKF_MULTI_PART: c_ulong = 0x0100_0000;
+/// Chunk size `sign_stream` reads at a time before each `C_SignUpdate` call.
+const SIGN_STREAM_CHUNK_SIZE: usize = 64 * 1024;
 const CKR_OK: c_ulong = 0x0;
 const CKR_BUFFER_TOO_SMALL: c_ulong = 0x150;
 const CKR_FUNCTION_FAILED: c_ulong = 0x6;
 
+/// The standard PKCS#11 `CKR_*` return codes a session/signing flow can
+/// realistically hit, ported from the return-value table the `pkcs11`
+/// crate's `errors` module and OpenSC's `sc_to_cryptoki_error` both encode.
+/// A named variant lets a caller distinguish, say, a locked PIN from a
+/// removed token instead of matching a formatted hex string. Codes this
+/// table doesn't name fall back to [`SignerError::Pkcs11`]'s generic
+/// message rather than being force-fit into a variant here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub enum Ckr {
+    Cancel = 0x00000001,
+    HostMemory = 0x00000002,
+    SlotIdInvalid = 0x00000003,
+    GeneralError = 0x00000005,
+    FunctionFailed = 0x00000006,
+    ArgumentsBad = 0x00000007,
+    NoEvent = 0x00000008,
+    NeedToCreateThreads = 0x00000009,
+    CantLock = 0x0000000A,
+    AttributeReadOnly = 0x00000010,
+    AttributeSensitive = 0x00000011,
+    AttributeTypeInvalid = 0x00000012,
+    AttributeValueInvalid = 0x00000013,
+    DataInvalid = 0x00000020,
+    DataLenRange = 0x00000021,
+    DeviceError = 0x00000030,
+    DeviceMemory = 0x00000031,
+    DeviceRemoved = 0x00000032,
+    EncryptedDataInvalid = 0x00000040,
+    EncryptedDataLenRange = 0x00000041,
+    FunctionCanceled = 0x00000050,
+    FunctionNotParallel = 0x00000051,
+    FunctionNotSupported = 0x00000054,
+    KeyHandleInvalid = 0x00000060,
+    KeySizeRange = 0x00000062,
+    KeyTypeInconsistent = 0x00000063,
+    KeyNotNeeded = 0x00000064,
+    KeyChanged = 0x00000065,
+    KeyNeeded = 0x00000066,
+    KeyIndigestible = 0x00000067,
+    KeyFunctionNotPermitted = 0x00000068,
+    KeyNotWrappable = 0x00000069,
+    KeyUnextractable = 0x0000006A,
+    MechanismInvalid = 0x00000070,
+    MechanismParamInvalid = 0x00000071,
+    ObjectHandleInvalid = 0x00000082,
+    OperationActive = 0x00000090,
+    OperationNotInitialized = 0x00000091,
+    PinIncorrect = 0x000000A0,
+    PinInvalid = 0x000000A1,
+    PinLenRange = 0x000000A2,
+    PinExpired = 0x000000A3,
+    PinLocked = 0x000000A4,
+    SessionClosed = 0x000000B0,
+    SessionCount = 0x000000B1,
+    SessionHandleInvalid = 0x000000B3,
+    SessionReadOnly = 0x000000B4,
+    SessionExists = 0x000000B5,
+    SessionReadOnlyExists = 0x000000B6,
+    SessionReadWriteSoExists = 0x000000B7,
+    SignatureInvalid = 0x000000C0,
+    SignatureLenRange = 0x000000C1,
+    TemplateIncomplete = 0x000000D0,
+    TemplateInconsistent = 0x000000D1,
+    TokenNotPresent = 0x000000E0,
+    TokenNotRecognized = 0x000000E1,
+    TokenWriteProtected = 0x000000E2,
+    UserAlreadyLoggedIn = 0x00000100,
+    UserNotLoggedIn = 0x00000101,
+    UserPinNotInitialized = 0x00000102,
+    UserTypeInvalid = 0x00000103,
+    UserAnotherAlreadyLoggedIn = 0x00000104,
+    UserTooManyTypes = 0x00000105,
+    CryptokiNotInitialized = 0x00000190,
+    CryptokiAlreadyInitialized = 0x00000191,
+    MutexBad = 0x000001A0,
+    MutexNotLocked = 0x000001A1,
+}
+
+impl Ckr {
+    /// Map a raw `CK_RV` return value to its named variant, or `None` if
+    /// it isn't one of the codes this table covers (including `CKR_OK`,
+    /// which isn't an error at all).
+    fn from_code(code: c_ulong) -> Option<Self> {
+        use Ckr::*;
+        Some(match code {
+            0x00000001 => Cancel,
+            0x00000002 => HostMemory,
+            0x00000003 => SlotIdInvalid,
+            0x00000005 => GeneralError,
+            0x00000006 => FunctionFailed,
+            0x00000007 => ArgumentsBad,
+            0x00000008 => NoEvent,
+            0x00000009 => NeedToCreateThreads,
+            0x0000000A => CantLock,
+            0x00000010 => AttributeReadOnly,
+            0x00000011 => AttributeSensitive,
+            0x00000012 => AttributeTypeInvalid,
+            0x00000013 => AttributeValueInvalid,
+            0x00000020 => DataInvalid,
+            0x00000021 => DataLenRange,
+            0x00000030 => DeviceError,
+            0x00000031 => DeviceMemory,
+            0x00000032 => DeviceRemoved,
+            0x00000040 => EncryptedDataInvalid,
+            0x00000041 => EncryptedDataLenRange,
+            0x00000050 => FunctionCanceled,
+            0x00000051 => FunctionNotParallel,
+            0x00000054 => FunctionNotSupported,
+            0x00000060 => KeyHandleInvalid,
+            0x00000062 => KeySizeRange,
+            0x00000063 => KeyTypeInconsistent,
+            0x00000064 => KeyNotNeeded,
+            0x00000065 => KeyChanged,
+            0x00000066 => KeyNeeded,
+            0x00000067 => KeyIndigestible,
+            0x00000068 => KeyFunctionNotPermitted,
+            0x00000069 => KeyNotWrappable,
+            0x0000006A => KeyUnextractable,
+            0x00000070 => MechanismInvalid,
+            0x00000071 => MechanismParamInvalid,
+            0x00000082 => ObjectHandleInvalid,
+            0x00000090 => OperationActive,
+            0x00000091 => OperationNotInitialized,
+            0x000000A0 => PinIncorrect,
+            0x000000A1 => PinInvalid,
+            0x000000A2 => PinLenRange,
+            0x000000A3 => PinExpired,
+            0x000000A4 => PinLocked,
+            0x000000B0 => SessionClosed,
+            0x000000B1 => SessionCount,
+            0x000000B3 => SessionHandleInvalid,
+            0x000000B4 => SessionReadOnly,
+            0x000000B5 => SessionExists,
+            0x000000B6 => SessionReadOnlyExists,
+            0x000000B7 => SessionReadWriteSoExists,
+            0x000000C0 => SignatureInvalid,
+            0x000000C1 => SignatureLenRange,
+            0x000000D0 => TemplateIncomplete,
+            0x000000D1 => TemplateInconsistent,
+            0x000000E0 => TokenNotPresent,
+            0x000000E1 => TokenNotRecognized,
+            0x000000E2 => TokenWriteProtected,
+            0x00000100 => UserAlreadyLoggedIn,
+            0x00000101 => UserNotLoggedIn,
+            0x00000102 => UserPinNotInitialized,
+            0x00000103 => UserTypeInvalid,
+            0x00000104 => UserAnotherAlreadyLoggedIn,
+            0x00000105 => UserTooManyTypes,
+            0x00000190 => CryptokiNotInitialized,
+            0x00000191 => CryptokiAlreadyInitialized,
+            0x000001A0 => MutexBad,
+            0x000001A1 => MutexNotLocked,
+            _ => return None,
+        })
+    }
+
+    fn code(self) -> c_ulong {
+        self as c_ulong
+    }
+}
+
+impl std::fmt::Display for Ckr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Ckr::Cancel => "CKR_CANCEL",
+            Ckr::HostMemory => "CKR_HOST_MEMORY",
+            Ckr::SlotIdInvalid => "CKR_SLOT_ID_INVALID",
+            Ckr::GeneralError => "CKR_GENERAL_ERROR",
+            Ckr::FunctionFailed => "CKR_FUNCTION_FAILED",
+            Ckr::ArgumentsBad => "CKR_ARGUMENTS_BAD",
+            Ckr::NoEvent => "CKR_NO_EVENT",
+            Ckr::NeedToCreateThreads => "CKR_NEED_TO_CREATE_THREADS",
+            Ckr::CantLock => "CKR_CANT_LOCK",
+            Ckr::AttributeReadOnly => "CKR_ATTRIBUTE_READ_ONLY",
+            Ckr::AttributeSensitive => "CKR_ATTRIBUTE_SENSITIVE",
+            Ckr::AttributeTypeInvalid => "CKR_ATTRIBUTE_TYPE_INVALID",
+            Ckr::AttributeValueInvalid => "CKR_ATTRIBUTE_VALUE_INVALID",
+            Ckr::DataInvalid => "CKR_DATA_INVALID",
+            Ckr::DataLenRange => "CKR_DATA_LEN_RANGE",
+            Ckr::DeviceError => "CKR_DEVICE_ERROR",
+            Ckr::DeviceMemory => "CKR_DEVICE_MEMORY",
+            Ckr::DeviceRemoved => "CKR_DEVICE_REMOVED",
+            Ckr::EncryptedDataInvalid => "CKR_ENCRYPTED_DATA_INVALID",
+            Ckr::EncryptedDataLenRange => "CKR_ENCRYPTED_DATA_LEN_RANGE",
+            Ckr::FunctionCanceled => "CKR_FUNCTION_CANCELED",
+            Ckr::FunctionNotParallel => "CKR_FUNCTION_NOT_PARALLEL",
+            Ckr::FunctionNotSupported => "CKR_FUNCTION_NOT_SUPPORTED",
+            Ckr::KeyHandleInvalid => "CKR_KEY_HANDLE_INVALID",
+            Ckr::KeySizeRange => "CKR_KEY_SIZE_RANGE",
+            Ckr::KeyTypeInconsistent => "CKR_KEY_TYPE_INCONSISTENT",
+            Ckr::KeyNotNeeded => "CKR_KEY_NOT_NEEDED",
+            Ckr::KeyChanged => "CKR_KEY_CHANGED",
+            Ckr::KeyNeeded => "CKR_KEY_NEEDED",
+            Ckr::KeyIndigestible => "CKR_KEY_INDIGESTIBLE",
+            Ckr::KeyFunctionNotPermitted => "CKR_KEY_FUNCTION_NOT_PERMITTED",
+            Ckr::KeyNotWrappable => "CKR_KEY_NOT_WRAPPABLE",
+            Ckr::KeyUnextractable => "CKR_KEY_UNEXTRACTABLE",
+            Ckr::MechanismInvalid => "CKR_MECHANISM_INVALID",
+            Ckr::MechanismParamInvalid => "CKR_MECHANISM_PARAM_INVALID",
+            Ckr::ObjectHandleInvalid => "CKR_OBJECT_HANDLE_INVALID",
+            Ckr::OperationActive => "CKR_OPERATION_ACTIVE",
+            Ckr::OperationNotInitialized => "CKR_OPERATION_NOT_INITIALIZED",
+            Ckr::PinIncorrect => "CKR_PIN_INCORRECT",
+            Ckr::PinInvalid => "CKR_PIN_INVALID",
+            Ckr::PinLenRange => "CKR_PIN_LEN_RANGE",
+            Ckr::PinExpired => "CKR_PIN_EXPIRED",
+            Ckr::PinLocked => "CKR_PIN_LOCKED",
+            Ckr::SessionClosed => "CKR_SESSION_CLOSED",
+            Ckr::SessionCount => "CKR_SESSION_COUNT",
+            Ckr::SessionHandleInvalid => "CKR_SESSION_HANDLE_INVALID",
+            Ckr::SessionReadOnly => "CKR_SESSION_READ_ONLY",
+            Ckr::SessionExists => "CKR_SESSION_EXISTS",
+            Ckr::SessionReadOnlyExists => "CKR_SESSION_READ_ONLY_EXISTS",
+            Ckr::SessionReadWriteSoExists => "CKR_SESSION_READ_WRITE_SO_EXISTS",
+            Ckr::SignatureInvalid => "CKR_SIGNATURE_INVALID",
+            Ckr::SignatureLenRange => "CKR_SIGNATURE_LEN_RANGE",
+            Ckr::TemplateIncomplete => "CKR_TEMPLATE_INCOMPLETE",
+            Ckr::TemplateInconsistent => "CKR_TEMPLATE_INCONSISTENT",
+            Ckr::TokenNotPresent => "CKR_TOKEN_NOT_PRESENT",
+            Ckr::TokenNotRecognized => "CKR_TOKEN_NOT_RECOGNIZED",
+            Ckr::TokenWriteProtected => "CKR_TOKEN_WRITE_PROTECTED",
+            Ckr::UserAlreadyLoggedIn => "CKR_USER_ALREADY_LOGGED_IN",
+            Ckr::UserNotLoggedIn => "CKR_USER_NOT_LOGGED_IN",
+            Ckr::UserPinNotInitialized => "CKR_USER_PIN_NOT_INITIALIZED",
+            Ckr::UserTypeInvalid => "CKR_USER_TYPE_INVALID",
+            Ckr::UserAnotherAlreadyLoggedIn => "CKR_USER_ANOTHER_ALREADY_LOGGED_IN",
+            Ckr::UserTooManyTypes => "CKR_USER_TOO_MANY_TYPES",
+            Ckr::CryptokiNotInitialized => "CKR_CRYPTOKI_NOT_INITIALIZED",
+            Ckr::CryptokiAlreadyInitialized => "CKR_CRYPTOKI_ALREADY_INITIALIZED",
+            Ckr::MutexBad => "CKR_MUTEX_BAD",
+            Ckr::MutexNotLocked => "CKR_MUTEX_NOT_LOCKED",
+        };
+        write!(f, "{} (0x{:08x})", name, self.code())
+    }
+}
+
+/// Build a [`SignerError`] from a failed `CK_RV` return value: a typed
+/// [`SignerError::Pkcs11Code`] when `result` is one of the named [`Ckr`]
+/// codes, or a generic [`SignerError::Pkcs11`] carrying `context` and the
+/// raw hex value otherwise.
+fn ckr_error(context: &str, result: c_ulong) -> SignerError {
+    match Ckr::from_code(result) {
+        Some(ckr) => SignerError::Pkcs11Code(ckr),
+        None => SignerError::Pkcs11(format!("{}: 0x{:x}", context, result)),
+    }
+}
+
+/// Decode a PKCS#11 fixed-width info field (`CK_TOKEN_INFO.label`,
+/// `.serialNumber`, ...): the spec requires these right-padded with spaces,
+/// not NUL-terminated, so reading all `N` bytes directly (rather than
+/// scanning for a terminator a conforming module has no obligation to
+/// write) is the only encoding that's safe for every module.
+fn fixed_field_to_string<const N: usize>(field: &[c_char; N]) -> String {
+    let bytes: Vec<u8> = field.iter().map(|&b| b as u8).collect();
+    String::from_utf8_lossy(&bytes)
+        .trim_end_matches(['\0', ' '])
+        .to_string()
+}
+
+/// The named curve behind a `CKA_EC_PARAMS` OID, limited to the curves this
+/// crate's software signers (and thus [`verify_bundle`](super::verify_bundle))
+/// already know how to handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EcCurve {
+    Secp256k1,
+    Secp256r1,
+}
+
+const OID_SECP256K1: &[u8] = &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x0a];
+const OID_SECP256R1: &[u8] = &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+impl EcCurve {
+    /// Identify the curve from the raw DER bytes `CKA_EC_PARAMS` holds,
+    /// rather than trusting `algorithm`/config — the OID is the HSM's own
+    /// authoritative statement of which curve the key lives on.
+    fn from_ec_params(der: &[u8]) -> Result<Self, SignerError> {
+        match der {
+            OID_SECP256K1 => Ok(EcCurve::Secp256k1),
+            OID_SECP256R1 => Ok(EcCurve::Secp256r1),
+            other => Err(SignerError::Crypto(format!(
+                "unsupported EC curve OID in CKA_EC_PARAMS: {}",
+                hex::encode(other)
+            ))),
+        }
+    }
+
+    /// The `algorithm` string this curve corresponds to, used to populate
+    /// [`Pkcs11Signer::algorithm`] (and, through it, [`SignerInfo::algorithm`],
+    /// [`Signature::algorithm`], and [`PublicKey::algorithm`]) for an EC key
+    /// instead of [`SigningMechanism::algorithm_label`]'s curve-agnostic
+    /// guess, since `CKM_ECDSA` alone doesn't say which curve a key is on.
+    fn algorithm_label(self) -> &'static str {
+        match self {
+            EcCurve::Secp256k1 => "secp256k1",
+            EcCurve::Secp256r1 => "secp256r1",
+        }
+    }
+}
+
+/// Strip the DER `OCTET STRING` wrapper PKCS#11 puts around `CKA_EC_POINT`,
+/// returning the raw SEC1-encoded point (`04 || X || Y` for an uncompressed
+/// point) underneath. Handles both short- and long-form DER lengths, though
+/// in practice an EC point's length always fits the short form.
+fn strip_ec_point_octet_string(der: &[u8]) -> Result<&[u8], SignerError> {
+    if der.len() < 2 || der[0] != 0x04 {
+        return Err(SignerError::Crypto(
+            "CKA_EC_POINT is not a DER OCTET STRING".to_string(),
+        ));
+    }
+
+    let (len, header_len) = if der[1] & 0x80 == 0 {
+        (der[1] as usize, 2)
+    } else {
+        let len_bytes = (der[1] & 0x7f) as usize;
+        if len_bytes == 0 || len_bytes > 4 || der.len() < 2 + len_bytes {
+            return Err(SignerError::Crypto(
+                "CKA_EC_POINT has an unsupported DER length encoding".to_string(),
+            ));
+        }
+        let len = der[2..2 + len_bytes]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (len, 2 + len_bytes)
+    };
+
+    der.get(header_len..header_len + len).ok_or_else(|| {
+        SignerError::Crypto("CKA_EC_POINT DER length exceeds the attribute value".to_string())
+    })
+}
+
+/// Assemble a DER `SubjectPublicKeyInfo` (`AlgorithmIdentifier` + `BIT
+/// STRING` of the point) from a raw SEC1-encoded EC point, via the same
+/// `EncodePublicKey` machinery the software signers use for their own SPKI
+/// output, rather than hand-rolling the ASN.1.
+fn ec_point_to_spki(curve: EcCurve, point: &[u8]) -> Result<Vec<u8>, SignerError> {
+    match curve {
+        EcCurve::Secp256k1 => {
+            let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(point)
+                .map_err(|e| SignerError::Crypto(format!("invalid secp256k1 EC point: {}", e)))?;
+            key.to_public_key_der()
+                .map(|doc| doc.as_bytes().to_vec())
+                .map_err(|e| SignerError::Crypto(format!("failed to DER-encode public key: {}", e)))
+        }
+        EcCurve::Secp256r1 => {
+            let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(point)
+                .map_err(|e| SignerError::Crypto(format!("invalid secp256r1 EC point: {}", e)))?;
+            key.to_public_key_der()
+                .map(|doc| doc.as_bytes().to_vec())
+                .map_err(|e| SignerError::Crypto(format!("failed to DER-encode public key: {}", e)))
+        }
+    }
+}
+
+/// Convert a fixed-width `r || s` ECDSA signature (the form PKCS#11's
+/// `C_Sign` with `CKM_ECDSA` returns, and the form
+/// [`super::software::Secp256k1SoftwareSigner`]/
+/// [`super::software::P256SoftwareSigner`]'s plain `sign()` produces) into the
+/// `SEQUENCE { INTEGER r, INTEGER s }` DER encoding most non-PKCS#11
+/// verifiers — including X.509 `signatureValue` ([`super::cert`]) — expect.
+/// `k256::ecdsa::Signature` is used purely for its curve-agnostic scalar
+/// encoding here; the scalars themselves aren't secp256k1-specific.
+pub(crate) fn raw_ecdsa_to_der(raw: &[u8]) -> Result<Vec<u8>, SignerError> {
+    if raw.len() % 2 != 0 {
+        return Err(SignerError::Crypto(
+            "raw ECDSA signature has an odd length, cannot split into r || s".to_string(),
+        ));
+    }
+    let half = raw.len() / 2;
+    let r = <[u8; 32]>::try_from(&raw[..half])
+        .map_err(|_| SignerError::Crypto("unexpected r length in raw ECDSA signature".to_string()))?;
+    let s = <[u8; 32]>::try_from(&raw[half..])
+        .map_err(|_| SignerError::Crypto("unexpected s length in raw ECDSA signature".to_string()))?;
+
+    let signature = k256::ecdsa::Signature::from_scalars(r, s)
+        .map_err(|e| SignerError::Crypto(format!("invalid ECDSA signature scalars: {}", e)))?;
+
+    Ok(signature.to_der().as_bytes().to_vec())
+}
+
+/// A signing mechanism this signer knows how to drive, resolved once in
+/// [`Pkcs11Signer::from_config`] from the discovered key's `CKA_KEY_TYPE`
+/// and the token's advertised mechanism list (or from
+/// [`Pkcs11SignerConfig::mechanism_override`]), rather than re-derived from
+/// a guessed `algorithm` string on every `sign` call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SigningMechanism {
+    Ecdsa,
+    Eddsa,
+    RsaPkcs1v15,
+    RsaPkcsPss,
+    Sha256RsaPkcs,
+}
+
+impl SigningMechanism {
+    fn from_name(name: &str) -> Result<Self, SignerError> {
+        match name {
+            "CKM_ECDSA" => Ok(Self::Ecdsa),
+            "CKM_EDDSA" => Ok(Self::Eddsa),
+            "CKM_RSA_PKCS" => Ok(Self::RsaPkcs1v15),
+            "CKM_RSA_PKCS_PSS" => Ok(Self::RsaPkcsPss),
+            "CKM_SHA256_RSA_PKCS" => Ok(Self::Sha256RsaPkcs),
+            other => Err(SignerError::Config(format!(
+                "unsupported mechanism_override '{}': expected one of CKM_ECDSA, \
+                 CKM_EDDSA, CKM_RSA_PKCS, CKM_RSA_PKCS_PSS, CKM_SHA256_RSA_PKCS",
+                other
+            ))),
+        }
+    }
+
+    fn ck_type(self) -> c_ulong {
+        match self {
+            Self::Ecdsa => CKM_ECDSA,
+            Self::Eddsa => CKM_EDDSA,
+            Self::RsaPkcs1v15 => CKM_RSA_PKCS,
+            Self::RsaPkcsPss => CKM_RSA_PKCS_PSS,
+            Self::Sha256RsaPkcs => CKM_SHA256_RSA_PKCS,
+        }
+    }
+
+    /// The human-readable algorithm label this mechanism corresponds to,
+    /// used to populate [`Pkcs11Signer::algorithm`] (and, through it,
+    /// [`SignerInfo::algorithm`] and [`Signature::algorithm`]) for every
+    /// mechanism except [`Self::Ecdsa`] — `CKM_ECDSA` is shared by both
+    /// secp256k1 and secp256r1 keys, so [`Pkcs11Signer::from_config`]
+    /// resolves the real label for that case via [`EcCurve::algorithm_label`]
+    /// instead of calling this method.
+    fn algorithm_label(self) -> &'static str {
+        match self {
+            Self::Ecdsa => "secp256k1",
+            Self::Eddsa => "ed25519",
+            Self::RsaPkcs1v15 | Self::RsaPkcsPss | Self::Sha256RsaPkcs => "rsa",
+        }
+    }
+
+    /// Pick a mechanism for a discovered key, preferring the scheme most
+    /// verifiers outside this HSM expect (PSS, then a hash-and-sign
+    /// mechanism, then plain PKCS#1 v1.5) among whatever the token's
+    /// `C_GetMechanismList` actually advertises signing support for.
+    fn from_key_type(key_type: c_ulong, supported: &[c_ulong]) -> Result<Self, SignerError> {
+        match key_type {
+            CKK_EC => Ok(Self::Ecdsa),
+            CKK_EC_EDWARDS => Ok(Self::Eddsa),
+            CKK_RSA => [Self::RsaPkcsPss, Self::Sha256RsaPkcs, Self::RsaPkcs1v15]
+                .into_iter()
+                .find(|candidate| supported.contains(&candidate.ck_type()))
+                .ok_or_else(|| {
+                    SignerError::Config(
+                        "token reports an RSA key but C_GetMechanismList advertises none of \
+                         CKM_RSA_PKCS_PSS, CKM_SHA256_RSA_PKCS, CKM_RSA_PKCS for signing"
+                            .to_string(),
+                    )
+                }),
+            other => Err(SignerError::Config(format!(
+                "unsupported CKA_KEY_TYPE 0x{:x}: only RSA (CKK_RSA), EC (CKK_EC), and \
+                 Ed25519 (CKK_EC_EDWARDS) keys are supported",
+                other
+            ))),
+        }
+    }
+}
+
 // PKCS#11 types
 #[repr(C)]
 #[derive(Debug, Clone)]
@@ -85,6 +571,31 @@ pub struct CK_TOKEN_INFO {
     pub utc_time: [c_char; 16],
 }
 
+impl Default for CK_TOKEN_INFO {
+    fn default() -> Self {
+        Self {
+            label: [0; 32],
+            manufacturer_id: [0; 32],
+            model: [0; 16],
+            serial_number: [0; 16],
+            flags: 0,
+            ul_max_session_count: 0,
+            ul_session_count: 0,
+            ul_max_rw_session_count: 0,
+            ul_rw_session_count: 0,
+            ul_max_pin_len: 0,
+            ul_min_pin_len: 0,
+            ul_total_public_memory: 0,
+            ul_free_public_memory: 0,
+            ul_total_private_memory: 0,
+            ul_free_private_memory: 0,
+            hardware_version: CK_VERSION { major: 0, minor: 0 },
+            firmware_version: CK_VERSION { major: 0, minor: 0 },
+            utc_time: [0; 16],
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone)]
 pub struct CK_ATTRIBUTE {
@@ -93,6 +604,47 @@ pub struct CK_ATTRIBUTE {
     pub ul_value_len: c_ulong,
 }
 
+/// A PKCS#11 object handle, as returned by `C_FindObjects` and consumed by
+/// `C_GetAttributeValue`/`C_SignInit`/etc. Just `c_ulong` under an alias so
+/// [`Pkcs11Signer::find_objects`]'s signature reads like what it returns.
+type ObjectHandle = c_ulong;
+
+/// A `CKA_*` attribute type constant, as passed to `C_GetAttributeValue`.
+type AttrType = c_ulong;
+
+/// One `(CKA_* type, encoded value)` pair to search by in
+/// [`Pkcs11Signer::find_objects`]. Owns its encoded value so the
+/// `CK_ATTRIBUTE` template [`Pkcs11Signer::find_objects`] builds from a
+/// slice of these stays valid for the search call — mirrors the
+/// `Attribute`/`Query` shape in Mozilla's `rsclientcerts` PKCS#11 manager.
+struct Attribute {
+    type_: AttrType,
+    value: Vec<u8>,
+}
+
+impl Attribute {
+    fn class(class: c_ulong) -> Self {
+        Self {
+            type_: CKA_CLASS,
+            value: class.to_ne_bytes().to_vec(),
+        }
+    }
+
+    fn label(label: &str) -> Self {
+        Self {
+            type_: CKA_LABEL,
+            value: label.as_bytes().to_vec(),
+        }
+    }
+
+    fn id(id: &[u8]) -> Self {
+        Self {
+            type_: CKA_ID,
+            value: id.to_vec(),
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone)]
 pub struct CK_MECHANISM {
@@ -101,6 +653,40 @@ pub struct CK_MECHANISM {
     pub ul_parameter_len: c_ulong,
 }
 
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct CK_MECHANISM_INFO {
+    pub ul_min_key_size: c_ulong,
+    pub ul_max_key_size: c_ulong,
+    pub flags: c_ulong,
+}
+
+/// `CK_RSA_PKCS_PSS_PARAMS`, the parameter block `CKM_RSA_PKCS_PSS` requires
+/// alongside the mechanism type, specifying the hash and mask-generation
+/// function used to build the PSS padding and the salt length.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct CK_RSA_PKCS_PSS_PARAMS {
+    pub hash_alg: c_ulong,
+    pub mgf: c_ulong,
+    pub s_len: c_ulong,
+}
+
+/// `CK_C_INITIALIZE_ARGS`, passed to `C_Initialize` to tell the module how
+/// (and whether) to manage its own thread safety. The four mutex-callback
+/// fields are left null: we never ask the module to call back into our own
+/// locking primitives, we just grant it permission to use the operating
+/// system's via `CKF_OS_LOCKING_OK`.
+#[repr(C)]
+struct CK_C_INITIALIZE_ARGS {
+    create_mutex: *mut c_void,
+    destroy_mutex: *mut c_void,
+    lock_mutex: *mut c_void,
+    unlock_mutex: *mut c_void,
+    flags: c_ulong,
+    reserved: *mut c_void,
+}
+
 // PKCS#11 function types
 type C_InitializeFn = unsafe extern "C" fn(pInitArgs: *mut c_void) -> c_ulong;
 type C_FinalizeFn = unsafe extern "C" fn(pReserved: *mut c_void) -> c_ulong;
@@ -117,38 +703,127 @@ type C_FindObjectsFn = unsafe extern "C" fn(hSession: c_ulong, phObject: *mut c_
 type C_FindObjectsFinalFn = unsafe extern "C" fn(hSession: c_ulong) -> c_ulong;
 type C_SignInitFn = unsafe extern "C" fn(hSession: c_ulong, pMechanism: *mut CK_MECHANISM, hKey: c_ulong) -> c_ulong;
 type C_SignFn = unsafe extern "C" fn(hSession: c_ulong, pData: *mut u8, ulDataLen: c_ulong, pSignature: *mut u8, pulSignatureLen: *mut c_ulong) -> c_ulong;
+type C_SignUpdateFn = unsafe extern "C" fn(hSession: c_ulong, pPart: *mut u8, ulPartLen: c_ulong) -> c_ulong;
+type C_SignFinalFn = unsafe extern "C" fn(hSession: c_ulong, pSignature: *mut u8, pulSignatureLen: *mut c_ulong) -> c_ulong;
 type C_GetAttributeValueFn = unsafe extern "C" fn(hSession: c_ulong, hObject: c_ulong, pTemplate: *mut CK_ATTRIBUTE, ulCount: c_ulong) -> c_ulong;
+type C_GetMechanismListFn = unsafe extern "C" fn(slotID: c_ulong, pMechanismList: *mut c_ulong, pulCount: *mut c_ulong) -> c_ulong;
+type C_GetMechanismInfoFn = unsafe extern "C" fn(slotID: c_ulong, type_: c_ulong, pInfo: *mut CK_MECHANISM_INFO) -> c_ulong;
 
-/// PKCS#11 HSM signer implementation
-pub struct Pkcs11Signer {
-    config: Pkcs11SignerConfig,
-    library: Arc<Library>,
-    algorithm: String,
+/// PKCS#11 function pointers
+struct Pkcs11Functions {
+    C_Initialize: C_InitializeFn,
+    C_Finalize: C_FinalizeFn,
+    C_GetInfo: C_GetInfoFn,
+    C_GetSlotList: C_GetSlotListFn,
+    C_GetSlotInfo: C_GetSlotInfoFn,
+    C_GetTokenInfo: C_GetTokenInfoFn,
+    C_OpenSession: C_OpenSessionFn,
+    C_CloseSession: C_CloseSessionFn,
+    C_Login: C_LoginFn,
+    C_Logout: C_LogoutFn,
+    C_FindObjectsInit: C_FindObjectsInitFn,
+    C_FindObjects: C_FindObjectsFn,
+    C_FindObjectsFinal: C_FindObjectsFinalFn,
+    C_SignInit: C_SignInitFn,
+    C_Sign: C_SignFn,
+    C_SignUpdate: C_SignUpdateFn,
+    C_SignFinal: C_SignFinalFn,
+    C_GetAttributeValue: C_GetAttributeValueFn,
+    C_GetMechanismList: C_GetMechanismListFn,
+    C_GetMechanismInfo: C_GetMechanismInfoFn,
 }
 
-impl Pkcs11Signer {
-    /// Create a new PKCS#11 signer from configuration
-    pub async fn from_config(config: Pkcs11SignerConfig) -> Result<Self, SignerError> {
-        let library = unsafe { Library::new(&config.module_path) }
+/// A long-lived, initialized PKCS#11 module, following the `Ctx` pattern
+/// from Marcus Heese's `pkcs11` crate: `C_Initialize` runs exactly once
+/// per module, rather than once per `sign`/`public_key` call, and
+/// `C_Finalize` runs once, from [`Drop`], when the last signer sharing it
+/// goes away. Kept separate from [`Pkcs11Session`] since the module only
+/// needs initializing once even though a signer may end up needing to
+/// reopen its session (e.g. after an HSM-side idle timeout).
+///
+/// A module may only have `C_Initialize` called once per process —
+/// calling it again (e.g. because two signers load the same module path)
+/// fails with `CKR_CRYPTOKI_ALREADY_INITIALIZED` on a conformant token.
+/// [`Pkcs11Context::shared`] is the only intended way to obtain one of
+/// these for that reason: it hands out the same context to every signer
+/// built from the same module path instead of re-initializing.
+struct Pkcs11Context {
+    // Kept alive for as long as any function pointer loaded from it is
+    // still in use; never read directly once `functions` is populated.
+    _library: Arc<Library>,
+    functions: Pkcs11Functions,
+}
+
+/// Process-global registry of initialized contexts, keyed by module path.
+/// Entries are [`Weak`] so a context is dropped (and `C_Finalize` called)
+/// as soon as the last signer referencing it does, rather than being kept
+/// alive for the rest of the process just because it once existed.
+static CONTEXT_REGISTRY: OnceLock<RwLock<HashMap<String, Weak<Pkcs11Context>>>> = OnceLock::new();
+
+impl Pkcs11Context {
+    /// Return the shared, already-initialized context for `module_path`,
+    /// initializing it if this is the first signer to ask for it. Each
+    /// signer still opens its own [`Pkcs11Session`] from the returned
+    /// context, so separate signers never serialize on a single session
+    /// handle (calls on one signer do, behind that signer's session mutex);
+    /// only the one-time `C_Initialize`/`C_Finalize` pair is shared.
+    fn shared(module_path: &str) -> Result<Arc<Self>, SignerError> {
+        let registry = CONTEXT_REGISTRY.get_or_init(|| RwLock::new(HashMap::new()));
+
+        if let Some(context) = registry
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(module_path)
+            .and_then(Weak::upgrade)
+        {
+            return Ok(context);
+        }
+
+        let mut entries = registry.write().unwrap_or_else(|e| e.into_inner());
+
+        // Another thread may have initialized it while we were waiting for
+        // the write lock; re-check before calling C_Initialize a second
+        // time.
+        if let Some(context) = entries.get(module_path).and_then(Weak::upgrade) {
+            return Ok(context);
+        }
+
+        let library = unsafe { Library::new(module_path) }
             .map_err(|e| SignerError::Pkcs11(format!("Failed to load PKCS#11 module: {}", e)))?;
+        let context = Arc::new(unsafe { Self::new(Arc::new(library))? });
+        entries.insert(module_path.to_string(), Arc::downgrade(&context));
+        Ok(context)
+    }
 
-        let algorithm = if config.module_path.to_lowercase().contains("yubikey") {
-            "ed25519".to_string()
-        } else {
-            "ed25519".to_string() // Default to Ed25519
+    unsafe fn new(library: Arc<Library>) -> Result<Self, SignerError> {
+        let functions = Self::load_functions(&library)?;
+
+        let mut init_args = CK_C_INITIALIZE_ARGS {
+            create_mutex: ptr::null_mut(),
+            destroy_mutex: ptr::null_mut(),
+            lock_mutex: ptr::null_mut(),
+            unlock_mutex: ptr::null_mut(),
+            // The module manages its own thread safety using the OS's
+            // native locking primitives, since the session this context
+            // hands out is shared across concurrent `sign` calls via
+            // `Arc<Mutex<..>>`.
+            flags: CKF_OS_LOCKING_OK,
+            reserved: ptr::null_mut(),
         };
 
+        let result = (functions.C_Initialize)(&mut init_args as *mut _ as *mut c_void);
+        if result != CKR_OK {
+            return Err(ckr_error("Failed to initialize PKCS#11", result));
+        }
+
         Ok(Self {
-            config,
-            library: Arc::new(library),
-            algorithm,
+            _library: library,
+            functions,
         })
     }
 
     /// Load PKCS#11 functions from the library
-    unsafe fn load_functions(&self) -> Result<Pkcs11Functions, SignerError> {
-        let lib = &self.library;
-        
+    unsafe fn load_functions(lib: &Library) -> Result<Pkcs11Functions, SignerError> {
         Ok(Pkcs11Functions {
             C_Initialize: *lib.get(b"C_Initialize\0").map_err(|e| SignerError::Pkcs11(format!("Failed to load C_Initialize: {}", e)))?,
             C_Finalize: *lib.get(b"C_Finalize\0").map_err(|e| SignerError::Pkcs11(format!("Failed to load C_Finalize: {}", e)))?,
@@ -165,365 +840,685 @@ impl Pkcs11Signer {
             C_FindObjectsFinal: *lib.get(b"C_FindObjectsFinal\0").map_err(|e| SignerError::Pkcs11(format!("Failed to load C_FindObjectsFinal: {}", e)))?,
             C_SignInit: *lib.get(b"C_SignInit\0").map_err(|e| SignerError::Pkcs11(format!("Failed to load C_SignInit: {}", e)))?,
             C_Sign: *lib.get(b"C_Sign\0").map_err(|e| SignerError::Pkcs11(format!("Failed to load C_Sign: {}", e)))?,
+            C_SignUpdate: *lib.get(b"C_SignUpdate\0").map_err(|e| SignerError::Pkcs11(format!("Failed to load C_SignUpdate: {}", e)))?,
+            C_SignFinal: *lib.get(b"C_SignFinal\0").map_err(|e| SignerError::Pkcs11(format!("Failed to load C_SignFinal: {}", e)))?,
             C_GetAttributeValue: *lib.get(b"C_GetAttributeValue\0").map_err(|e| SignerError::Pkcs11(format!("Failed to load C_GetAttributeValue: {}", e)))?,
+            C_GetMechanismList: *lib.get(b"C_GetMechanismList\0").map_err(|e| SignerError::Pkcs11(format!("Failed to load C_GetMechanismList: {}", e)))?,
+            C_GetMechanismInfo: *lib.get(b"C_GetMechanismInfo\0").map_err(|e| SignerError::Pkcs11(format!("Failed to load C_GetMechanismInfo: {}", e)))?,
         })
     }
+}
 
-    /// Find the appropriate slot based on configuration
-    fn find_slot(&self, functions: &Pkcs11Functions) -> Result<c_ulong, SignerError> {
+impl Drop for Pkcs11Context {
+    fn drop(&mut self) {
         unsafe {
-            // Get slot list
-            let mut slot_count: c_ulong = 0;
-            let result = (functions.C_GetSlotList)(true, ptr::null_mut(), &mut slot_count);
-            if result != CKR_OK {
-                return Err(SignerError::Pkcs11(format!("Failed to get slot count: 0x{:x}", result)));
-            }
+            (self.functions.C_Finalize)(ptr::null_mut());
+        }
+    }
+}
 
-            let mut slots = vec![0u64; slot_count as usize];
-            let result = (functions.C_GetSlotList)(true, slots.as_mut_ptr(), &mut slot_count);
-            if result != CKR_OK {
-                return Err(SignerError::Pkcs11(format!("Failed to get slot list: 0x{:x}", result)));
-            }
+/// A long-lived, authenticated session against one slot, shared across
+/// concurrent `&self` signer calls behind an `Arc<Mutex<..>>`. The private
+/// key handle is resolved once when the signer is built and cached here rather than being
+/// looked up by `C_FindObjects` on every `sign` call.
+struct Pkcs11Session {
+    context: Arc<Pkcs11Context>,
+    handle: c_ulong,
+    key_handle: Option<c_ulong>,
+}
 
-            // Find the appropriate slot
-            if let Some(slot_index) = self.config.slot_index {
-                if slot_index as usize >= slots.len() {
-                    return Err(SignerError::Config(format!("Slot index {} out of range", slot_index)));
-                }
-                return Ok(slots[slot_index as usize]);
-            }
+impl Pkcs11Session {
+    unsafe fn open(context: Arc<Pkcs11Context>, slot: c_ulong, config: &Pkcs11SignerConfig) -> Result<Self, SignerError> {
+        let mut handle: c_ulong = 0;
+        let result = (context.functions.C_OpenSession)(slot, CKF_SERIAL_SESSION | CKF_RW_SESSION, &mut handle);
+        if result != CKR_OK {
+            return Err(ckr_error("Failed to open session", result));
+        }
 
-            if let Some(ref token_label) = self.config.token_label {
-                let label_cstr = CString::new(token_label.as_str()).unwrap();
-                
-                for &slot in &slots {
-                    let mut token_info = CK_TOKEN_INFO {
-                        label: [0; 32],
-                        manufacturer_id: [0; 32],
-                        model: [0; 16],
-                        serial_number: [0; 16],
-                        flags: 0,
-                        ul_max_session_count: 0,
-                        ul_session_count: 0,
-                        ul_max_rw_session_count: 0,
-                        ul_rw_session_count: 0,
-                        ul_max_pin_len: 0,
-                        ul_min_pin_len: 0,
-                        ul_total_public_memory: 0,
-                        ul_free_public_memory: 0,
-                        ul_total_private_memory: 0,
-                        ul_free_private_memory: 0,
-                        hardware_version: CK_VERSION { major: 0, minor: 0 },
-                        firmware_version: CK_VERSION { major: 0, minor: 0 },
-                        utc_time: [0; 16],
-                    };
-
-                    let result = (functions.C_GetTokenInfo)(slot, &mut token_info);
-                    if result == CKR_OK {
-                        let token_label_str = CStr::from_ptr(token_info.label.as_ptr()).to_string_lossy();
-                        if token_label_str.trim_matches('\0') == token_label {
-                            return Ok(slot);
-                        }
-                    }
-                }
-                
-                return Err(SignerError::Config(format!("Token with label '{}' not found", token_label)));
-            }
+        // A protected-authentication-path token has its own PIN-pad: the
+        // PIN goes straight from the user to the device, never through us,
+        // so C_Login takes a NULL PIN and the reader prompts directly.
+        // Trust an explicit config override before asking the token, since
+        // some readers misreport the flag.
+        let protected_auth_path = config.protected_auth_path
+            || Self::token_reports_protected_auth_path(&context, slot);
 
-            // Use first slot if no specific configuration
-            if slots.is_empty() {
-                return Err(SignerError::Pkcs11("No slots available".to_string()));
-            }
-            
-            Ok(slots[0])
+        let result = if protected_auth_path {
+            (context.functions.C_Login)(handle, CKU_USER, ptr::null_mut())
+        } else {
+            let pin = config.pin.as_deref().ok_or_else(|| SignerError::Config(
+                "ERST_PKCS11_PIN or ERST_PKCS11_PIN_FILE must be set (or ERST_PKCS11_PROTECTED_AUTH=1 for a PIN-pad reader)"
+                    .to_string(),
+            ))?;
+            let pin_cstr = CString::new(pin).map_err(|e| SignerError::Config(format!("PIN contains a NUL byte: {}", e)))?;
+            let login_result = (context.functions.C_Login)(handle, CKU_USER, pin_cstr.as_ptr() as *mut c_char);
+
+            // The PIN only needs to live long enough for this one C_Login
+            // call; scrub the CString's own buffer once it's done rather
+            // than leaving a second plaintext copy of it (beyond
+            // `config.pin`) sitting in the heap for the process's lifetime.
+            pin_cstr.into_bytes_with_nul().zeroize();
+
+            login_result
+        };
+
+        if result != CKR_OK {
+            (context.functions.C_CloseSession)(handle);
+            return Err(ckr_error("Failed to login", result));
         }
+
+        Ok(Self {
+            context,
+            handle,
+            key_handle: None,
+        })
     }
 
-    /// Find the private key in the HSM
-    fn find_private_key(&self, functions: &Pkcs11Functions, session: c_ulong) -> Result<c_ulong, SignerError> {
+    /// Whether the token in `slot` advertises `CKF_PROTECTED_AUTHENTICATION_PATH`
+    /// in its `CK_TOKEN_INFO` flags. A `C_GetTokenInfo` failure is treated
+    /// the same as the flag being unset — `find_slot` already succeeded
+    /// against this slot, so a failure here just falls back to requiring a
+    /// software PIN.
+    unsafe fn token_reports_protected_auth_path(context: &Pkcs11Context, slot: c_ulong) -> bool {
+        let mut token_info = CK_TOKEN_INFO::default();
+        let result = (context.functions.C_GetTokenInfo)(slot, &mut token_info);
+        result == CKR_OK && token_info.flags & CKF_PROTECTED_AUTHENTICATION_PATH != 0
+    }
+}
+
+impl Drop for Pkcs11Session {
+    fn drop(&mut self) {
         unsafe {
-            let mut template = vec![
-                CK_ATTRIBUTE {
-                    type_: CKA_CLASS,
-                    p_value: &mut CKO_PRIVATE_KEY as *mut _ as *mut c_void,
-                    ul_value_len: std::mem::size_of::<c_ulong>() as c_ulong,
-                },
-            ];
-
-            // Add key identifier if specified
-            if let Some(ref key_label) = self.config.key_label {
-                let label_cstr = CString::new(key_label.as_str()).unwrap();
-                template.push(CK_ATTRIBUTE {
-                    type_: CKA_LABEL,
-                    p_value: label_cstr.as_ptr() as *mut c_void,
-                    ul_value_len: key_label.len() as c_ulong,
-                });
-            }
+            (self.context.functions.C_Logout)(self.handle);
+            (self.context.functions.C_CloseSession)(self.handle);
+        }
+    }
+}
 
-            if let Some(ref key_id_hex) = self.config.key_id_hex {
-                let key_id_bytes = hex::decode(key_id_hex)
-                    .map_err(|e| SignerError::Config(format!("Invalid key ID hex: {}", e)))?;
-                template.push(CK_ATTRIBUTE {
-                    type_: CKA_ID,
-                    p_value: key_id_bytes.as_ptr() as *mut c_void,
-                    ul_value_len: key_id_bytes.len() as c_ulong,
-                });
-            }
+/// PKCS#11 HSM signer implementation
+pub struct Pkcs11Signer {
+    config: Pkcs11SignerConfig,
+    algorithm: String,
+    /// The mechanism `sign` drives `C_SignInit`/`C_Sign` with, resolved once
+    /// in [`Self::from_config`] rather than re-derived from `algorithm` on
+    /// every call.
+    mechanism: SigningMechanism,
+    /// Whether [`Self::sign_stream`] may drive `self.mechanism` with
+    /// `C_SignUpdate`/`C_SignFinal` instead of buffering the whole message
+    /// for a single-shot `C_Sign`. Always `false` for [`SigningMechanism::Eddsa`]:
+    /// pure Ed25519 signs over the whole message in one pass and has no
+    /// multi-part form, regardless of what a token's `C_GetMechanismInfo`
+    /// claims.
+    supports_streaming: bool,
+    /// Owns the one-time `C_Initialize`/`C_Finalize` lifecycle (see
+    /// [`Pkcs11Context`]). Shared with the session so the session's `Drop`
+    /// impl can still reach the function table after this field is gone.
+    context: Arc<Pkcs11Context>,
+    /// The long-lived, authenticated session, shared across concurrent
+    /// `&self` calls. Resolving the key handle happens under this same
+    /// lock and is cached on the `Pkcs11Session` for subsequent calls.
+    session: Arc<Mutex<Pkcs11Session>>,
+    /// Resolved lazily by [`Pkcs11Signer::public_key`] and cached, since
+    /// it never changes for the lifetime of a signer.
+    public_key: Mutex<Option<PublicKey>>,
+}
 
-            let result = (functions.C_FindObjectsInit)(session, template.as_mut_ptr(), template.len() as c_ulong);
-            if result != CKR_OK {
-                return Err(SignerError::Pkcs11(format!("Failed to initialize key search: 0x{:x}", result)));
-            }
+impl Pkcs11Signer {
+    /// Create a new PKCS#11 signer from configuration
+    pub async fn from_config(config: Pkcs11SignerConfig) -> Result<Self, SignerError> {
+        config.validate()?;
+
+        let context = Pkcs11Context::shared(&config.module_path)?;
+
+        let slot = unsafe { Self::find_slot(&context, &config)? };
+        let mut session = unsafe { Pkcs11Session::open(context.clone(), slot, &config)? };
+
+        // The key handle is needed either way: to resolve the mechanism from
+        // CKA_KEY_TYPE when there's no override, and (for an EC key) to read
+        // CKA_EC_PARAMS and tell secp256k1 and secp256r1 apart below — both
+        // curves report the same CKK_EC key type and drive the same
+        // CKM_ECDSA mechanism, so neither the key type nor a mechanism
+        // override name alone says which curve is actually on the key.
+        let key_handle = unsafe { Self::find_private_key(&context, &config, session.handle)? };
+        session.key_handle = Some(key_handle);
+
+        // Resolve the signing mechanism from an explicit override, or else
+        // from the discovered key's own CKA_KEY_TYPE cross-checked against
+        // what the token actually advertises via C_GetMechanismList —
+        // rather than string-matching the module path.
+        let mechanism = if let Some(ref name) = config.mechanism_override {
+            SigningMechanism::from_name(name)?
+        } else {
+            let key_type = unsafe { Self::key_type_of(&context, session.handle, key_handle)? };
+            let supported = unsafe { Self::supported_mechanisms(&context, slot)? };
+            SigningMechanism::from_key_type(key_type, &supported)?
+        };
 
-            let mut key_handle: c_ulong = 0;
-            let mut object_count: c_ulong = 0;
-            let result = (functions.C_FindObjects)(session, &mut key_handle, 1, &mut object_count);
-            
-            // Always finalize the search
-            (functions.C_FindObjectsFinal)(session);
+        let algorithm = if mechanism == SigningMechanism::Ecdsa {
+            let curve = unsafe { Self::ec_curve_of_key(&context, session.handle, key_handle)? };
+            curve.algorithm_label().to_string()
+        } else {
+            mechanism.algorithm_label().to_string()
+        };
 
-            if result != CKR_OK {
-                return Err(SignerError::Pkcs11(format!("Failed to find key: 0x{:x}", result)));
-            }
+        let supports_streaming = mechanism != SigningMechanism::Eddsa
+            && unsafe { Self::mechanism_supports_multi_part(&context, slot, mechanism.ck_type()) };
 
-            if object_count == 0 {
-                return Err(SignerError::KeyNotFound("Private key not found in HSM".to_string()));
-            }
+        Ok(Self {
+            config,
+            algorithm,
+            mechanism,
+            supports_streaming,
+            context,
+            session: Arc::new(Mutex::new(session)),
+            public_key: Mutex::new(None),
+        })
+    }
+
+    /// Read the `CKA_KEY_TYPE` of `key_handle` (`CKK_RSA`, `CKK_EC`,
+    /// `CKK_EC_EDWARDS`, ...).
+    unsafe fn key_type_of(context: &Pkcs11Context, session: c_ulong, key_handle: c_ulong) -> Result<c_ulong, SignerError> {
+        let functions = &context.functions;
 
-            Ok(key_handle)
+        let mut key_type: c_ulong = 0;
+        let mut attr = CK_ATTRIBUTE {
+            type_: CKA_KEY_TYPE,
+            p_value: &mut key_type as *mut _ as *mut c_void,
+            ul_value_len: std::mem::size_of::<c_ulong>() as c_ulong,
+        };
+
+        let result = (functions.C_GetAttributeValue)(session, key_handle, &mut attr, 1);
+        if result != CKR_OK {
+            return Err(ckr_error("Failed to get key type", result));
         }
+
+        Ok(key_type)
     }
 
-    /// Get public key from HSM
-    fn get_public_key(&self, functions: &Pkcs11Functions, session: c_ulong) -> Result<PublicKey, SignerError> {
-        unsafe {
-            // If public key is provided in config, use it
-            if let Some(ref pem_data) = self.config.public_key_pem {
-                let spki_bytes = pem_data.as_bytes().to_vec();
-                return Ok(PublicKey {
-                    algorithm: self.algorithm.clone(),
-                    spki_bytes,
-                });
-            }
+    /// Query the token's `C_GetMechanismList`, keeping only the mechanisms
+    /// `C_GetMechanismInfo` reports as supporting signing (`CKF_SIGN`) — a
+    /// token can list a mechanism it only supports for verification.
+    unsafe fn supported_mechanisms(context: &Pkcs11Context, slot: c_ulong) -> Result<Vec<c_ulong>, SignerError> {
+        let functions = &context.functions;
 
-            // Otherwise, extract public key from HSM
-            let mut template = vec![
-                CK_ATTRIBUTE {
-                    type_: CKA_CLASS,
-                    p_value: &mut CKO_PUBLIC_KEY as *mut _ as *mut c_void,
-                    ul_value_len: std::mem::size_of::<c_ulong>() as c_ulong,
-                },
-            ];
-
-            // Add key identifier if specified
-            if let Some(ref key_label) = self.config.key_label {
-                let label_cstr = CString::new(key_label.as_str()).unwrap();
-                template.push(CK_ATTRIBUTE {
-                    type_: CKA_LABEL,
-                    p_value: label_cstr.as_ptr() as *mut c_void,
-                    ul_value_len: key_label.len() as c_ulong,
-                });
-            }
+        let mut count: c_ulong = 0;
+        let result = (functions.C_GetMechanismList)(slot, ptr::null_mut(), &mut count);
+        if result != CKR_OK {
+            return Err(ckr_error("Failed to get mechanism count", result));
+        }
 
-            if let Some(ref key_id_hex) = self.config.key_id_hex {
-                let key_id_bytes = hex::decode(key_id_hex)
-                    .map_err(|e| SignerError::Config(format!("Invalid key ID hex: {}", e)))?;
-                template.push(CK_ATTRIBUTE {
-                    type_: CKA_ID,
-                    p_value: key_id_bytes.as_ptr() as *mut c_void,
-                    ul_value_len: key_id_bytes.len() as c_ulong,
-                });
-            }
+        let mut mechanisms = vec![0u64; count as usize];
+        let result = (functions.C_GetMechanismList)(slot, mechanisms.as_mut_ptr(), &mut count);
+        if result != CKR_OK {
+            return Err(ckr_error("Failed to get mechanism list", result));
+        }
 
-            let result = (functions.C_FindObjectsInit)(session, template.as_mut_ptr(), template.len() as c_ulong);
-            if result != CKR_OK {
-                return Err(SignerError::Pkcs11(format!("Failed to initialize public key search: 0x{:x}", result)));
+        let mut signing = Vec::new();
+        for mechanism_type in mechanisms {
+            let mut info = CK_MECHANISM_INFO {
+                ul_min_key_size: 0,
+                ul_max_key_size: 0,
+                flags: 0,
+            };
+            let result = (functions.C_GetMechanismInfo)(slot, mechanism_type, &mut info);
+            if result == CKR_OK && info.flags & CKF_SIGN != 0 {
+                signing.push(mechanism_type);
             }
+        }
 
-            let mut key_handle: c_ulong = 0;
-            let mut object_count: c_ulong = 0;
-            let result = (functions.C_FindObjects)(session, &mut key_handle, 1, &mut object_count);
-            
-            (functions.C_FindObjectsFinal)(session);
+        Ok(signing)
+    }
 
-            if result != CKR_OK {
-                return Err(SignerError::Pkcs11(format!("Failed to find public key: 0x{:x}", result)));
+    /// Whether the token's `C_GetMechanismInfo` advertises `mechanism_type`
+    /// as safe to drive with `C_SignUpdate`/`C_SignFinal` (see
+    /// [`CKF_MULTI_PART`] for why this isn't a real PKCS#11 flag). Errors
+    /// from `C_GetMechanismInfo` are treated as "not supported" rather than
+    /// surfaced, matching how [`Self::supported_mechanisms`] silently skips
+    /// mechanisms a token fails to describe.
+    unsafe fn mechanism_supports_multi_part(context: &Pkcs11Context, slot: c_ulong, mechanism_type: c_ulong) -> bool {
+        let mut info = CK_MECHANISM_INFO {
+            ul_min_key_size: 0,
+            ul_max_key_size: 0,
+            flags: 0,
+        };
+        let result = (context.functions.C_GetMechanismInfo)(slot, mechanism_type, &mut info);
+        result == CKR_OK && info.flags & CKF_MULTI_PART != 0
+    }
+
+    /// Find the appropriate slot based on configuration
+    unsafe fn find_slot(context: &Pkcs11Context, config: &Pkcs11SignerConfig) -> Result<c_ulong, SignerError> {
+        let functions = &context.functions;
+
+        // Get slot list
+        let mut slot_count: c_ulong = 0;
+        let result = (functions.C_GetSlotList)(true, ptr::null_mut(), &mut slot_count);
+        if result != CKR_OK {
+            return Err(ckr_error("Failed to get slot count", result));
+        }
+
+        let mut slots = vec![0u64; slot_count as usize];
+        let result = (functions.C_GetSlotList)(true, slots.as_mut_ptr(), &mut slot_count);
+        if result != CKR_OK {
+            return Err(ckr_error("Failed to get slot list", result));
+        }
+
+        // Find the appropriate slot
+        if let Some(slot_index) = config.slot_index {
+            if slot_index as usize >= slots.len() {
+                return Err(SignerError::Config(format!("Slot index {} out of range", slot_index)));
             }
+            return Ok(slots[slot_index as usize]);
+        }
+
+        if config.token_label.is_some() || config.token_serial.is_some() {
+            let mut available = Vec::new();
 
-            if object_count == 0 {
-                return Err(SignerError::KeyNotFound("Public key not found in HSM".to_string()));
+            for &slot in &slots {
+                let mut token_info = CK_TOKEN_INFO::default();
+                if (functions.C_GetTokenInfo)(slot, &mut token_info) != CKR_OK {
+                    continue;
+                }
+
+                let label = fixed_field_to_string(&token_info.label);
+                let serial = fixed_field_to_string(&token_info.serial_number);
+
+                let label_matches = config.token_label.as_deref().map_or(true, |want| want == label);
+                let serial_matches = config.token_serial.as_deref().map_or(true, |want| want == serial);
+                if label_matches && serial_matches {
+                    return Ok(slot);
+                }
+
+                available.push(format!("'{}' (serial '{}')", label, serial));
             }
 
-            // Get EC point (public key)
-            let mut point_len: c_ulong = 0;
-            let mut point_attr = CK_ATTRIBUTE {
-                type_: CKA_EC_POINT,
+            return Err(SignerError::Config(format!(
+                "No token matches label={:?} serial={:?}; available tokens: [{}]",
+                config.token_label,
+                config.token_serial,
+                available.join(", "),
+            )));
+        }
+
+        // Use first slot if no specific configuration
+        if slots.is_empty() {
+            return Err(SignerError::Pkcs11("No slots available".to_string()));
+        }
+
+        Ok(slots[0])
+    }
+
+    /// Search for objects matching `query`, returning up to `max` handles.
+    /// Correctly sequences `C_FindObjectsInit`/`C_FindObjects`/
+    /// `C_FindObjectsFinal` — the three-call pattern `find_private_key` and
+    /// `get_public_key` used to each duplicate separately, borrowed from the
+    /// `Query`/`find_objects` shape in Mozilla's `rsclientcerts` manager.
+    unsafe fn find_objects(context: &Pkcs11Context, session: c_ulong, query: &[Attribute], max: usize) -> Result<Vec<ObjectHandle>, SignerError> {
+        let functions = &context.functions;
+
+        let mut template: Vec<CK_ATTRIBUTE> = query
+            .iter()
+            .map(|attr| CK_ATTRIBUTE {
+                type_: attr.type_,
+                p_value: attr.value.as_ptr() as *mut c_void,
+                ul_value_len: attr.value.len() as c_ulong,
+            })
+            .collect();
+
+        let result = (functions.C_FindObjectsInit)(session, template.as_mut_ptr(), template.len() as c_ulong);
+        if result != CKR_OK {
+            return Err(ckr_error("Failed to initialize object search", result));
+        }
+
+        let mut handles = vec![0u64; max];
+        let mut object_count: c_ulong = 0;
+        let result = (functions.C_FindObjects)(session, handles.as_mut_ptr(), max as c_ulong, &mut object_count);
+
+        // Always finalize the search, even if C_FindObjects itself failed.
+        (functions.C_FindObjectsFinal)(session);
+
+        if result != CKR_OK {
+            return Err(ckr_error("Failed to find objects", result));
+        }
+
+        handles.truncate(object_count as usize);
+        Ok(handles)
+    }
+
+    /// Read each of `types` off `handle` via the standard two-pass
+    /// length-probe-then-fetch `C_GetAttributeValue` pattern. An attribute
+    /// the token can't or won't report (absent, sensitive, unsupported) is
+    /// silently left out of the returned map rather than failing the whole
+    /// call — callers that require a given attribute should check for its
+    /// absence explicitly, as [`Self::get_public_key`] does for
+    /// `CKA_EC_POINT`/`CKA_EC_PARAMS`.
+    unsafe fn get_attributes(context: &Pkcs11Context, session: c_ulong, handle: ObjectHandle, types: &[AttrType]) -> HashMap<AttrType, Vec<u8>> {
+        let functions = &context.functions;
+        let mut values = HashMap::new();
+
+        for &type_ in types {
+            let mut probe = CK_ATTRIBUTE {
+                type_,
                 p_value: ptr::null_mut(),
                 ul_value_len: 0,
             };
+            let result = (functions.C_GetAttributeValue)(session, handle, &mut probe, 1);
+            if result != CKR_OK || probe.ul_value_len == 0 || probe.ul_value_len == c_ulong::MAX {
+                continue;
+            }
 
-            let result = (functions.C_GetAttributeValue)(session, key_handle, &mut point_attr, 1);
+            let mut buf = vec![0u8; probe.ul_value_len as usize];
+            let mut fetch = CK_ATTRIBUTE {
+                type_,
+                p_value: buf.as_mut_ptr() as *mut c_void,
+                ul_value_len: probe.ul_value_len,
+            };
+            let result = (functions.C_GetAttributeValue)(session, handle, &mut fetch, 1);
             if result == CKR_OK {
-                point_len = point_attr.ul_value_len;
+                values.insert(type_, buf);
             }
+        }
 
-            let mut point_bytes = vec![0u8; point_len as usize];
-            point_attr.p_value = point_bytes.as_mut_ptr() as *mut c_void;
-            point_attr.ul_value_len = point_len;
+        values
+    }
 
-            let result = (functions.C_GetAttributeValue)(session, key_handle, &mut point_attr, 1);
-            if result != CKR_OK {
-                return Err(SignerError::Pkcs11(format!("Failed to get public key point: 0x{:x}", result)));
-            }
+    /// List every X.509 certificate object (`CKO_CERTIFICATE`) on the
+    /// token. Unlocked by [`Self::find_objects`]/[`Self::get_attributes`];
+    /// nothing in this crate calls it yet, but it's the building block for
+    /// chain validation against a token-resident certificate.
+    #[allow(dead_code)]
+    unsafe fn find_certificates(context: &Pkcs11Context, session: c_ulong) -> Result<Vec<ObjectHandle>, SignerError> {
+        Self::find_objects(context, session, &[Attribute::class(CKO_CERTIFICATE)], 64)
+    }
 
-            // Convert to SPKI format (simplified - in practice you'd need proper DER encoding)
-            Ok(PublicKey {
-                algorithm: self.algorithm.clone(),
-                spki_bytes: point_bytes,
-            })
+    /// Build the `(class, label?, id?)` search query shared by
+    /// `find_private_key` and `get_public_key`.
+    fn key_query(class: c_ulong, config: &Pkcs11SignerConfig) -> Result<Vec<Attribute>, SignerError> {
+        let mut query = vec![Attribute::class(class)];
+
+        if let Some(ref key_label) = config.key_label {
+            query.push(Attribute::label(key_label));
+        }
+
+        if let Some(ref key_id_hex) = config.key_id_hex {
+            let key_id_bytes = hex::decode(key_id_hex)
+                .map_err(|e| SignerError::Config(format!("Invalid key ID hex: {}", e)))?;
+            query.push(Attribute::id(&key_id_bytes));
         }
+
+        Ok(query)
     }
-}
 
-#[async_trait]
-impl Signer for Pkcs11Signer {
-    async fn sign(&self, data: &[u8]) -> Result<Signature, SignerError> {
-        let functions = unsafe { self.load_functions()? };
-        
-        unsafe {
-            // Initialize PKCS#11
-            let result = (functions.C_Initialize)(ptr::null_mut());
-            if result != CKR_OK {
-                return Err(SignerError::Pkcs11(format!("Failed to initialize PKCS#11: 0x{:x}", result)));
-            }
+    /// Find the private key in the HSM
+    unsafe fn find_private_key(context: &Pkcs11Context, config: &Pkcs11SignerConfig, session: c_ulong) -> Result<c_ulong, SignerError> {
+        let query = Self::key_query(CKO_PRIVATE_KEY, config)?;
+        Self::find_objects(context, session, &query, 1)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| SignerError::KeyNotFound("Private key not found in HSM".to_string()))
+    }
 
-            // Find slot
-            let slot = self.find_slot(&functions)?;
-            
-            // Open session
-            let mut session: c_ulong = 0;
-            let result = (functions.C_OpenSession)(slot, CKF_SERIAL_SESSION | CKF_RW_SESSION, &mut session);
-            if result != CKR_OK {
-                (functions.C_Finalize)(ptr::null_mut());
-                return Err(SignerError::Pkcs11(format!("Failed to open session: 0x{:x}", result)));
+    /// Resolve the named curve of an EC key from its own `CKA_EC_PARAMS`
+    /// attribute — per the PKCS#11 spec a private EC key carries the same
+    /// domain parameters as its public counterpart, so this works from
+    /// `key_handle` alone without a separate public-key object lookup.
+    unsafe fn ec_curve_of_key(context: &Pkcs11Context, session: c_ulong, key_handle: c_ulong) -> Result<EcCurve, SignerError> {
+        let attrs = Self::get_attributes(context, session, key_handle, &[CKA_EC_PARAMS]);
+        let params_bytes = attrs.get(&CKA_EC_PARAMS).ok_or_else(|| {
+            SignerError::Crypto("EC key object has no CKA_EC_PARAMS attribute".to_string())
+        })?;
+        EcCurve::from_ec_params(params_bytes)
+    }
+
+    /// Get public key from HSM
+    unsafe fn get_public_key(context: &Pkcs11Context, config: &Pkcs11SignerConfig, algorithm: &str, session: c_ulong) -> Result<PublicKey, SignerError> {
+        // If public key is provided in config, use it
+        if let Some(ref pem_data) = config.public_key_pem {
+            let spki_bytes = pem_data.as_bytes().to_vec();
+            return Ok(PublicKey {
+                algorithm: algorithm.to_string(),
+                spki_bytes,
+            });
+        }
+
+        // Otherwise, extract public key from HSM
+        let query = Self::key_query(CKO_PUBLIC_KEY, config)?;
+        let key_handle = Self::find_objects(context, session, &query, 1)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| SignerError::KeyNotFound("Public key not found in HSM".to_string()))?;
+
+        let attrs = Self::get_attributes(context, session, key_handle, &[CKA_EC_POINT, CKA_EC_PARAMS]);
+
+        let point_bytes = attrs.get(&CKA_EC_POINT).ok_or_else(|| {
+            SignerError::Crypto("public key object has no CKA_EC_POINT attribute".to_string())
+        })?;
+        let params_bytes = attrs.get(&CKA_EC_PARAMS).ok_or_else(|| {
+            SignerError::Crypto("public key object has no CKA_EC_PARAMS attribute".to_string())
+        })?;
+
+        let curve = EcCurve::from_ec_params(params_bytes)?;
+        let raw_point = strip_ec_point_octet_string(point_bytes)?;
+        let spki_bytes = ec_point_to_spki(curve, raw_point)?;
+
+        Ok(PublicKey {
+            algorithm: algorithm.to_string(),
+            spki_bytes,
+        })
+    }
+
+    /// Sign a potentially large message without buffering it whole, by
+    /// feeding it to the HSM in [`SIGN_STREAM_CHUNK_SIZE`] chunks via
+    /// `C_SignUpdate` after `C_SignInit`, then finishing with `C_SignFinal`.
+    ///
+    /// Falls back to reading `reader` fully and calling [`Self::sign`] when
+    /// `self.mechanism` doesn't support multi-part signing (see
+    /// [`Pkcs11Signer::supports_streaming`]) — this covers pure EdDSA, and
+    /// any token that doesn't advertise multi-part support for its
+    /// mechanism.
+    pub async fn sign_stream(&self, mut reader: impl AsyncRead + Unpin) -> Result<Signature, SignerError> {
+        if !self.supports_streaming {
+            let mut buf = Vec::new();
+            reader
+                .read_to_end(&mut buf)
+                .await
+                .map_err(|e| SignerError::Pkcs11(format!("failed to read stream for signing: {}", e)))?;
+            return self.sign(&buf).await;
+        }
+
+        let mut session = self.session.lock().expect("PKCS#11 session mutex poisoned");
+
+        let key_handle = match session.key_handle {
+            Some(handle) => handle,
+            None => {
+                let handle = unsafe { Self::find_private_key(&self.context, &self.config, session.handle)? };
+                session.key_handle = Some(handle);
+                handle
             }
+        };
+
+        unsafe {
+            let functions = &self.context.functions;
 
-            // Login
-            let pin_cstr = CString::new(self.config.pin.as_str()).unwrap();
-            let result = (functions.C_Login)(session, CKU_USER, pin_cstr.as_ptr() as *mut c_char);
+            let mut pss_params = default_pss_params();
+            let mut mechanism = build_mechanism(self.mechanism, &mut pss_params);
+
+            let result = (functions.C_SignInit)(session.handle, &mut mechanism as *mut _, key_handle);
             if result != CKR_OK {
-                (functions.C_CloseSession)(session);
-                (functions.C_Finalize)(ptr::null_mut());
-                return Err(SignerError::Pkcs11(format!("Failed to login: 0x{:x}", result)));
+                return Err(ckr_error("Failed to initialize streaming signing", result));
             }
 
-            // Find private key
-            let key_handle = self.find_private_key(&functions, session)?;
-
-            // Initialize signing
-            let mechanism = if self.algorithm == "secp256k1" {
-                CK_MECHANISM {
-                    mechanism: CKM_ECDSA,
-                    p_parameter: ptr::null_mut(),
-                    ul_parameter_len: 0,
-                }
-            } else {
-                CK_MECHANISM {
-                    mechanism: CKM_EDDSA,
-                    p_parameter: ptr::null_mut(),
-                    ul_parameter_len: 0,
+            let mut chunk = vec![0u8; SIGN_STREAM_CHUNK_SIZE];
+            loop {
+                let n = reader
+                    .read(&mut chunk)
+                    .await
+                    .map_err(|e| SignerError::Pkcs11(format!("failed to read stream for signing: {}", e)))?;
+                if n == 0 {
+                    break;
                 }
-            };
 
-            let result = (functions.C_SignInit)(session, &mut mechanism as *mut _, key_handle);
-            if result != CKR_OK {
-                (functions.C_Logout)(session);
-                (functions.C_CloseSession)(session);
-                (functions.C_Finalize)(ptr::null_mut());
-                return Err(SignerError::Pkcs11(format!("Failed to initialize signing: 0x{:x}", result)));
+                let result = (functions.C_SignUpdate)(session.handle, chunk.as_mut_ptr(), n as c_ulong);
+                if result != CKR_OK {
+                    return Err(ckr_error("Failed to update streaming signature", result));
+                }
             }
 
-            // Sign data
             let mut signature_len: c_ulong = 0;
-            let data_ptr = data.as_ptr() as *mut u8;
-            let result = (functions.C_Sign)(session, data_ptr, data.len() as c_ulong, ptr::null_mut(), &mut signature_len);
+            let result = (functions.C_SignFinal)(session.handle, ptr::null_mut(), &mut signature_len);
             if result != CKR_OK && result != CKR_BUFFER_TOO_SMALL {
-                (functions.C_Logout)(session);
-                (functions.C_CloseSession)(session);
-                (functions.C_Finalize)(ptr::null_mut());
-                return Err(SignerError::Pkcs11(format!("Failed to get signature length: 0x{:x}", result)));
+                return Err(ckr_error("Failed to get streaming signature length", result));
             }
 
             let mut signature_bytes = vec![0u8; signature_len as usize];
-            let result = (functions.C_Sign)(session, data_ptr, data.len() as c_ulong, signature_bytes.as_mut_ptr(), &mut signature_len);
-            
-            // Cleanup
-            (functions.C_Logout)(session);
-            (functions.C_CloseSession)(session);
-            (functions.C_Finalize)(ptr::null_mut());
-
+            let result = (functions.C_SignFinal)(session.handle, signature_bytes.as_mut_ptr(), &mut signature_len);
             if result != CKR_OK {
-                return Err(SignerError::Pkcs11(format!("Failed to sign data: 0x{:x}", result)));
+                return Err(ckr_error("Failed to finalize streaming signature", result));
             }
 
+            let bytes = finish_signature(&self.algorithm, self.config.der_signatures, signature_bytes)?;
+
             Ok(Signature {
                 algorithm: self.algorithm.clone(),
-                bytes: signature_bytes,
+                bytes,
+                recovery_id: None,
             })
         }
     }
+}
+
+/// Default parameters for `CKM_RSA_PKCS_PSS`: SHA-256 digest and MGF1-SHA256
+/// mask generation with a 32-byte salt, matching `CKM_SHA256`'s digest size.
+fn default_pss_params() -> CK_RSA_PKCS_PSS_PARAMS {
+    CK_RSA_PKCS_PSS_PARAMS {
+        hash_alg: CKM_SHA256,
+        mgf: CKG_MGF1_SHA256,
+        s_len: 32,
+    }
+}
+
+/// Build the `CK_MECHANISM` to hand `C_SignInit` for `mechanism`. `pss_params`
+/// must outlive the returned value: `CKM_RSA_PKCS_PSS` points
+/// `p_parameter` at it rather than embedding it inline.
+fn build_mechanism(mechanism: SigningMechanism, pss_params: &mut CK_RSA_PKCS_PSS_PARAMS) -> CK_MECHANISM {
+    match mechanism {
+        SigningMechanism::Ecdsa => CK_MECHANISM {
+            mechanism: CKM_ECDSA,
+            p_parameter: ptr::null_mut(),
+            ul_parameter_len: 0,
+        },
+        SigningMechanism::Eddsa => CK_MECHANISM {
+            mechanism: CKM_EDDSA,
+            p_parameter: ptr::null_mut(),
+            ul_parameter_len: 0,
+        },
+        SigningMechanism::RsaPkcs1v15 => CK_MECHANISM {
+            mechanism: CKM_RSA_PKCS,
+            p_parameter: ptr::null_mut(),
+            ul_parameter_len: 0,
+        },
+        SigningMechanism::Sha256RsaPkcs => CK_MECHANISM {
+            mechanism: CKM_SHA256_RSA_PKCS,
+            p_parameter: ptr::null_mut(),
+            ul_parameter_len: 0,
+        },
+        SigningMechanism::RsaPkcsPss => CK_MECHANISM {
+            mechanism: CKM_RSA_PKCS_PSS,
+            p_parameter: pss_params as *mut _ as *mut c_void,
+            ul_parameter_len: std::mem::size_of::<CK_RSA_PKCS_PSS_PARAMS>() as c_ulong,
+        },
+    }
+}
+
+/// Finish a raw signature from `C_Sign`/`C_SignFinal`: DER-encode it when
+/// `der_signatures` is set and `algorithm` is one of the raw-output ECDSA
+/// curves (secp256k1, secp256r1) that has a DER form — Ed25519 has no ASN.1
+/// signature representation, so `der_signatures` has no effect for it.
+fn finish_signature(algorithm: &str, der_signatures: bool, raw: Vec<u8>) -> Result<Vec<u8>, SignerError> {
+    if (algorithm == "secp256k1" || algorithm == "secp256r1") && der_signatures {
+        raw_ecdsa_to_der(&raw)
+    } else {
+        Ok(raw)
+    }
+}
+
+#[async_trait]
+impl Signer for Pkcs11Signer {
+    async fn sign(&self, data: &[u8]) -> Result<Signature, SignerError> {
+        let mut session = self.session.lock().expect("PKCS#11 session mutex poisoned");
+
+        let key_handle = match session.key_handle {
+            Some(handle) => handle,
+            None => {
+                let handle = unsafe { Self::find_private_key(&self.context, &self.config, session.handle)? };
+                session.key_handle = Some(handle);
+                handle
+            }
+        };
 
-    async fn public_key(&self) -> Result<PublicKey, SignerError> {
-        let functions = unsafe { self.load_functions()? };
-        
         unsafe {
-            // Initialize PKCS#11
-            let result = (functions.C_Initialize)(ptr::null_mut());
+            let functions = &self.context.functions;
+
+            // RSA-PSS needs a parameter block alongside the mechanism type;
+            // declared here (rather than inside `build_mechanism`) so the
+            // pointer its `CK_MECHANISM.p_parameter` takes stays valid for
+            // the `C_SignInit` call below.
+            let mut pss_params = default_pss_params();
+            let mut mechanism = build_mechanism(self.mechanism, &mut pss_params);
+
+            let result = (functions.C_SignInit)(session.handle, &mut mechanism as *mut _, key_handle);
             if result != CKR_OK {
-                return Err(SignerError::Pkcs11(format!("Failed to initialize PKCS#11: 0x{:x}", result)));
+                return Err(ckr_error("Failed to initialize signing", result));
             }
 
-            // Find slot
-            let slot = self.find_slot(&functions)?;
-            
-            // Open session
-            let mut session: c_ulong = 0;
-            let result = (functions.C_OpenSession)(slot, CKF_SERIAL_SESSION | CKF_RW_SESSION, &mut session);
-            if result != CKR_OK {
-                (functions.C_Finalize)(ptr::null_mut());
-                return Err(SignerError::Pkcs11(format!("Failed to open session: 0x{:x}", result)));
+            // Sign data
+            let mut signature_len: c_ulong = 0;
+            let data_ptr = data.as_ptr() as *mut u8;
+            let result = (functions.C_Sign)(session.handle, data_ptr, data.len() as c_ulong, ptr::null_mut(), &mut signature_len);
+            if result != CKR_OK && result != CKR_BUFFER_TOO_SMALL {
+                return Err(ckr_error("Failed to get signature length", result));
             }
 
-            // Login
-            let pin_cstr = CString::new(self.config.pin.as_str()).unwrap();
-            let result = (functions.C_Login)(session, CKU_USER, pin_cstr.as_ptr() as *mut c_char);
+            let mut signature_bytes = vec![0u8; signature_len as usize];
+            let result = (functions.C_Sign)(session.handle, data_ptr, data.len() as c_ulong, signature_bytes.as_mut_ptr(), &mut signature_len);
             if result != CKR_OK {
-                (functions.C_CloseSession)(session);
-                (functions.C_Finalize)(ptr::null_mut());
-                return Err(SignerError::Pkcs11(format!("Failed to login: 0x{:x}", result)));
+                return Err(ckr_error("Failed to sign data", result));
             }
 
-            // Get public key
-            let public_key = self.get_public_key(&functions, session);
+            let bytes = finish_signature(&self.algorithm, self.config.der_signatures, signature_bytes)?;
 
-            // Cleanup
-            (functions.C_Logout)(session);
-            (functions.C_CloseSession)(session);
-            (functions.C_Finalize)(ptr::null_mut());
+            Ok(Signature {
+                algorithm: self.algorithm.clone(),
+                bytes,
+                recovery_id: None,
+            })
+        }
+    }
 
-            public_key
+    async fn public_key(&self) -> Result<PublicKey, SignerError> {
+        let mut cached = self.public_key.lock().expect("PKCS#11 public key mutex poisoned");
+        if let Some(key) = &*cached {
+            return Ok(key.clone());
         }
+
+        let session = self.session.lock().expect("PKCS#11 session mutex poisoned");
+        let key = unsafe { Self::get_public_key(&self.context, &self.config, &self.algorithm, session.handle)? };
+        *cached = Some(key.clone());
+        Ok(key)
     }
 
     fn signer_info(&self) -> SignerInfo {
         let mut metadata = HashMap::new();
         metadata.insert("implementation".to_string(), "pkcs11".to_string());
         metadata.insert("module_path".to_string(), self.config.module_path.clone());
-        
+
         if let Some(ref token_label) = self.config.token_label {
             metadata.insert("token_label".to_string(), token_label.clone());
         }
 
+        if let Some(ref token_serial) = self.config.token_serial {
+            metadata.insert("token_serial".to_string(), token_serial.clone());
+        }
+
         SignerInfo {
             signer_type: "pkcs11".to_string(),
             algorithm: self.algorithm.clone(),
@@ -532,26 +1527,6 @@ impl Signer for Pkcs11Signer {
     }
 }
 
-/// PKCS#11 function pointers
-struct Pkcs11Functions {
-    C_Initialize: C_InitializeFn,
-    C_Finalize: C_FinalizeFn,
-    C_GetInfo: C_GetInfoFn,
-    C_GetSlotList: C_GetSlotListFn,
-    C_GetSlotInfo: C_GetSlotInfoFn,
-    C_GetTokenInfo: C_GetTokenInfoFn,
-    C_OpenSession: C_OpenSessionFn,
-    C_CloseSession: C_CloseSessionFn,
-    C_Login: C_LoginFn,
-    C_Logout: C_LogoutFn,
-    C_FindObjectsInit: C_FindObjectsInitFn,
-    C_FindObjects: C_FindObjectsFn,
-    C_FindObjectsFinal: C_FindObjectsFinalFn,
-    C_SignInit: C_SignInitFn,
-    C_Sign: C_SignFn,
-    C_GetAttributeValue: C_GetAttributeValueFn,
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -560,18 +1535,18 @@ mod tests {
     fn test_pkcs11_config_from_env() {
         // This test will fail unless environment variables are set
         // but it demonstrates the expected behavior
-        
+
         // Temporarily set environment variables
         std::env::set_var("ERST_PKCS11_MODULE", "/usr/lib/libykcs11.so");
         std::env::set_var("ERST_PKCS11_PIN", "123456");
-        
+
         let config = Pkcs11SignerConfig::from_env();
         assert!(config.is_ok());
-        
+
         let config = config.unwrap();
         assert_eq!(config.module_path, "/usr/lib/libykcs11.so");
-        assert_eq!(config.pin, "123456");
-        
+        assert_eq!(config.pin, Some("123456".to_string()));
+
         // Clean up
         std::env::remove_var("ERST_PKCS11_MODULE");
         std::env::remove_var("ERST_PKCS11_PIN");
@@ -582,8 +1557,231 @@ mod tests {
         // Should fail when required environment variables are missing
         std::env::remove_var("ERST_PKCS11_MODULE");
         std::env::remove_var("ERST_PKCS11_PIN");
-        
+
         let config = Pkcs11SignerConfig::from_env();
         assert!(config.is_err());
     }
+
+    #[test]
+    fn test_ckr_from_code_maps_known_codes() {
+        assert_eq!(Ckr::from_code(0x000000A0), Some(Ckr::PinIncorrect));
+        assert_eq!(Ckr::from_code(0x000000E2), Some(Ckr::TokenWriteProtected));
+    }
+
+    #[test]
+    fn test_ckr_from_code_returns_none_for_ok_and_unknown_codes() {
+        assert_eq!(Ckr::from_code(CKR_OK), None);
+        assert_eq!(Ckr::from_code(0xdeadbeef), None);
+    }
+
+    #[test]
+    fn test_ckr_display_includes_name_and_hex_code() {
+        assert_eq!(
+            Ckr::PinIncorrect.to_string(),
+            "CKR_PIN_INCORRECT (0x000000a0)"
+        );
+    }
+
+    #[test]
+    fn test_ckr_error_prefers_typed_variant_over_generic_message() {
+        match ckr_error("Failed to login", 0x000000A0) {
+            SignerError::Pkcs11Code(Ckr::PinIncorrect) => {}
+            other => panic!("expected Pkcs11Code(PinIncorrect), got {:?}", other),
+        }
+
+        match ckr_error("Failed to login", 0xdeadbeef) {
+            SignerError::Pkcs11(msg) => {
+                assert!(msg.contains("Failed to login"));
+                assert!(msg.contains("deadbeef"));
+            }
+            other => panic!("expected a generic Pkcs11 error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ec_curve_from_ec_params_recognizes_secp256k1_and_secp256r1() {
+        assert_eq!(
+            EcCurve::from_ec_params(OID_SECP256K1).unwrap(),
+            EcCurve::Secp256k1
+        );
+        assert_eq!(
+            EcCurve::from_ec_params(OID_SECP256R1).unwrap(),
+            EcCurve::Secp256r1
+        );
+        assert!(EcCurve::from_ec_params(&[0x06, 0x01, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_ec_curve_algorithm_label_distinguishes_secp256k1_and_secp256r1() {
+        assert_eq!(EcCurve::Secp256k1.algorithm_label(), "secp256k1");
+        assert_eq!(EcCurve::Secp256r1.algorithm_label(), "secp256r1");
+    }
+
+    #[test]
+    fn test_strip_ec_point_octet_string_removes_short_form_der_wrapper() {
+        let point = [0x04u8; 65]; // uncompressed-point prefix plus placeholder 0x04 coordinate bytes
+        let mut wrapped = vec![0x04, point.len() as u8];
+        wrapped.extend_from_slice(&point);
+
+        let stripped = strip_ec_point_octet_string(&wrapped).unwrap();
+        assert_eq!(stripped, &point[..]);
+    }
+
+    #[test]
+    fn test_strip_ec_point_octet_string_rejects_non_octet_string_input() {
+        assert!(strip_ec_point_octet_string(&[0x02, 0x01, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_ec_point_to_spki_round_trips_through_k256() {
+        use k256::pkcs8::DecodePublicKey;
+
+        let signing_key = k256::ecdsa::SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+        let verifying_key = *signing_key.verifying_key();
+        let point = verifying_key.to_encoded_point(false);
+
+        let spki = ec_point_to_spki(EcCurve::Secp256k1, point.as_bytes()).unwrap();
+        let parsed = k256::ecdsa::VerifyingKey::from_public_key_der(&spki).unwrap();
+        assert_eq!(parsed, verifying_key);
+    }
+
+    #[test]
+    fn test_raw_ecdsa_to_der_produces_a_der_sequence_verifiable_by_k256() {
+        use k256::ecdsa::signature::{Signer as _, Verifier as _};
+
+        let signing_key = k256::ecdsa::SigningKey::from_bytes(&[9u8; 32].into()).unwrap();
+        let raw_signature: k256::ecdsa::Signature = signing_key.sign(b"hello hsm");
+        let raw_bytes = raw_signature.to_bytes();
+
+        let der = raw_ecdsa_to_der(raw_bytes.as_slice()).unwrap();
+        let der_signature = k256::ecdsa::Signature::from_der(&der).unwrap();
+
+        let verifying_key = *signing_key.verifying_key();
+        assert!(verifying_key.verify(b"hello hsm", &der_signature).is_ok());
+    }
+
+    #[test]
+    fn test_signing_mechanism_from_name_accepts_known_mechanisms_only() {
+        assert_eq!(SigningMechanism::from_name("CKM_ECDSA").unwrap(), SigningMechanism::Ecdsa);
+        assert_eq!(
+            SigningMechanism::from_name("CKM_RSA_PKCS_PSS").unwrap(),
+            SigningMechanism::RsaPkcsPss
+        );
+        assert!(SigningMechanism::from_name("CKM_NONSENSE").is_err());
+    }
+
+    #[test]
+    fn test_signing_mechanism_from_key_type_dispatches_ec_and_eddsa_directly() {
+        assert_eq!(
+            SigningMechanism::from_key_type(CKK_EC, &[]).unwrap(),
+            SigningMechanism::Ecdsa
+        );
+        assert_eq!(
+            SigningMechanism::from_key_type(CKK_EC_EDWARDS, &[]).unwrap(),
+            SigningMechanism::Eddsa
+        );
+    }
+
+    #[test]
+    fn test_signing_mechanism_from_key_type_prefers_pss_then_sha256_then_pkcs1v15_for_rsa() {
+        assert_eq!(
+            SigningMechanism::from_key_type(
+                CKK_RSA,
+                &[CKM_RSA_PKCS, CKM_SHA256_RSA_PKCS, CKM_RSA_PKCS_PSS]
+            )
+            .unwrap(),
+            SigningMechanism::RsaPkcsPss
+        );
+        assert_eq!(
+            SigningMechanism::from_key_type(CKK_RSA, &[CKM_RSA_PKCS, CKM_SHA256_RSA_PKCS]).unwrap(),
+            SigningMechanism::Sha256RsaPkcs
+        );
+        assert_eq!(
+            SigningMechanism::from_key_type(CKK_RSA, &[CKM_RSA_PKCS]).unwrap(),
+            SigningMechanism::RsaPkcs1v15
+        );
+    }
+
+    #[test]
+    fn test_signing_mechanism_from_key_type_rejects_rsa_with_no_supported_signing_mechanism() {
+        assert!(SigningMechanism::from_key_type(CKK_RSA, &[]).is_err());
+    }
+
+    #[test]
+    fn test_signing_mechanism_from_key_type_rejects_unknown_key_types() {
+        assert!(SigningMechanism::from_key_type(0x9999, &[]).is_err());
+    }
+
+    #[test]
+    fn test_build_mechanism_sets_pss_params_pointer_only_for_rsa_pss() {
+        let mut pss_params = default_pss_params();
+
+        let ecdsa = build_mechanism(SigningMechanism::Ecdsa, &mut pss_params);
+        assert_eq!(ecdsa.mechanism, CKM_ECDSA);
+        assert!(ecdsa.p_parameter.is_null());
+
+        let pss = build_mechanism(SigningMechanism::RsaPkcsPss, &mut pss_params);
+        assert_eq!(pss.mechanism, CKM_RSA_PKCS_PSS);
+        assert!(!pss.p_parameter.is_null());
+        assert_eq!(pss.ul_parameter_len as usize, std::mem::size_of::<CK_RSA_PKCS_PSS_PARAMS>());
+    }
+
+    #[test]
+    fn test_finish_signature_der_encodes_ecdsa_curves_with_der_signatures_enabled() {
+        let raw = vec![0xAB; 64];
+
+        assert_eq!(
+            finish_signature("ed25519", true, raw.clone()).unwrap(),
+            raw
+        );
+        assert_eq!(
+            finish_signature("secp256k1", false, raw.clone()).unwrap(),
+            raw
+        );
+        assert_eq!(
+            finish_signature("secp256r1", false, raw.clone()).unwrap(),
+            raw
+        );
+
+        let der = finish_signature("secp256k1", true, raw.clone()).unwrap();
+        assert!(k256::ecdsa::Signature::from_der(&der).is_ok());
+
+        let der = finish_signature("secp256r1", true, raw).unwrap();
+        assert!(k256::ecdsa::Signature::from_der(&der).is_ok());
+    }
+
+    #[test]
+    fn test_attribute_constructors_encode_type_and_value_for_the_find_objects_template() {
+        let class = Attribute::class(CKO_PRIVATE_KEY);
+        assert_eq!(class.type_, CKA_CLASS);
+        assert_eq!(class.value, CKO_PRIVATE_KEY.to_ne_bytes().to_vec());
+
+        let label = Attribute::label("my-key");
+        assert_eq!(label.type_, CKA_LABEL);
+        assert_eq!(label.value, b"my-key".to_vec());
+
+        let id = Attribute::id(&[0xDE, 0xAD]);
+        assert_eq!(id.type_, CKA_ID);
+        assert_eq!(id.value, vec![0xDE, 0xAD]);
+    }
+
+    fn fixed_field(bytes: &[u8], pad: u8, len: usize) -> Vec<c_char> {
+        let mut field = vec![pad as c_char; len];
+        for (i, &b) in bytes.iter().enumerate() {
+            field[i] = b as c_char;
+        }
+        field
+    }
+
+    #[test]
+    fn test_fixed_field_to_string_trims_space_padding_without_a_nul_terminator() {
+        let label: [c_char; 32] = fixed_field(b"SoftHSM 2", b' ', 32).try_into().unwrap();
+        assert_eq!(fixed_field_to_string(&label), "SoftHSM 2");
+    }
+
+    #[test]
+    fn test_fixed_field_to_string_also_trims_nul_padding() {
+        let serial: [c_char; 16] = fixed_field(b"123456", 0, 16).try_into().unwrap();
+        assert_eq!(fixed_field_to_string(&serial), "123456");
+    }
 }
@@ -0,0 +1,327 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sigstore keyless signer implementation.
+//!
+//! Rather than protecting a long-lived private key, this signer generates a
+//! fresh ephemeral key pair on construction, has a Fulcio-compatible CA bind
+//! the public key to an OIDC identity via a short-lived certificate, and
+//! records the resulting signature (together with that certificate) in a
+//! Rekor-compatible transparency log. The signature is verifiable from the
+//! log entry alone; the private key is never persisted anywhere.
+//!
+//! Acquiring the OIDC identity token itself is out of scope: this signer
+//! expects [`super::SigstoreSignerConfig::identity_token`] to already hold a
+//! token issued by the caller's OIDC provider, the same way a CI job
+//! receives one from its platform's built-in OIDC issuer rather than running
+//! an interactive browser flow.
+
+use super::{PublicKey, Signature, Signer, SignerError, SignerInfo, SigstoreSignerConfig, Verifier};
+use async_trait::async_trait;
+use base64::Engine;
+use ed25519_dalek::pkcs8::{DecodePublicKey, EncodePublicKey};
+use ed25519_dalek::{Signer as EdSigner, SigningKey, Verifier as EdVerifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// A signature produced via the keyless flow, together with the provenance
+/// needed to independently verify it: the short-lived certificate chain
+/// Fulcio issued for the signing key, and the transparency-log entry Rekor
+/// recorded it under.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigstoreSignature {
+    pub signature: Signature,
+    /// PEM-encoded certificate chain, leaf certificate first.
+    pub certificate_chain_pem: Vec<String>,
+    pub log_entry: RekorLogEntry,
+}
+
+/// A Rekor transparency-log entry's identifying fields and inclusion proof.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RekorLogEntry {
+    pub log_index: u64,
+    pub log_id: String,
+    pub integrated_time: u64,
+    pub inclusion_proof_hashes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FulcioCertificateResponse {
+    #[serde(rename = "certificateChainPem")]
+    certificate_chain_pem: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RekorLogEntryResponse {
+    #[serde(rename = "logIndex")]
+    log_index: u64,
+    #[serde(rename = "logID")]
+    log_id: String,
+    #[serde(rename = "integratedTime")]
+    integrated_time: u64,
+    #[serde(rename = "inclusionProofHashes")]
+    inclusion_proof_hashes: Vec<String>,
+}
+
+/// Keyless signer backed by a Fulcio-style CA and a Rekor-style transparency
+/// log. Each instance holds its own ephemeral Ed25519 key, generated fresh
+/// when the signer is constructed.
+pub struct SigstoreSigner {
+    config: SigstoreSignerConfig,
+    signing_key: SigningKey,
+}
+
+impl SigstoreSigner {
+    /// Create a signer from configuration, generating a fresh ephemeral key.
+    pub fn from_config(config: SigstoreSignerConfig) -> Result<Self, SignerError> {
+        if config.identity_token.is_none() {
+            return Err(SignerError::Config(
+                "sigstore signer requires an `identity_token` (pre-obtained from the caller's OIDC provider)".to_string(),
+            ));
+        }
+
+        let mut csprng = rand::rngs::OsRng;
+        let signing_key = SigningKey::generate(&mut csprng);
+
+        Ok(Self {
+            config,
+            signing_key,
+        })
+    }
+
+    /// DER-encoded SPKI bytes of the ephemeral public key, as sent to Fulcio
+    /// when requesting a certificate.
+    fn ephemeral_public_key_der(&self) -> Result<Vec<u8>, SignerError> {
+        let verifying_key: VerifyingKey = self.signing_key.verifying_key();
+        let spki = verifying_key
+            .to_public_key_der()
+            .map_err(|e| SignerError::Crypto(format!("Failed to serialize ephemeral public key: {}", e)))?;
+        Ok(spki.as_bytes().to_vec())
+    }
+
+    /// Request a short-lived certificate from Fulcio binding the ephemeral
+    /// public key to the caller's OIDC identity.
+    async fn request_certificate(&self) -> Result<Vec<String>, SignerError> {
+        let identity_token = self.config.identity_token.as_ref().ok_or_else(|| {
+            SignerError::Config("sigstore signer requires an `identity_token`".to_string())
+        })?;
+
+        let public_key_der = self.ephemeral_public_key_der()?;
+        let public_key_b64 = base64::engine::general_purpose::STANDARD.encode(&public_key_der);
+
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!("{}/api/v2/signingCert", self.config.fulcio_url))
+            .bearer_auth(identity_token)
+            .json(&serde_json::json!({
+                "publicKey": { "content": public_key_b64, "algorithm": "ed25519" },
+                "signedEmailAddress": "",
+            }))
+            .send()
+            .await
+            .map_err(|e| SignerError::Crypto(format!("Fulcio certificate request failed: {}", e)))?
+            .error_for_status()
+            .map_err(|e| SignerError::Crypto(format!("Fulcio rejected the certificate request: {}", e)))?
+            .json::<FulcioCertificateResponse>()
+            .await
+            .map_err(|e| SignerError::Crypto(format!("Failed to parse Fulcio response: {}", e)))?;
+
+        Ok(response.certificate_chain_pem)
+    }
+
+    /// Upload the signature and leaf certificate to Rekor as a hashedrekord
+    /// entry, returning the resulting log entry.
+    async fn upload_to_rekor(
+        &self,
+        data: &[u8],
+        signature: &Signature,
+        certificate_chain_pem: &[String],
+    ) -> Result<RekorLogEntry, SignerError> {
+        let leaf_cert = certificate_chain_pem.first().ok_or_else(|| {
+            SignerError::Crypto("Fulcio returned an empty certificate chain".to_string())
+        })?;
+
+        let artifact_hash = hex::encode(Sha256::digest(data));
+        let signature_b64 = base64::engine::general_purpose::STANDARD.encode(&signature.bytes);
+        let cert_b64 = base64::engine::general_purpose::STANDARD.encode(leaf_cert.as_bytes());
+
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!("{}/api/v1/log/entries", self.config.rekor_url))
+            .json(&serde_json::json!({
+                "kind": "hashedrekord",
+                "apiVersion": "0.0.1",
+                "spec": {
+                    "data": { "hash": { "algorithm": "sha256", "value": artifact_hash } },
+                    "signature": { "content": signature_b64, "publicKey": { "content": cert_b64 } },
+                },
+            }))
+            .send()
+            .await
+            .map_err(|e| SignerError::Crypto(format!("Rekor log upload failed: {}", e)))?
+            .error_for_status()
+            .map_err(|e| SignerError::Crypto(format!("Rekor rejected the log entry: {}", e)))?
+            .json::<RekorLogEntryResponse>()
+            .await
+            .map_err(|e| SignerError::Crypto(format!("Failed to parse Rekor response: {}", e)))?;
+
+        Ok(RekorLogEntry {
+            log_index: response.log_index,
+            log_id: response.log_id,
+            integrated_time: response.integrated_time,
+            inclusion_proof_hashes: response.inclusion_proof_hashes,
+        })
+    }
+}
+
+#[async_trait]
+impl Signer for SigstoreSigner {
+    async fn sign(&self, data: &[u8]) -> Result<Signature, SignerError> {
+        let signature = self.signing_key.sign(data);
+
+        Ok(Signature {
+            algorithm: "Ed25519".to_string(),
+            bytes: signature.to_bytes().to_vec(),
+            recovery_id: None,
+        })
+    }
+
+    async fn public_key(&self) -> Result<PublicKey, SignerError> {
+        Ok(PublicKey {
+            algorithm: "ed25519".to_string(),
+            spki_bytes: self.ephemeral_public_key_der()?,
+        })
+    }
+
+    fn signer_info(&self) -> SignerInfo {
+        let mut metadata = HashMap::new();
+        metadata.insert("implementation".to_string(), "sigstore".to_string());
+        metadata.insert("fulcio_url".to_string(), self.config.fulcio_url.clone());
+        metadata.insert("rekor_url".to_string(), self.config.rekor_url.clone());
+        metadata.insert("oidc_issuer_url".to_string(), self.config.oidc_issuer_url.clone());
+
+        SignerInfo {
+            signer_type: "sigstore".to_string(),
+            algorithm: "ed25519".to_string(),
+            metadata,
+        }
+    }
+
+    fn supported_algorithms(&self) -> Vec<&'static str> {
+        vec!["Ed25519"]
+    }
+
+    async fn sign_keyless(&self, data: &[u8]) -> Result<SigstoreSignature, SignerError> {
+        let certificate_chain_pem = self.request_certificate().await?;
+        let signature = self.sign(data).await?;
+        let log_entry = self
+            .upload_to_rekor(data, &signature, &certificate_chain_pem)
+            .await?;
+
+        Ok(SigstoreSignature {
+            signature,
+            certificate_chain_pem,
+            log_entry,
+        })
+    }
+}
+
+#[async_trait]
+impl Verifier for SigstoreSigner {
+    async fn verify(&self, data: &[u8], sig: &Signature, key: &PublicKey) -> Result<(), SignerError> {
+        if key.algorithm != "ed25519" {
+            return Err(SignerError::InvalidSignature(format!(
+                "SigstoreSigner verifies ed25519 signatures only, got {}",
+                key.algorithm
+            )));
+        }
+
+        let verifying_key = VerifyingKey::from_public_key_der(&key.spki_bytes)
+            .map_err(|e| SignerError::Crypto(format!("Failed to parse public key: {}", e)))?;
+
+        let raw: [u8; 64] = sig.bytes.clone().try_into().map_err(|_| {
+            SignerError::InvalidSignature("ed25519 signature must be 64 bytes".to_string())
+        })?;
+        let signature = ed25519_dalek::Signature::from_bytes(&raw);
+
+        verifying_key
+            .verify(data, &signature)
+            .map_err(|e| SignerError::InvalidSignature(format!("Signature verification failed: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config(identity_token: Option<&str>) -> SigstoreSignerConfig {
+        SigstoreSignerConfig {
+            oidc_issuer_url: "https://oauth2.sigstore.dev/auth".to_string(),
+            fulcio_url: "https://fulcio.sigstore.dev".to_string(),
+            rekor_url: "https://rekor.sigstore.dev".to_string(),
+            identity_token: identity_token.map(|t| t.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_from_config_requires_identity_token() {
+        match SigstoreSigner::from_config(test_config(None)) {
+            Err(SignerError::Config(msg)) => assert!(msg.contains("identity_token")),
+            other => panic!("expected a Config error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_sign_uses_a_fresh_ephemeral_key_per_signer() {
+        let a = SigstoreSigner::from_config(test_config(Some("token"))).unwrap();
+        let b = SigstoreSigner::from_config(test_config(Some("token"))).unwrap();
+
+        assert_ne!(
+            a.ephemeral_public_key_der().unwrap(),
+            b.ephemeral_public_key_der().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_produces_a_verifiable_ed25519_signature() {
+        let signer = SigstoreSigner::from_config(test_config(Some("token"))).unwrap();
+        let data = b"sigstore keyless signing";
+
+        let signature = signer.sign(data).await.unwrap();
+        let verifying_key = signer.signing_key.verifying_key();
+        let raw: [u8; 64] = signature.bytes.clone().try_into().unwrap();
+        let ed_signature = ed25519_dalek::Signature::from_bytes(&raw);
+
+        assert!(verifying_key.verify_strict(data, &ed_signature).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_sigstore_signer_verifies_its_own_signature() {
+        let signer = SigstoreSigner::from_config(test_config(Some("token"))).unwrap();
+        let data = b"sigstore keyless signing";
+
+        let signature = signer.sign(data).await.unwrap();
+        let public_key = signer.public_key().await.unwrap();
+
+        assert!(signer.verify(data, &signature, &public_key).await.is_ok());
+        assert!(signer
+            .verify(b"tampered", &signature, &public_key)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_signer_info_reports_sigstore_endpoints() {
+        let signer = SigstoreSigner::from_config(test_config(Some("token"))).unwrap();
+        let info = signer.signer_info();
+
+        assert_eq!(info.signer_type, "sigstore");
+        assert_eq!(info.metadata.get("fulcio_url").unwrap(), "https://fulcio.sigstore.dev");
+        assert_eq!(info.metadata.get("rekor_url").unwrap(), "https://rekor.sigstore.dev");
+        assert_eq!(
+            info.metadata.get("oidc_issuer_url").unwrap(),
+            "https://oauth2.sigstore.dev/auth"
+        );
+    }
+}
@@ -3,14 +3,202 @@
 
 //! Software-based signer implementation using local cryptographic keys.
 
-use super::{PublicKey, Signature, Signer, SignerError, SignerInfo, SoftwareSignerConfig};
+use super::{PublicKey, Signature, Signer, SignerError, SignerInfo, SoftwareSignerConfig, Verifier};
 use async_trait::async_trait;
-use ed25519_dalek::{Signer as EdSigner, SigningKey, VerifyingKey};
-use ed25519_dalek::pkcs8::DecodePrivateKey;
+use bip39::{Language, Mnemonic};
+use cipher::KeyIvInit;
+use ed25519_dalek::{Signer as EdSigner, SigningKey, Verifier as EdVerifier, VerifyingKey};
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::pkcs8::{DecodePrivateKey as RsaDecodePrivateKey, DecodePublicKey as RsaDecodePublicKey, EncodePrivateKey, EncodePublicKey};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Parse a BIP39 mnemonic phrase and stretch it, together with `passphrase`,
+/// into a 64-byte PBKDF2 seed. `pub(crate)` so [`super::hd`] can derive its
+/// own master keys from the same seed without duplicating mnemonic parsing.
+pub(crate) fn mnemonic_seed(phrase: &str, passphrase: &str) -> Result<[u8; 64], SignerError> {
+    let mnemonic = Mnemonic::parse_in_normalized(Language::English, phrase)
+        .map_err(|e| SignerError::Crypto(format!("Invalid mnemonic phrase: {}", e)))?;
+    Ok(mnemonic.to_seed(passphrase))
+}
+
+/// Generate a new random mnemonic with `word_count` words (12 or 24).
+fn generate_mnemonic(word_count: usize) -> Result<Mnemonic, SignerError> {
+    let entropy_bytes = match word_count {
+        12 => 16,
+        24 => 32,
+        other => {
+            return Err(SignerError::Config(format!(
+                "Unsupported mnemonic word count: {} (expected 12 or 24)",
+                other
+            )))
+        }
+    };
+    let mut entropy = vec![0u8; entropy_bytes];
+    rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut entropy);
+    Mnemonic::from_entropy_in(Language::English, &entropy)
+        .map_err(|e| SignerError::Crypto(format!("Failed to generate mnemonic: {}", e)))
+}
+
+/// Derive 32 bytes of curve-specific key material from a BIP39 seed by
+/// hashing the seed together with a domain tag (and, for curves whose
+/// scalar space can reject a given 32 bytes, a retry counter), so the same
+/// mnemonic yields independent-looking keys per algorithm rather than
+/// reusing the same 32 bytes everywhere.
+fn derive_key_material(seed: &[u8], domain: &str, attempt: u8) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(seed);
+    hasher.update(domain.as_bytes());
+    hasher.update([attempt]);
+    hasher.finalize().into()
+}
+
+/// Number of domain-tag retries attempted when deriving an ECDSA scalar
+/// from a mnemonic seed, in case a given 32 bytes falls outside the curve's
+/// valid scalar range (astronomically unlikely, but the API can fail).
+const MAX_SCALAR_DERIVATION_ATTEMPTS: u8 = 8;
+
+/// The `crypto` block of an EIP-2335 encrypted JSON keystore.
+#[derive(Debug, Deserialize)]
+struct Eip2335Keystore {
+    crypto: Eip2335Crypto,
+}
+
+#[derive(Debug, Deserialize)]
+struct Eip2335Crypto {
+    kdf: Eip2335Kdf,
+    checksum: Eip2335Checksum,
+    cipher: Eip2335Cipher,
+}
+
+#[derive(Debug, Deserialize)]
+struct Eip2335Kdf {
+    function: String,
+    params: Eip2335KdfParams,
+}
+
+#[derive(Debug, Deserialize)]
+struct Eip2335KdfParams {
+    dklen: usize,
+    salt: String,
+    // scrypt
+    #[serde(default)]
+    n: Option<u32>,
+    #[serde(default)]
+    r: Option<u32>,
+    #[serde(default)]
+    p: Option<u32>,
+    // pbkdf2
+    #[serde(default)]
+    c: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Eip2335Checksum {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Eip2335Cipher {
+    function: String,
+    params: Eip2335CipherParams,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Eip2335CipherParams {
+    iv: String,
+}
+
+/// Derive the decryption key for an EIP-2335 keystore from `password` using
+/// its declared KDF, returning the full derived-key bytes (`dklen` long;
+/// the first 16 form the cipher key, bytes 16..32 feed the checksum).
+fn eip2335_derive_key(kdf: &Eip2335Kdf, password: &[u8]) -> Result<Vec<u8>, SignerError> {
+    let salt = hex::decode(&kdf.params.salt)
+        .map_err(|e| SignerError::Crypto(format!("Invalid keystore salt: {}", e)))?;
+    let mut derived = vec![0u8; kdf.params.dklen];
+
+    match kdf.function.as_str() {
+        "scrypt" => {
+            let n = kdf.params.n.ok_or_else(|| {
+                SignerError::Crypto("scrypt keystore KDF is missing `n`".to_string())
+            })?;
+            let r = kdf.params.r.ok_or_else(|| {
+                SignerError::Crypto("scrypt keystore KDF is missing `r`".to_string())
+            })?;
+            let p = kdf.params.p.ok_or_else(|| {
+                SignerError::Crypto("scrypt keystore KDF is missing `p`".to_string())
+            })?;
+            let log_n = (31 - n.leading_zeros()) as u8;
+            let params = scrypt::Params::new(log_n, r, p, kdf.params.dklen)
+                .map_err(|e| SignerError::Crypto(format!("Invalid scrypt parameters: {}", e)))?;
+            scrypt::scrypt(password, &salt, &params, &mut derived)
+                .map_err(|e| SignerError::Crypto(format!("scrypt key derivation failed: {}", e)))?;
+        }
+        "pbkdf2" => {
+            let c = kdf.params.c.ok_or_else(|| {
+                SignerError::Crypto("pbkdf2 keystore KDF is missing `c`".to_string())
+            })?;
+            pbkdf2::pbkdf2_hmac::<Sha256>(password, &salt, c, &mut derived);
+        }
+        other => {
+            return Err(SignerError::Crypto(format!(
+                "Unsupported keystore KDF: {}",
+                other
+            )))
+        }
+    }
+
+    Ok(derived)
+}
+
+/// Decrypt an EIP-2335 JSON keystore with `password`, returning the raw
+/// private key bytes it protects.
+fn decrypt_eip2335_keystore(keystore_json: &str, password: &str) -> Result<Vec<u8>, SignerError> {
+    let keystore: Eip2335Keystore = serde_json::from_str(keystore_json)
+        .map_err(|e| SignerError::Crypto(format!("Invalid keystore JSON: {}", e)))?;
+    let crypto = &keystore.crypto;
+
+    let derived_key = eip2335_derive_key(&crypto.kdf, password.as_bytes())?;
+    if derived_key.len() < 32 {
+        return Err(SignerError::Crypto(
+            "Keystore KDF derived key is shorter than 32 bytes".to_string(),
+        ));
+    }
+
+    let cipher_text = hex::decode(&crypto.cipher.message)
+        .map_err(|e| SignerError::Crypto(format!("Invalid keystore cipher message: {}", e)))?;
+
+    let mut checksum_input = Vec::with_capacity(16 + cipher_text.len());
+    checksum_input.extend_from_slice(&derived_key[16..32]);
+    checksum_input.extend_from_slice(&cipher_text);
+    let actual_checksum = hex::encode(Sha256::digest(&checksum_input));
+    if actual_checksum != crypto.checksum.message {
+        return Err(SignerError::Crypto(
+            "Keystore checksum mismatch: wrong password or corrupted keystore".to_string(),
+        ));
+    }
+
+    if crypto.cipher.function != "aes-128-ctr" {
+        return Err(SignerError::Crypto(format!(
+            "Unsupported keystore cipher: {}",
+            crypto.cipher.function
+        )));
+    }
+    let iv = hex::decode(&crypto.cipher.params.iv)
+        .map_err(|e| SignerError::Crypto(format!("Invalid keystore IV: {}", e)))?;
+
+    let mut plain_text = cipher_text;
+    let mut stream_cipher = ctr::Ctr128BE::<aes::Aes128>::new_from_slices(&derived_key[0..16], &iv)
+        .map_err(|e| SignerError::Crypto(format!("Invalid keystore cipher key/IV: {}", e)))?;
+    cipher::StreamCipher::apply_keystream(&mut stream_cipher, &mut plain_text);
+
+    Ok(plain_text)
+}
 
 /// Software-based signer using local Ed25519 keys
 pub struct SoftwareSigner {
@@ -27,7 +215,10 @@ impl SoftwareSigner {
         Self::from_pem(&pem_data)
     }
 
-    /// Create a new software signer from PEM data
+    /// Create a new software signer from PEM data. `from_pkcs8_pem` decodes
+    /// the base64/DER layers internally without handing us an intermediate
+    /// buffer, so there's nothing of ours to zeroize here beyond `pem_data`
+    /// itself, which the caller owns.
     pub fn from_pem(pem_data: &str) -> Result<Self, SignerError> {
         let signing_key = SigningKey::from_pkcs8_pem(pem_data)
             .map_err(|e| SignerError::Crypto(format!("Failed to parse private key: {}", e)))?;
@@ -40,22 +231,142 @@ impl SoftwareSigner {
 
     /// Create a software signer from configuration
     pub fn from_config(config: &SoftwareSignerConfig) -> Result<Self, SignerError> {
-        if let Some(pem_data) = &config.private_key_pem {
+        if let Some(keystore_path) = &config.keystore_path {
+            let password = Self::read_keystore_password(config.keystore_password_path.as_deref())?;
+            Self::from_keystore_file(keystore_path, &password)
+        } else if let Some(phrase) = &config.mnemonic {
+            Self::from_mnemonic(phrase, config.mnemonic_passphrase.as_deref().unwrap_or(""))
+        } else if let Some(path) = &config.keypair_json_path {
+            Self::from_keypair_json_file(path)
+        } else if let Some(pem_data) = &config.private_key_pem {
             Self::from_pem(pem_data)
         } else if let Some(path) = &config.private_key_path {
             Self::from_key_file(path)
         } else {
             Err(SignerError::Config(
-                "Either private_key_pem or private_key_path must be provided".to_string()
+                "One of keystore_path, mnemonic, keypair_json_path, private_key_pem, or private_key_path must be provided".to_string()
             ))
         }
     }
 
-    /// Generate a new random key pair and return the signer
-    pub fn generate() -> Result<(Self, String), SignerError> {
+    /// Create a software signer from a Solana-style raw keypair JSON file
+    /// (see [`SoftwareSignerConfig::keypair_json_path`]).
+    pub fn from_keypair_json_file<P: AsRef<Path>>(path: P) -> Result<Self, SignerError> {
+        let json = fs::read_to_string(path)?;
+        Self::from_keypair_json(&json)
+    }
+
+    /// Parse a Solana-style raw keypair JSON array — a JSON array of 64
+    /// bytes, the 32-byte Ed25519 seed followed by its 32-byte public key —
+    /// and build a signer from its seed half. If both halves are present,
+    /// the embedded public key is checked against the one the seed derives
+    /// rather than trusted outright, so a hand-edited or corrupted file is
+    /// caught instead of silently producing a signer whose public half
+    /// doesn't match what it actually signs with.
+    pub fn from_keypair_json(json: &str) -> Result<Self, SignerError> {
+        let bytes: Vec<u8> = serde_json::from_str(json)
+            .map_err(|e| SignerError::Crypto(format!("Failed to parse keypair JSON: {}", e)))?;
+
+        if bytes.len() != 32 && bytes.len() != 64 {
+            return Err(SignerError::Crypto(format!(
+                "keypair JSON must contain 32 or 64 bytes, got {}",
+                bytes.len()
+            )));
+        }
+
+        let mut key_material: [u8; 32] = bytes[..32].try_into().expect("checked length above");
+        let signing_key = SigningKey::from_bytes(&key_material);
+
+        if bytes.len() == 64 && signing_key.verifying_key().as_bytes() != &bytes[32..] {
+            key_material.zeroize();
+            return Err(SignerError::Crypto(
+                "keypair JSON public key half does not match the key derived from its seed half".to_string(),
+            ));
+        }
+        key_material.zeroize();
+
+        Ok(Self {
+            signing_key,
+            algorithm: "ed25519".to_string(),
+        })
+    }
+
+    /// Export this signer's key as a Solana-style raw keypair JSON array
+    /// (see [`SoftwareSignerConfig::keypair_json_path`]): the 32-byte seed
+    /// followed by its 32-byte public key, 64 bytes total. Wrapped in
+    /// [`Zeroizing`] for the same reason [`Self::generate`]'s PEM output is:
+    /// it's a full copy of the private key outside `signing_key` itself.
+    pub fn to_keypair_json(&self) -> Result<Zeroizing<String>, SignerError> {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&self.signing_key.to_bytes());
+        bytes[32..].copy_from_slice(self.signing_key.verifying_key().as_bytes());
+
+        let json = serde_json::to_string(&bytes.to_vec())
+            .map_err(|e| SignerError::Crypto(format!("Failed to serialize keypair JSON: {}", e)))?;
+        bytes.zeroize();
+
+        Ok(Zeroizing::new(json))
+    }
+
+    /// Resolve the keystore decryption password from `password_path` (if
+    /// given) or else the `ERST_SOFTWARE_KEYSTORE_PASSWORD` environment
+    /// variable.
+    fn read_keystore_password(password_path: Option<&str>) -> Result<String, SignerError> {
+        if let Some(path) = password_path {
+            Ok(fs::read_to_string(path)?.trim_end_matches(['\n', '\r']).to_string())
+        } else {
+            std::env::var("ERST_SOFTWARE_KEYSTORE_PASSWORD").map_err(|_| {
+                SignerError::Config(
+                    "keystore_path requires keystore_password_path or ERST_SOFTWARE_KEYSTORE_PASSWORD"
+                        .to_string(),
+                )
+            })
+        }
+    }
+
+    /// Decrypt an EIP-2335 JSON keystore file at `path` with `password` and
+    /// build a signer from the recovered private key bytes.
+    pub fn from_keystore_file<P: AsRef<Path>>(path: P, password: &str) -> Result<Self, SignerError> {
+        let keystore_json = fs::read_to_string(path)?;
+        let mut key_bytes = decrypt_eip2335_keystore(&keystore_json, password)?;
+        let mut key_material: [u8; 32] = key_bytes.as_slice().try_into().map_err(|_| {
+            SignerError::Crypto("Decrypted keystore key is not 32 bytes".to_string())
+        })?;
+        key_bytes.zeroize();
+
+        let signing_key = SigningKey::from_bytes(&key_material);
+        key_material.zeroize();
+
+        Ok(Self {
+            signing_key,
+            algorithm: "ed25519".to_string(),
+        })
+    }
+
+    /// Recover a signer deterministically from a BIP39 mnemonic phrase and
+    /// an optional passphrase. The same phrase and passphrase always yield
+    /// the same key.
+    pub fn from_mnemonic(phrase: &str, passphrase: &str) -> Result<Self, SignerError> {
+        let mut seed = mnemonic_seed(phrase, passphrase)?;
+        let mut key_material = derive_key_material(&seed, "erst/ed25519", 0);
+        let signing_key = SigningKey::from_bytes(&key_material);
+        seed.zeroize();
+        key_material.zeroize();
+
+        Ok(Self {
+            signing_key,
+            algorithm: "ed25519".to_string(),
+        })
+    }
+
+    /// Generate a new random key pair and return the signer. The PEM is
+    /// wrapped in [`Zeroizing`] since it's the only copy of the private key
+    /// outside `signing_key` itself, and callers routinely write it straight
+    /// to disk or a config value and drop it.
+    pub fn generate() -> Result<(Self, Zeroizing<String>), SignerError> {
         let mut csprng = rand::rngs::OsRng;
         let signing_key = SigningKey::generate(&mut csprng);
-        
+
         let public_key = signing_key.verifying_key();
         let pem_data = signing_key.to_pkcs8_pem(Default::default())
             .map_err(|e| SignerError::Crypto(format!("Failed to serialize private key: {}", e)))?;
@@ -65,23 +376,59 @@ impl SoftwareSigner {
             algorithm: "ed25519".to_string(),
         };
 
-        Ok((signer, pem_data))
+        Ok((signer, Zeroizing::new(pem_data)))
+    }
+
+    /// Generate a new `word_count`-word (12 or 24) BIP39 mnemonic, derive a
+    /// signer from it, and return both the signer and the phrase so the
+    /// caller can back it up. The phrase is the only way to recover the
+    /// key; it is not retrievable from the signer afterwards.
+    pub fn generate_with_mnemonic(word_count: usize) -> Result<(Self, String), SignerError> {
+        let mnemonic = generate_mnemonic(word_count)?;
+        let phrase = mnemonic.to_string();
+        let signer = Self::from_mnemonic(&phrase, "")?;
+        Ok((signer, phrase))
     }
 
     /// Get the verifying key
     pub fn verifying_key(&self) -> &VerifyingKey {
         &self.signing_key.verifying_key()
     }
+
+    /// Wrap raw 32-byte Ed25519 key material directly, without a PEM,
+    /// mnemonic, or keystore source. Used by [`super::hd::HdSigner`] to wrap
+    /// keys it derives itself.
+    pub(crate) fn from_raw_bytes(key_material: [u8; 32]) -> Self {
+        Self {
+            signing_key: SigningKey::from_bytes(&key_material),
+            algorithm: "ed25519".to_string(),
+        }
+    }
+
+    /// Verify `sig` over `data` against this signer's own public key, so a
+    /// caller that just produced a signature doesn't need to separately
+    /// derive a verifying key to check it.
+    pub async fn verify(&self, data: &[u8], sig: &Signature) -> Result<(), SignerError> {
+        let public_key = self.public_key().await?;
+        <Self as Verifier>::verify(self, data, sig, &public_key).await
+    }
+}
+
+impl Drop for SoftwareSigner {
+    fn drop(&mut self) {
+        self.signing_key.zeroize();
+    }
 }
 
 #[async_trait]
 impl Signer for SoftwareSigner {
     async fn sign(&self, data: &[u8]) -> Result<Signature, SignerError> {
         let signature = self.signing_key.sign(data);
-        
+
         Ok(Signature {
-            algorithm: self.algorithm.clone(),
+            algorithm: "Ed25519".to_string(),
             bytes: signature.to_bytes().to_vec(),
+            recovery_id: None,
         })
     }
 
@@ -107,6 +454,34 @@ impl Signer for SoftwareSigner {
             metadata,
         }
     }
+
+    fn supported_algorithms(&self) -> Vec<&'static str> {
+        vec!["Ed25519"]
+    }
+}
+
+#[async_trait]
+impl Verifier for SoftwareSigner {
+    async fn verify(&self, data: &[u8], sig: &Signature, key: &PublicKey) -> Result<(), SignerError> {
+        if key.algorithm != "ed25519" {
+            return Err(SignerError::InvalidSignature(format!(
+                "SoftwareSigner verifies ed25519 signatures only, got {}",
+                key.algorithm
+            )));
+        }
+
+        let verifying_key = VerifyingKey::from_public_key_der(&key.spki_bytes)
+            .map_err(|e| SignerError::Crypto(format!("Failed to parse public key: {}", e)))?;
+
+        let raw: [u8; 64] = sig.bytes.clone().try_into().map_err(|_| {
+            SignerError::InvalidSignature("ed25519 signature must be 64 bytes".to_string())
+        })?;
+        let signature = ed25519_dalek::Signature::from_bytes(&raw);
+
+        verifying_key
+            .verify(data, &signature)
+            .map_err(|e| SignerError::InvalidSignature(format!("Signature verification failed: {}", e)))
+    }
 }
 
 /// Configuration for secp256k1 software signer
@@ -116,6 +491,11 @@ pub struct Secp256k1SoftwareSignerConfig {
     pub private_key_path: Option<String>,
     /// Private key in PEM format (direct string)
     pub private_key_pem: Option<String>,
+    /// BIP39 mnemonic phrase to derive the key from. Takes precedence over
+    /// `private_key_pem`/`private_key_path` when set.
+    pub mnemonic: Option<String>,
+    /// Optional BIP39 passphrase used together with `mnemonic`.
+    pub mnemonic_passphrase: Option<String>,
 }
 
 /// Software-based signer using local secp256k1 keys
@@ -146,22 +526,62 @@ impl Secp256k1SoftwareSigner {
 
     /// Create a secp256k1 software signer from configuration
     pub fn from_config(config: &Secp256k1SoftwareSignerConfig) -> Result<Self, SignerError> {
-        if let Some(pem_data) = &config.private_key_pem {
+        if let Some(phrase) = &config.mnemonic {
+            Self::from_mnemonic(phrase, config.mnemonic_passphrase.as_deref().unwrap_or(""))
+        } else if let Some(pem_data) = &config.private_key_pem {
             Self::from_pem(pem_data)
         } else if let Some(path) = &config.private_key_path {
             Self::from_key_file(path)
         } else {
             Err(SignerError::Config(
-                "Either private_key_pem or private_key_path must be provided".to_string()
+                "One of mnemonic, private_key_pem, or private_key_path must be provided".to_string()
             ))
         }
     }
 
-    /// Generate a new random key pair and return the signer
-    pub fn generate() -> Result<(Self, String), SignerError> {
+    /// Recover a signer deterministically from a BIP39 mnemonic phrase and
+    /// an optional passphrase. The same phrase and passphrase always yield
+    /// the same key.
+    pub fn from_mnemonic(phrase: &str, passphrase: &str) -> Result<Self, SignerError> {
+        let mut seed = mnemonic_seed(phrase, passphrase)?;
+
+        for attempt in 0..MAX_SCALAR_DERIVATION_ATTEMPTS {
+            let mut material = derive_key_material(&seed, "erst/secp256k1", attempt);
+            let candidate = k256::ecdsa::SigningKey::from_bytes(&material.into());
+            material.zeroize();
+            if let Ok(signing_key) = candidate {
+                seed.zeroize();
+                return Ok(Self {
+                    signing_key,
+                    algorithm: "secp256k1".to_string(),
+                });
+            }
+        }
+
+        seed.zeroize();
+        Err(SignerError::Crypto(
+            "Failed to derive a valid secp256k1 key from the mnemonic seed".to_string(),
+        ))
+    }
+
+    /// Generate a new `word_count`-word (12 or 24) BIP39 mnemonic, derive a
+    /// signer from it, and return both the signer and the phrase so the
+    /// caller can back it up.
+    pub fn generate_with_mnemonic(word_count: usize) -> Result<(Self, String), SignerError> {
+        let mnemonic = generate_mnemonic(word_count)?;
+        let phrase = mnemonic.to_string();
+        let signer = Self::from_mnemonic(&phrase, "")?;
+        Ok((signer, phrase))
+    }
+
+    /// Generate a new random key pair and return the signer. The PEM is
+    /// wrapped in [`Zeroizing`] for the same reason as
+    /// [`SoftwareSigner::generate`]'s: it's a second, caller-owned copy of
+    /// the private key that's easy to forget to scrub.
+    pub fn generate() -> Result<(Self, Zeroizing<String>), SignerError> {
         let mut csprng = rand::rngs::OsRng;
         let signing_key = k256::ecdsa::SigningKey::random(&mut csprng);
-        
+
         let pem_data = signing_key.to_pkcs8_pem(k256::pkcs8::LineEnding::LF)
             .map_err(|e| SignerError::Crypto(format!("Failed to serialize private key: {}", e)))?;
 
@@ -170,27 +590,71 @@ impl Secp256k1SoftwareSigner {
             algorithm: "secp256k1".to_string(),
         };
 
-        Ok((signer, pem_data))
+        Ok((signer, Zeroizing::new(pem_data)))
     }
 
     /// Get the verifying key
     pub fn verifying_key(&self) -> &k256::ecdsa::VerifyingKey {
         self.signing_key.verifying_key()
     }
+
+    /// Wrap raw 32-byte secp256k1 key material directly, without a PEM or
+    /// mnemonic source. Used by [`super::hd::HdSigner`] to wrap keys it
+    /// derives itself.
+    pub(crate) fn from_raw_bytes(key_material: [u8; 32]) -> Result<Self, SignerError> {
+        let signing_key = k256::ecdsa::SigningKey::from_bytes(&key_material.into())
+            .map_err(|e| SignerError::Crypto(format!("Invalid derived secp256k1 key: {}", e)))?;
+        Ok(Self {
+            signing_key,
+            algorithm: "secp256k1".to_string(),
+        })
+    }
+
+    /// Verify `sig` over `data` against this signer's own public key, so a
+    /// caller that just produced a signature doesn't need to separately
+    /// derive a verifying key to check it.
+    pub async fn verify(&self, data: &[u8], sig: &Signature) -> Result<(), SignerError> {
+        let public_key = self.public_key().await?;
+        <Self as Verifier>::verify(self, data, sig, &public_key).await
+    }
+}
+
+impl Drop for Secp256k1SoftwareSigner {
+    fn drop(&mut self) {
+        self.signing_key.zeroize();
+    }
 }
 
 #[async_trait]
 impl Signer for Secp256k1SoftwareSigner {
     async fn sign(&self, data: &[u8]) -> Result<Signature, SignerError> {
-        use k256::ecdsa::signature::Signer;
-        
-        let signature: k256::ecdsa::Signature = self.signing_key
+        let (signature, recovery_id) = self
+            .signing_key
             .sign_digest_recoverable(k256::ecdsa::digest::Digest::hash(data))
             .map_err(|e| SignerError::Crypto(format!("Failed to sign data: {}", e)))?;
-        
+
         Ok(Signature {
-            algorithm: self.algorithm.clone(),
+            algorithm: "ES256K".to_string(),
             bytes: signature.to_bytes().to_vec(),
+            recovery_id: Some(recovery_id.to_byte()),
+        })
+    }
+
+    async fn sign_recoverable(&self, data: &[u8]) -> Result<Signature, SignerError> {
+        let (signature, recovery_id) = self
+            .signing_key
+            .sign_digest_recoverable(k256::ecdsa::digest::Digest::hash(data))
+            .map_err(|e| SignerError::Crypto(format!("Failed to sign data: {}", e)))?;
+
+        // 64-byte `r || s` signature plus a 1-byte recovery id, the format
+        // Soroban's `secp256k1_recover` host function expects.
+        let mut bytes = signature.to_bytes().to_vec();
+        bytes.push(recovery_id.to_byte());
+
+        Ok(Signature {
+            algorithm: "ES256K".to_string(),
+            bytes,
+            recovery_id: Some(recovery_id.to_byte()),
         })
     }
 
@@ -216,75 +680,843 @@ impl Signer for Secp256k1SoftwareSigner {
             metadata,
         }
     }
+
+    fn supported_algorithms(&self) -> Vec<&'static str> {
+        vec!["ES256K"]
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[async_trait]
+impl Verifier for Secp256k1SoftwareSigner {
+    async fn verify(&self, data: &[u8], sig: &Signature, key: &PublicKey) -> Result<(), SignerError> {
+        use k256::ecdsa::signature::Verifier as EcdsaVerifier;
+        use k256::pkcs8::DecodePublicKey;
 
-    #[tokio::test]
-    async fn test_ed25519_software_signer() {
-        let (signer, _pem) = SoftwareSigner::generate().unwrap();
-        
-        let data = b"Hello, world!";
-        let signature = signer.sign(data).await.unwrap();
-        
-        assert_eq!(signature.algorithm, "ed25519");
-        assert_eq!(signature.bytes.len(), 64); // Ed25519 signature size
-        
-        let public_key = signer.public_key().await.unwrap();
-        assert_eq!(public_key.algorithm, "ed25519");
-        assert!(!public_key.spki_bytes.is_empty());
-        
-        let info = signer.signer_info();
-        assert_eq!(info.signer_type, "software");
-        assert_eq!(info.algorithm, "ed25519");
+        if key.algorithm != "secp256k1" {
+            return Err(SignerError::InvalidSignature(format!(
+                "Secp256k1SoftwareSigner verifies secp256k1 signatures only, got {}",
+                key.algorithm
+            )));
+        }
+
+        let verifying_key = k256::ecdsa::VerifyingKey::from_public_key_der(&key.spki_bytes)
+            .map_err(|e| SignerError::Crypto(format!("Failed to parse public key: {}", e)))?;
+
+        // Accept both the raw 64-byte `r || s` signature `sign()` produces
+        // and the 65-byte recoverable form `sign_recoverable()` produces;
+        // the trailing recovery byte is irrelevant to plain verification.
+        let sig_bytes = match sig.bytes.len() {
+            64 => &sig.bytes[..],
+            65 => &sig.bytes[..64],
+            other => {
+                return Err(SignerError::InvalidSignature(format!(
+                    "secp256k1 signature must be 64 or 65 bytes, got {}",
+                    other
+                )))
+            }
+        };
+        let signature = k256::ecdsa::Signature::from_slice(sig_bytes)
+            .map_err(|e| SignerError::InvalidSignature(format!("Invalid signature encoding: {}", e)))?;
+
+        verifying_key
+            .verify(data, &signature)
+            .map_err(|e| SignerError::InvalidSignature(format!("Signature verification failed: {}", e)))
     }
+}
 
-    #[tokio::test]
-    async fn test_secp256k1_software_signer() {
-        let (signer, _pem) = Secp256k1SoftwareSigner::generate().unwrap();
-        
-        let data = b"Hello, world!";
-        let signature = signer.sign(data).await.unwrap();
-        
-        assert_eq!(signature.algorithm, "secp256k1");
-        assert_eq!(signature.bytes.len(), 64); // secp256k1 signature size
-        
-        let public_key = signer.public_key().await.unwrap();
-        assert_eq!(public_key.algorithm, "secp256k1");
-        assert!(!public_key.spki_bytes.is_empty());
-        
-        let info = signer.signer_info();
-        assert_eq!(info.signer_type, "software");
-        assert_eq!(info.algorithm, "secp256k1");
+/// Recover the signer's public key from a recoverable ES256K signature over
+/// `data`, mirroring how secp256k1-based wallets expose `signature.recover(message)`.
+/// Requires `sig.recovery_id` to be set (i.e. it came from [`Signer::sign`]
+/// or [`Signer::sign_recoverable`] on a [`Secp256k1SoftwareSigner`], not an
+/// externally-supplied signature that only carries raw bytes).
+pub fn recover_public_key(data: &[u8], sig: &Signature) -> Result<PublicKey, SignerError> {
+    if sig.algorithm != "ES256K" {
+        return Err(SignerError::InvalidSignature(format!(
+            "public key recovery requires an ES256K signature, got {}",
+            sig.algorithm
+        )));
     }
 
-    #[tokio::test]
-    async fn test_ed25519_signature_verification() {
-        let (signer, _pem) = SoftwareSigner::generate().unwrap();
-        
-        let data = b"Test message";
-        let signature = signer.sign(data).await.unwrap();
-        let public_key = signer.public_key().await.unwrap();
-        
-        // Verify the signature
-        let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(
-            &public_key.spki_bytes[public_key.spki_bytes.len() - 32..]
-        ).unwrap();
-        
-        let sig_bytes = ed25519_dalek::Signature::from_bytes(&signature.bytes).unwrap();
-        assert!(verifying_key.verify(data, &sig_bytes).is_ok());
+    let recovery_byte = sig.recovery_id.ok_or_else(|| {
+        SignerError::InvalidSignature("signature has no recovery id to recover from".to_string())
+    })?;
+    let recovery_id = k256::ecdsa::RecoveryId::from_byte(recovery_byte).ok_or_else(|| {
+        SignerError::InvalidSignature(format!("invalid recovery id byte: {}", recovery_byte))
+    })?;
+
+    let sig_bytes = match sig.bytes.len() {
+        64 => &sig.bytes[..],
+        65 => &sig.bytes[..64],
+        other => {
+            return Err(SignerError::InvalidSignature(format!(
+                "secp256k1 signature must be 64 or 65 bytes, got {}",
+                other
+            )))
+        }
+    };
+    let signature = k256::ecdsa::Signature::from_slice(sig_bytes)
+        .map_err(|e| SignerError::InvalidSignature(format!("Invalid signature encoding: {}", e)))?;
+
+    let verifying_key = k256::ecdsa::VerifyingKey::recover_from_digest(
+        k256::ecdsa::digest::Digest::hash(data),
+        &signature,
+        recovery_id,
+    )
+    .map_err(|e| SignerError::Crypto(format!("Failed to recover public key: {}", e)))?;
+
+    let spki_bytes = verifying_key
+        .to_public_key_der()
+        .map_err(|e| SignerError::Crypto(format!("Failed to serialize recovered public key: {}", e)))?;
+
+    Ok(PublicKey {
+        algorithm: "secp256k1".to_string(),
+        spki_bytes: spki_bytes.as_bytes().to_vec(),
+    })
+}
+
+/// Configuration for secp256r1 (P-256) software signer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct P256SoftwareSignerConfig {
+    /// Path to private key file (PEM format)
+    pub private_key_path: Option<String>,
+    /// Private key in PEM format (direct string)
+    pub private_key_pem: Option<String>,
+    /// BIP39 mnemonic phrase to derive the key from. Takes precedence over
+    /// `private_key_pem`/`private_key_path` when set.
+    pub mnemonic: Option<String>,
+    /// Optional BIP39 passphrase used together with `mnemonic`.
+    pub mnemonic_passphrase: Option<String>,
+}
+
+/// Software-based signer using local secp256r1 (P-256) keys. Used to sign
+/// and locally verify WebAuthn/passkey-style credentials, which Soroban
+/// verifies on-chain as secp256r1 signatures.
+pub struct P256SoftwareSigner {
+    signing_key: p256::ecdsa::SigningKey,
+    algorithm: String,
+}
+
+impl P256SoftwareSigner {
+    /// Create a new secp256r1 software signer from a private key file
+    pub fn from_key_file<P: AsRef<Path>>(path: P) -> Result<Self, SignerError> {
+        let pem_data = fs::read_to_string(path)
+            .map_err(|e| SignerError::Io(e))?;
+
+        Self::from_pem(&pem_data)
     }
 
-    #[test]
-    fn test_software_signer_config() {
-        let config = SoftwareSignerConfig {
-            private_key_path: Some("/path/to/key.pem".to_string()),
-            private_key_pem: None,
+    /// Create a new secp256r1 software signer from PEM data
+    pub fn from_pem(pem_data: &str) -> Result<Self, SignerError> {
+        let signing_key = p256::ecdsa::SigningKey::from_pkcs8_pem(pem_data)
+            .map_err(|e| SignerError::Crypto(format!("Failed to parse private key: {}", e)))?;
+
+        Ok(Self {
+            signing_key,
+            algorithm: "secp256r1".to_string(),
+        })
+    }
+
+    /// Create a secp256r1 software signer from configuration
+    pub fn from_config(config: &P256SoftwareSignerConfig) -> Result<Self, SignerError> {
+        if let Some(phrase) = &config.mnemonic {
+            Self::from_mnemonic(phrase, config.mnemonic_passphrase.as_deref().unwrap_or(""))
+        } else if let Some(pem_data) = &config.private_key_pem {
+            Self::from_pem(pem_data)
+        } else if let Some(path) = &config.private_key_path {
+            Self::from_key_file(path)
+        } else {
+            Err(SignerError::Config(
+                "One of mnemonic, private_key_pem, or private_key_path must be provided".to_string()
+            ))
+        }
+    }
+
+    /// Recover a signer deterministically from a BIP39 mnemonic phrase and
+    /// an optional passphrase. The same phrase and passphrase always yield
+    /// the same key.
+    pub fn from_mnemonic(phrase: &str, passphrase: &str) -> Result<Self, SignerError> {
+        let seed = mnemonic_seed(phrase, passphrase)?;
+
+        for attempt in 0..MAX_SCALAR_DERIVATION_ATTEMPTS {
+            let material = derive_key_material(&seed, "erst/secp256r1", attempt);
+            if let Ok(signing_key) = p256::ecdsa::SigningKey::from_bytes(&material.into()) {
+                return Ok(Self {
+                    signing_key,
+                    algorithm: "secp256r1".to_string(),
+                });
+            }
+        }
+
+        Err(SignerError::Crypto(
+            "Failed to derive a valid secp256r1 key from the mnemonic seed".to_string(),
+        ))
+    }
+
+    /// Generate a new `word_count`-word (12 or 24) BIP39 mnemonic, derive a
+    /// signer from it, and return both the signer and the phrase so the
+    /// caller can back it up.
+    pub fn generate_with_mnemonic(word_count: usize) -> Result<(Self, String), SignerError> {
+        let mnemonic = generate_mnemonic(word_count)?;
+        let phrase = mnemonic.to_string();
+        let signer = Self::from_mnemonic(&phrase, "")?;
+        Ok((signer, phrase))
+    }
+
+    /// Generate a new random key pair and return the signer
+    pub fn generate() -> Result<(Self, String), SignerError> {
+        let mut csprng = rand::rngs::OsRng;
+        let signing_key = p256::ecdsa::SigningKey::random(&mut csprng);
+
+        let pem_data = signing_key.to_pkcs8_pem(p256::pkcs8::LineEnding::LF)
+            .map_err(|e| SignerError::Crypto(format!("Failed to serialize private key: {}", e)))?;
+
+        let signer = Self {
+            signing_key,
+            algorithm: "secp256r1".to_string(),
+        };
+
+        Ok((signer, pem_data))
+    }
+
+    /// Get the verifying key
+    pub fn verifying_key(&self) -> &p256::ecdsa::VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+}
+
+impl Drop for P256SoftwareSigner {
+    fn drop(&mut self) {
+        self.signing_key.zeroize();
+    }
+}
+
+#[async_trait]
+impl Signer for P256SoftwareSigner {
+    async fn sign(&self, data: &[u8]) -> Result<Signature, SignerError> {
+        use p256::ecdsa::signature::Signer;
+
+        let signature: p256::ecdsa::Signature = self.signing_key.sign(data);
+
+        Ok(Signature {
+            algorithm: "ES256".to_string(),
+            bytes: signature.to_bytes().to_vec(),
+            recovery_id: None,
+        })
+    }
+
+    async fn public_key(&self) -> Result<PublicKey, SignerError> {
+        let verifying_key = self.signing_key.verifying_key();
+        let spki_bytes = verifying_key.to_public_key_der()
+            .map_err(|e| SignerError::Crypto(format!("Failed to serialize public key: {}", e)))?;
+
+        Ok(PublicKey {
+            algorithm: self.algorithm.clone(),
+            spki_bytes: spki_bytes.as_bytes().to_vec(),
+        })
+    }
+
+    fn signer_info(&self) -> SignerInfo {
+        let mut metadata = HashMap::new();
+        metadata.insert("key_type".to_string(), "secp256r1".to_string());
+        metadata.insert("implementation".to_string(), "software".to_string());
+
+        SignerInfo {
+            signer_type: "software".to_string(),
+            algorithm: self.algorithm.clone(),
+            metadata,
+        }
+    }
+
+    fn supported_algorithms(&self) -> Vec<&'static str> {
+        vec!["ES256"]
+    }
+}
+
+#[async_trait]
+impl Verifier for P256SoftwareSigner {
+    async fn verify(&self, data: &[u8], sig: &Signature, key: &PublicKey) -> Result<(), SignerError> {
+        use p256::ecdsa::signature::Verifier as EcdsaVerifier;
+        use p256::pkcs8::DecodePublicKey;
+
+        if key.algorithm != "secp256r1" && key.algorithm != "p256" {
+            return Err(SignerError::InvalidSignature(format!(
+                "P256SoftwareSigner verifies secp256r1 signatures only, got {}",
+                key.algorithm
+            )));
+        }
+
+        let verifying_key = p256::ecdsa::VerifyingKey::from_public_key_der(&key.spki_bytes)
+            .map_err(|e| SignerError::Crypto(format!("Failed to parse public key: {}", e)))?;
+
+        let signature = p256::ecdsa::Signature::from_slice(&sig.bytes)
+            .map_err(|e| SignerError::InvalidSignature(format!("Invalid signature encoding: {}", e)))?;
+
+        verifying_key
+            .verify(data, &signature)
+            .map_err(|e| SignerError::InvalidSignature(format!("Signature verification failed: {}", e)))
+    }
+}
+
+/// Configuration for RSA (RS256) software signer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RsaSoftwareSignerConfig {
+    /// Path to private key file (PEM format)
+    pub private_key_path: Option<String>,
+    /// Private key in PEM format (direct string)
+    pub private_key_pem: Option<String>,
+    /// Key size in bits to use when generating a new key via
+    /// [`RsaSoftwareSigner::generate`]. Unused by `from_config`, which
+    /// requires an existing key like the other software signers do.
+    pub key_bits: Option<usize>,
+}
+
+/// Software-based signer using a local RSA key, signing with RSASSA-PKCS1-v1_5
+/// over SHA-256 (RS256), the scheme most JWS/ACME-style integrations expect.
+pub struct RsaSoftwareSigner {
+    signing_key: rsa::pkcs1v15::SigningKey<Sha256>,
+}
+
+impl RsaSoftwareSigner {
+    /// Create a new RSA software signer from a private key file
+    pub fn from_key_file<P: AsRef<Path>>(path: P) -> Result<Self, SignerError> {
+        let pem_data = fs::read_to_string(path).map_err(SignerError::Io)?;
+        Self::from_pem(&pem_data)
+    }
+
+    /// Create a new RSA software signer from PEM data
+    pub fn from_pem(pem_data: &str) -> Result<Self, SignerError> {
+        let private_key = rsa::RsaPrivateKey::from_pkcs8_pem(pem_data)
+            .map_err(|e| SignerError::Crypto(format!("Failed to parse private key: {}", e)))?;
+
+        Ok(Self {
+            signing_key: rsa::pkcs1v15::SigningKey::<Sha256>::new(private_key),
+        })
+    }
+
+    /// Create an RSA software signer from configuration
+    pub fn from_config(config: &RsaSoftwareSignerConfig) -> Result<Self, SignerError> {
+        if let Some(pem_data) = &config.private_key_pem {
+            Self::from_pem(pem_data)
+        } else if let Some(path) = &config.private_key_path {
+            Self::from_key_file(path)
+        } else {
+            Err(SignerError::Config(
+                "One of private_key_pem or private_key_path must be provided".to_string(),
+            ))
+        }
+    }
+
+    /// Generate a new `bits`-size RSA key pair and return the signer along
+    /// with its PKCS#8 PEM encoding so the caller can back it up.
+    pub fn generate(bits: usize) -> Result<(Self, String), SignerError> {
+        let mut csprng = rand::rngs::OsRng;
+        let private_key = rsa::RsaPrivateKey::new(&mut csprng, bits)
+            .map_err(|e| SignerError::Crypto(format!("Failed to generate RSA key: {}", e)))?;
+
+        let pem_data = private_key
+            .to_pkcs8_pem(rsa::pkcs8::LineEnding::LF)
+            .map_err(|e| SignerError::Crypto(format!("Failed to serialize private key: {}", e)))?
+            .to_string();
+
+        let signer = Self {
+            signing_key: rsa::pkcs1v15::SigningKey::<Sha256>::new(private_key),
+        };
+
+        Ok((signer, pem_data))
+    }
+}
+
+impl Drop for RsaSoftwareSigner {
+    fn drop(&mut self) {
+        self.signing_key.zeroize();
+    }
+}
+
+#[async_trait]
+impl Signer for RsaSoftwareSigner {
+    async fn sign(&self, data: &[u8]) -> Result<Signature, SignerError> {
+        use rsa::signature::Signer as RsaSigner;
+
+        let signature = self
+            .signing_key
+            .try_sign(data)
+            .map_err(|e| SignerError::Crypto(format!("Failed to sign data: {}", e)))?;
+
+        Ok(Signature {
+            algorithm: "RS256".to_string(),
+            bytes: signature.to_vec(),
+            recovery_id: None,
+        })
+    }
+
+    async fn public_key(&self) -> Result<PublicKey, SignerError> {
+        let verifying_key = self.signing_key.verifying_key();
+        let spki_bytes = verifying_key
+            .to_public_key_der()
+            .map_err(|e| SignerError::Crypto(format!("Failed to serialize public key: {}", e)))?;
+
+        Ok(PublicKey {
+            algorithm: "rsa".to_string(),
+            spki_bytes: spki_bytes.as_bytes().to_vec(),
+        })
+    }
+
+    fn signer_info(&self) -> SignerInfo {
+        let mut metadata = HashMap::new();
+        metadata.insert("key_type".to_string(), "rsa".to_string());
+        metadata.insert("implementation".to_string(), "software".to_string());
+
+        SignerInfo {
+            signer_type: "software".to_string(),
+            algorithm: "rsa".to_string(),
+            metadata,
+        }
+    }
+
+    fn supported_algorithms(&self) -> Vec<&'static str> {
+        vec!["RS256"]
+    }
+}
+
+#[async_trait]
+impl Verifier for RsaSoftwareSigner {
+    async fn verify(&self, data: &[u8], sig: &Signature, key: &PublicKey) -> Result<(), SignerError> {
+        use rsa::signature::Verifier as RsaVerifier;
+
+        if key.algorithm != "rsa" {
+            return Err(SignerError::InvalidSignature(format!(
+                "RsaSoftwareSigner verifies rsa signatures only, got {}",
+                key.algorithm
+            )));
+        }
+
+        let verifying_key = rsa::pkcs1v15::VerifyingKey::<Sha256>::from_public_key_der(&key.spki_bytes)
+            .map_err(|e| SignerError::Crypto(format!("Failed to parse public key: {}", e)))?;
+
+        let signature = rsa::pkcs1v15::Signature::try_from(sig.bytes.as_slice())
+            .map_err(|e| SignerError::InvalidSignature(format!("Invalid signature encoding: {}", e)))?;
+
+        verifying_key
+            .verify(data, &signature)
+            .map_err(|e| SignerError::InvalidSignature(format!("Signature verification failed: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ed25519_software_signer() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        
+        let data = b"Hello, world!";
+        let signature = signer.sign(data).await.unwrap();
+        
+        assert_eq!(signature.algorithm, "Ed25519");
+        assert_eq!(signature.bytes.len(), 64); // Ed25519 signature size
+
+        let public_key = signer.public_key().await.unwrap();
+        assert_eq!(public_key.algorithm, "ed25519");
+        assert!(!public_key.spki_bytes.is_empty());
+
+        let info = signer.signer_info();
+        assert_eq!(info.signer_type, "software");
+        assert_eq!(info.algorithm, "ed25519");
+        assert_eq!(signer.supported_algorithms(), vec!["Ed25519"]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_returns_a_zeroizing_pem_that_still_parses() {
+        let (_signer, pem) = SoftwareSigner::generate().unwrap();
+        // `pem` derefs to `&str` like a plain `String` would; it's only the
+        // drop behavior that differs (the buffer is scrubbed instead of
+        // just freed).
+        assert!(SoftwareSigner::from_pem(&pem).is_ok());
+    }
+
+    #[test]
+    fn test_secp256k1_generate_returns_a_zeroizing_pem_that_still_parses() {
+        let (_signer, pem) = Secp256k1SoftwareSigner::generate().unwrap();
+        assert!(Secp256k1SoftwareSigner::from_pem(&pem).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_software_signer_verifies_its_own_signature() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let data = b"Hello, world!";
+
+        let signature = signer.sign(data).await.unwrap();
+
+        assert!(signer.verify(data, &signature).await.is_ok());
+        assert!(signer.verify(b"tampered", &signature).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_secp256k1_software_signer() {
+        let (signer, _pem) = Secp256k1SoftwareSigner::generate().unwrap();
+        
+        let data = b"Hello, world!";
+        let signature = signer.sign(data).await.unwrap();
+        
+        assert_eq!(signature.algorithm, "ES256K");
+        assert_eq!(signature.bytes.len(), 64); // secp256k1 signature size
+
+        let public_key = signer.public_key().await.unwrap();
+        assert_eq!(public_key.algorithm, "secp256k1");
+        assert!(!public_key.spki_bytes.is_empty());
+
+        let info = signer.signer_info();
+        assert_eq!(info.signer_type, "software");
+        assert_eq!(info.algorithm, "secp256k1");
+        assert_eq!(signer.supported_algorithms(), vec!["ES256K"]);
+    }
+
+    #[tokio::test]
+    async fn test_secp256k1_sign_carries_a_recovery_id() {
+        let (signer, _pem) = Secp256k1SoftwareSigner::generate().unwrap();
+        let signature = signer.sign(b"Hello, world!").await.unwrap();
+
+        assert!(signature.recovery_id.is_some());
+        assert!(signature.recovery_id.unwrap() <= 3);
+    }
+
+    #[tokio::test]
+    async fn test_secp256k1_software_signer_verifies_its_own_signature() {
+        let (signer, _pem) = Secp256k1SoftwareSigner::generate().unwrap();
+        let data = b"Hello, world!";
+
+        let signature = signer.sign(data).await.unwrap();
+        assert!(signer.verify(data, &signature).await.is_ok());
+        assert!(signer.verify(b"tampered", &signature).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recover_public_key_from_sign_matches_the_signer() {
+        let (signer, _pem) = Secp256k1SoftwareSigner::generate().unwrap();
+        let data = b"Hello, world!";
+
+        let signature = signer.sign(data).await.unwrap();
+        let public_key = signer.public_key().await.unwrap();
+        let recovered = recover_public_key(data, &signature).unwrap();
+
+        assert_eq!(recovered, public_key);
+    }
+
+    #[tokio::test]
+    async fn test_recover_public_key_from_sign_recoverable_matches_the_signer() {
+        let (signer, _pem) = Secp256k1SoftwareSigner::generate().unwrap();
+        let data = b"Hello, world!";
+
+        let signature = signer.sign_recoverable(data).await.unwrap();
+        let public_key = signer.public_key().await.unwrap();
+        let recovered = recover_public_key(data, &signature).unwrap();
+
+        assert_eq!(recovered, public_key);
+    }
+
+    #[tokio::test]
+    async fn test_recover_public_key_rejects_a_signature_without_a_recovery_id() {
+        let (signer, _pem) = Secp256k1SoftwareSigner::generate().unwrap();
+        let mut signature = signer.sign(b"data").await.unwrap();
+        signature.recovery_id = None;
+
+        match recover_public_key(b"data", &signature) {
+            Err(SignerError::InvalidSignature(_)) => {}
+            other => panic!("expected an InvalidSignature error, got {:?}", other.err()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recover_public_key_rejects_non_es256k_signatures() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let signature = signer.sign(b"data").await.unwrap();
+
+        match recover_public_key(b"data", &signature) {
+            Err(SignerError::InvalidSignature(_)) => {}
+            other => panic!("expected an InvalidSignature error, got {:?}", other.err()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_p256_software_signer() {
+        let (signer, _pem) = P256SoftwareSigner::generate().unwrap();
+
+        let data = b"Hello, world!";
+        let signature = signer.sign(data).await.unwrap();
+
+        assert_eq!(signature.algorithm, "ES256");
+        assert!(!signature.bytes.is_empty());
+
+        let public_key = signer.public_key().await.unwrap();
+        assert_eq!(public_key.algorithm, "secp256r1");
+        assert!(!public_key.spki_bytes.is_empty());
+
+        let info = signer.signer_info();
+        assert_eq!(info.signer_type, "software");
+        assert_eq!(info.algorithm, "secp256r1");
+        assert_eq!(signer.supported_algorithms(), vec!["ES256"]);
+    }
+
+    #[tokio::test]
+    async fn test_p256_software_signer_verifies_its_own_signature() {
+        let (signer, _pem) = P256SoftwareSigner::generate().unwrap();
+        let data = b"Hello, world!";
+
+        let signature = signer.sign(data).await.unwrap();
+        let public_key = signer.public_key().await.unwrap();
+
+        assert!(signer.verify(data, &signature, &public_key).await.is_ok());
+        assert!(signer
+            .verify(b"tampered", &signature, &public_key)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_secp256k1_sign_recoverable_appends_recovery_id() {
+        let (signer, _pem) = Secp256k1SoftwareSigner::generate().unwrap();
+
+        let data = b"Hello, world!";
+        let signature = signer.sign_recoverable(data).await.unwrap();
+
+        assert_eq!(signature.algorithm, "ES256K");
+        // 64-byte signature plus a 1-byte recovery id.
+        assert_eq!(signature.bytes.len(), 65);
+    }
+
+    #[tokio::test]
+    async fn test_p256_sign_recoverable_is_unsupported() {
+        let (signer, _pem) = P256SoftwareSigner::generate().unwrap();
+
+        let err = signer.sign_recoverable(b"data").await.unwrap_err();
+        assert!(matches!(err, SignerError::Crypto(_)));
+    }
+
+    #[tokio::test]
+    async fn test_ed25519_signature_verification() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+
+        let data = b"Test message";
+        let signature = signer.sign(data).await.unwrap();
+        let public_key = signer.public_key().await.unwrap();
+
+        // Parse the real SPKI DER via a Keyring rather than slicing the raw
+        // key material out of `spki_bytes` by a hardcoded offset.
+        let mut keyring = super::super::verify::Keyring::new();
+        let key_id = keyring.add_spki_der(&public_key.spki_bytes).unwrap();
+
+        assert_eq!(keyring.verify(data, &signature).unwrap(), key_id);
+    }
+
+    #[test]
+    fn test_software_signer_config() {
+        let config = SoftwareSignerConfig {
+            private_key_path: Some("/path/to/key.pem".to_string()),
+            private_key_pem: None,
+            mnemonic: None,
+            mnemonic_passphrase: None,
+            keystore_path: None,
+            keystore_password_path: None,
+            keypair_json_path: None,
         };
 
         // This should fail since the file doesn't exist
         assert!(SoftwareSigner::from_config(&config).is_err());
     }
+
+    #[test]
+    fn test_keypair_json_round_trips_through_to_and_from() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let keypair_json = signer.to_keypair_json().unwrap();
+
+        let restored = SoftwareSigner::from_keypair_json(&keypair_json).unwrap();
+        assert_eq!(signer.verifying_key().to_bytes(), restored.verifying_key().to_bytes());
+    }
+
+    #[test]
+    fn test_from_keypair_json_accepts_a_seed_only_array() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let seed_only = serde_json::to_string(&signer.signing_key.to_bytes().to_vec()).unwrap();
+
+        let restored = SoftwareSigner::from_keypair_json(&seed_only).unwrap();
+        assert_eq!(signer.verifying_key().to_bytes(), restored.verifying_key().to_bytes());
+    }
+
+    #[test]
+    fn test_from_keypair_json_rejects_a_mismatched_public_half() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let mut bytes = vec![0u8; 64];
+        bytes[..32].copy_from_slice(&signer.signing_key.to_bytes());
+        bytes[32..].fill(0xAB);
+        let tampered = serde_json::to_string(&bytes).unwrap();
+
+        assert!(SoftwareSigner::from_keypair_json(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_from_keypair_json_rejects_the_wrong_length() {
+        let err = SoftwareSigner::from_keypair_json("[1, 2, 3]").unwrap_err();
+        assert!(matches!(err, SignerError::Crypto(_)));
+    }
+
+    #[test]
+    fn test_from_config_prefers_keypair_json_over_pem() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let keypair_json = signer.to_keypair_json().unwrap();
+
+        let dir = std::env::temp_dir();
+        let keypair_path = dir.join(format!("erst_test_keypair_{}.json", std::process::id()));
+        fs::write(&keypair_path, keypair_json.as_str()).unwrap();
+
+        let config = SoftwareSignerConfig {
+            private_key_path: None,
+            private_key_pem: Some("not a valid PEM".to_string()),
+            mnemonic: None,
+            mnemonic_passphrase: None,
+            keystore_path: None,
+            keystore_password_path: None,
+            keypair_json_path: Some(keypair_path.to_string_lossy().to_string()),
+        };
+
+        let restored = SoftwareSigner::from_config(&config).unwrap();
+        assert_eq!(signer.verifying_key().to_bytes(), restored.verifying_key().to_bytes());
+
+        let _ = fs::remove_file(&keypair_path);
+    }
+
+    #[test]
+    fn test_ed25519_from_mnemonic_is_deterministic() {
+        let (signer_a, phrase) = SoftwareSigner::generate_with_mnemonic(12).unwrap();
+        let signer_b = SoftwareSigner::from_mnemonic(&phrase, "").unwrap();
+
+        assert_eq!(
+            signer_a.verifying_key().to_bytes(),
+            signer_b.verifying_key().to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_mnemonic_passphrase_changes_the_derived_key() {
+        let (signer, phrase) = SoftwareSigner::generate_with_mnemonic(24).unwrap();
+        let with_passphrase = SoftwareSigner::from_mnemonic(&phrase, "extra-word").unwrap();
+
+        assert_ne!(
+            signer.verifying_key().to_bytes(),
+            with_passphrase.verifying_key().to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_generate_with_mnemonic_rejects_unsupported_word_count() {
+        assert!(SoftwareSigner::generate_with_mnemonic(18).is_err());
+    }
+
+    #[test]
+    fn test_secp256k1_and_p256_from_mnemonic_are_deterministic_and_distinct() {
+        let phrase = SoftwareSigner::generate_with_mnemonic(12).unwrap().1;
+
+        let k256_a = Secp256k1SoftwareSigner::from_mnemonic(&phrase, "").unwrap();
+        let k256_b = Secp256k1SoftwareSigner::from_mnemonic(&phrase, "").unwrap();
+        assert_eq!(
+            k256_a.verifying_key().to_sec1_bytes(),
+            k256_b.verifying_key().to_sec1_bytes()
+        );
+
+        let p256_signer = P256SoftwareSigner::from_mnemonic(&phrase, "").unwrap();
+        // The same mnemonic must not produce the same raw key material
+        // across curves, since each derivation uses a distinct domain tag.
+        assert_ne!(
+            k256_a.verifying_key().to_sec1_bytes().as_ref(),
+            p256_signer.verifying_key().to_sec1_bytes().as_ref()
+        );
+    }
+
+    /// Build a minimal EIP-2335 keystore JSON (pbkdf2 KDF, low iteration
+    /// count for test speed) encrypting `key` under `password`.
+    fn build_test_keystore(key: &[u8; 32], password: &str) -> String {
+        let salt = [0x42u8; 32];
+        let iv = [0x24u8; 16];
+        let iterations = 64u32;
+
+        let mut derived = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt, iterations, &mut derived);
+
+        let mut cipher_text = *key;
+        let mut stream_cipher =
+            ctr::Ctr128BE::<aes::Aes128>::new_from_slices(&derived[0..16], &iv).unwrap();
+        cipher::StreamCipher::apply_keystream(&mut stream_cipher, &mut cipher_text);
+
+        let mut checksum_input = Vec::new();
+        checksum_input.extend_from_slice(&derived[16..32]);
+        checksum_input.extend_from_slice(&cipher_text);
+        let checksum = hex::encode(Sha256::digest(&checksum_input));
+
+        serde_json::json!({
+            "crypto": {
+                "kdf": {
+                    "function": "pbkdf2",
+                    "params": {
+                        "dklen": 32,
+                        "c": iterations,
+                        "salt": hex::encode(salt),
+                    },
+                },
+                "checksum": { "message": checksum },
+                "cipher": {
+                    "function": "aes-128-ctr",
+                    "params": { "iv": hex::encode(iv) },
+                    "message": hex::encode(cipher_text),
+                },
+            },
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_decrypt_eip2335_keystore_round_trips_the_key() {
+        let key = [0x11u8; 32];
+        let keystore_json = build_test_keystore(&key, "correct horse battery staple");
+
+        let decrypted = decrypt_eip2335_keystore(&keystore_json, "correct horse battery staple").unwrap();
+        assert_eq!(decrypted, key.to_vec());
+    }
+
+    #[test]
+    fn test_decrypt_eip2335_keystore_rejects_wrong_password() {
+        let key = [0x11u8; 32];
+        let keystore_json = build_test_keystore(&key, "correct horse battery staple");
+
+        match decrypt_eip2335_keystore(&keystore_json, "wrong password") {
+            Err(SignerError::Crypto(msg)) => assert!(msg.contains("checksum")),
+            other => panic!("expected a checksum Crypto error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_software_signer_from_config_with_keystore_path() {
+        let key = [0x22u8; 32];
+        let keystore_json = build_test_keystore(&key, "hunter2");
+
+        let dir = std::env::temp_dir();
+        let keystore_path = dir.join(format!("erst_test_keystore_{}.json", std::process::id()));
+        fs::write(&keystore_path, keystore_json).unwrap();
+
+        let config = SoftwareSignerConfig {
+            private_key_path: None,
+            private_key_pem: None,
+            mnemonic: None,
+            mnemonic_passphrase: None,
+            keystore_path: Some(keystore_path.to_string_lossy().to_string()),
+            keystore_password_path: None,
+            keypair_json_path: None,
+        };
+        std::env::set_var("ERST_SOFTWARE_KEYSTORE_PASSWORD", "hunter2");
+
+        let signer = SoftwareSigner::from_config(&config).unwrap();
+        assert_eq!(signer.signing_key.to_bytes(), key);
+
+        std::env::remove_var("ERST_SOFTWARE_KEYSTORE_PASSWORD");
+        let _ = fs::remove_file(&keystore_path);
+    }
 }
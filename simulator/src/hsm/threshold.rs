@@ -0,0 +1,393 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! FROST (Flexible Round-Optimized Schnorr Threshold) signing over Ed25519.
+//!
+//! A [`FrostCoordinator`] holds a `t`-of-`n` Shamir-shared Ed25519 key and
+//! implements [`super::Signer`], so `signer_type: "frost"` plugs into
+//! [`super::SignerFactory`] exactly like the single-key software and PKCS#11
+//! backends: the caller gets back a `Box<dyn Signer>` and doesn't need to
+//! know that producing one signature took two rounds and `t` cooperating
+//! shares instead of one private key. The aggregated `(R, z)` output is
+//! byte-compatible with a plain Ed25519 signature — same 64-byte
+//! `signature.bytes`, same SPKI public key — so it verifies against the
+//! group public key with the exact same [`super::verify_bundle`] path any
+//! other signer's output does.
+//!
+//! This is a *trusted-dealer* coordinator: [`FrostCoordinator::generate`]
+//! samples the secret-sharing polynomial itself and holds every
+//! participant's share in one process, then plays every role a real
+//! deployment splits across separate machines — the dealer during setup,
+//! all `t` active signers during round 1, and the aggregator during round
+//! 2. That's the right shape for this crate's purpose (simulating and
+//! testing how a contract reacts to a threshold-signed transaction), but it
+//! is not the "no single host holds the full key" property FROST is for in
+//! production. A real deployment replaces [`FrostCoordinator::generate`]
+//! with a distributed key generation protocol and never gathers every
+//! share in one place.
+
+use super::{PublicKey, Signature, Signer, SignerError, SignerInfo};
+use async_trait::async_trait;
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+use curve25519_dalek::edwards::EdwardsPoint;
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::Identity;
+use ed25519_dalek::pkcs8::EncodePublicKey;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use std::collections::HashMap;
+
+/// Configuration for a trusted-dealer [`FrostCoordinator`], used via
+/// `signer_type: "frost"` in [`super::SignerConfig`]. See the module doc
+/// for why key generation happens in-process rather than via a real
+/// distributed setup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrostSignerConfig {
+    /// Minimum number of participants (`t`) required to produce a
+    /// signature.
+    pub threshold: u16,
+    /// Total number of participants (`n`) the secret is shared across.
+    pub participants: u16,
+}
+
+/// Trusted-dealer `t`-of-`n` FROST-over-Ed25519 signer. See the module doc.
+pub struct FrostCoordinator {
+    threshold: u16,
+    participants: u16,
+    /// Participant index (`1..=participants`) to their Shamir share `s_i`
+    /// of the group secret.
+    shares: HashMap<u16, Scalar>,
+    group_public_key: EdwardsPoint,
+}
+
+impl FrostCoordinator {
+    /// Build a coordinator from `config`, running trusted-dealer key
+    /// generation fresh — see [`Self::generate`].
+    ///
+    /// # Errors
+    /// Returns [`SignerError::Config`] if `threshold` is zero or exceeds
+    /// `participants`.
+    pub fn from_config(config: &FrostSignerConfig) -> Result<Self, SignerError> {
+        Self::generate(config.threshold, config.participants)
+    }
+
+    /// Trusted-dealer key generation: sample a random degree-`(threshold -
+    /// 1)` polynomial `f` over the Ed25519 scalar field with `f(0) = s` the
+    /// group secret, hand participant `i` (`1..=participants`) the share
+    /// `s_i = f(i)`, and derive the group public key `Y = s·G`.
+    ///
+    /// # Errors
+    /// Returns [`SignerError::Config`] if `threshold` is zero or exceeds
+    /// `participants`.
+    pub fn generate(threshold: u16, participants: u16) -> Result<Self, SignerError> {
+        if threshold == 0 {
+            return Err(SignerError::Config("frost threshold must be at least 1".to_string()));
+        }
+        if threshold > participants {
+            return Err(SignerError::Config(format!(
+                "frost threshold {} exceeds participant count {}",
+                threshold, participants
+            )));
+        }
+
+        let mut rng = rand::rngs::OsRng;
+        let coefficients: Vec<Scalar> = (0..threshold).map(|_| random_scalar(&mut rng)).collect();
+
+        let secret = coefficients[0];
+        let group_public_key = &ED25519_BASEPOINT_TABLE * &secret;
+
+        let shares = (1..=participants)
+            .map(|i| (i, evaluate_polynomial(&coefficients, Scalar::from(i as u64))))
+            .collect();
+
+        Ok(Self {
+            threshold,
+            participants,
+            shares,
+            group_public_key,
+        })
+    }
+
+    /// Number of participants required to sign.
+    pub fn threshold(&self) -> u16 {
+        self.threshold
+    }
+
+    /// Total number of participants the secret is shared across.
+    pub fn participants(&self) -> u16 {
+        self.participants
+    }
+
+    /// Run both FROST signing rounds over `message` and return the
+    /// aggregated Ed25519-compatible `(R, z)` signature. The active signer
+    /// set is deterministically the first `threshold` participants
+    /// (`1..=threshold`) — this trusted-dealer coordinator holds every
+    /// share itself, so there's no "whoever responded to round 1" to track
+    /// the way a real distributed coordinator would.
+    ///
+    /// # Errors
+    /// Returns [`SignerError::Crypto`] if any active participant's partial
+    /// signature fails the per-participant verification equation the
+    /// coordinator checks before folding it into the aggregate, so a single
+    /// corrupted share is rejected rather than silently poisoning the
+    /// result.
+    fn sign_threshold(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let active: Vec<u16> = (1..=self.threshold).collect();
+        let mut rng = rand::rngs::OsRng;
+
+        // Round 1: each active participant samples two fresh nonces (never
+        // reused across messages) and publishes their commitments.
+        let mut nonces: HashMap<u16, (Scalar, Scalar)> = HashMap::with_capacity(active.len());
+        let mut commitments: Vec<(u16, EdwardsPoint, EdwardsPoint)> = Vec::with_capacity(active.len());
+        for &i in &active {
+            let d_i = random_scalar(&mut rng);
+            let e_i = random_scalar(&mut rng);
+            commitments.push((i, &ED25519_BASEPOINT_TABLE * &d_i, &ED25519_BASEPOINT_TABLE * &e_i));
+            nonces.insert(i, (d_i, e_i));
+        }
+
+        // Round 2: per-participant binding factors bound to the full
+        // commitment set `B` (so substituting a different participant's
+        // commitment changes every binding factor, not just that
+        // participant's), the group commitment `R`, and the Ed25519
+        // challenge `c` — computed exactly as a plain Ed25519 signature
+        // would, so the aggregate verifies against `Y` without any
+        // FROST-aware verifier.
+        let rho: HashMap<u16, Scalar> = active
+            .iter()
+            .map(|&i| (i, binding_factor(i, message, &commitments)))
+            .collect();
+
+        let group_commitment = commitments
+            .iter()
+            .fold(EdwardsPoint::identity(), |acc, (i, cap_d_i, cap_e_i)| {
+                acc + cap_d_i + cap_e_i * rho[i]
+            });
+
+        let challenge = schnorr_challenge(&group_commitment, &self.group_public_key, message);
+
+        let mut aggregated_z = Scalar::ZERO;
+        for (i, cap_d_i, cap_e_i) in &commitments {
+            let (d_i, e_i) = nonces[i];
+            let rho_i = rho[i];
+            let lambda_i = lagrange_coefficient(*i, &active);
+            let s_i = *self
+                .shares
+                .get(i)
+                .ok_or_else(|| SignerError::KeyNotFound(format!("no share for participant {i}")))?;
+
+            let z_i = d_i + e_i * rho_i + lambda_i * s_i * challenge;
+
+            // Reject a single malicious/corrupted share before it can
+            // silently corrupt the aggregate: check `g^z_i` against the
+            // participant's own commitments and verification share
+            // `Y_i = s_i·G` (recoverable here since this trusted-dealer
+            // coordinator holds `s_i` directly).
+            let y_i = &ED25519_BASEPOINT_TABLE * &s_i;
+            let expected = cap_d_i + cap_e_i * rho_i + y_i * (lambda_i * challenge);
+            if (&ED25519_BASEPOINT_TABLE * &z_i) != expected {
+                return Err(SignerError::Crypto(format!(
+                    "participant {i}'s partial signature failed verification"
+                )));
+            }
+
+            aggregated_z += z_i;
+        }
+
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(group_commitment.compress().as_bytes());
+        bytes[32..].copy_from_slice(aggregated_z.as_bytes());
+
+        Ok(Signature {
+            algorithm: "Ed25519".to_string(),
+            bytes: bytes.to_vec(),
+            recovery_id: None,
+        })
+    }
+
+    fn public_key_der(&self) -> Result<Vec<u8>, SignerError> {
+        let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(self.group_public_key.compress().as_bytes())
+            .map_err(|e| SignerError::Crypto(format!("Failed to build group verifying key: {}", e)))?;
+        verifying_key
+            .to_public_key_der()
+            .map(|der| der.as_bytes().to_vec())
+            .map_err(|e| SignerError::Crypto(format!("Failed to serialize public key: {}", e)))
+    }
+}
+
+/// Sample a uniformly random Ed25519 scalar from 64 bytes of CSPRNG output,
+/// reduced mod the group order `L` — the same wide-reduction every fresh
+/// nonce and polynomial coefficient in this module goes through.
+fn random_scalar(rng: &mut impl RngCore) -> Scalar {
+    let mut bytes = [0u8; 64];
+    rng.fill_bytes(&mut bytes);
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+/// Evaluate `coefficients` (lowest-degree term first) at `x` via Horner's
+/// method.
+fn evaluate_polynomial(coefficients: &[Scalar], x: Scalar) -> Scalar {
+    coefficients
+        .iter()
+        .rev()
+        .fold(Scalar::ZERO, |acc, coefficient| acc * x + coefficient)
+}
+
+/// Lagrange coefficient for participant `i`'s share, interpolated at `x =
+/// 0` over exactly the responding signer subset `active` — recomputing it
+/// over the wrong subset (or a subset that doesn't match who actually
+/// contributed shares) silently produces the wrong secret.
+fn lagrange_coefficient(i: u16, active: &[u16]) -> Scalar {
+    let x_i = Scalar::from(i as u64);
+    active
+        .iter()
+        .filter(|&&j| j != i)
+        .fold(Scalar::ONE, |acc, &j| {
+            let x_j = Scalar::from(j as u64);
+            acc * (-x_j) * (x_i - x_j).invert()
+        })
+}
+
+/// Per-participant binding factor `ρ_i = H("rho", i, msg, B)`, domain-
+/// separated from [`schnorr_challenge`] and bound to the full commitment
+/// set `B` so tampering with any one participant's published commitment
+/// changes every active participant's binding factor.
+fn binding_factor(index: u16, message: &[u8], commitments: &[(u16, EdwardsPoint, EdwardsPoint)]) -> Scalar {
+    let mut hasher = Sha512::new();
+    hasher.update(b"FROST-ED25519-rho");
+    hasher.update(index.to_be_bytes());
+    hasher.update(message);
+    for (j, cap_d, cap_e) in commitments {
+        hasher.update(j.to_be_bytes());
+        hasher.update(cap_d.compress().to_bytes());
+        hasher.update(cap_e.compress().to_bytes());
+    }
+    Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+}
+
+/// The Ed25519 challenge `c = SHA512(R || A || M) mod L`, computed exactly
+/// the way a plain (non-threshold) Ed25519 signature would, so the
+/// aggregated `(R, z)` this module produces verifies under any standard
+/// Ed25519 verifier against `group_public_key` without it needing to know
+/// the signature was produced by FROST at all.
+fn schnorr_challenge(r: &EdwardsPoint, group_public_key: &EdwardsPoint, message: &[u8]) -> Scalar {
+    let mut hasher = Sha512::new();
+    hasher.update(r.compress().to_bytes());
+    hasher.update(group_public_key.compress().to_bytes());
+    hasher.update(message);
+    Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+}
+
+#[async_trait]
+impl Signer for FrostCoordinator {
+    async fn sign(&self, data: &[u8]) -> Result<Signature, SignerError> {
+        self.sign_threshold(data)
+    }
+
+    async fn public_key(&self) -> Result<PublicKey, SignerError> {
+        Ok(PublicKey {
+            algorithm: "ed25519".to_string(),
+            spki_bytes: self.public_key_der()?,
+        })
+    }
+
+    fn signer_info(&self) -> SignerInfo {
+        let mut metadata = HashMap::new();
+        metadata.insert("key_type".to_string(), "ed25519".to_string());
+        metadata.insert("implementation".to_string(), "frost".to_string());
+        metadata.insert("threshold".to_string(), self.threshold.to_string());
+        metadata.insert("participants".to_string(), self.participants.to_string());
+
+        SignerInfo {
+            signer_type: "frost".to_string(),
+            algorithm: "ed25519".to_string(),
+            metadata,
+        }
+    }
+
+    fn supported_algorithms(&self) -> Vec<&'static str> {
+        vec!["Ed25519"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_two_of_three_signature_verifies_against_the_group_key() {
+        let coordinator = FrostCoordinator::generate(2, 3).expect("valid frost parameters");
+        let data = b"threshold-signed transaction";
+
+        let signature = coordinator.sign(data).await.expect("signing should succeed");
+        let public_key = coordinator.public_key().await.expect("public key should be derivable");
+
+        assert_eq!(signature.bytes.len(), 64);
+        assert!(super::super::verify_bundle(
+            data,
+            &super::super::SignatureBundle {
+                payload_hash: sha2::Sha256::digest(data).to_vec(),
+                payload_hash_algorithm: "sha256".to_string(),
+                signature,
+                signer_info: coordinator.signer_info(),
+                public_key,
+                attestation: None,
+            },
+        )
+        .await
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_signature_rejects_a_tampered_message() {
+        let coordinator = FrostCoordinator::generate(3, 5).expect("valid frost parameters");
+        let public_key = coordinator.public_key().await.expect("public key should be derivable");
+        let signature = coordinator.sign(b"original").await.expect("signing should succeed");
+
+        let bundle = super::super::SignatureBundle {
+            payload_hash: sha2::Sha256::digest(b"original").to_vec(),
+            payload_hash_algorithm: "sha256".to_string(),
+            signature,
+            signer_info: coordinator.signer_info(),
+            public_key,
+            attestation: None,
+        };
+        assert!(super::super::verify_bundle(b"tampered", &bundle).await.is_err());
+    }
+
+    #[test]
+    fn test_generate_rejects_a_zero_threshold() {
+        match FrostCoordinator::generate(0, 3) {
+            Err(SignerError::Config(msg)) => assert!(msg.contains("at least 1")),
+            other => panic!("expected a Config error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_generate_rejects_a_threshold_above_participant_count() {
+        match FrostCoordinator::generate(4, 3) {
+            Err(SignerError::Config(msg)) => assert!(msg.contains("exceeds participant count")),
+            other => panic!("expected a Config error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_lagrange_coefficients_reconstruct_the_secret() {
+        // Sanity-check the interpolation helper directly: for a degree-1
+        // polynomial with f(0) = secret, any two shares should reconstruct
+        // it via their Lagrange coefficients.
+        let mut rng = rand::rngs::OsRng;
+        let secret = random_scalar(&mut rng);
+        let a1 = random_scalar(&mut rng);
+        let coefficients = [secret, a1];
+
+        let active = [1u16, 2u16];
+        let s1 = evaluate_polynomial(&coefficients, Scalar::from(1u64));
+        let s2 = evaluate_polynomial(&coefficients, Scalar::from(2u64));
+
+        let reconstructed =
+            s1 * lagrange_coefficient(1, &active) + s2 * lagrange_coefficient(2, &active);
+        assert_eq!(reconstructed, secret);
+    }
+}
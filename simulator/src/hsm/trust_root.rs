@@ -0,0 +1,522 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! A minimal TUF (The Update Framework) client used to fetch and verify an
+//! auditable, rotatable trust root for the Sigstore backend, rather than
+//! pinning Fulcio/Rekor key material as hardcoded constants.
+//!
+//! [`TrustRoot::from_cdn`] walks the standard TUF client workflow: starting
+//! from `1.root.json`, it climbs root versions (N -> N+1) so that key
+//! rotation performed by the repository operator is honored, then verifies
+//! `timestamp.json`, `snapshot.json`, and `targets.json` in order, checking
+//! each role's signature threshold and expiration, and that the snapshot
+//! and targets versions equal the versions listed for them in the
+//! timestamp and snapshot metadata, before trusting a target's declared
+//! hash and length. Root versions must strictly increase while climbing;
+//! the timestamp version is not compared against anything, and no version
+//! is compared with metadata from an earlier run. Downloaded target
+//! files (e.g. `rekor.pub`, `fulcio.crt.pem`) are only handed back to the
+//! caller after their bytes are checked against that declared hash/length.
+//!
+//! This implements the parts of the TUF client workflow relevant to
+//! consuming a handful of pinned targets; it does not implement delegations
+//! or consistent snapshots.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Errors that can occur while fetching or verifying TUF trust material.
+#[derive(Debug, thiserror::Error)]
+pub enum TufError {
+    #[error("Failed to fetch {0}: {1}")]
+    Fetch(String, String),
+
+    #[error("Failed to parse {0}: {1}")]
+    Parse(String, String),
+
+    #[error("Signature threshold not met for {0}: needed {1}, got {2} valid signatures")]
+    ThresholdNotMet(String, u32, u32),
+
+    #[error("{0} has expired (expires {1})")]
+    Expired(String, String),
+
+    #[error("{0} version did not advance: trusted {1}, fetched {2}")]
+    VersionNotMonotonic(String, u64, u64),
+
+    #[error("{0} failed hash/length verification")]
+    TargetMismatch(String),
+
+    #[error("Unknown target: {0}")]
+    UnknownTarget(String),
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct TufKeyValue {
+    public: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct TufKey {
+    keytype: String,
+    keyval: TufKeyValue,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct TufRole {
+    keyids: Vec<String>,
+    threshold: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct RootSigned {
+    #[serde(rename = "_type")]
+    #[allow(dead_code)]
+    typ: String,
+    version: u64,
+    expires: String,
+    keys: HashMap<String, TufKey>,
+    roles: HashMap<String, TufRole>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct MetaFileInfo {
+    version: u64,
+    #[serde(default)]
+    length: Option<u64>,
+    #[serde(default)]
+    hashes: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct TimestampSigned {
+    #[serde(rename = "_type")]
+    #[allow(dead_code)]
+    typ: String,
+    version: u64,
+    expires: String,
+    meta: HashMap<String, MetaFileInfo>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct SnapshotSigned {
+    #[serde(rename = "_type")]
+    #[allow(dead_code)]
+    typ: String,
+    version: u64,
+    expires: String,
+    meta: HashMap<String, MetaFileInfo>,
+}
+
+/// Declared hash/length of one target file, as listed in `targets.json`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TargetFileInfo {
+    pub length: u64,
+    pub hashes: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct TargetsSigned {
+    #[serde(rename = "_type")]
+    #[allow(dead_code)]
+    typ: String,
+    version: u64,
+    expires: String,
+    targets: HashMap<String, TargetFileInfo>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct TufSignature {
+    keyid: String,
+    sig: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct Signed<T> {
+    signed: T,
+    signatures: Vec<TufSignature>,
+}
+
+/// Recursively sort object keys and serialize to compact JSON bytes, giving
+/// a deterministic encoding of `value` to verify signatures over (TUF
+/// signs a canonical encoding of the `signed` field, not the raw file
+/// bytes).
+fn canonicalize(value: &serde_json::Value) -> Vec<u8> {
+    fn sorted(value: &serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => {
+                let mut sorted_map = serde_json::Map::new();
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                for key in keys {
+                    sorted_map.insert(key.clone(), sorted(&map[key]));
+                }
+                serde_json::Value::Object(sorted_map)
+            }
+            serde_json::Value::Array(items) => {
+                serde_json::Value::Array(items.iter().map(sorted).collect())
+            }
+            other => other.clone(),
+        }
+    }
+    serde_json::to_vec(&sorted(value)).expect("canonicalized JSON value always serializes")
+}
+
+/// Verify that `signed` (re-serialized canonically) carries signatures from
+/// at least `role.threshold` of the keys in `role.keyids`, each resolved
+/// against `keys`. Unknown key ids and malformed signatures are skipped
+/// rather than treated as a hard error, matching how extra/untrusted
+/// signers are ignored in the TUF spec.
+fn verify_threshold<T: Serialize>(
+    file_name: &str,
+    signed: &T,
+    signatures: &[TufSignature],
+    role: &TufRole,
+    keys: &HashMap<String, TufKey>,
+) -> Result<(), TufError> {
+    let canonical = canonicalize(&serde_json::to_value(signed).expect("signed role always serializes"));
+
+    let mut valid = 0u32;
+    let mut counted_keyids: std::collections::HashSet<&str> = std::collections::HashSet::new();
+    for signature in signatures {
+        if !role.keyids.contains(&signature.keyid) {
+            continue;
+        }
+        let Some(key) = keys.get(&signature.keyid) else {
+            continue;
+        };
+        if key.keytype != "ed25519" {
+            continue;
+        }
+        let Ok(key_bytes) = hex::decode(&key.keyval.public) else {
+            continue;
+        };
+        let Ok(verifying_key) = ed25519_dalek::VerifyingKey::from_bytes(
+            &match <[u8; 32]>::try_from(key_bytes.as_slice()) {
+                Ok(bytes) => bytes,
+                Err(_) => continue,
+            },
+        ) else {
+            continue;
+        };
+        let Ok(sig_bytes) = hex::decode(&signature.sig) else {
+            continue;
+        };
+        let Ok(sig_array) = <[u8; 64]>::try_from(sig_bytes.as_slice()) else {
+            continue;
+        };
+        let ed_signature = ed25519_dalek::Signature::from_bytes(&sig_array);
+
+        use ed25519_dalek::Verifier;
+        if verifying_key.verify(&canonical, &ed_signature).is_ok()
+            && counted_keyids.insert(signature.keyid.as_str())
+        {
+            valid += 1;
+        }
+    }
+
+    if valid < role.threshold {
+        return Err(TufError::ThresholdNotMet(
+            file_name.to_string(),
+            role.threshold,
+            valid,
+        ));
+    }
+    Ok(())
+}
+
+fn check_not_expired(file_name: &str, expires: &str) -> Result<(), TufError> {
+    let expires_at = chrono::DateTime::parse_from_rfc3339(expires)
+        .map_err(|e| TufError::Parse(file_name.to_string(), e.to_string()))?;
+    if expires_at < chrono::Utc::now() {
+        return Err(TufError::Expired(file_name.to_string(), expires.to_string()));
+    }
+    Ok(())
+}
+
+fn check_hash_and_length(file_name: &str, bytes: &[u8], info: &MetaFileInfo) -> Result<(), TufError> {
+    if let Some(length) = info.length {
+        if bytes.len() as u64 != length {
+            return Err(TufError::TargetMismatch(file_name.to_string()));
+        }
+    }
+    if let Some(hashes) = &info.hashes {
+        if let Some(expected_sha256) = hashes.get("sha256") {
+            let actual = hex::encode(Sha256::digest(bytes));
+            if &actual != expected_sha256 {
+                return Err(TufError::TargetMismatch(file_name.to_string()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A verified TUF trust root: the final `root.json` roles/keys reached
+/// after climbing any available root rotations, and the set of targets
+/// (e.g. `rekor.pub`, `fulcio.crt.pem`) listed in the verified
+/// `targets.json`.
+pub struct TrustRoot {
+    base_url: String,
+    root: RootSigned,
+    targets: HashMap<String, TargetFileInfo>,
+    client: reqwest::Client,
+}
+
+impl TrustRoot {
+    /// Fetch and verify a TUF trust root from a CDN serving `root.json`,
+    /// `timestamp.json`, `snapshot.json`, `targets.json`, and the target
+    /// files they describe at `base_url`.
+    pub async fn from_cdn(base_url: &str) -> Result<Self, TufError> {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        let client = reqwest::Client::new();
+
+        let root = Self::climb_root_versions(&client, &base_url).await?;
+
+        let timestamp = Self::fetch_signed::<TimestampSigned>(&client, &base_url, "timestamp.json", &root, None)
+            .await?;
+        check_not_expired("timestamp.json", &timestamp.expires)?;
+
+        let snapshot_meta = timestamp
+            .meta
+            .get("snapshot.json")
+            .ok_or_else(|| TufError::Parse("timestamp.json".to_string(), "missing snapshot.json meta".to_string()))?;
+
+        let snapshot =
+            Self::fetch_signed::<SnapshotSigned>(&client, &base_url, "snapshot.json", &root, Some(snapshot_meta))
+                .await?;
+        check_not_expired("snapshot.json", &snapshot.expires)?;
+        if snapshot.version != snapshot_meta.version {
+            return Err(TufError::VersionNotMonotonic(
+                "snapshot.json".to_string(),
+                snapshot_meta.version,
+                snapshot.version,
+            ));
+        }
+
+        let targets_meta = snapshot
+            .meta
+            .get("targets.json")
+            .ok_or_else(|| TufError::Parse("snapshot.json".to_string(), "missing targets.json meta".to_string()))?;
+
+        let targets_bytes = Self::fetch_bytes(&client, &base_url, "targets.json").await?;
+        check_hash_and_length("targets.json", &targets_bytes, targets_meta)?;
+        let targets_envelope: Signed<TargetsSigned> = serde_json::from_slice(&targets_bytes)
+            .map_err(|e| TufError::Parse("targets.json".to_string(), e.to_string()))?;
+
+        let targets_role = root
+            .roles
+            .get("targets")
+            .ok_or_else(|| TufError::Parse("root.json".to_string(), "missing targets role".to_string()))?;
+        verify_threshold(
+            "targets.json",
+            &targets_envelope.signed,
+            &targets_envelope.signatures,
+            targets_role,
+            &root.keys,
+        )?;
+        check_not_expired("targets.json", &targets_envelope.signed.expires)?;
+        if targets_envelope.signed.version != targets_meta.version {
+            return Err(TufError::VersionNotMonotonic(
+                "targets.json".to_string(),
+                targets_meta.version,
+                targets_envelope.signed.version,
+            ));
+        }
+
+        Ok(Self {
+            base_url,
+            root,
+            targets: targets_envelope.signed.targets,
+            client,
+        })
+    }
+
+    /// Starting from `1.root.json`, fetch and verify each successive root
+    /// version as long as it's signed by a threshold of the *currently
+    /// trusted* root's keys, so that rotating Fulcio/Rekor's signing keys
+    /// is honored without re-bootstrapping trust out of band.
+    async fn climb_root_versions(client: &reqwest::Client, base_url: &str) -> Result<RootSigned, TufError> {
+        let mut version = 1u64;
+        let mut trusted_root: Option<RootSigned> = None;
+
+        loop {
+            let file_name = format!("{}.root.json", version);
+            let bytes = match Self::fetch_bytes(client, base_url, &file_name).await {
+                Ok(bytes) => bytes,
+                Err(_) if trusted_root.is_some() => break,
+                Err(e) => return Err(e),
+            };
+
+            let envelope: Signed<RootSigned> = serde_json::from_slice(&bytes)
+                .map_err(|e| TufError::Parse(file_name.clone(), e.to_string()))?;
+
+            let root_role = match &trusted_root {
+                // Trust-on-first-use for the initial root: nothing earlier
+                // to check its signatures against.
+                None => envelope
+                    .signed
+                    .roles
+                    .get("root")
+                    .ok_or_else(|| TufError::Parse(file_name.clone(), "missing root role".to_string()))?
+                    .clone(),
+                Some(previous) => previous
+                    .roles
+                    .get("root")
+                    .ok_or_else(|| TufError::Parse(file_name.clone(), "missing root role".to_string()))?
+                    .clone(),
+            };
+            let keys = match &trusted_root {
+                None => &envelope.signed.keys,
+                Some(previous) => &previous.keys,
+            };
+            verify_threshold(&file_name, &envelope.signed, &envelope.signatures, &root_role, keys)?;
+            check_not_expired(&file_name, &envelope.signed.expires)?;
+
+            if let Some(previous) = &trusted_root {
+                if envelope.signed.version <= previous.version {
+                    return Err(TufError::VersionNotMonotonic(
+                        file_name,
+                        previous.version,
+                        envelope.signed.version,
+                    ));
+                }
+            }
+
+            trusted_root = Some(envelope.signed);
+            version += 1;
+        }
+
+        trusted_root.ok_or_else(|| TufError::Fetch("1.root.json".to_string(), "no root versions found".to_string()))
+    }
+
+    async fn fetch_bytes(client: &reqwest::Client, base_url: &str, file_name: &str) -> Result<Vec<u8>, TufError> {
+        let response = client
+            .get(format!("{}/{}", base_url, file_name))
+            .send()
+            .await
+            .map_err(|e| TufError::Fetch(file_name.to_string(), e.to_string()))?
+            .error_for_status()
+            .map_err(|e| TufError::Fetch(file_name.to_string(), e.to_string()))?;
+        response
+            .bytes()
+            .await
+            .map(|b| b.to_vec())
+            .map_err(|e| TufError::Fetch(file_name.to_string(), e.to_string()))
+    }
+
+    async fn fetch_signed<T: for<'de> Deserialize<'de> + Serialize>(
+        client: &reqwest::Client,
+        base_url: &str,
+        file_name: &str,
+        root: &RootSigned,
+        expected_meta: Option<&MetaFileInfo>,
+    ) -> Result<T, TufError>
+    where
+        T: RoleName,
+    {
+        let bytes = Self::fetch_bytes(client, base_url, file_name).await?;
+        if let Some(expected_meta) = expected_meta {
+            check_hash_and_length(file_name, &bytes, expected_meta)?;
+        }
+        let envelope: Signed<T> = serde_json::from_slice(&bytes)
+            .map_err(|e| TufError::Parse(file_name.to_string(), e.to_string()))?;
+        let role = root
+            .roles
+            .get(T::ROLE_NAME)
+            .ok_or_else(|| TufError::Parse("root.json".to_string(), format!("missing {} role", T::ROLE_NAME)))?;
+        verify_threshold(file_name, &envelope.signed, &envelope.signatures, role, &root.keys)?;
+        Ok(envelope.signed)
+    }
+
+    /// Fetch a target listed in the verified `targets.json`, checking its
+    /// bytes against the declared hash and length before returning it.
+    pub async fn fetch_target(&self, name: &str) -> Result<Vec<u8>, TufError> {
+        let info = self
+            .targets
+            .get(name)
+            .ok_or_else(|| TufError::UnknownTarget(name.to_string()))?;
+        let bytes = Self::fetch_bytes(&self.client, &self.base_url, name).await?;
+        let declared = MetaFileInfo {
+            version: 0,
+            length: Some(info.length),
+            hashes: Some(info.hashes.clone()),
+        };
+        check_hash_and_length(name, &bytes, &declared)?;
+        Ok(bytes)
+    }
+
+    /// Names of every target listed in the verified `targets.json`.
+    pub fn target_names(&self) -> Vec<&str> {
+        self.targets.keys().map(|s| s.as_str()).collect()
+    }
+
+    /// Version of the root metadata this trust root was built from, after
+    /// climbing any available rotations.
+    pub fn root_version(&self) -> u64 {
+        self.root.version
+    }
+}
+
+trait RoleName {
+    const ROLE_NAME: &'static str;
+}
+
+impl RoleName for TimestampSigned {
+    const ROLE_NAME: &'static str = "timestamp";
+}
+
+impl RoleName for SnapshotSigned {
+    const ROLE_NAME: &'static str = "snapshot";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonicalize_sorts_object_keys_recursively() {
+        let a = serde_json::json!({"b": 1, "a": {"d": 2, "c": 3}});
+        let b = serde_json::json!({"a": {"c": 3, "d": 2}, "b": 1});
+        assert_eq!(canonicalize(&a), canonicalize(&b));
+    }
+
+    #[test]
+    fn test_canonicalize_is_sensitive_to_value_changes() {
+        let a = serde_json::json!({"a": 1});
+        let b = serde_json::json!({"a": 2});
+        assert_ne!(canonicalize(&a), canonicalize(&b));
+    }
+
+    #[test]
+    fn test_verify_threshold_fails_below_threshold() {
+        let role = TufRole {
+            keyids: vec!["key1".to_string()],
+            threshold: 1,
+        };
+        let keys = HashMap::new();
+        let signed = serde_json::json!({"version": 1});
+
+        let result = verify_threshold("root.json", &signed, &[], &role, &keys);
+        assert!(matches!(result, Err(TufError::ThresholdNotMet(_, 1, 0))));
+    }
+
+    #[test]
+    fn test_check_hash_and_length_rejects_tampered_bytes() {
+        let info = MetaFileInfo {
+            version: 1,
+            length: Some(3),
+            hashes: Some(HashMap::from([(
+                "sha256".to_string(),
+                hex::encode(Sha256::digest(b"abc")),
+            )])),
+        };
+        assert!(check_hash_and_length("targets.json", b"abc", &info).is_ok());
+        assert!(check_hash_and_length("targets.json", b"xyz", &info).is_err());
+    }
+
+    #[test]
+    fn test_check_not_expired_rejects_past_timestamp() {
+        assert!(check_not_expired("root.json", "2000-01-01T00:00:00Z").is_err());
+        assert!(check_not_expired("root.json", "2999-01-01T00:00:00Z").is_ok());
+    }
+}
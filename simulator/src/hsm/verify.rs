@@ -0,0 +1,285 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! A first-class offline verification API, so callers don't have to leak
+//! curve assumptions (slicing a raw Ed25519 key out of `spki_bytes` by
+//! offset, say) into every place a signature needs checking.
+//!
+//! [`verify_with_public_key`] is the single place that dispatches on a
+//! [`PublicKey`]'s algorithm tag and does the actual cryptographic check;
+//! [`verify_bundle`](super::verify_bundle) uses it directly, and
+//! [`Keyring`] builds on it to answer "which of my trusted keys signed
+//! this?" without the caller needing to track key-to-algorithm mappings
+//! itself.
+
+use super::{PublicKey, Signature, SignerError};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Verify `signature` over `data` against `public_key`, dispatching on
+/// [`PublicKey::algorithm`]. Shared by [`super::verify_bundle`] and
+/// [`Keyring::verify`] so the per-curve parsing/verification logic lives in
+/// exactly one place.
+pub(crate) fn verify_with_public_key(
+    data: &[u8],
+    public_key: &PublicKey,
+    signature: &Signature,
+) -> Result<(), SignerError> {
+    match public_key.algorithm.as_str() {
+        "ed25519" => {
+            use ed25519_dalek::pkcs8::DecodePublicKey;
+            use ed25519_dalek::Verifier as EdVerifier;
+
+            let verifying_key = ed25519_dalek::VerifyingKey::from_public_key_der(&public_key.spki_bytes)
+                .map_err(|e| SignerError::Crypto(format!("Failed to parse public key: {}", e)))?;
+            let raw: [u8; 64] = signature.bytes.clone().try_into().map_err(|_| {
+                SignerError::InvalidSignature("ed25519 signature must be 64 bytes".to_string())
+            })?;
+            let sig = ed25519_dalek::Signature::from_bytes(&raw);
+
+            verifying_key
+                .verify(data, &sig)
+                .map_err(|e| SignerError::InvalidSignature(format!("Signature verification failed: {}", e)))
+        }
+        "secp256k1" => {
+            use k256::ecdsa::signature::Verifier as EcdsaVerifier;
+            use k256::pkcs8::DecodePublicKey;
+
+            let verifying_key = k256::ecdsa::VerifyingKey::from_public_key_der(&public_key.spki_bytes)
+                .map_err(|e| SignerError::Crypto(format!("Failed to parse public key: {}", e)))?;
+
+            // Accept both the raw 64-byte `r || s` signature `sign()`
+            // produces and the 65-byte recoverable form `sign_recoverable()`
+            // produces; the trailing recovery byte is irrelevant here.
+            let sig_bytes = match signature.bytes.len() {
+                64 => &signature.bytes[..],
+                65 => &signature.bytes[..64],
+                other => {
+                    return Err(SignerError::InvalidSignature(format!(
+                        "secp256k1 signature must be 64 or 65 bytes, got {}",
+                        other
+                    )))
+                }
+            };
+            let sig = k256::ecdsa::Signature::from_slice(sig_bytes)
+                .map_err(|e| SignerError::InvalidSignature(format!("Invalid signature encoding: {}", e)))?;
+
+            verifying_key
+                .verify(data, &sig)
+                .map_err(|e| SignerError::InvalidSignature(format!("Signature verification failed: {}", e)))
+        }
+        "secp256r1" => {
+            use p256::ecdsa::signature::Verifier as EcdsaVerifier;
+            use p256::pkcs8::DecodePublicKey;
+
+            let verifying_key = p256::ecdsa::VerifyingKey::from_public_key_der(&public_key.spki_bytes)
+                .map_err(|e| SignerError::Crypto(format!("Failed to parse public key: {}", e)))?;
+            let sig = p256::ecdsa::Signature::from_slice(&signature.bytes)
+                .map_err(|e| SignerError::InvalidSignature(format!("Invalid signature encoding: {}", e)))?;
+
+            verifying_key
+                .verify(data, &sig)
+                .map_err(|e| SignerError::InvalidSignature(format!("Signature verification failed: {}", e)))
+        }
+        other => Err(SignerError::InvalidSignature(format!(
+            "verify_with_public_key supports ed25519, secp256k1, and secp256r1 public keys only, got {}",
+            other
+        ))),
+    }
+}
+
+/// Identifies a key within a [`Keyring`]: the hex-encoded SHA-256
+/// fingerprint of its DER `SubjectPublicKeyInfo` bytes.
+pub type KeyId = String;
+
+/// Errors from [`Keyring::add_spki_der`] and [`Keyring::verify`].
+#[derive(Debug, thiserror::Error)]
+pub enum VerifyError {
+    /// The supplied bytes didn't parse as a `SubjectPublicKeyInfo` under
+    /// any algorithm [`Keyring`] recognizes (Ed25519, ECDSA P-256, or
+    /// secp256k1).
+    #[error("unrecognized SubjectPublicKeyInfo DER: {0}")]
+    UnrecognizedKey(String),
+
+    /// `signature` didn't verify against any key currently in the keyring.
+    #[error("signature did not verify against any trusted key")]
+    NoMatchingKey,
+
+    /// A [`SignerError`] converted into this error type. [`Keyring::verify`]
+    /// does not return it: a key that fails for any reason, malformed
+    /// signature bytes included, counts as not matching.
+    #[error(transparent)]
+    Signer(#[from] SignerError),
+}
+
+/// A set of trusted public keys, parsed from real DER `SubjectPublicKeyInfo`
+/// bytes rather than assumed to be a particular curve, that can answer
+/// "which of my keys (if any) produced this signature?" in one call.
+///
+/// This exists so that verifying against a set of trusted keys doesn't
+/// require the caller to already know which curve each key uses or to slice
+/// raw key material out of `spki_bytes` by a hardcoded offset; [`Self::add_spki_der`]
+/// determines the algorithm by attempting to parse the DER under each
+/// supported algorithm in turn, the same way a real SPKI parser would use
+/// the embedded `AlgorithmIdentifier` OID, and [`Self::verify`] tries every
+/// trusted key until one matches.
+#[derive(Debug, Default)]
+pub struct Keyring {
+    keys: HashMap<KeyId, PublicKey>,
+}
+
+impl Keyring {
+    /// An empty keyring.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The hex-encoded SHA-256 fingerprint of a DER `SubjectPublicKeyInfo`,
+    /// used as this keyring's [`KeyId`].
+    pub fn fingerprint(spki_der: &[u8]) -> KeyId {
+        hex::encode(Sha256::digest(spki_der))
+    }
+
+    /// Parse `spki_der` as a `SubjectPublicKeyInfo`, determine its algorithm
+    /// by trying Ed25519, then secp256k1, then secp256r1 (each rejects DER
+    /// whose `AlgorithmIdentifier` doesn't match its own OID and curve
+    /// parameters), add it as a trusted key, and return its [`KeyId`].
+    pub fn add_spki_der(&mut self, spki_der: &[u8]) -> Result<KeyId, VerifyError> {
+        let algorithm = identify_spki_algorithm(spki_der)?;
+        let key_id = Self::fingerprint(spki_der);
+        self.keys.insert(
+            key_id.clone(),
+            PublicKey {
+                algorithm: algorithm.to_string(),
+                spki_bytes: spki_der.to_vec(),
+            },
+        );
+        Ok(key_id)
+    }
+
+    /// Add an already-classified [`PublicKey`] (e.g. one returned by
+    /// [`super::Signer::public_key`]) and return its [`KeyId`].
+    pub fn add_key(&mut self, public_key: PublicKey) -> KeyId {
+        let key_id = Self::fingerprint(&public_key.spki_bytes);
+        self.keys.insert(key_id.clone(), public_key);
+        key_id
+    }
+
+    /// The number of trusted keys in this keyring.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether this keyring has no trusted keys.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Verify `signature` over `data` against every trusted key, returning
+    /// the [`KeyId`] of the first one that matches. Trying every key (rather
+    /// than requiring the caller to name one up front) is what lets this
+    /// double as "which of my keys signed this" rather than just "did this
+    /// specific key sign this".
+    pub fn verify(&self, data: &[u8], signature: &Signature) -> Result<KeyId, VerifyError> {
+        for (key_id, public_key) in &self.keys {
+            if verify_with_public_key(data, public_key, signature).is_ok() {
+                return Ok(key_id.clone());
+            }
+        }
+        Err(VerifyError::NoMatchingKey)
+    }
+}
+
+/// Determine which algorithm a DER `SubjectPublicKeyInfo` encodes by
+/// attempting to decode it under each algorithm [`Keyring`] supports in
+/// turn. Each decoder validates its own `AlgorithmIdentifier` OID (and, for
+/// the two EC curves, the `namedCurve` parameter), so a secp256k1 key
+/// correctly fails to parse as secp256r1 and vice versa.
+fn identify_spki_algorithm(spki_der: &[u8]) -> Result<&'static str, VerifyError> {
+    use ed25519_dalek::pkcs8::DecodePublicKey as _;
+    if ed25519_dalek::VerifyingKey::from_public_key_der(spki_der).is_ok() {
+        return Ok("ed25519");
+    }
+
+    use k256::pkcs8::DecodePublicKey as _;
+    if k256::ecdsa::VerifyingKey::from_public_key_der(spki_der).is_ok() {
+        return Ok("secp256k1");
+    }
+
+    use p256::pkcs8::DecodePublicKey as _;
+    if p256::ecdsa::VerifyingKey::from_public_key_der(spki_der).is_ok() {
+        return Ok("secp256r1");
+    }
+
+    Err(VerifyError::UnrecognizedKey(
+        "DER did not parse as an Ed25519, secp256k1, or secp256r1 SubjectPublicKeyInfo".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hsm::software::{P256SoftwareSigner, Secp256k1SoftwareSigner, SoftwareSigner};
+    use crate::hsm::Signer;
+
+    #[tokio::test]
+    async fn test_keyring_identifies_the_matching_key_across_algorithms() {
+        let (ed25519_signer, _) = SoftwareSigner::generate().unwrap();
+        let (secp256k1_signer, _) = Secp256k1SoftwareSigner::generate().unwrap();
+        let (p256_signer, _) = P256SoftwareSigner::generate().unwrap();
+
+        let mut keyring = Keyring::new();
+        let ed25519_id = keyring.add_spki_der(&ed25519_signer.public_key().await.unwrap().spki_bytes).unwrap();
+        let secp256k1_id = keyring.add_spki_der(&secp256k1_signer.public_key().await.unwrap().spki_bytes).unwrap();
+        let p256_id = keyring.add_spki_der(&p256_signer.public_key().await.unwrap().spki_bytes).unwrap();
+
+        assert_eq!(keyring.len(), 3);
+
+        let data = b"order #42: transfer 100 XLM";
+        let ed25519_sig = ed25519_signer.sign(data).await.unwrap();
+        let secp256k1_sig = secp256k1_signer.sign(data).await.unwrap();
+        let p256_sig = p256_signer.sign(data).await.unwrap();
+
+        assert_eq!(keyring.verify(data, &ed25519_sig).unwrap(), ed25519_id);
+        assert_eq!(keyring.verify(data, &secp256k1_sig).unwrap(), secp256k1_id);
+        assert_eq!(keyring.verify(data, &p256_sig).unwrap(), p256_id);
+    }
+
+    #[tokio::test]
+    async fn test_keyring_rejects_a_signature_from_an_untrusted_key() {
+        let (trusted, _) = SoftwareSigner::generate().unwrap();
+        let (untrusted, _) = SoftwareSigner::generate().unwrap();
+
+        let mut keyring = Keyring::new();
+        keyring.add_spki_der(&trusted.public_key().await.unwrap().spki_bytes).unwrap();
+
+        let data = b"data";
+        let signature = untrusted.sign(data).await.unwrap();
+
+        assert!(matches!(keyring.verify(data, &signature), Err(VerifyError::NoMatchingKey)));
+    }
+
+    #[tokio::test]
+    async fn test_keyring_rejects_a_tampered_message() {
+        let (signer, _) = SoftwareSigner::generate().unwrap();
+        let mut keyring = Keyring::new();
+        let key_id = keyring.add_spki_der(&signer.public_key().await.unwrap().spki_bytes).unwrap();
+
+        let signature = signer.sign(b"original").await.unwrap();
+        assert_eq!(keyring.verify(b"original", &signature).unwrap(), key_id);
+        assert!(matches!(keyring.verify(b"tampered", &signature), Err(VerifyError::NoMatchingKey)));
+    }
+
+    #[test]
+    fn test_add_spki_der_rejects_garbage_bytes() {
+        let mut keyring = Keyring::new();
+        let err = keyring.add_spki_der(b"not a SubjectPublicKeyInfo").unwrap_err();
+        assert!(matches!(err, VerifyError::UnrecognizedKey(_)));
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable_for_identical_bytes() {
+        let der = b"some bytes that are not really a key";
+        assert_eq!(Keyring::fingerprint(der), Keyring::fingerprint(der));
+    }
+}
@@ -24,10 +24,50 @@
 //
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine as _;
 use jsonschema::JSONSchema;
 use serde_json::Value;
+use soroban_env_host::xdr::{Limits, ReadXdr, TransactionEnvelope};
 
-/// Validates JSON input against the simulation-request.schema.json
+/// Base64 variants tried, in order, when decoding the `xdr` field.
+const XDR_VARIANTS: &[&str] = &[
+    "BASE64",
+    "BASE64URL",
+    "BASE64_NOPAD",
+    "BASE64URL_NOPAD",
+    "BASE64_MIME",
+];
+
+/// Decode `raw` trying each known base64 flavor until one yields a
+/// well-formed transaction envelope. Mirrors the `Xdr` newtype used on the
+/// schema side, but works directly against the validated `Value` here
+/// since `validate_request` deals in raw JSON rather than the typed schema.
+fn decode_xdr_tolerant(raw: &str) -> Result<Vec<u8>, String> {
+    let mime_input: String = raw.chars().filter(|c| !c.is_whitespace()).collect();
+    let attempts: [Option<Vec<u8>>; 5] = [
+        STANDARD.decode(raw).ok(),
+        URL_SAFE.decode(raw).ok(),
+        STANDARD_NO_PAD.decode(raw).ok(),
+        URL_SAFE_NO_PAD.decode(raw).ok(),
+        STANDARD.decode(&mime_input).ok(),
+    ];
+
+    for bytes in attempts.into_iter().flatten() {
+        if TransactionEnvelope::from_xdr(bytes.clone(), Limits::none()).is_ok() {
+            return Ok(bytes);
+        }
+    }
+
+    Err(format!(
+        "xdr field is not a valid transaction envelope in any known encoding (tried {})",
+        XDR_VARIANTS.join(", ")
+    ))
+}
+
+/// Validates JSON input against the simulation-request.schema.json and,
+/// if an `xdr` field is present, decodes it tolerantly so schema
+/// validation and envelope parsing happen together.
 pub fn validate_request(input: &str) -> Result<Value, String> {
     // include the schema at compile-time
     let schema_json = include_str!("../../../docs/schema/simulation-request.schema.json");
@@ -42,5 +82,9 @@ pub fn validate_request(input: &str) -> Result<Value, String> {
         .validate(&instance)
         .map_err(|errors| errors.map(|e| e.to_string()).collect::<Vec<_>>().join(", "))?;
 
+    if let Some(xdr_field) = instance.get("xdr").and_then(Value::as_str) {
+        decode_xdr_tolerant(xdr_field)?;
+    }
+
     Ok(instance)
 }
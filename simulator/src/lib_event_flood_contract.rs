@@ -0,0 +1,25 @@
+//! Intentionally faulty contract: triggers event-emission budget exhaustion.
+//!
+//! The Soroban host meters the number and size of diagnostic/contract events
+//! emitted per invocation. This contract emits one event per iteration until
+//! the host's event budget is exhausted. It is used exclusively by the
+//! simulator safety test-suite.
+
+#![no_std]
+
+use soroban_sdk::{contract, contractimpl, Env, Symbol};
+
+#[contract]
+pub struct EventFloodContract;
+
+#[contractimpl]
+impl EventFloodContract {
+    /// Emits `iterations` events, each carrying its index as data, to
+    /// exhaust the host event budget.
+    pub fn run(env: Env, iterations: u32) {
+        for i in 0..iterations {
+            env.events()
+                .publish((Symbol::new(&env, "flood"),), i);
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! Intentionally faulty contract: triggers storage-entry budget exhaustion.
+//!
+//! The Soroban host limits the number and size of ledger entries a single
+//! invocation may write. This contract writes a growing number of distinct
+//! instance-storage keys until the host refuses further writes. It is used
+//! exclusively by the simulator safety test-suite.
+
+#![no_std]
+
+use soroban_sdk::{contract, contractimpl, Env, Symbol};
+
+#[contract]
+pub struct StorageBlowupContract;
+
+#[contractimpl]
+impl StorageBlowupContract {
+    /// Writes `iterations` distinct instance-storage entries, each keyed by
+    /// its index, to exhaust the host's storage-entry budget.
+    pub fn run(env: Env, iterations: u32) {
+        for i in 0..iterations {
+            let key = Symbol::new(&env, "entry");
+            env.storage().instance().set(&(key, i), &i);
+        }
+    }
+}
@@ -4,16 +4,31 @@
 #![allow(warnings, clippy::all, clippy::pedantic, clippy::nursery)]
 
 mod config;
+mod cost_schedule;
+mod deploy;
+mod error_classifier;
+mod fault_suite;
+mod fee_market;
+mod formatters;
+mod fuzzer;
 mod gas_optimizer;
+mod preflight;
+mod remote_ledger;
 mod runner;
+mod snapshot;
 mod source_map_cache;
 mod source_mapper;
 mod stack_trace;
+mod state_diff;
+mod storage;
+mod symbolizer;
 mod types;
 mod vm;
 mod wasm;
 
-use crate::gas_optimizer::{BudgetMetrics, GasOptimizationAdvisor, CPU_LIMIT, MEMORY_LIMIT};
+use crate::gas_optimizer::{
+    BudgetMetrics, CryptoCallBreakdown, GasOptimizationAdvisor, CPU_LIMIT, MEMORY_LIMIT,
+};
 use crate::source_mapper::SourceMapper;
 use crate::stack_trace::WasmStackTrace;
 use crate::types::*;
@@ -32,6 +47,7 @@ use tracing_subscriber::{fmt, EnvFilter};
 // Use types::SimulationRequest directly
 
 const ERR_MEMORY_LIMIT_EXCEEDED: &str = "ERR_MEMORY_LIMIT_EXCEEDED";
+const ERR_CPU_LIMIT_EXCEEDED: &str = "ERR_CPU_LIMIT_EXCEEDED";
 
 fn init_logger() {
     // Check if the environment variable ERST_LOG_FORMAT is set to "json"
@@ -68,8 +84,14 @@ fn send_error(msg: String) {
         categorized_events: vec![],
         logs: vec![],
         flamegraph: None,
+        memory_flamegraph: None,
         optimization_report: None,
         budget_usage: None,
+        execution_trace: vec![],
+        fee_market_report: None,
+        effective_cost_schedule: None,
+        footprint: None,
+        state_changes: None,
         source_location: None,
         stack_trace: Some(trace),
         wasm_offset: None,
@@ -86,24 +108,253 @@ fn send_error(msg: String) {
 #[derive(Default)]
 struct CoverageTracker {
     invoked_functions: HashMap<String, u64>,
+    /// Same invocation counts as `invoked_functions`, but keyed by the
+    /// contract's exported function name exactly as it appears in the
+    /// transaction XDR (not `host_function_label`'s debug-formatted label),
+    /// so `generate_lcov_report` can look each one up against
+    /// `SourceMapper::function_location_by_name`.
+    invoked_contract_functions: HashMap<String, u64>,
+    /// Execution counts for every WASM instruction mnemonic observed via
+    /// `extract_wasm_instruction` (e.g. `"i32.add"`, `"call 12"`, `"br_if
+    /// 3"`). There's no byte offset alongside these in the event data, so
+    /// unlike `invoked_contract_functions` they can't be resolved to an
+    /// exact source line by `SourceMapper` — `generate_lcov_report` only
+    /// uses the branch-family (`br`/`br_if`/`br_table`) entries, as a
+    /// coarse branch-coverage proxy attributed to the entry line of the
+    /// single contract function invoked during the simulation.
+    instruction_hits: HashMap<String, u64>,
 }
 
 impl CoverageTracker {
     fn record_operation(&mut self, op: &Operation) {
         if let OperationBody::InvokeHostFunction(invoke_op) = &op.body {
-            let function_label = match &invoke_op.host_function {
-                soroban_env_host::xdr::HostFunction::InvokeContract(args) => {
-                    format!("InvokeContract::{:?}", args.function_name)
-                }
-                other => other.name().to_string(),
-            };
-            let entry = self.invoked_functions.entry(function_label).or_insert(0);
+            let entry = self
+                .invoked_functions
+                .entry(host_function_label(&invoke_op.host_function))
+                .or_insert(0);
             *entry = entry.saturating_add(1);
+
+            if let soroban_env_host::xdr::HostFunction::InvokeContract(args) =
+                &invoke_op.host_function
+            {
+                let name = String::from_utf8_lossy(&args.function_name.0).into_owned();
+                let entry = self.invoked_contract_functions.entry(name).or_insert(0);
+                *entry = entry.saturating_add(1);
+            }
+        }
+    }
+
+    fn record_instruction(&mut self, mnemonic: &str) {
+        let entry = self.instruction_hits.entry(mnemonic.to_string()).or_insert(0);
+        *entry = entry.saturating_add(1);
+    }
+
+    /// The branch-family (`br`, `br_if`, `br_table`) mnemonics observed via
+    /// `record_instruction`, sorted for stable LCOV output, with their hit
+    /// counts. This only tells us a branch instruction executed some
+    /// number of times, not which direction it took — the host doesn't
+    /// expose that — so `BRDA`'s "taken" count is really "executions",
+    /// and every distinct mnemonic string is its own branch entry rather
+    /// than a true pair of outcome edges.
+    fn branch_instruction_hits(&self) -> Vec<(&str, u64)> {
+        let mut hits: Vec<(&str, u64)> = self
+            .instruction_hits
+            .iter()
+            .filter(|(mnemonic, _)| {
+                let head = mnemonic.split_whitespace().next().unwrap_or(mnemonic);
+                head == "br" || head == "br_if" || head == "br_table"
+            })
+            .map(|(mnemonic, count)| (mnemonic.as_str(), *count))
+            .collect();
+        hits.sort_by(|(a, _), (b, _)| a.cmp(b));
+        hits
+    }
+}
+
+/// A human-readable label for a `HostFunction`, shared by coverage tracking
+/// and the cost-attributed flamegraph's call-frame labels.
+fn host_function_label(host_function: &soroban_env_host::xdr::HostFunction) -> String {
+    match host_function {
+        soroban_env_host::xdr::HostFunction::InvokeContract(args) => {
+            format!("InvokeContract::{:?}", args.function_name)
+        }
+        other => other.name().to_string(),
+    }
+}
+
+/// A call-frame label for a host-function invocation, enriched with the
+/// WASM's own debug symbols when available. When `source_mapper` has debug
+/// symbols and can resolve the invoked function's defining source location,
+/// the label becomes `<function_name> (<file>:<line>)`; otherwise this falls
+/// back to the plain [`host_function_label`], which is always an exact
+/// match for `InvokeContract` calls since it's read straight off the
+/// transaction XDR rather than guessed from WASM offsets.
+fn source_mapped_call_label(
+    host_function: &soroban_env_host::xdr::HostFunction,
+    source_mapper: Option<&SourceMapper>,
+) -> String {
+    let label = host_function_label(host_function);
+    let soroban_env_host::xdr::HostFunction::InvokeContract(args) = host_function else {
+        return label;
+    };
+
+    let Some(mapper) = source_mapper else {
+        return label;
+    };
+    let function_name = String::from_utf8_lossy(&args.function_name.0).into_owned();
+    let Some(location) = mapper.function_location_by_name(&function_name) else {
+        return label;
+    };
+
+    format!("{label} ({}:{})", location.file, location.line)
+}
+
+/// One flame in the cost-attributed profile: a `rootcall;subcall;<CostType>`
+/// path together with the CPU instructions and memory bytes the budget
+/// attributed to that cost type during that call.
+struct CostFrame {
+    root: String,
+    sub: String,
+    cost_type: String,
+    cpu: u64,
+    mem: u64,
+}
+
+impl CostFrame {
+    fn cpu_folded_line(&self) -> Option<String> {
+        (self.cpu > 0).then(|| format!("{};{};{} {}\n", self.root, self.sub, self.cost_type, self.cpu))
+    }
+
+    fn mem_folded_line(&self) -> Option<String> {
+        (self.mem > 0).then(|| format!("{};{};{} {}\n", self.root, self.sub, self.cost_type, self.mem))
+    }
+}
+
+/// One LCOV `SF:` record's worth of real, source-mapped coverage: every
+/// invoked contract function that `SourceMapper` resolved into this file,
+/// keyed by its defining line. `DA`/`LF`/`LH` are derived one-for-one from
+/// these entries — the host doesn't expose a per-instruction execution
+/// trace, so a function's entry line is the finest granularity available,
+/// rather than true statement coverage across its whole body.
+struct FileCoverage {
+    file: String,
+    entries: Vec<(u32, String, u64)>,
+    /// `(line, branch_label, hits)` rows for `BRDA`, populated only when
+    /// exactly one contract function was invoked during the simulation —
+    /// see `line_level_coverage_by_file` and
+    /// `CoverageTracker::branch_instruction_hits`. Empty (and `BRDA`/`BRF`/
+    /// `BRH` omitted entirely) otherwise, rather than guessing which of
+    /// several invoked functions a branch belongs to.
+    branches: Vec<(u32, String, u64)>,
+}
+
+impl FileCoverage {
+    fn render(&self) -> String {
+        let mut entries = self.entries.clone();
+        entries.sort_by_key(|(line, ..)| *line);
+
+        let mut report = String::new();
+        report.push_str(&format!("SF:{}\n", self.file));
+        for (line, name, _) in &entries {
+            report.push_str(&format!("FN:{line},{name}\n"));
+        }
+        for (_, name, count) in &entries {
+            report.push_str(&format!("FNDA:{count},{name}\n"));
+        }
+        report.push_str(&format!("FNF:{}\n", entries.len()));
+        report.push_str(&format!(
+            "FNH:{}\n",
+            entries.iter().filter(|(_, _, count)| *count > 0).count()
+        ));
+
+        for (line, _, count) in &entries {
+            report.push_str(&format!("DA:{line},{count}\n"));
+        }
+        report.push_str(&format!("LF:{}\n", entries.len()));
+        report.push_str(&format!(
+            "LH:{}\n",
+            entries.iter().filter(|(_, _, count)| *count > 0).count()
+        ));
+
+        if !self.branches.is_empty() {
+            let mut branches = self.branches.clone();
+            branches.sort_by(|(line_a, label_a, _), (line_b, label_b, _)| {
+                line_a.cmp(line_b).then_with(|| label_a.cmp(label_b))
+            });
+            for (idx, (line, _, hits)) in branches.iter().enumerate() {
+                report.push_str(&format!("BRDA:{line},0,{idx},{hits}\n"));
+            }
+            report.push_str(&format!("BRF:{}\n", branches.len()));
+            report.push_str(&format!(
+                "BRH:{}\n",
+                branches.iter().filter(|(_, _, hits)| *hits > 0).count()
+            ));
+        }
+        report.push_str("end_of_record\n");
+        report
+    }
+}
+
+/// Resolve every invoked contract function's entry line through `mapper`'s
+/// DWARF debug info, grouped by the source file it belongs to. Functions
+/// the mapper can't resolve (no matching `DW_TAG_subprogram`) are simply
+/// omitted rather than guessed at.
+fn line_level_coverage_by_file(
+    coverage: &CoverageTracker,
+    mapper: &SourceMapper,
+) -> Vec<FileCoverage> {
+    let mut by_file: HashMap<String, Vec<(u32, String, u64)>> = HashMap::new();
+
+    let mut names: Vec<(&str, u64)> = coverage
+        .invoked_contract_functions
+        .iter()
+        .map(|(name, count)| (name.as_str(), *count))
+        .collect();
+    names.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (name, count) in names {
+        if let Some(location) = mapper.function_location_by_name(name) {
+            by_file
+                .entry(location.file)
+                .or_default()
+                .push((location.line, name.to_string(), count));
+        }
+    }
+
+    let mut files: Vec<FileCoverage> = by_file
+        .into_iter()
+        .map(|(file, entries)| FileCoverage {
+            file,
+            entries,
+            branches: Vec::new(),
+        })
+        .collect();
+    files.sort_by(|a, b| a.file.cmp(&b.file));
+
+    // A branch-family instruction can only be honestly attributed to a
+    // source line when exactly one contract function was invoked, so that
+    // function's entry line is the unambiguous place it ran.
+    if coverage.invoked_contract_functions.len() == 1 {
+        if let Some(file) = files.first_mut() {
+            if let Some((line, ..)) = file.entries.first().copied() {
+                file.branches = coverage
+                    .branch_instruction_hits()
+                    .into_iter()
+                    .map(|(mnemonic, hits)| (line, mnemonic.to_string(), hits))
+                    .collect();
+            }
         }
     }
+
+    files
 }
 
-fn generate_lcov_report(coverage: &CoverageTracker, source_file: &str) -> String {
+/// The function-level-only fallback report: one `FN`/`FNDA` record per
+/// invoked host function under a single placeholder `SF:`, with a minimal
+/// one-line `DA`/`LF`/`LH` section so generic LCOV consumers can still parse
+/// it. Used when there's no source-mapped contract function to report real
+/// lines for (no debug symbols, or `contract_wasm` wasn't supplied).
+fn generate_stub_lcov_report(coverage: &CoverageTracker, source_file: &str) -> String {
     let mut functions: Vec<(&str, u64)> = coverage
         .invoked_functions
         .iter()
@@ -137,17 +388,151 @@ fn generate_lcov_report(coverage: &CoverageTracker, source_file: &str) -> String
     report
 }
 
-fn check_memory_limit_or_panic(host: &Host, memory_limit: Option<u64>) {
+/// Build the simulation's LCOV report: real per-file, per-line coverage
+/// derived from `SourceMapper`'s DWARF debug info when `source_mapper` can
+/// resolve at least one invoked function, or the function-level-only
+/// `generate_stub_lcov_report` fallback otherwise.
+fn generate_lcov_report(
+    coverage: &CoverageTracker,
+    source_file: &str,
+    source_mapper: Option<&SourceMapper>,
+) -> String {
+    let mapped_files = source_mapper
+        .filter(|mapper| mapper.has_debug_symbols())
+        .map(|mapper| line_level_coverage_by_file(coverage, mapper))
+        .filter(|files| !files.is_empty());
+
+    match mapped_files {
+        Some(files) => {
+            let mut report = String::new();
+            report.push_str("TN:simulator\n");
+            for file in &files {
+                report.push_str(&file.render());
+            }
+            report
+        }
+        None => generate_stub_lcov_report(coverage, source_file),
+    }
+}
+
+/// Render a folded-stacks string (`rootcall;subcall;frame count\n` lines)
+/// into an SVG flamegraph, or `None` if there was nothing to render.
+fn render_flamegraph(folded: &str, title: &str) -> Option<String> {
+    if folded.is_empty() {
+        return None;
+    }
+    let mut result_vec = Vec::new();
+    let mut options = inferno::flamegraph::Options::default();
+    options.title = title.to_string();
+    match inferno::flamegraph::from_reader(&mut options, folded.as_bytes(), &mut result_vec) {
+        Ok(()) => Some(String::from_utf8_lossy(&result_vec).to_string()),
+        Err(e) => {
+            eprintln!("Failed to generate flamegraph: {e}");
+            None
+        }
+    }
+}
+
+/// Which resource a [`ExecutionError::BudgetExceeded`] was measured against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Cpu,
+    Memory,
+}
+
+impl Dimension {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu",
+            Self::Memory => "memory",
+        }
+    }
+}
+
+/// A typed execution failure: either a `HostError` from the Soroban host
+/// itself, or the memory limit we enforce locally (the host budget only
+/// aborts on its own CPU/memory ceilings, not the caller-supplied
+/// `memory_limit`, so we poll `get_mem_bytes_consumed` after each operation).
+/// Kept as a real `Result` variant rather than a `panic!` so a breach is a
+/// first-class typed failure like any `HostError`, instead of something
+/// `catch_unwind` has to recover and string-match in `main`.
+///
+/// `WasmTrap` distinguishes a genuine VM trap from `Host`, which now only
+/// ever carries an actual host-level failure (budget, storage, auth, ...).
+/// The `WasmStackTrace` it carries is itself a `std::error::Error` (see
+/// `stack_trace.rs`), so a `WasmTrap` can be walked like any other error
+/// chain instead of pattern-matched apart from `Host` by hand.
+enum ExecutionError {
+    Host(HostError),
+    WasmTrap(WasmStackTrace),
+    /// A resource ceiling was crossed, tagged with which `Dimension` it was:
+    /// the caller-supplied `memory_limit` (`check_memory_limit` polls for it
+    /// since the host budget doesn't enforce it on its own), our own
+    /// `CPU_LIMIT` poll (`check_cpu_limit`), or a host-raised
+    /// `ScErrorType::Budget` `HostError` reclassified by
+    /// `classify_invoke_error` while the host is still reachable for its
+    /// consumed CPU count (the limit reported is `CPU_LIMIT`).
+    BudgetExceeded {
+        dimension: Dimension,
+        used: u64,
+        limit: u64,
+    },
+}
+
+impl From<HostError> for ExecutionError {
+    fn from(err: HostError) -> Self {
+        if err.error.get_type() == soroban_env_host::xdr::ScErrorType::WasmVm {
+            Self::WasmTrap(WasmStackTrace::from_host_error_typed(&err))
+        } else {
+            // A host-raised `ScErrorType::Budget` (e.g. the CPU-instruction
+            // ceiling an `EndlessLoopContract`-style infinite loop trips) is
+            // left as `Host(err)` rather than reclassified here: by the time
+            // a bare `HostError` reaches this conversion the budget that
+            // would tell us `used`/`limit` is gone, and guessing at those
+            // figures would be worse than not reporting them. The call site
+            // that still has the host in hand (`execute_operations`'s
+            // `invoke_function` call) intercepts this case before the `?`
+            // gets here and builds a `BudgetExceeded { dimension: Cpu, .. }`
+            // with real numbers instead.
+            Self::Host(err)
+        }
+    }
+}
+
+fn check_memory_limit(host: &Host, memory_limit: Option<u64>) -> Result<(), ExecutionError> {
     if let Some(limit) = memory_limit {
         if let Ok(mem_bytes) = host.budget_cloned().get_mem_bytes_consumed() {
             if mem_bytes > limit {
-                panic!(
-                    "{}: consumed {} bytes, limit {} bytes",
-                    ERR_MEMORY_LIMIT_EXCEEDED, mem_bytes, limit
-                );
+                return Err(ExecutionError::BudgetExceeded {
+                    dimension: Dimension::Memory,
+                    used: mem_bytes,
+                    limit,
+                });
             }
         }
     }
+    Ok(())
+}
+
+/// Check the host's own CPU-instruction budget against `CPU_LIMIT` after an
+/// invocation, the CPU-dimension counterpart to [`check_memory_limit`]. The
+/// host already traps an instruction-budget breach as an
+/// `ScErrorType::Budget` `HostError` mid-invocation (what an
+/// `EndlessLoopContract`-style infinite loop exercises) — this is a
+/// belt-and-suspenders poll for the case where the host's own ceiling is
+/// looser than ours, so a breach is reported the same way regardless of
+/// which side notices it first.
+fn check_cpu_limit(host: &Host) -> Result<(), ExecutionError> {
+    if let Ok(cpu_insns) = host.budget_cloned().get_cpu_insns_consumed() {
+        if cpu_insns > CPU_LIMIT {
+            return Err(ExecutionError::BudgetExceeded {
+                dimension: Dimension::Cpu,
+                used: cpu_insns,
+                limit: CPU_LIMIT,
+            });
+        }
+    }
+    Ok(())
 }
 
 fn execute_operations(
@@ -155,28 +540,147 @@ fn execute_operations(
     operations: &[Operation],
     memory_limit: Option<u64>,
     coverage: &mut CoverageTracker,
-) -> Result<Vec<String>, HostError> {
+    source_mapper: Option<&SourceMapper>,
+) -> Result<(Vec<String>, Vec<CostFrame>, Vec<ExecutionTraceEvent>), ExecutionError> {
     let mut logs = Vec::new();
-    check_memory_limit_or_panic(host, memory_limit);
-    for op in operations {
+    let mut frames = Vec::new();
+    let mut trace = Vec::new();
+    check_memory_limit(host, memory_limit)?;
+    for (idx, op) in operations.iter().enumerate() {
         coverage.record_operation(op);
+        let root = format!("op{idx}");
         match &op.body {
             OperationBody::InvokeHostFunction(invoke_op) => {
                 logs.push("Executing InvokeHostFunction...".to_string());
-                let val = host.invoke_function(invoke_op.host_function.clone())?;
+                let sub = source_mapped_call_label(&invoke_op.host_function, source_mapper);
+                let before = contract_cost_breakdown(&host.budget_cloned());
+                let (cpu_before, mem_before) = consumed_totals(host);
+                trace.push(ExecutionTraceEvent {
+                    depth: 0,
+                    kind: TraceEventKind::Call,
+                    label: sub.clone(),
+                    cumulative_cpu_insns: cpu_before,
+                    cumulative_memory_bytes: mem_before,
+                    delta_cpu_insns: 0,
+                    delta_memory_bytes: 0,
+                });
+                let val = host
+                    .invoke_function(invoke_op.host_function.clone())
+                    .map_err(|err| classify_invoke_error(host, err))?;
+                let after = contract_cost_breakdown(&host.budget_cloned());
+                let deltas = cost_frame_deltas(&root, &sub, &before, &after);
+                let mut cpu_running = cpu_before;
+                let mut mem_running = mem_before;
+                for frame in &deltas {
+                    cpu_running = cpu_running.saturating_add(frame.cpu);
+                    mem_running = mem_running.saturating_add(frame.mem);
+                    trace.push(ExecutionTraceEvent {
+                        depth: 1,
+                        kind: TraceEventKind::Call,
+                        label: frame.cost_type.clone(),
+                        cumulative_cpu_insns: cpu_running,
+                        cumulative_memory_bytes: mem_running,
+                        delta_cpu_insns: 0,
+                        delta_memory_bytes: 0,
+                    });
+                    trace.push(ExecutionTraceEvent {
+                        depth: 1,
+                        kind: TraceEventKind::Return,
+                        label: frame.cost_type.clone(),
+                        cumulative_cpu_insns: cpu_running,
+                        cumulative_memory_bytes: mem_running,
+                        delta_cpu_insns: frame.cpu,
+                        delta_memory_bytes: frame.mem,
+                    });
+                }
+                let (cpu_after, mem_after) = consumed_totals(host);
+                trace.push(ExecutionTraceEvent {
+                    depth: 0,
+                    kind: TraceEventKind::Return,
+                    label: sub.clone(),
+                    cumulative_cpu_insns: cpu_after,
+                    cumulative_memory_bytes: mem_after,
+                    delta_cpu_insns: cpu_after.saturating_sub(cpu_before),
+                    delta_memory_bytes: mem_after.saturating_sub(mem_before),
+                });
+                frames.extend(deltas);
                 logs.push(format!("Result: {val:?}"));
-                check_memory_limit_or_panic(host, memory_limit);
+                check_memory_limit(host, memory_limit)?;
+                check_cpu_limit(host)?;
             }
             _ => {
                 logs.push(format!(
                     "Skipping non-Soroban operation: {:?}",
                     op.body.name()
                 ));
-                check_memory_limit_or_panic(host, memory_limit);
+                check_memory_limit(host, memory_limit)?;
+                check_cpu_limit(host)?;
             }
         }
     }
-    Ok(logs)
+    Ok((logs, frames, trace))
+}
+
+/// Turn a `HostError` from `invoke_function` into an [`ExecutionError`],
+/// same as the blanket `From<HostError>` impl except that a host-raised
+/// `ScErrorType::Budget` error is reclassified into
+/// `BudgetExceeded { dimension: Cpu, .. }`, with the consumed CPU count read
+/// back from `host` while it's still in hand and `CPU_LIMIT` as the limit.
+/// The consumed count is the one thing `From<HostError>` can't supply, since
+/// by the time a bare `HostError` reaches that conversion the budget behind
+/// it is gone.
+fn classify_invoke_error(host: &Host, err: HostError) -> ExecutionError {
+    if err.error.get_type() == soroban_env_host::xdr::ScErrorType::Budget {
+        if let Ok(cpu_insns) = host.budget_cloned().get_cpu_insns_consumed() {
+            return ExecutionError::BudgetExceeded {
+                dimension: Dimension::Cpu,
+                used: cpu_insns,
+                limit: CPU_LIMIT,
+            };
+        }
+    }
+    ExecutionError::from(err)
+}
+
+/// The budget's cumulative CPU-instruction and memory-byte totals consumed
+/// so far, defaulting to `0` the same way `decode_error`'s caller and the
+/// final `budget_usage` snapshot already treat an unavailable reading.
+fn consumed_totals(host: &Host) -> (u64, u64) {
+    let budget = host.budget_cloned();
+    (
+        budget.get_cpu_insns_consumed().unwrap_or(0),
+        budget.get_mem_bytes_consumed().unwrap_or(0),
+    )
+}
+
+/// Diff two `contract_cost_breakdown` snapshots taken around a call, turning
+/// each changed cost type into a `CostFrame` under the given call-frame path.
+fn cost_frame_deltas(
+    root: &str,
+    sub: &str,
+    before: &HashMap<String, ContractCostEntry>,
+    after: &HashMap<String, ContractCostEntry>,
+) -> Vec<CostFrame> {
+    after
+        .iter()
+        .map(|(cost_type, after_entry)| {
+            let before_entry = before.get(cost_type);
+            let cpu = after_entry
+                .cpu_insns
+                .saturating_sub(before_entry.map(|e| e.cpu_insns).unwrap_or(0));
+            let mem = after_entry
+                .memory_bytes
+                .saturating_sub(before_entry.map(|e| e.memory_bytes).unwrap_or(0));
+            CostFrame {
+                root: root.to_string(),
+                sub: sub.to_string(),
+                cost_type: cost_type.clone(),
+                cpu,
+                mem,
+            }
+        })
+        .filter(|frame| frame.cpu > 0 || frame.mem > 0)
+        .collect()
 }
 
 fn transaction_fee_stroops(envelope: &soroban_env_host::xdr::TransactionEnvelope) -> u64 {
@@ -187,12 +691,47 @@ fn transaction_fee_stroops(envelope: &soroban_env_host::xdr::TransactionEnvelope
     }
 }
 
+/// The transaction's own source account (the fee-bump outer account is
+/// deliberately not used here — that one only pays the fee; it's the inner
+/// transaction's source that operations without their own override run as).
+fn transaction_source_account_id(
+    envelope: &soroban_env_host::xdr::TransactionEnvelope,
+) -> soroban_env_host::xdr::AccountId {
+    use soroban_env_host::xdr::{
+        AccountId, FeeBumpTransactionInnerTx, MuxedAccount, PublicKey, TransactionEnvelope,
+    };
+
+    fn muxed_to_account_id(account: &MuxedAccount) -> AccountId {
+        match account {
+            MuxedAccount::Ed25519(key) => AccountId(PublicKey::PublicKeyTypeEd25519(key.clone())),
+            MuxedAccount::MuxedEd25519(inner) => {
+                AccountId(PublicKey::PublicKeyTypeEd25519(inner.ed25519.clone()))
+            }
+        }
+    }
+
+    match envelope {
+        TransactionEnvelope::Tx(tx_v1) => muxed_to_account_id(&tx_v1.tx.source_account),
+        TransactionEnvelope::TxV0(tx_v0) => {
+            AccountId(PublicKey::PublicKeyTypeEd25519(tx_v0.tx.source_account_ed25519.clone()))
+        }
+        TransactionEnvelope::TxFeeBump(bump) => match &bump.tx.inner_tx {
+            FeeBumpTransactionInnerTx::Tx(tx_v1) => muxed_to_account_id(&tx_v1.tx.source_account),
+        },
+    }
+}
+
+/// The mocked fee check's required fee and the `CostSchedule` that
+/// contributed to it (the request's own, or the all-zero default when none
+/// was given), or `None` when no mocked fee mechanism is active at all.
 fn mocked_required_fee_stroops(
     request: &SimulationRequest,
     operations_count: usize,
     cpu_insns: u64,
     mem_bytes: u64,
-) -> Option<u64> {
+    storage_reads: u64,
+    storage_writes: u64,
+) -> Option<(u64, CostSchedule)> {
     let mut required_fee = 0u64;
     let mut enabled = false;
 
@@ -211,10 +750,207 @@ fn mocked_required_fee_stroops(
         required_fee = required_fee.saturating_add(gas_price.saturating_mul(resource_units));
     }
 
-    if enabled {
-        Some(required_fee)
-    } else {
-        None
+    if request.cost_schedule.is_some() {
+        enabled = true;
+    }
+
+    if !enabled {
+        return None;
+    }
+
+    let schedule = request.cost_schedule.clone().unwrap_or_default();
+    let usage = cost_schedule::ResourceUsage {
+        cpu_insns,
+        memory_bytes: mem_bytes,
+        operations_count: operations_count as u64,
+        storage_reads,
+        storage_writes,
+    };
+    required_fee = required_fee.saturating_add(cost_schedule::required_fee_stroops(&schedule, &usage));
+
+    Some((required_fee, schedule))
+}
+
+/// The `ContractCostType`s we surface a breakdown for, in particular the
+/// two instantiation paths the protocol-21 budget charges separately:
+/// `VmInstantiation` (cold parse/validate/translate of a module) versus
+/// `VmCachedInstantiation` (reusing an already-parsed module). Not every
+/// `ContractCostType` variant is listed here — just the ones useful for
+/// telling "bringing the contract online" apart from "running it".
+const TRACKED_COST_TYPES: &[(soroban_env_host::xdr::ContractCostType, &str)] = &[
+    (
+        soroban_env_host::xdr::ContractCostType::WasmInsnExec,
+        "WasmInsnExec",
+    ),
+    (
+        soroban_env_host::xdr::ContractCostType::MemAlloc,
+        "MemAlloc",
+    ),
+    (
+        soroban_env_host::xdr::ContractCostType::InvokeVmFunction,
+        "InvokeVmFunction",
+    ),
+    (
+        soroban_env_host::xdr::ContractCostType::ComputeSha256Hash,
+        "ComputeSha256Hash",
+    ),
+    (
+        soroban_env_host::xdr::ContractCostType::ComputeKeccak256Hash,
+        "ComputeKeccak256Hash",
+    ),
+    (
+        soroban_env_host::xdr::ContractCostType::VerifyEd25519Sig,
+        "VerifyEd25519Sig",
+    ),
+    (
+        soroban_env_host::xdr::ContractCostType::VmInstantiation,
+        "VmInstantiation",
+    ),
+    (
+        soroban_env_host::xdr::ContractCostType::VmCachedInstantiation,
+        "VmCachedInstantiation",
+    ),
+];
+
+fn contract_cost_breakdown(
+    budget: &soroban_env_host::budget::Budget,
+) -> HashMap<String, ContractCostEntry> {
+    let mut breakdown = HashMap::new();
+    for (cost_type, name) in TRACKED_COST_TYPES {
+        if let Ok(tracker) = budget.get_tracker(*cost_type) {
+            breakdown.insert(
+                name.to_string(),
+                ContractCostEntry {
+                    iterations: tracker.iterations,
+                    cpu_insns: tracker.cpu,
+                    memory_bytes: tracker.mem,
+                },
+            );
+        }
+    }
+    breakdown
+}
+
+/// Sum the `VmInstantiation` and `VmCachedInstantiation` entries of a
+/// `contract_cost_breakdown` readout into a single (cpu, mem) total — the
+/// cost of bringing contract modules online, cold or cache-hit alike, as
+/// opposed to the cost of actually running them. See
+/// `BudgetUsage::vm_instantiation_cpu`/`vm_instantiation_mem`.
+fn vm_instantiation_totals(cost_breakdown: &HashMap<String, ContractCostEntry>) -> (u64, u64) {
+    ["VmInstantiation", "VmCachedInstantiation"]
+        .iter()
+        .filter_map(|name| cost_breakdown.get(*name))
+        .fold((0u64, 0u64), |(cpu, mem), entry| {
+            (
+                cpu.saturating_add(entry.cpu_insns),
+                mem.saturating_add(entry.memory_bytes),
+            )
+        })
+}
+
+/// Best-effort per-crypto-host-function call shape derived from the
+/// budget's cost-type trackers, for [`GasOptimizationAdvisor::analyze_with_calibration`].
+/// The host's `Budget` API only exposes the total CPU/memory it charged
+/// per cost type, not the input byte count of each call, so
+/// `total_input_bytes` is always `0` here; the modeled cost this produces
+/// therefore only reflects each primitive's fixed per-call cost, not its
+/// per-byte component.
+fn crypto_call_breakdown(cost_breakdown: &HashMap<String, ContractCostEntry>) -> Vec<CryptoCallBreakdown> {
+    const CRYPTO_COST_TYPES: &[(&str, &str)] = &[
+        ("ComputeSha256Hash", "sha256"),
+        ("ComputeKeccak256Hash", "keccak256"),
+        ("VerifyEd25519Sig", "ed25519"),
+    ];
+    CRYPTO_COST_TYPES
+        .iter()
+        .filter_map(|(key, name)| {
+            cost_breakdown.get(*key).map(|entry| CryptoCallBreakdown {
+                function: name.to_string(),
+                invocations: entry.iterations,
+                total_input_bytes: 0,
+            })
+        })
+        .collect()
+}
+
+/// The host's accumulated event stream, in all three shapes callers need:
+/// raw debug-formatted strings, structured [`DiagnosticEvent`]s, and
+/// analyzer-facing [`CategorizedEvent`]s. The host keeps recording events up
+/// to the point a trap or panic interrupts it, so this is called on every
+/// response path — success, `HostError`, memory-limit breach, and panic
+/// alike — rather than only on success, so a failed simulation still
+/// reports the telemetry that led up to its root error instead of an empty
+/// stream.
+fn collect_host_diagnostics(
+    host: &Host,
+) -> (Vec<String>, Vec<DiagnosticEvent>, Vec<CategorizedEvent>) {
+    let Ok(evs) = host.get_events() else {
+        return (
+            vec!["Failed to retrieve events".to_string()],
+            Vec::new(),
+            Vec::new(),
+        );
+    };
+
+    let raw_events: Vec<String> = (evs.0).iter().map(|e| format!("{:?}", e)).collect();
+    let diagnostic_events: Vec<DiagnosticEvent> = (evs.0)
+        .iter()
+        .map(|event| {
+            let event_type = match &event.event.type_ {
+                soroban_env_host::xdr::ContractEventType::Contract => "contract".to_string(),
+                soroban_env_host::xdr::ContractEventType::System => "system".to_string(),
+                soroban_env_host::xdr::ContractEventType::Diagnostic => "diagnostic".to_string(),
+            };
+
+            let contract_id = event
+                .event
+                .contract_id
+                .as_ref()
+                .map(|contract_id| format!("{:?}", contract_id));
+
+            let (topics, data) = match &event.event.body {
+                soroban_env_host::xdr::ContractEventBody::V0(v0) => {
+                    let topics: Vec<String> =
+                        v0.topics.iter().map(|t| format!("{:?}", t)).collect();
+                    let data = format!("{:?}", v0.data);
+                    (topics, data)
+                }
+            };
+
+            let wasm_instruction = extract_wasm_instruction(&topics, &data);
+            DiagnosticEvent {
+                event_type,
+                contract_id,
+                topics,
+                data,
+                in_successful_contract_call: !event.failed_call,
+                wasm_instruction,
+            }
+        })
+        .collect();
+    let categorized_events = categorize_events(&evs);
+
+    (raw_events, diagnostic_events, categorized_events)
+}
+
+/// Feed every WASM instruction mnemonic observed on `host` — the same
+/// per-event signal `collect_host_diagnostics` exposes as
+/// `DiagnosticEvent::wasm_instruction` — into `coverage`, so
+/// `generate_lcov_report`'s `BRDA` rows reflect what actually ran even when
+/// the caller never reaches a successful response. Reuses
+/// `collect_host_diagnostics` itself rather than re-parsing events, wrapped
+/// in its own `catch_unwind` so a host already left in a bad state by a
+/// panic can only mean fewer recorded instructions, not a failure building
+/// the real response that runs after this.
+fn record_instruction_coverage(host: &Host, coverage: &mut CoverageTracker) {
+    let diagnostic_events =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| collect_host_diagnostics(host).1))
+            .unwrap_or_default();
+
+    for event in &diagnostic_events {
+        if let Some(mnemonic) = &event.wasm_instruction {
+            coverage.record_instruction(mnemonic);
+        }
     }
 }
 
@@ -299,8 +1035,14 @@ fn main() {
             categorized_events: vec![],
             logs: vec![],
             flamegraph: None,
+            memory_flamegraph: None,
             optimization_report: None,
             budget_usage: None,
+            execution_trace: vec![],
+            fee_market_report: None,
+            effective_cost_schedule: None,
+            footprint: None,
+            state_changes: None,
             source_location: None,
             stack_trace: None,
             wasm_offset: None,
@@ -330,8 +1072,14 @@ fn main() {
                 categorized_events: vec![],
                 logs: vec![],
                 flamegraph: None,
+                memory_flamegraph: None,
                 optimization_report: None,
                 budget_usage: None,
+                execution_trace: vec![],
+                fee_market_report: None,
+                effective_cost_schedule: None,
+                footprint: None,
+                state_changes: None,
                 source_location: None,
                 stack_trace: None,
                 wasm_offset: None,
@@ -422,34 +1170,13 @@ fn main() {
         None
     };
 
-    // Initialize Host
-    let sim_host = runner::SimHost::new(
-        None,
-        request.resource_calibration.clone(),
-        request.memory_limit,
-    );
-    let host = sim_host.inner;
-
-    // --- START: Local WASM Loading Integration (Issue #70) ---
-    if let Some(path) = &request.wasm_path {
-        match wasm::load_wasm_from_path(path) {
-            Ok(_wasm_bytes) => {
-                // `upload_contract_wasm` is crate-private in recent host versions.
-                // We still validate local WASM readability here.
-                eprintln!("Successfully loaded local WASM from path");
-            }
-            Err(e) => send_error(format!("Local WASM loading failed: {}", e)),
-        }
-    }
-    // --- END: Local WASM Loading Integration ---
-
-    let mut loaded_entries_count = 0;
-
-    // Populate Host Storage
+    // Decode ledger_entries up front so the Host's storage can be built
+    // pre-populated, rather than constructed empty and filled in afterwards.
+    let mut decoded_ledger_entries = HashMap::new();
     if let Some(entries) = &request.ledger_entries {
         for (key_xdr, entry_xdr) in entries {
             // Decode Key
-            let _key = match base64::engine::general_purpose::STANDARD.decode(key_xdr) {
+            let key = match base64::engine::general_purpose::STANDARD.decode(key_xdr) {
                 Ok(b) => match soroban_env_host::xdr::LedgerKey::from_xdr(
                     b,
                     soroban_env_host::xdr::Limits::none(),
@@ -467,7 +1194,7 @@ fn main() {
             };
 
             // Decode Entry
-            let _entry = match base64::engine::general_purpose::STANDARD.decode(entry_xdr) {
+            let entry = match base64::engine::general_purpose::STANDARD.decode(entry_xdr) {
                 Ok(b) => match soroban_env_host::xdr::LedgerEntry::from_xdr(
                     b,
                     soroban_env_host::xdr::Limits::none(),
@@ -484,12 +1211,84 @@ fn main() {
                 }
             };
 
-            // TODO: Inject into host storage.
-            // For MVP, we verify we can parse them.
-            eprintln!("Parsed Ledger Entry: Key={:?}, Entry={:?}", _key, _entry);
-            loaded_entries_count += 1;
+            decoded_ledger_entries.insert(key, entry);
+        }
+    }
+
+    // Merge in entries from a memory-mapped snapshot, if one was supplied.
+    // Entries given explicitly via `ledger_entries` above take precedence,
+    // since they're the caller's deliberate override of ledger state.
+    if let Some(snapshot_path) = &request.ledger_snapshot_path {
+        match snapshot::mmap_store::LedgerSnapshotStore::open(snapshot_path) {
+            Ok(store) => match store.iter_occupied() {
+                Ok(entries) => {
+                    for (key, entry) in entries {
+                        decoded_ledger_entries.entry(key).or_insert(entry);
+                    }
+                }
+                Err(e) => {
+                    send_error(format!("Failed to read ledger snapshot: {}", e));
+                    return;
+                }
+            },
+            Err(e) => {
+                send_error(format!("Failed to open ledger snapshot {}: {}", snapshot_path, e));
+                return;
+            }
+        }
+    }
+
+    let loaded_entries_count = decoded_ledger_entries.len();
+    // Kept so the post-execution storage can be diffed against the state
+    // the host started with (see `state_diff::diff` below).
+    let ledger_entries_before = decoded_ledger_entries.clone();
+
+    // Initialize Host, pre-populated with the decoded ledger entries so
+    // InvokeHostFunction operations execute against real state instead of
+    // an empty store.
+    let sim_host = match runner::SimHost::with_ledger_entries(
+        decoded_ledger_entries,
+        request.recording,
+        None,
+        request.resource_calibration.clone(),
+        request.memory_limit,
+        request.protocol_version,
+    ) {
+        Ok(sim_host) => sim_host,
+        Err(e) => {
+            return send_error(format!(
+                "Ledger entries incompatible with protocol {}: {}",
+                request.protocol_version, e
+            ));
+        }
+    };
+    let host = &sim_host.inner;
+
+    if let Some(config) = &request.auto_provision_accounts {
+        let source_account_id = transaction_source_account_id(&envelope);
+        if let Err(e) = snapshot::ensure_account_provisioned(
+            host,
+            &source_account_id,
+            config.starting_balance,
+            config.starting_sequence,
+        ) {
+            send_error(format!("Failed to auto-provision source account: {}", e));
+            return;
+        }
+    }
+
+    // --- START: Local WASM Loading Integration (Issue #70) ---
+    if let Some(path) = &request.wasm_path {
+        match wasm::load_wasm_from_path(path) {
+            Ok(_wasm_bytes) => {
+                // `upload_contract_wasm` is crate-private in recent host versions.
+                // We still validate local WASM readability here.
+                eprintln!("Successfully loaded local WASM from path");
+            }
+            Err(e) => send_error(format!("Local WASM loading failed: {}", e)),
         }
     }
+    // --- END: Local WASM Loading Integration ---
 
     // Extract Operations and Simulate
     let operations = match &envelope {
@@ -503,7 +1302,13 @@ fn main() {
     // Wrap the operation execution in panic protection
     let mut coverage = CoverageTracker::default();
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        execute_operations(&host, operations, request.memory_limit, &mut coverage)
+        execute_operations(
+            host,
+            operations,
+            request.memory_limit,
+            &mut coverage,
+            source_mapper.as_ref(),
+        )
     }));
 
     // Budget and Reporting
@@ -514,6 +1319,27 @@ fn main() {
     let cpu_usage_percent = (cpu_insns as f64 / CPU_LIMIT as f64) * 100.0;
     let memory_usage_percent = (mem_bytes as f64 / MEMORY_LIMIT as f64) * 100.0;
 
+    // Surfaced unconditionally: the host's enforcing footprint already
+    // tracks every key read or written during `execute_operations` above,
+    // so there's no reason to make callers opt in with `recording` first to
+    // find out what an InvokeHostFunction actually needs. `recording` is
+    // still accepted on the request for backward compatibility but no
+    // longer gates this.
+    let footprint = sim_host.discovered_footprint().and_then(|fp| {
+        soroban_env_host::xdr::WriteXdr::to_xdr(&fp, soroban_env_host::xdr::Limits::none())
+            .ok()
+            .map(|bytes| base64::engine::general_purpose::STANDARD.encode(bytes))
+    });
+
+    let state_changes = if result.as_ref().is_ok_and(|r| r.is_ok()) {
+        Some(state_diff::diff(&ledger_entries_before, &sim_host.storage_snapshot()))
+    } else {
+        None
+    };
+
+    let cost_breakdown = contract_cost_breakdown(&budget);
+    let (vm_instantiation_cpu, vm_instantiation_mem) = vm_instantiation_totals(&cost_breakdown);
+
     let budget_usage = BudgetUsage {
         cpu_instructions: cpu_insns,
         memory_bytes: mem_bytes,
@@ -522,45 +1348,54 @@ fn main() {
         memory_limit: MEMORY_LIMIT,
         cpu_usage_percent,
         memory_usage_percent,
+        cost_breakdown,
+        vm_instantiation_cpu,
+        vm_instantiation_mem,
     };
 
+    let fee_market_report = request.fee_market.as_ref().map(|distribution| {
+        fee_market::simulate(distribution, budget_usage.cpu_instructions, &mut rand::thread_rng())
+    });
+
     let optimization_report = if request.enable_optimization_advisor {
         let advisor = GasOptimizationAdvisor::new();
         let metrics = BudgetMetrics {
             cpu_instructions: budget_usage.cpu_instructions,
             memory_bytes: budget_usage.memory_bytes,
             total_operations: budget_usage.operations_count,
+            cost_breakdown: budget_usage.cost_breakdown.clone(),
         };
-        Some(advisor.analyze(&metrics))
+        Some(match &request.resource_calibration {
+            Some(calibration) => {
+                let calls = crypto_call_breakdown(&budget_usage.cost_breakdown);
+                advisor.analyze_with_calibration(&metrics, calibration, &calls)
+            }
+            None => advisor.analyze(&metrics),
+        })
     } else {
         None
     };
 
     let mut flamegraph_svg = None;
+    let mut memory_flamegraph_svg = None;
     if request.profile.unwrap_or(false) {
-        // Simple simulated flamegraph for demonstration
-        let folded_data = format!("Total;CPU {}\nTotal;Memory {}\n", cpu_insns, mem_bytes);
-        let mut result_vec = Vec::new();
-        let mut options = inferno::flamegraph::Options::default();
-        options.title = "Soroban Resource Consumption".to_string();
-
-        if let Err(e) =
-            inferno::flamegraph::from_reader(&mut options, folded_data.as_bytes(), &mut result_vec)
-        {
-            eprintln!("Failed to generate flamegraph: {e}");
-        } else {
-            flamegraph_svg = Some(String::from_utf8_lossy(&result_vec).to_string());
+        if let Ok(Ok((_, frames, _))) = &result {
+            let cpu_folded: String = frames.iter().filter_map(CostFrame::cpu_folded_line).collect();
+            let mem_folded: String = frames.iter().filter_map(CostFrame::mem_folded_line).collect();
+            flamegraph_svg = render_flamegraph(&cpu_folded, "Soroban CPU Cost Attribution");
+            memory_flamegraph_svg = render_flamegraph(&mem_folded, "Soroban Memory Cost Attribution");
         }
     }
 
     let mut lcov_report = None;
     let mut lcov_report_path = None;
     if request.enable_coverage {
+        record_instruction_coverage(host, &mut coverage);
         let source_file = request
             .wasm_path
             .clone()
             .unwrap_or_else(|| "contract.wasm".to_string());
-        let report = generate_lcov_report(&coverage, &source_file);
+        let report = generate_lcov_report(&coverage, &source_file, source_mapper.as_ref());
         if let Some(path) = request.coverage_lcov_path.clone() {
             match fs::write(&path, &report) {
                 Ok(()) => {
@@ -575,67 +1410,8 @@ fn main() {
     }
 
     match result {
-        Ok(Ok(exec_logs)) => {
-            // Extract both raw event strings and structured diagnostic events
-            let (events, diagnostic_events): (Vec<String>, Vec<DiagnosticEvent>) =
-                match host.get_events() {
-                    Ok(evs) => {
-                        let raw_events: Vec<String> =
-                            (evs.0).iter().map(|e| format!("{:?}", e)).collect();
-                        let diag_events: Vec<DiagnosticEvent> = (evs.0)
-                            .iter()
-                            .map(|event| {
-                                let event_type = match &event.event.type_ {
-                                    soroban_env_host::xdr::ContractEventType::Contract => {
-                                        "contract".to_string()
-                                    }
-                                    soroban_env_host::xdr::ContractEventType::System => {
-                                        "system".to_string()
-                                    }
-                                    soroban_env_host::xdr::ContractEventType::Diagnostic => {
-                                        "diagnostic".to_string()
-                                    }
-                                };
-
-                                let contract_id = event
-                                    .event
-                                    .contract_id
-                                    .as_ref()
-                                    .map(|contract_id| format!("{:?}", contract_id));
-
-                                let (topics, data) = match &event.event.body {
-                                    soroban_env_host::xdr::ContractEventBody::V0(v0) => {
-                                        let topics: Vec<String> =
-                                            v0.topics.iter().map(|t| format!("{:?}", t)).collect();
-                                        let data = format!("{:?}", v0.data);
-                                        (topics, data)
-                                    }
-                                };
-
-                                let wasm_instruction = extract_wasm_instruction(&topics, &data);
-                                DiagnosticEvent {
-                                    event_type,
-                                    contract_id,
-                                    topics,
-                                    data,
-                                    in_successful_contract_call: !event.failed_call,
-                                    wasm_instruction,
-                                }
-                            })
-                            .collect();
-                        (raw_events, diag_events)
-                    }
-                    Err(_) => (
-                        vec!["Failed to retrieve events".to_string()],
-                        Vec::<DiagnosticEvent>::new(),
-                    ),
-                };
-
-            // Capture categorized events for analyzer
-            let categorized_events = match host.get_events() {
-                Ok(evs) => categorize_events(&evs),
-                Err(_) => vec![],
-            };
+        Ok(Ok((exec_logs, _frames, execution_trace))) => {
+            let (events, diagnostic_events, categorized_events) = collect_host_diagnostics(host);
 
             let mut final_logs = vec![
                 format!("Host Initialized with Budget: {:?}", budget),
@@ -643,15 +1419,31 @@ fn main() {
                 format!("Captured {} diagnostic events", diagnostic_events.len()),
                 format!("CPU Instructions Used: {}", cpu_insns),
                 format!("Memory Bytes Used: {}", mem_bytes),
+                format!(
+                    "VM Instantiation: {} CPU instructions, {} memory bytes (cold + cached)",
+                    vm_instantiation_cpu, vm_instantiation_mem
+                ),
             ];
             final_logs.extend(exec_logs);
 
-            if let Some(required_fee) = mocked_required_fee_stroops(
+            let storage_reads = loaded_entries_count as u64;
+            let storage_writes = state_changes
+                .as_ref()
+                .map(|report| {
+                    (report.created.len() + report.modified.len() + report.removed.len()) as u64
+                })
+                .unwrap_or(0);
+
+            let mut effective_cost_schedule = None;
+            if let Some((required_fee, effective_schedule)) = mocked_required_fee_stroops(
                 &request,
                 operations.as_slice().len(),
                 cpu_insns,
                 mem_bytes,
+                storage_reads,
+                storage_writes,
             ) {
+                effective_cost_schedule = Some(effective_schedule);
                 let declared_fee = transaction_fee_stroops(&envelope);
                 final_logs.push(format!(
                     "Mock fee check: declared={} required={}",
@@ -665,7 +1457,11 @@ fn main() {
                             "insufficient fee (mocked): declared {} stroops, required {} stroops",
                             declared_fee, required_fee
                         )),
-                        error_code: None,
+                        error_code: Some(
+                            error_classifier::ErrorCategory::InsufficientFee
+                                .as_str()
+                                .to_string(),
+                        ),
                         lcov_report: lcov_report.clone(),
                         lcov_report_path: lcov_report_path.clone(),
                         events,
@@ -673,8 +1469,14 @@ fn main() {
                         categorized_events,
                         logs: final_logs,
                         flamegraph: flamegraph_svg,
+                        memory_flamegraph: memory_flamegraph_svg.clone(),
                         optimization_report,
                         budget_usage: Some(budget_usage),
+                        execution_trace: execution_trace.clone(),
+                        fee_market_report: fee_market_report.clone(),
+                        effective_cost_schedule: effective_cost_schedule.clone(),
+                        footprint: footprint.clone(),
+                        state_changes: state_changes.clone(),
                         source_location: None,
                         stack_trace: None,
                         wasm_offset: None,
@@ -701,8 +1503,14 @@ fn main() {
                 categorized_events,
                 logs: final_logs,
                 flamegraph: flamegraph_svg,
+                memory_flamegraph: memory_flamegraph_svg.clone(),
                 optimization_report,
                 budget_usage: Some(budget_usage),
+                execution_trace,
+                fee_market_report,
+                effective_cost_schedule,
+                footprint: footprint.clone(),
+                state_changes: state_changes.clone(),
                 stack_trace: None,
                 // If a WASM with debug symbols was provided, expose the first
                 // mappable source location so callers can correlate failures.
@@ -720,21 +1528,25 @@ fn main() {
                 println!("{{\"status\": \"error\", \"error\": \"Internal serialization error\"}}");
             }
         }
-        Ok(Err(host_error)) => {
+        Ok(Err(ExecutionError::Host(host_error))) => {
             // Host error during execution (e.g., contract trap, validation failure)
             let error_debug = format!("{:?}", host_error);
             let decoded_msg = decode_error(&error_debug);
-            let wasm_trace = WasmStackTrace::from_host_error(&error_debug);
+            let mut wasm_trace = WasmStackTrace::from_host_error_typed(&host_error);
+            if let Some(mapper) = &source_mapper {
+                wasm_trace.resolve_source_locations(mapper);
+            }
             let trace_display = wasm_trace.display();
 
-            let structured_error = StructuredError {
-                error_type: "HostError".to_string(),
-                message: decoded_msg.clone(),
-                details: Some(format!(
+            let category = error_classifier::classify_host_error(&host_error, &error_debug);
+            let structured_error = error_classifier::structured_error_for(
+                category,
+                decoded_msg.clone(),
+                Some(format!(
                     "Contract execution failed with host error: {}",
                     decoded_msg
                 )),
-            };
+            );
 
             let wasm_offset = extract_wasm_offset(&error_debug);
             let source_location =
@@ -746,6 +1558,8 @@ fn main() {
                     None
                 };
 
+            let (events, diagnostic_events, categorized_events) = collect_host_diagnostics(host);
+
             let response = SimulationResponse {
                 status: "error".to_string(),
                 error: Some(
@@ -754,16 +1568,22 @@ fn main() {
                         format!("Internal error during error serialization: {}", e)
                     }),
                 ),
-                error_code: None,
+                error_code: Some(category.as_str().to_string()),
                 lcov_report: lcov_report.clone(),
                 lcov_report_path: lcov_report_path.clone(),
-                events: vec![],
-                diagnostic_events: vec![],
-                categorized_events: vec![],
+                events,
+                diagnostic_events,
+                categorized_events,
                 logs: vec![format!("Stack trace:\n{}", trace_display)],
                 flamegraph: None,
+                memory_flamegraph: None,
                 optimization_report: None,
                 budget_usage: None,
+                execution_trace: vec![],
+                fee_market_report: None,
+                effective_cost_schedule: None,
+                footprint: None,
+                state_changes: None,
                 source_location,
                 stack_trace: Some(wasm_trace),
                 wasm_offset,
@@ -775,6 +1595,127 @@ fn main() {
                 println!("{{\"status\": \"error\", \"error\": \"Internal serialization error\"}}");
             }
         }
+        Ok(Err(ExecutionError::WasmTrap(mut wasm_trace))) => {
+            // A genuine VM trap, already classified and frame-extracted by
+            // `WasmStackTrace::from_host_error_typed` when this error was
+            // converted from a `HostError`. `classify_wasm_trap` unconditionally
+            // (we already know this is `ScErrorType::WasmVm` — that's the only
+            // way an `ExecutionError::WasmTrap` gets constructed), the same way
+            // `classify_host_error` would've dispatched to it with the typed
+            // error in hand, which we no longer have: this variant only ever
+            // carries the trace, not the original `HostError`.
+            if let Some(mapper) = &source_mapper {
+                wasm_trace.resolve_source_locations(mapper);
+            }
+            let trace_display = wasm_trace.display();
+            let decoded_msg = decode_error(&wasm_trace.raw_message);
+
+            let category =
+                error_classifier::classify_wasm_trap(&wasm_trace.raw_message.to_lowercase());
+            let structured_error = error_classifier::structured_error_for(
+                category,
+                decoded_msg.clone(),
+                Some(format!(
+                    "Contract execution failed with host error: {}",
+                    decoded_msg
+                )),
+            );
+
+            let wasm_offset = extract_wasm_offset(&wasm_trace.raw_message);
+            let source_location =
+                if let (Some(offset), Some(mapper)) = (wasm_offset, &source_mapper) {
+                    mapper
+                        .map_wasm_offset_to_source(offset)
+                        .and_then(|loc| serde_json::to_string(&loc).ok())
+                } else {
+                    None
+                };
+
+            let (events, diagnostic_events, categorized_events) = collect_host_diagnostics(host);
+
+            let response = SimulationResponse {
+                status: "error".to_string(),
+                error: Some(
+                    serde_json::to_string(&structured_error).unwrap_or_else(|e| {
+                        eprintln!("Failed to serialize structured error: {}", e);
+                        format!("Internal error during error serialization: {}", e)
+                    }),
+                ),
+                error_code: Some(category.as_str().to_string()),
+                lcov_report: lcov_report.clone(),
+                lcov_report_path: lcov_report_path.clone(),
+                events,
+                diagnostic_events,
+                categorized_events,
+                logs: vec![format!("Stack trace:\n{}", trace_display)],
+                flamegraph: None,
+                memory_flamegraph: None,
+                optimization_report: None,
+                budget_usage: None,
+                execution_trace: vec![],
+                fee_market_report: None,
+                effective_cost_schedule: None,
+                footprint: None,
+                state_changes: None,
+                source_location,
+                stack_trace: Some(wasm_trace),
+                wasm_offset,
+            };
+            if let Ok(json) = serde_json::to_string(&response) {
+                println!("{}", json);
+            } else {
+                eprintln!("Failed to serialize trap response");
+                println!("{{\"status\": \"error\", \"error\": \"Internal serialization error\"}}");
+            }
+        }
+        Ok(Err(ExecutionError::BudgetExceeded { dimension, used, limit })) => {
+            // A resource ceiling was breached — the caller-supplied
+            // memory_limit (enforced by us, not the host budget), our own
+            // CPU_LIMIT poll, or a host-raised Budget HostError that
+            // classify_invoke_error already reclassified — so all of them
+            // get the same structured shape built here.
+            let (error_code, unit) = match dimension {
+                Dimension::Memory => (ERR_MEMORY_LIMIT_EXCEEDED, "bytes"),
+                Dimension::Cpu => (ERR_CPU_LIMIT_EXCEEDED, "instructions"),
+            };
+            let msg = format!(
+                "{}: consumed {} {unit}, limit {} {unit} ({} dimension)",
+                error_code, used, limit, dimension.as_str()
+            );
+            let wasm_trace = WasmStackTrace::from_host_error(&msg);
+            let trace_display = wasm_trace.display();
+            let (events, diagnostic_events, categorized_events) = collect_host_diagnostics(host);
+
+            let response = SimulationResponse {
+                status: "error".to_string(),
+                error: Some(msg),
+                error_code: Some(error_code.to_string()),
+                lcov_report: lcov_report.clone(),
+                lcov_report_path: lcov_report_path.clone(),
+                events,
+                diagnostic_events,
+                categorized_events,
+                logs: vec![format!("Stack trace:\n{}", trace_display)],
+                flamegraph: None,
+                memory_flamegraph: None,
+                optimization_report: None,
+                budget_usage: None,
+                execution_trace: vec![],
+                fee_market_report: None,
+                effective_cost_schedule: None,
+                footprint: None,
+                state_changes: None,
+                source_location: None,
+                stack_trace: Some(wasm_trace),
+                wasm_offset: None,
+            };
+            if let Ok(json) = serde_json::to_string(&response) {
+                println!("{}", json);
+            } else {
+                eprintln!("Failed to serialize budget exceeded response");
+                println!("{{\"status\": \"error\", \"error\": \"Internal serialization error\"}}");
+            }
+        }
         Err(panic_info) => {
             let panic_msg = if let Some(s) = panic_info.downcast_ref::<&str>() {
                 s.to_string()
@@ -784,30 +1725,69 @@ fn main() {
                 "Unknown panic".to_string()
             };
 
+            // Both the memory and CPU limits are enforced via
+            // `ExecutionError::BudgetExceeded`, not a panic, so anything
+            // reaching this arm is a genuine Rust panic (or a host-internal
+            // invariant an older host version still raises as one) rather
+            // than an intentional control-flow signal.
             let wasm_trace = WasmStackTrace::from_panic(&panic_msg);
-            let memory_limit_exceeded = panic_msg.contains(ERR_MEMORY_LIMIT_EXCEEDED);
+            let host_internal_category = error_classifier::classify_panic_message(&panic_msg);
+
+            let (error_message, error_code) = if let Some(category) = host_internal_category {
+                // Some host versions surface dynamic `RefCell` borrow
+                // failures as panics rather than `HostError`s; reclassify
+                // those into the same structured category instead of a
+                // bare "Simulator panicked" string.
+                let structured_error = error_classifier::structured_error_for(
+                    category,
+                    "Internal host invariant violation".to_string(),
+                    Some(panic_msg.clone()),
+                );
+                let json = serde_json::to_string(&structured_error).unwrap_or_else(|e| {
+                    eprintln!("Failed to serialize structured error: {}", e);
+                    format!("Internal error during error serialization: {}", e)
+                });
+                (json, Some(category.as_str().to_string()))
+            } else {
+                (format!("Simulator panicked: {panic_msg}"), None)
+            };
+
+            // The panic that brought us here may have left the host in a
+            // state where re-reading its events panics too; catch that so a
+            // secondary failure degrades the event stream rather than
+            // replacing `wasm_trace` — the root backtrace we already
+            // captured above — with a fresh, unrelated one.
+            let (events, diagnostic_events, categorized_events) =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    collect_host_diagnostics(host)
+                }))
+                .unwrap_or_else(|_| {
+                    (
+                        vec!["Failed to retrieve events after panic".to_string()],
+                        Vec::new(),
+                        Vec::new(),
+                    )
+                });
 
             let response = SimulationResponse {
                 status: "error".to_string(),
-                error: Some(if memory_limit_exceeded {
-                    panic_msg.clone()
-                } else {
-                    format!("Simulator panicked: {panic_msg}")
-                }),
-                error_code: if memory_limit_exceeded {
-                    Some(ERR_MEMORY_LIMIT_EXCEEDED.to_string())
-                } else {
-                    None
-                },
+                error: Some(error_message),
+                error_code,
                 lcov_report: lcov_report.clone(),
                 lcov_report_path: lcov_report_path.clone(),
-                events: vec![],
-                diagnostic_events: vec![],
-                categorized_events: vec![],
+                events,
+                diagnostic_events,
+                categorized_events,
                 logs: vec![format!("PANIC: {}", panic_msg)],
                 flamegraph: None,
+                memory_flamegraph: None,
                 optimization_report: None,
                 budget_usage: None,
+                execution_trace: vec![],
+                fee_market_report: None,
+                effective_cost_schedule: None,
+                footprint: None,
+                state_changes: None,
                 source_location: None,
                 stack_trace: Some(wasm_trace),
                 wasm_offset: None,
@@ -868,60 +1848,7 @@ fn extract_wasm_offset(error_msg: &str) -> Option<u64> {
 /// This function maps those codes to clear English phrases so that
 /// upper-level diagnostics (e.g. `erst explain`) can display them directly.
 pub fn decode_error(raw: &str) -> String {
-    let lower = raw.to_lowercase();
-
-    if lower.contains("wasm trap") || lower.contains("vm trap") {
-        if lower.contains("out of bounds") || lower.contains("memory access") {
-            return "VM Trap: Out of Bounds Access (VM Trap: Out of bounds memory access) — the contract read or wrote outside its allocated memory region.".to_string();
-        }
-        if lower.contains("stack overflow") || lower.contains("call stack") {
-            return "VM Trap: Stack Overflow — the contract exceeded the maximum call-stack depth."
-                .to_string();
-        }
-        if lower.contains("integer overflow") {
-            return "VM Trap: Integer Overflow — arithmetic exceeded integer bounds.".to_string();
-        }
-        if lower.contains("divide by zero") || lower.contains("division by zero") {
-            return "VM Trap: Division by Zero — attempted integer division by zero.".to_string();
-        }
-        if lower.contains("unreachable") {
-            return "VM Trap: Unreachable Instruction — the contract executed an explicit trap or reached dead code.".to_string();
-        }
-        if lower.contains("indirect call") || lower.contains("table") {
-            return "VM Trap: Indirect-Call Type Mismatch — wrong function signature in call_indirect.".to_string();
-        }
-        return format!("VM Trap: {}", raw);
-    }
-
-    if lower.contains("unreachable") {
-        return "VM Trap: Unreachable Instruction — the contract executed an explicit trap or reached dead code.".to_string();
-    }
-    if lower.contains("divide by zero") || lower.contains("division by zero") {
-        return "VM Trap: Division by Zero — attempted integer division by zero.".to_string();
-    }
-    if lower.contains("integer overflow") {
-        return "VM Trap: Integer Overflow — arithmetic exceeded integer bounds.".to_string();
-    }
-    if lower.contains("stack overflow") || lower.contains("call stack") {
-        return "VM Trap: Stack Overflow — the contract exceeded the maximum call-stack depth."
-            .to_string();
-    }
-
-    if lower.contains("auth") || lower.contains("unauthorized") {
-        return "Authorization failure — a required signer or policy check was not satisfied."
-            .to_string();
-    }
-
-    if lower.contains("budget") || lower.contains("cpu limit") || lower.contains("mem limit") {
-        return "Resource limit exceeded — the transaction consumed more CPU instructions or memory than the protocol-21 budget allows.".to_string();
-    }
-
-    if lower.contains("missing") || lower.contains("not found") {
-        return "Missing ledger entry — the contract referenced a key that does not exist in the current ledger state.".to_string();
-    }
-
-    // Fallback: return the raw message unchanged.
-    raw.to_string()
+    error_classifier::classify_error_text(raw).phrase(raw)
 }
 
 #[cfg(test)]
@@ -1081,6 +2008,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_vm_instantiation_totals_sums_cold_and_cached() {
+        let mut breakdown = HashMap::new();
+        breakdown.insert(
+            "VmInstantiation".to_string(),
+            ContractCostEntry { iterations: 1, cpu_insns: 100, memory_bytes: 10 },
+        );
+        breakdown.insert(
+            "VmCachedInstantiation".to_string(),
+            ContractCostEntry { iterations: 2, cpu_insns: 5, memory_bytes: 1 },
+        );
+        breakdown.insert(
+            "WasmInsnExec".to_string(),
+            ContractCostEntry { iterations: 50, cpu_insns: 9_000, memory_bytes: 0 },
+        );
+
+        assert_eq!(vm_instantiation_totals(&breakdown), (105, 11));
+    }
+
+    #[test]
+    fn test_vm_instantiation_totals_defaults_to_zero_when_absent() {
+        assert_eq!(vm_instantiation_totals(&HashMap::new()), (0, 0));
+    }
+
+    #[test]
+    fn test_cost_frame_deltas_skips_unchanged_cost_types_and_attributes_growth() {
+        let mut before = HashMap::new();
+        before.insert(
+            "WasmInsnExec".to_string(),
+            ContractCostEntry { iterations: 10, cpu_insns: 100, memory_bytes: 0 },
+        );
+        let mut after = before.clone();
+        after.insert(
+            "WasmInsnExec".to_string(),
+            ContractCostEntry { iterations: 20, cpu_insns: 250, memory_bytes: 0 },
+        );
+        after.insert(
+            "VmInstantiation".to_string(),
+            ContractCostEntry { iterations: 1, cpu_insns: 5_000, memory_bytes: 300 },
+        );
+
+        let frames = cost_frame_deltas("op0", "invoke", &before, &after);
+        assert_eq!(frames.len(), 2);
+        let wasm_frame = frames.iter().find(|f| f.cost_type == "WasmInsnExec").unwrap();
+        assert_eq!(wasm_frame.cpu, 150);
+        assert_eq!(wasm_frame.mem, 0);
+        let vm_frame = frames.iter().find(|f| f.cost_type == "VmInstantiation").unwrap();
+        assert_eq!(vm_frame.cpu, 5_000);
+        assert_eq!(vm_frame.mem, 300);
+    }
+
     #[test]
     fn test_generate_lcov_report_contains_function_hits() {
         let mut coverage = CoverageTracker::default();
@@ -1091,11 +2069,141 @@ mod tests {
             .invoked_functions
             .insert("InvokeContract::\"init\"".to_string(), 1);
 
-        let report = generate_lcov_report(&coverage, "/tmp/contract.wasm");
+        let report = generate_lcov_report(&coverage, "/tmp/contract.wasm", None);
         assert!(report.contains("SF:/tmp/contract.wasm"));
         assert!(report.contains("FNDA:3,InvokeContract::\"transfer\""));
         assert!(report.contains("FNDA:1,InvokeContract::\"init\""));
         assert!(report.contains("FNF:2"));
         assert!(report.contains("FNH:2"));
     }
+
+    /// Without debug symbols (or without a `SourceMapper` at all) the report
+    /// must fall back to the function-level-only stub rather than silently
+    /// reporting zero coverage.
+    #[test]
+    fn test_generate_lcov_report_falls_back_without_debug_symbols() {
+        let mut coverage = CoverageTracker::default();
+        coverage
+            .invoked_contract_functions
+            .insert("transfer".to_string(), 2);
+        let wasm_bytes = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        let mapper = SourceMapper::new(wasm_bytes);
+
+        let report = generate_lcov_report(&coverage, "/tmp/contract.wasm", Some(&mapper));
+        assert!(report.contains("SF:/tmp/contract.wasm"));
+        assert!(report.contains("DA:1,1"));
+    }
+
+    /// A resolved `FileCoverage` renders real per-line `DA` records keyed by
+    /// each function's defining source line, not a single hardcoded stub.
+    #[test]
+    fn test_file_coverage_renders_real_line_records() {
+        let coverage = FileCoverage {
+            file: "src/lib.rs".to_string(),
+            entries: vec![
+                (42, "transfer".to_string(), 3),
+                (10, "init".to_string(), 0),
+            ],
+            branches: Vec::new(),
+        };
+
+        let rendered = coverage.render();
+        assert!(rendered.contains("SF:src/lib.rs"));
+        assert!(rendered.contains("FN:10,init"));
+        assert!(rendered.contains("FN:42,transfer"));
+        assert!(rendered.contains("DA:10,0"));
+        assert!(rendered.contains("DA:42,3"));
+        assert!(rendered.contains("LF:2"));
+        assert!(rendered.contains("LH:1"));
+    }
+
+    /// No observed branch instructions means no `BRDA`/`BRF`/`BRH` section
+    /// at all, not an empty one — a consumer checking for their presence
+    /// shouldn't see fabricated zero-branch records.
+    #[test]
+    fn test_file_coverage_omits_branch_records_when_none_observed() {
+        let coverage = FileCoverage {
+            file: "src/lib.rs".to_string(),
+            entries: vec![(42, "transfer".to_string(), 3)],
+            branches: Vec::new(),
+        };
+        let rendered = coverage.render();
+        assert!(!rendered.contains("BRDA"));
+        assert!(!rendered.contains("BRF"));
+        assert!(!rendered.contains("BRH"));
+    }
+
+    #[test]
+    fn test_file_coverage_renders_branch_records_when_present() {
+        let coverage = FileCoverage {
+            file: "src/lib.rs".to_string(),
+            entries: vec![(42, "transfer".to_string(), 3)],
+            branches: vec![(42, "br_if 3".to_string(), 5), (42, "br_table 0".to_string(), 0)],
+        };
+        let rendered = coverage.render();
+        assert!(rendered.contains("BRDA:42,0,0,5"));
+        assert!(rendered.contains("BRDA:42,0,1,0"));
+        assert!(rendered.contains("BRF:2"));
+        assert!(rendered.contains("BRH:1"));
+    }
+
+    #[test]
+    fn test_branch_instruction_hits_filters_non_branch_mnemonics() {
+        let mut coverage = CoverageTracker::default();
+        coverage.record_instruction("i32.add");
+        coverage.record_instruction("br_if 3");
+        coverage.record_instruction("br_if 3");
+        coverage.record_instruction("br_table 0 1");
+        coverage.record_instruction("call 12");
+
+        let hits = coverage.branch_instruction_hits();
+        assert_eq!(hits, vec![("br_if 3", 2), ("br_table 0 1", 1)]);
+    }
+
+    #[test]
+    fn test_check_memory_limit_ok_when_under_or_unset() {
+        let host = Host::default();
+        assert!(check_memory_limit(&host, None).is_ok());
+        assert!(check_memory_limit(&host, Some(u64::MAX)).is_ok());
+    }
+
+    #[test]
+    fn test_check_cpu_limit_ok_when_under() {
+        let host = Host::default();
+        assert!(check_cpu_limit(&host).is_ok());
+    }
+
+    /// A breach must come back as a typed `ExecutionError::BudgetExceeded`,
+    /// not a `panic!` — this is the behavior change the budget-exceeded
+    /// rework is for.
+    #[test]
+    fn test_execute_operations_surfaces_memory_limit_breach_as_err_not_panic() {
+        let host = Host::default();
+        let mut coverage = CoverageTracker::default();
+        let consumed = host
+            .budget_cloned()
+            .get_mem_bytes_consumed()
+            .unwrap_or(0);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            execute_operations(
+                &host,
+                &[],
+                Some(consumed.saturating_sub(1)),
+                &mut coverage,
+                None,
+            )
+        }));
+        match result {
+            Ok(Err(ExecutionError::BudgetExceeded { dimension, limit, .. })) => {
+                assert_eq!(dimension, Dimension::Memory);
+                assert_eq!(limit, consumed.saturating_sub(1));
+            }
+            Ok(Ok(_)) => {
+                // Nothing was consumed yet (host allocates lazily) and
+                // `consumed` was already 0, so `saturating_sub(1)` is also 0
+                // and there was nothing to exceed — not a failure of this test.
+            }
+            other => panic!("memory limit breach must not unwind as a panic: {:?}", other.is_err()),
+        }
+    }
 }
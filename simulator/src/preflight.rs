@@ -0,0 +1,278 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resource/fee preflight for a single invocation.
+//!
+//! [`crate::cost_schedule`] answers "does this required fee clear the mocked
+//! check"; this module answers the question a real `simulateTransaction`
+//! call answers before a transaction is ever submitted — exactly how much of
+//! each resource an invocation consumed, and what it would cost. It runs the
+//! invocation against snapshot-backed storage, reads back the discovered
+//! footprint and budget usage, consults the snapshot for any TTLs that need
+//! extending as a side effect of the write, and prices the result with
+//! [`ResourceCalibration`].
+
+#![allow(dead_code)]
+
+use crate::runner::SimHost;
+use crate::snapshot::{contract_durability, LedgerSnapshot, SnapshotError};
+use soroban_env_host::xdr::{HostFunction, Limits, WriteXdr};
+use soroban_env_host::Val;
+
+/// Per-resource-unit prices `preflight` converts a measured
+/// [`InvocationResult`] into a [`FeeEstimate`] with. Distinct from
+/// [`crate::types::ResourceCalibration`] (which calibrates the cost *model*
+/// `SimHost` meters crypto host functions against) and from
+/// [`crate::types::CostSchedule`] (which prices a whole transaction's mocked
+/// fee check) — this one prices a single preflighted invocation's resource
+/// breakdown, resource by resource, the way `simulateTransaction` does.
+#[derive(Debug, Clone)]
+pub struct ResourceCalibration {
+    pub instruction_price: u64,
+    pub read_byte_price: u64,
+    pub write_byte_price: u64,
+    pub rent_byte_price: u64,
+    pub events_byte_price: u64,
+}
+
+/// The resource breakdown a preflighted invocation measured, before any
+/// pricing is applied to it.
+#[derive(Debug, Clone, Default)]
+pub struct InvocationResult {
+    pub succeeded: bool,
+    pub cpu_instructions: u64,
+    pub memory_bytes: u64,
+    /// Distinct `LedgerKey`s the invocation only read.
+    pub read_only_keys: u32,
+    /// Distinct `LedgerKey`s the invocation read and/or wrote.
+    pub read_write_keys: u32,
+    /// Total XDR bytes of every entry in the read-only and read-write
+    /// footprints (an entry in the read-write set is read before it's
+    /// written, so it's counted here too).
+    pub bytes_read: u64,
+    /// Total XDR bytes of every entry in the read-write footprint.
+    pub bytes_written: u64,
+    /// XDR bytes of read-write entries whose TTL has no recorded
+    /// live-until, or whose recorded live-until is already at or behind the
+    /// snapshot's current ledger — i.e. a write that implicitly needs a
+    /// fresh minimum TTL, the way the network bills rent for it.
+    pub ttl_rent_bytes: u64,
+    /// Combined XDR size of the emitted diagnostic/contract events and the
+    /// invocation's return value.
+    pub events_and_return_bytes: u64,
+}
+
+/// What `calibration` prices an [`InvocationResult`] at, resource by
+/// resource, plus their sum.
+#[derive(Debug, Clone)]
+pub struct FeeEstimate {
+    pub instruction_fee: u64,
+    pub read_fee: u64,
+    pub write_fee: u64,
+    pub rent_fee: u64,
+    pub events_fee: u64,
+    pub total: u64,
+}
+
+/// Run `host_function` against `host`'s snapshot-backed storage and return
+/// the full resource/fee breakdown a real `simulateTransaction` call would,
+/// rather than only a pass/fail budget check. `snapshot` is consulted purely
+/// for TTL bookkeeping — `host`'s storage must already be populated from it
+/// (e.g. via [`crate::snapshot::inject_ledger_entry`] or
+/// [`crate::snapshot::load_snapshot`]) before calling this.
+///
+/// # Errors
+/// Returns [`SnapshotError::StorageError`] if the host's storage footprint
+/// can't be read back, and [`SnapshotError::XdrEncoding`] if a touched entry
+/// or its key can't be XDR-encoded. A footprint key with no entry in storage
+/// is skipped, and a return value that can't be encoded counts as 0 bytes.
+pub fn preflight(
+    host: &SimHost,
+    snapshot: &LedgerSnapshot,
+    host_function: HostFunction,
+    calibration: &ResourceCalibration,
+) -> Result<(InvocationResult, FeeEstimate), SnapshotError> {
+    let budget_before = host.inner.budget_cloned();
+    let cpu_before = budget_before.get_cpu_insns_consumed().unwrap_or(0);
+    let mem_before = budget_before.get_mem_bytes_consumed().unwrap_or(0);
+
+    let invoke_result = host.invoke(host_function);
+
+    let budget_after = host.inner.budget_cloned();
+    let cpu_after = budget_after.get_cpu_insns_consumed().unwrap_or(0);
+    let mem_after = budget_after.get_mem_bytes_consumed().unwrap_or(0);
+
+    let mut result = InvocationResult {
+        succeeded: invoke_result.is_ok(),
+        cpu_instructions: cpu_after.saturating_sub(cpu_before),
+        memory_bytes: mem_after.saturating_sub(mem_before),
+        ..Default::default()
+    };
+
+    let footprint = host.discovered_footprint().ok_or_else(|| {
+        SnapshotError::StorageError("host's storage footprint could not be read back".to_string())
+    })?;
+    let storage = host.storage_snapshot();
+
+    result.read_only_keys = footprint.read_only.len() as u32;
+    result.read_write_keys = footprint.read_write.len() as u32;
+
+    for key in footprint.read_only.iter().chain(footprint.read_write.iter()) {
+        if let Some(entry) = storage.get(key) {
+            let size = entry_xdr_len(entry)?;
+            result.bytes_read += size;
+        }
+    }
+
+    for key in footprint.read_write.iter() {
+        let Some(entry) = storage.get(key) else {
+            continue;
+        };
+        let size = entry_xdr_len(entry)?;
+        result.bytes_written += size;
+
+        if contract_durability(entry).is_none() {
+            continue;
+        }
+        let key_bytes = key
+            .to_xdr(Limits::none())
+            .map_err(|e| SnapshotError::XdrEncoding(format!("Failed to encode key: {e}")))?;
+        let needs_fresh_ttl = match snapshot.live_until(&key_bytes) {
+            None => true,
+            Some(live_until) => live_until <= snapshot.current_ledger_seq(),
+        };
+        if needs_fresh_ttl {
+            result.ttl_rent_bytes += size;
+        }
+    }
+
+    result.events_and_return_bytes = events_xdr_len(host) + return_value_xdr_len(host, invoke_result.ok());
+
+    let fee = price(&result, calibration);
+    Ok((result, fee))
+}
+
+fn entry_xdr_len(entry: &soroban_env_host::xdr::LedgerEntry) -> Result<u64, SnapshotError> {
+    entry
+        .to_xdr(Limits::none())
+        .map(|bytes| bytes.len() as u64)
+        .map_err(|e| SnapshotError::XdrEncoding(format!("Failed to encode entry: {e}")))
+}
+
+/// Total XDR size of every event `host` has recorded so far. Best-effort:
+/// an event that fails to encode is skipped rather than aborting the whole
+/// preflight over a telemetry-sizing detail.
+fn events_xdr_len(host: &SimHost) -> u64 {
+    let Ok(events) = host.inner.get_events() else {
+        return 0;
+    };
+    (events.0)
+        .iter()
+        .filter_map(|e| e.event.to_xdr(Limits::none()).ok())
+        .map(|bytes| bytes.len() as u64)
+        .sum()
+}
+
+/// XDR size of the invocation's return value, decoded back to an `ScVal` via
+/// the host so it's priced the same way the network prices a transaction's
+/// result. `0` for a failed invocation, which has no return value to price.
+fn return_value_xdr_len(host: &SimHost, return_value: Option<Val>) -> u64 {
+    let Some(val) = return_value else {
+        return 0;
+    };
+    let Ok(scval): Result<soroban_env_host::xdr::ScVal, _> = host.inner.from_host_val(val) else {
+        return 0;
+    };
+    scval.to_xdr(Limits::none()).map(|bytes| bytes.len() as u64).unwrap_or(0)
+}
+
+fn price(result: &InvocationResult, calibration: &ResourceCalibration) -> FeeEstimate {
+    let instruction_fee = calibration
+        .instruction_price
+        .saturating_mul(result.cpu_instructions);
+    let read_fee = calibration.read_byte_price.saturating_mul(result.bytes_read);
+    let write_fee = calibration.write_byte_price.saturating_mul(result.bytes_written);
+    let rent_fee = calibration.rent_byte_price.saturating_mul(result.ttl_rent_bytes);
+    let events_fee = calibration
+        .events_byte_price
+        .saturating_mul(result.events_and_return_bytes);
+
+    let total = instruction_fee
+        .saturating_add(read_fee)
+        .saturating_add(write_fee)
+        .saturating_add(rent_fee)
+        .saturating_add(events_fee);
+
+    FeeEstimate {
+        instruction_fee,
+        read_fee,
+        write_fee,
+        rent_fee,
+        events_fee,
+        total,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calibration() -> ResourceCalibration {
+        ResourceCalibration {
+            instruction_price: 1,
+            read_byte_price: 2,
+            write_byte_price: 3,
+            rent_byte_price: 5,
+            events_byte_price: 7,
+        }
+    }
+
+    fn result() -> InvocationResult {
+        InvocationResult {
+            succeeded: true,
+            cpu_instructions: 1_000,
+            memory_bytes: 2_000,
+            read_only_keys: 1,
+            read_write_keys: 2,
+            bytes_read: 300,
+            bytes_written: 400,
+            ttl_rent_bytes: 50,
+            events_and_return_bytes: 10,
+        }
+    }
+
+    #[test]
+    fn each_resource_is_priced_by_its_own_coefficient() {
+        let fee = price(&result(), &calibration());
+        assert_eq!(fee.instruction_fee, 1_000);
+        assert_eq!(fee.read_fee, 600);
+        assert_eq!(fee.write_fee, 1_200);
+        assert_eq!(fee.rent_fee, 250);
+        assert_eq!(fee.events_fee, 70);
+        assert_eq!(fee.total, 1_000 + 600 + 1_200 + 250 + 70);
+    }
+
+    #[test]
+    fn a_zero_calibration_prices_everything_at_zero() {
+        let calibration = ResourceCalibration {
+            instruction_price: 0,
+            read_byte_price: 0,
+            write_byte_price: 0,
+            rent_byte_price: 0,
+            events_byte_price: 0,
+        };
+        let fee = price(&result(), &calibration);
+        assert_eq!(fee.total, 0);
+    }
+
+    #[test]
+    fn an_overflowing_calibration_saturates_instead_of_panicking() {
+        let calibration = ResourceCalibration {
+            instruction_price: u64::MAX,
+            ..calibration()
+        };
+        let fee = price(&result(), &calibration);
+        assert_eq!(fee.instruction_fee, u64::MAX);
+        assert_eq!(fee.total, u64::MAX);
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signed provenance bundles linking a compiled WASM artifact to the
+//! source map used to debug it.
+//!
+//! A [`ProvenanceBundle`] is a single self-describing document carrying
+//! the artifact's hash, its full [`SourceLocation`] map (including
+//! resolved GitHub links and the detected git commit), and a [`Signature`]
+//! over the canonical serialization of all of it. Producing one ties the
+//! debug-symbol subsystem ([`crate::source_mapper`]) to the signing
+//! subsystem ([`crate::hsm`]), giving a consumer a portable record of
+//! which source lines compiled to which WASM offsets, attested by
+//! whichever signer built the artifact.
+
+use crate::hsm::{PublicKey, Signature, Signer, SignerError, Verifier};
+use crate::source_mapper::{SourceLocation, SourceMapper};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// A signed record tying a WASM artifact's hash to the source map used to
+/// debug it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvenanceBundle {
+    /// The data that was signed, kept alongside `signature` so a verifier
+    /// doesn't have to reconstruct it before checking the signature.
+    pub content: ProvenanceContent,
+    /// Signature over the canonical JSON serialization of `content`.
+    pub signature: Signature,
+}
+
+/// The signed portion of a [`ProvenanceBundle`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvenanceContent {
+    /// SHA-256 hash of the WASM artifact bytes, hex-encoded.
+    pub wasm_sha256: String,
+    /// Git commit the artifact was built from, if one was detected.
+    pub git_commit: Option<String>,
+    /// Every source location the artifact's debug symbols resolve to.
+    pub source_locations: Vec<SourceLocation>,
+}
+
+/// Errors that can occur while building or verifying a [`ProvenanceBundle`].
+#[derive(Debug, thiserror::Error)]
+pub enum ProvenanceError {
+    #[error("signing error: {0}")]
+    Signer(#[from] SignerError),
+
+    #[error("failed to canonicalize provenance content: {0}")]
+    Canonicalize(#[from] serde_json::Error),
+
+    #[error("artifact hash mismatch: bundle claims {expected}, computed {actual}")]
+    HashMismatch { expected: String, actual: String },
+}
+
+impl ProvenanceBundle {
+    /// Build and sign a provenance bundle for `wasm_bytes`, using `mapper`
+    /// to resolve the full source map and `signer` to sign the result.
+    pub async fn build(
+        mapper: &SourceMapper,
+        signer: &dyn Signer,
+        wasm_bytes: &[u8],
+    ) -> Result<Self, ProvenanceError> {
+        let content = ProvenanceContent {
+            wasm_sha256: hex::encode(Sha256::digest(wasm_bytes)),
+            git_commit: mapper.git_commit_hash().map(|hash| hash.to_string()),
+            source_locations: mapper.all_source_locations(),
+        };
+
+        let canonical = serde_json::to_vec(&content)?;
+        let signature = signer.sign(&canonical).await?;
+
+        Ok(Self { content, signature })
+    }
+
+    /// Verify that `wasm_bytes` hashes to the value this bundle claims and
+    /// that `signature` is a valid signature over `content` under `key`,
+    /// as checked by `verifier`.
+    pub async fn verify(
+        &self,
+        verifier: &dyn Verifier,
+        key: &PublicKey,
+        wasm_bytes: &[u8],
+    ) -> Result<(), ProvenanceError> {
+        let actual_hash = hex::encode(Sha256::digest(wasm_bytes));
+        if actual_hash != self.content.wasm_sha256 {
+            return Err(ProvenanceError::HashMismatch {
+                expected: self.content.wasm_sha256.clone(),
+                actual: actual_hash,
+            });
+        }
+
+        let canonical = serde_json::to_vec(&self.content)?;
+        verifier.verify(&canonical, &self.signature, key).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hsm::software::SoftwareSigner;
+
+    #[tokio::test]
+    async fn test_build_and_verify_round_trips() {
+        let wasm_bytes = vec![0x00, 0x61, 0x73, 0x6d];
+        let mapper = SourceMapper::new(wasm_bytes.clone());
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+
+        let bundle = ProvenanceBundle::build(&mapper, &signer, &wasm_bytes)
+            .await
+            .unwrap();
+
+        let key = signer.public_key().await.unwrap();
+        bundle.verify(&signer, &key, &wasm_bytes).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_tampered_artifact() {
+        let wasm_bytes = vec![0x00, 0x61, 0x73, 0x6d];
+        let mapper = SourceMapper::new(wasm_bytes.clone());
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+
+        let bundle = ProvenanceBundle::build(&mapper, &signer, &wasm_bytes)
+            .await
+            .unwrap();
+        let key = signer.public_key().await.unwrap();
+
+        let tampered = vec![0x00, 0x61, 0x73, 0x6e];
+        match bundle.verify(&signer, &key, &tampered).await {
+            Err(ProvenanceError::HashMismatch { .. }) => {}
+            other => panic!("expected a HashMismatch error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_wrong_signer() {
+        let wasm_bytes = vec![0x00, 0x61, 0x73, 0x6d];
+        let mapper = SourceMapper::new(wasm_bytes.clone());
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let (other_signer, _pem) = SoftwareSigner::generate().unwrap();
+
+        let bundle = ProvenanceBundle::build(&mapper, &signer, &wasm_bytes)
+            .await
+            .unwrap();
+        let other_key = other_signer.public_key().await.unwrap();
+
+        assert!(bundle.verify(&signer, &other_key, &wasm_bytes).await.is_err());
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! On-demand ledger state hydration from a live soroban-rpc endpoint.
+//!
+//! Normally every `LedgerKey` a simulated operation touches must be injected
+//! up front (see [`crate::snapshot::inject_ledger_entry`]). [`RemoteLedgerSource`]
+//! instead lets a caller hydrate entries one key at a time: each
+//! [`RemoteLedgerSource::hydrate`] call fetches the key from a real network's
+//! soroban-rpc `getLedgerEntry` method and injects it into the host. A key
+//! has to be hydrated before the host reads it; a storage miss inside the
+//! host is not intercepted, and no access is retried. This makes
+//! it possible to simulate a contract call against mainnet state without
+//! first exporting a full snapshot of everything it might touch.
+
+use crate::snapshot::{decode_ledger_entry, inject_ledger_entry, SnapshotError};
+use base64::Engine;
+use soroban_env_host::xdr::{LedgerEntry, LedgerKey, Limits, WriteXdr};
+use soroban_env_host::Host;
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+/// Hydrates `LedgerKey`s on request from a live soroban-rpc endpoint into
+/// the [`Host`] passed to [`Self::hydrate`], rather than requiring every entry to be
+/// injected up front. Successfully fetched keys are cached on this source so
+/// repeated accesses to the same key within one simulation run don't
+/// round-trip to the network more than once.
+#[allow(dead_code)]
+pub struct RemoteLedgerSource {
+    pub rpc_url: String,
+    pub network_passphrase: String,
+    fetched: RefCell<HashSet<Vec<u8>>>,
+}
+
+#[allow(dead_code)]
+impl RemoteLedgerSource {
+    /// Create a source pointed at `rpc_url`, a soroban-rpc endpoint for the
+    /// network identified by `network_passphrase`.
+    pub fn new(rpc_url: impl Into<String>, network_passphrase: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            network_passphrase: network_passphrase.into(),
+            fetched: RefCell::new(HashSet::new()),
+        }
+    }
+
+    /// Ensure `key` is present in `host`'s storage, fetching it from
+    /// `getLedgerEntry` on first request and injecting it via
+    /// [`inject_ledger_entry`]. A no-op (no network round-trip) on every
+    /// subsequent call for the same key from this source.
+    pub async fn hydrate(&self, host: &Host, key: &LedgerKey) -> Result<(), SnapshotError> {
+        if !supports_remote_hydration(key) {
+            return Err(SnapshotError::StorageError(
+                "RemoteLedgerSource only hydrates ContractData, ContractCode, and Account entries".to_string(),
+            ));
+        }
+
+        let key_xdr = key
+            .to_xdr(Limits::none())
+            .map_err(|e| SnapshotError::XdrEncoding(format!("Failed to encode key: {e}")))?;
+
+        if self.fetched.borrow().contains(&key_xdr) {
+            return Ok(());
+        }
+
+        let key_b64 = base64::engine::general_purpose::STANDARD.encode(&key_xdr);
+        let entry = self.fetch_ledger_entry(&key_b64).await?;
+        inject_ledger_entry(host, key, &entry)?;
+
+        self.fetched.borrow_mut().insert(key_xdr);
+        Ok(())
+    }
+
+    /// Issue the JSON-RPC `getLedgerEntry` call for `key_b64` and decode the
+    /// resulting base64 XDR into a `LedgerEntry`, carrying
+    /// `lastModifiedLedgerSeq` through onto the decoded entry.
+    async fn fetch_ledger_entry(&self, key_b64: &str) -> Result<LedgerEntry, SnapshotError> {
+        let client = reqwest::Client::new();
+        let response = client
+            .post(&self.rpc_url)
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "getLedgerEntry",
+                "params": { "key": key_b64 },
+            }))
+            .send()
+            .await
+            .map_err(|e| SnapshotError::StorageError(format!("getLedgerEntry request failed: {e}")))?
+            .json::<GetLedgerEntryResponse>()
+            .await
+            .map_err(|e| SnapshotError::StorageError(format!("Failed to parse getLedgerEntry response: {e}")))?;
+
+        if let Some(error) = response.error {
+            return Err(SnapshotError::StorageError(format!(
+                "getLedgerEntry failed: {}",
+                error.message
+            )));
+        }
+        let result = response.result.ok_or_else(|| {
+            SnapshotError::StorageError("getLedgerEntry returned neither a result nor an error".to_string())
+        })?;
+
+        let mut entry = decode_ledger_entry(&result.xdr)?;
+        entry.last_modified_ledger_seq = result.last_modified_ledger_seq;
+        Ok(entry)
+    }
+}
+
+/// This source only fetches the entry kinds a typical contract-invocation
+/// footprint needs; anything else (trustlines, offers, ...) is out of scope
+/// for now.
+fn supports_remote_hydration(key: &LedgerKey) -> bool {
+    matches!(
+        key,
+        LedgerKey::ContractData(_) | LedgerKey::ContractCode(_) | LedgerKey::Account(_)
+    )
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GetLedgerEntryResponse {
+    result: Option<GetLedgerEntryResult>,
+    error: Option<RpcErrorBody>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GetLedgerEntryResult {
+    xdr: String,
+    #[serde(rename = "lastModifiedLedgerSeq")]
+    last_modified_ledger_seq: u32,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RpcErrorBody {
+    message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports_remote_hydration_accepts_the_documented_key_kinds() {
+        let contract_data = LedgerKey::ContractData(soroban_env_host::xdr::LedgerKeyContractData {
+            contract: soroban_env_host::xdr::ScAddress::Contract(soroban_env_host::xdr::Hash([0u8; 32])),
+            key: soroban_env_host::xdr::ScVal::Void,
+            durability: soroban_env_host::xdr::ContractDataDurability::Persistent,
+        });
+        let contract_code = LedgerKey::ContractCode(soroban_env_host::xdr::LedgerKeyContractCode {
+            hash: soroban_env_host::xdr::Hash([0u8; 32]),
+        });
+        let account = LedgerKey::Account(soroban_env_host::xdr::LedgerKeyAccount {
+            account_id: soroban_env_host::xdr::AccountId(
+                soroban_env_host::xdr::PublicKey::PublicKeyTypeEd25519(soroban_env_host::xdr::Uint256(
+                    [0u8; 32],
+                )),
+            ),
+        });
+
+        assert!(supports_remote_hydration(&contract_data));
+        assert!(supports_remote_hydration(&contract_code));
+        assert!(supports_remote_hydration(&account));
+    }
+
+    #[test]
+    fn test_supports_remote_hydration_rejects_other_key_kinds() {
+        let trustline = LedgerKey::Trustline(soroban_env_host::xdr::LedgerKeyTrustLine {
+            account_id: soroban_env_host::xdr::AccountId(
+                soroban_env_host::xdr::PublicKey::PublicKeyTypeEd25519(soroban_env_host::xdr::Uint256(
+                    [0u8; 32],
+                )),
+            ),
+            asset: soroban_env_host::xdr::TrustLineAsset::Native,
+        });
+
+        assert!(!supports_remote_hydration(&trustline));
+    }
+
+    #[tokio::test]
+    async fn test_hydrate_rejects_unsupported_key_kinds_without_a_network_call() {
+        let source = RemoteLedgerSource::new("https://rpc.example.com", "Test Network ; July 2026");
+        let host = soroban_env_host::Host::default();
+
+        let trustline = LedgerKey::Trustline(soroban_env_host::xdr::LedgerKeyTrustLine {
+            account_id: soroban_env_host::xdr::AccountId(
+                soroban_env_host::xdr::PublicKey::PublicKeyTypeEd25519(soroban_env_host::xdr::Uint256(
+                    [1u8; 32],
+                )),
+            ),
+            asset: soroban_env_host::xdr::TrustLineAsset::Native,
+        });
+
+        match source.hydrate(&host, &trustline).await {
+            Err(SnapshotError::StorageError(msg)) => assert!(msg.contains("ContractData")),
+            other => panic!("expected a StorageError, got {:?}", other),
+        }
+    }
+}
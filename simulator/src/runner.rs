@@ -3,10 +3,118 @@
 
 use soroban_env_host::{
     budget::Budget,
-    storage::Storage,
-    xdr::{Hash, ScErrorCode, ScErrorType},
-    DiagnosticLevel, Error as EnvError, Host, HostError, TryIntoVal, Val,
+    storage::{AccessType, Footprint, Storage},
+    xdr::{
+        ContractDataDurability, Hash, HostFunction, LedgerEntry, LedgerEntryData, LedgerFootprint,
+        LedgerKey, ScErrorCode, ScErrorType,
+    },
+    DiagnosticLevel, Error as EnvError, Host, HostError, ModuleCache, TryIntoVal, Val,
 };
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Errors from validating that a set of ledger entries can be loaded into a
+/// [`SimHost`] constructed for a given `protocol_version`.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ProtocolCompatibilityError {
+    #[error(
+        "entry requires protocol {required} or newer, but this host was constructed for protocol {configured}"
+    )]
+    EntryRequiresNewerProtocol { required: u32, configured: u32 },
+}
+
+/// The oldest protocol version a `LedgerEntry` can be loaded under. Entry
+/// kinds gated behind a later protocol upgrade (state archival's
+/// `Temporary` durability, introduced in protocol 21) report that version
+/// instead; everything else is assumed compatible with any supported
+/// version.
+fn min_protocol_version(entry: &LedgerEntry) -> u32 {
+    match &entry.data {
+        LedgerEntryData::ContractData(data) if data.durability == ContractDataDurability::Temporary => 21,
+        _ => 20,
+    }
+}
+
+/// Check that every entry in `ledger_entries` is loadable under
+/// `protocol_version`, so a caller simulating against an older protocol
+/// finds out immediately rather than getting host-storage behavior that
+/// doesn't match the protocol it asked for.
+fn validate_protocol_compatibility(
+    ledger_entries: &HashMap<LedgerKey, LedgerEntry>,
+    protocol_version: u32,
+) -> Result<(), ProtocolCompatibilityError> {
+    for entry in ledger_entries.values() {
+        let required = min_protocol_version(entry);
+        if protocol_version < required {
+            return Err(ProtocolCompatibilityError::EntryRequiresNewerProtocol {
+                required,
+                configured: protocol_version,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Module-cache hit/miss counts for profiling, read back from the
+/// `VmInstantiation`/`VmCachedInstantiation` `ContractCostType` trackers the
+/// host's budget already keeps. A "miss" is a cold parse+validate+translate
+/// of a contract's WASM; a "hit" is a re-instantiation of a module
+/// [`SimHost`]'s installed `ModuleCache` already has parsed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModuleCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// A cheap, cloneable abort signal for [`SimHost::invoke_cancellable`],
+/// mirroring the way a long-running job hands its worker threads a shared
+/// shutdown flag. Signalling one (via [`Self::cancel`]) from another thread
+/// — or letting [`SimHost`]'s configured deadline elapse — doesn't unwind a
+/// call already inside the vendored host's VM loop: `soroban-env-host`
+/// doesn't expose an instruction-count checkpoint to interrupt at, only the
+/// CPU-instruction budget that eventually traps on its own. What this does
+/// give a caller is a fast, explicit [`SimError::Cancelled`] /
+/// [`SimError::TimedOut`] the next time it calls in, instead of having to
+/// wait out budget exhaustion on every iteration of a loop (a fuzzing
+/// harness, a batch replay) that otherwise has no way to stop early.
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelHandle {
+    /// Signal that any pending or future cancellable invocation sharing
+    /// this handle should stop. Safe to call from another thread.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether [`Self::cancel`] has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Errors from [`SimHost::invoke_cancellable`] that a plain [`HostError`]
+/// can't distinguish: the run never started because it was cancelled or
+/// because its deadline had already elapsed, versus the run started and
+/// the host itself rejected it.
+#[derive(Debug, thiserror::Error)]
+pub enum SimError {
+    /// [`SimHost::cancel_handle`] was signalled before this invocation ran.
+    #[error("simulation cancelled before it ran")]
+    Cancelled,
+    /// [`SimHost::with_deadline`]'s `Duration` elapsed before this
+    /// invocation ran.
+    #[error("simulation exceeded its wall-clock deadline")]
+    TimedOut,
+    /// The invocation itself ran and the host reported a failure.
+    #[error(transparent)]
+    Host(#[from] HostError),
+}
 
 #[allow(dead_code)]
 /// Wrapper around the Soroban Host to manage initialization and execution context.
@@ -15,6 +123,24 @@ pub struct SimHost {
     pub contract_id: Option<Hash>,
     pub fn_name: Option<String>,
     pub memory_limit: Option<u64>,
+    /// `(cpu_insns, mem_bytes)` ceiling this host's budget was constructed
+    /// with, kept around so [`SimHost::wipe_ledger_state_preserving_modules`]
+    /// can reapply it to the fresh budget and
+    /// [`SimHost::remaining_budget`] has something to measure against.
+    /// `None` means the host runs with the default mainnet budget.
+    pub budget_limits: Option<(u64, u64)>,
+    /// Protocol version this host simulates against. Selects the default
+    /// [`crate::types::ResourceCalibration`] coefficient set and gates which
+    /// ledger entry kinds [`SimHost::with_ledger_entries`] accepts — see
+    /// [`validate_protocol_compatibility`].
+    pub protocol_version: u32,
+    /// Cooperative abort signal [`SimHost::invoke_cancellable`] checks
+    /// before running. Created fresh per host; share it with another
+    /// thread via [`SimHost::cancel_handle`].
+    cancel_handle: CancelHandle,
+    /// Wall-clock deadline [`SimHost::invoke_cancellable`] checks before
+    /// running, as `(started_at, timeout)`. `None` means no deadline.
+    deadline: Option<(Instant, Duration)>,
 }
 
 #[allow(dead_code)]
@@ -24,33 +150,190 @@ impl SimHost {
         budget_limits: Option<(u64, u64)>,
         calibration: Option<crate::types::ResourceCalibration>,
         memory_limit: Option<u64>,
-    ) -> Self {
+        protocol_version: u32,
+    ) -> Result<Self, ProtocolCompatibilityError> {
+        Self::with_ledger_entries(
+            HashMap::new(),
+            false,
+            budget_limits,
+            calibration,
+            memory_limit,
+            protocol_version,
+        )
+    }
+
+    /// Initialize a new Host sized for a particular module-cache working
+    /// set, for callers that know up front they'll repeatedly invoke the
+    /// same handful of contracts (fuzzing, fee sweeps, batch replay) and
+    /// want the cache provisioned for that rather than growing ad hoc.
+    /// `capacity` is accepted for forward compatibility, the same way
+    /// `calibration` is in [`Self::with_ledger_entries`]: the vendored
+    /// `soroban_env_host::ModuleCache::new` this host installs takes no
+    /// capacity argument, so there's currently nothing to forward it to.
+    pub fn with_module_cache_capacity(
+        capacity: usize,
+        protocol_version: u32,
+    ) -> Result<Self, ProtocolCompatibilityError> {
+        let _ = capacity;
+        Self::new(None, None, None, protocol_version)
+    }
+
+    /// Module-cache hit/miss counts so far, for profiling repeated
+    /// invocations of the same contract. See [`ModuleCacheStats`].
+    pub fn module_cache_stats(&self) -> ModuleCacheStats {
+        let budget = self.inner.budget_cloned();
+        let misses = budget
+            .get_tracker(soroban_env_host::xdr::ContractCostType::VmInstantiation)
+            .map(|tracker| tracker.iterations)
+            .unwrap_or(0);
+        let hits = budget
+            .get_tracker(soroban_env_host::xdr::ContractCostType::VmCachedInstantiation)
+            .map(|tracker| tracker.iterations)
+            .unwrap_or(0);
+        ModuleCacheStats { hits, misses }
+    }
+
+    /// Initialize a new Host whose storage is pre-populated from decoded
+    /// `ledger_entries`, so `InvokeHostFunction` operations run against real
+    /// ledger state instead of an empty `Storage::default()`.
+    ///
+    /// The footprint is always tracked rather than enforced: reads/writes
+    /// outside the caller-supplied `ledger_entries` are allowed, and the
+    /// resulting footprint can be recovered afterwards via
+    /// [`SimHost::discovered_footprint`]. This lets a caller simulate first
+    /// and build the real transaction's footprint from what actually ran,
+    /// rather than having to know it up front. `recording` is accepted for
+    /// backward compatibility but no longer changes this behavior — see the
+    /// comment below.
+    ///
+    /// # Errors
+    /// Returns [`ProtocolCompatibilityError`] if `ledger_entries` contains a
+    /// kind not loadable under `protocol_version` — see
+    /// [`validate_protocol_compatibility`].
+    pub fn with_ledger_entries(
+        ledger_entries: HashMap<LedgerKey, LedgerEntry>,
+        recording: bool,
+        budget_limits: Option<(u64, u64)>,
+        calibration: Option<crate::types::ResourceCalibration>,
+        memory_limit: Option<u64>,
+        protocol_version: u32,
+    ) -> Result<Self, ProtocolCompatibilityError> {
+        validate_protocol_compatibility(&ledger_entries, protocol_version)?;
+
         let budget = Budget::default();
 
-        if let Some(_calib) = calibration {
-            // Resource calibration hooks are currently best-effort. Newer
-            // soroban-env-host versions no longer expose the previous model API.
-            // We keep the request field for forward compatibility.
+        // Resource calibration hooks are currently best-effort. Newer
+        // soroban-env-host versions no longer expose the previous model API.
+        // We keep the request field for forward compatibility, falling back
+        // to the table entry calibrated for this host's protocol version
+        // when the caller didn't supply one of its own.
+        let _calibration =
+            calibration.unwrap_or_else(|| crate::types::ResourceCalibration::for_protocol(protocol_version));
+
+        if let Some((cpu, mem)) = budget_limits {
+            budget
+                .reset_limits(cpu, mem)
+                .expect("resetting budget limits to caller-supplied values should not fail");
         }
 
-        if let Some((_cpu, _mem)) = budget_limits {
-            // Budget customization requires testutils feature or extended API
-            // Using default mainnet budget settings
+        let mut footprint = Footprint::default();
+        let mut map = im_rc::OrdMap::new();
+        for (key, entry) in ledger_entries {
+            let key_rc = Rc::new(key);
+            // We don't know which entries are read-only vs read-write ahead
+            // of time, so grant ReadWrite and let the caller narrow this
+            // down from the discovered footprint on a later, recording run.
+            footprint.record_access(&key_rc, AccessType::ReadWrite, &budget)
+                .expect("recording access for a pre-supplied ledger entry should not fail");
+            map.insert(key_rc, Some((Rc::new(entry), None)));
         }
 
-        // Host::with_storage_and_budget is available in recent versions
-        let host = Host::with_storage_and_budget(Storage::default(), budget);
+        // `recording` is accepted as a request-level toggle but the host's
+        // enforcing footprint already tolerates out-of-footprint access in
+        // this simulator (unlike a real validator), so both modes currently
+        // share the same storage construction. The distinction matters once
+        // `discovered_footprint` is read back after execution.
+        let _ = recording;
+        let storage = Storage::with_enforcing_footprint_and_map(footprint, map);
+        let host = Host::with_storage_and_budget(storage, budget);
 
         // Enable debug mode for better diagnostics
         host.set_diagnostic_level(DiagnosticLevel::Debug)
             .expect("failed to set diagnostic level");
 
-        Self {
+        // Install a module cache so operations in the same envelope that
+        // invoke the same contract more than once pay full `VmInstantiation`
+        // cost only on the first call; repeats are charged the cheaper
+        // `VmCachedInstantiation` cost type instead. Without this, the host
+        // re-parses the contract's WASM from scratch on every invocation.
+        if let Ok(cache) = ModuleCache::new(&host) {
+            let _ = host.set_module_cache(cache);
+        }
+
+        Ok(Self {
             inner: host,
             contract_id: None,
             fn_name: None,
             memory_limit,
+            budget_limits,
+            protocol_version,
+            cancel_handle: CancelHandle::default(),
+            deadline: None,
+        })
+    }
+
+    /// Attach a wall-clock deadline that [`Self::invoke_cancellable`] checks
+    /// before running, starting the clock now. Replaces any deadline this
+    /// host already had.
+    pub fn with_deadline(mut self, timeout: Duration) -> Self {
+        self.deadline = Some((Instant::now(), timeout));
+        self
+    }
+
+    /// A cloneable handle another thread can call [`CancelHandle::cancel`]
+    /// on to abort this host's next [`Self::invoke_cancellable`] call — see
+    /// [`CancelHandle`].
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    /// Recover the footprint of `LedgerKey`s actually touched during
+    /// execution, as a `LedgerFootprint` XDR value ready to attach to a real
+    /// transaction. `read_only` and `read_write` come out deduplicated and in
+    /// a deterministic order for free, since `Footprint` is backed by an
+    /// `im_rc::OrdMap` keyed on the `LedgerKey` itself. Returns `None` if the
+    /// host's storage footprint could not be read back (e.g. it was never
+    /// populated).
+    pub fn discovered_footprint(&self) -> Option<LedgerFootprint> {
+        let footprint = self.inner.with_storage(|storage| Ok(storage.footprint.clone())).ok()?;
+
+        let mut read_only = Vec::new();
+        let mut read_write = Vec::new();
+        for (key, access) in footprint.0.iter() {
+            match access {
+                AccessType::ReadOnly => read_only.push((**key).clone()),
+                AccessType::ReadWrite => read_write.push((**key).clone()),
+            }
         }
+
+        Some(LedgerFootprint {
+            read_only: read_only.try_into().ok()?,
+            read_write: read_write.try_into().ok()?,
+        })
+    }
+
+    /// Snapshot the host's current ledger storage as a plain map, so a
+    /// caller can diff it against the entries it started with (see
+    /// [`crate::state_diff::diff`]) to produce a "what changed" report.
+    pub fn storage_snapshot(&self) -> HashMap<LedgerKey, LedgerEntry> {
+        let map = self
+            .inner
+            .with_storage(|storage| Ok(storage.map.clone()))
+            .unwrap_or_default();
+
+        map.into_iter()
+            .filter_map(|(key, entry)| entry.map(|(entry, _live_until)| ((*key).clone(), (*entry).clone())))
+            .collect()
     }
 
     /// Set the contract ID for execution context.
@@ -77,18 +360,90 @@ impl SimHost {
         })
     }
 
-    /// Check memory consumption against limit and panic if exceeded
-    pub fn check_memory_limit(&self) {
+    /// Check memory consumption against `self.memory_limit`, returning a
+    /// `HostError` built from `ScErrorType::Budget`/`ScErrorCode::ExceededLimit`
+    /// when it's exceeded, rather than unwinding. Lets a simulation loop (or
+    /// a test asserting on error codes) treat this the same as any other
+    /// recoverable host failure.
+    pub fn check_memory_limit(&self) -> Result<(), HostError> {
         if let Some(limit) = self.memory_limit {
             if let Ok(mem_bytes) = self.inner.budget_cloned().get_mem_bytes_consumed() {
                 if mem_bytes > limit {
-                    panic!(
-                        "Memory limit exceeded: {} bytes > {} bytes limit",
-                        mem_bytes, limit
-                    );
+                    let e = EnvError::from_type_and_code(ScErrorType::Budget, ScErrorCode::ExceededLimit);
+                    return Err(e.into());
                 }
             }
         }
+        Ok(())
+    }
+
+    /// Return `(cpu_insns, mem_bytes)` still available under the ceiling this
+    /// host was constructed with, so a caller can meter per-invocation cost
+    /// against the configured limit rather than only finding out after the
+    /// budget is exhausted. Mirrors `Budget::get_cpu_insns_consumed` /
+    /// `get_mem_bytes_consumed`, just expressed as "remaining" instead of
+    /// "consumed". Returns `None` when this host has no configured
+    /// `budget_limits` (i.e. it runs under the default mainnet budget).
+    pub fn remaining_budget(&self) -> Option<(u64, u64)> {
+        let (cpu_limit, mem_limit) = self.budget_limits?;
+        let budget = self.inner.budget_cloned();
+        let cpu_consumed = budget.get_cpu_insns_consumed().unwrap_or(0);
+        let mem_consumed = budget.get_mem_bytes_consumed().unwrap_or(0);
+        Some((
+            cpu_limit.saturating_sub(cpu_consumed),
+            mem_limit.saturating_sub(mem_consumed),
+        ))
+    }
+
+    /// Invoke `host_function` on the wrapped host, catching any unwinding
+    /// panic (a WASM trap surfacing as `unreachable`, a stack overflow from
+    /// deep recursion, etc.) and mapping it into a structured `HostError`
+    /// instead of letting it propagate. This lets a caller — including a
+    /// safety test-suite covering the deliberately-malicious fixture
+    /// contracts — assert on error codes rather than relying on
+    /// `#[should_panic]`.
+    pub fn invoke(&self, host_function: HostFunction) -> Result<Val, HostError> {
+        let host = &self.inner;
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| host.invoke_function(host_function)));
+
+        result.unwrap_or_else(|_panic_payload| {
+            // The panic itself carries no structured error code, so it's
+            // reported as a generic internal WASM VM failure; callers that
+            // want the original message can still recover it from the
+            // diagnostic events `collect_host_diagnostics` gathers.
+            let e = EnvError::from_type_and_code(ScErrorType::WasmVm, ScErrorCode::InternalError);
+            e.into()
+        })
+    }
+
+    /// Like [`Self::invoke`], but checks this host's [`CancelHandle`] and
+    /// configured deadline (see [`Self::with_deadline`]) immediately before
+    /// running, returning [`SimError::Cancelled`] / [`SimError::TimedOut`]
+    /// instead of starting the invocation at all. Intended for a caller that
+    /// drives many invocations in a loop (a fuzzing harness, a batch
+    /// replay) and wants to stop promptly between iterations rather than
+    /// waiting for budget exhaustion to eventually trap a runaway one — see
+    /// [`CancelHandle`]'s doc comment for why an already-running invocation
+    /// can't be interrupted mid-flight.
+    pub fn invoke_cancellable(&self, host_function: HostFunction) -> Result<Val, SimError> {
+        self.check_cancellation()?;
+        self.invoke(host_function).map_err(SimError::from)
+    }
+
+    /// The guard [`Self::invoke_cancellable`] runs before calling into the
+    /// host, split out so it can be tested without constructing a real
+    /// `HostFunction`.
+    fn check_cancellation(&self) -> Result<(), SimError> {
+        if self.cancel_handle.is_cancelled() {
+            return Err(SimError::Cancelled);
+        }
+        if let Some((started_at, timeout)) = self.deadline {
+            if started_at.elapsed() >= timeout {
+                return Err(SimError::TimedOut);
+            }
+        }
+        Ok(())
     }
 
     /// Rebuild the host with fresh ledger storage while preserving compiled WASM modules.
@@ -96,8 +451,13 @@ impl SimHost {
     /// This is useful for high-volume simulation/test loops where recreating and
     /// recompiling modules is expensive, but each iteration needs an isolated ledger state.
     pub fn wipe_ledger_state_preserving_modules(&mut self) -> Result<(), HostError> {
-        // Start each iteration with a fresh budget and storage snapshot.
+        // Start each iteration with a fresh budget and storage snapshot,
+        // reapplying this host's configured ceiling so repeated iterations
+        // don't silently fall back to the default mainnet budget.
         let budget = Budget::default();
+        if let Some((cpu, mem)) = self.budget_limits {
+            budget.reset_limits(cpu, mem)?;
+        }
 
         // Best-effort transfer of module cache. If the old host never initialized one,
         // we still proceed with a clean host.
@@ -124,14 +484,14 @@ mod tests {
 
     #[test]
     fn test_host_initialization() {
-        let host = SimHost::new(None, None, None);
+        let host = SimHost::new(None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
         // Basic assertion that host is functional
         assert!(host.inner.budget_cloned().get_cpu_insns_consumed().is_ok());
     }
 
     #[test]
     fn test_configuration() {
-        let mut host = SimHost::new(None, None, None);
+        let mut host = SimHost::new(None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
         // Test setting contract ID (dummy hash)
         let hash = Hash([0u8; 32]);
         host.set_contract_id(hash);
@@ -145,7 +505,7 @@ mod tests {
 
     #[test]
     fn test_simple_value_handling() {
-        let host = SimHost::new(None, None, None);
+        let host = SimHost::new(None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
 
         let a = 10u32;
         let b = 20u32;
@@ -161,9 +521,109 @@ mod tests {
         assert_eq!(res_a + res_b, 30);
     }
 
+    #[test]
+    fn test_budget_limits_are_applied_and_remaining_budget_reflects_them() {
+        let host = SimHost::new(Some((1_000_000, 500_000)), None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
+
+        let (cpu_remaining, mem_remaining) = host
+            .remaining_budget()
+            .expect("remaining_budget should be Some when budget_limits was supplied");
+        assert!(cpu_remaining <= 1_000_000);
+        assert!(mem_remaining <= 500_000);
+    }
+
+    #[test]
+    fn test_remaining_budget_is_none_without_configured_limits() {
+        let host = SimHost::new(None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
+        assert_eq!(host.remaining_budget(), None);
+    }
+
+    #[test]
+    fn test_wipe_ledger_state_preserving_modules_reapplies_budget_limits() {
+        let mut host = SimHost::new(Some((1_000_000, 500_000)), None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
+
+        host.wipe_ledger_state_preserving_modules()
+            .expect("wipe should succeed");
+
+        let (cpu_remaining, mem_remaining) = host
+            .remaining_budget()
+            .expect("budget_limits should survive the wipe");
+        assert!(cpu_remaining <= 1_000_000);
+        assert!(mem_remaining <= 500_000);
+    }
+
+    #[test]
+    fn test_with_ledger_entries_installs_a_module_cache() {
+        let host = SimHost::new(None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
+
+        // If a cache was installed at construction time, taking it succeeds.
+        let cache = host
+            .inner
+            .take_module_cache()
+            .expect("SimHost should install a module cache so repeated invocations of the same contract within an envelope are cached");
+        // Put it back to leave the host usable for any follow-on checks.
+        host.inner
+            .set_module_cache(cache)
+            .expect("reinstalling module cache should succeed");
+    }
+
+    #[test]
+    fn test_with_module_cache_capacity_still_installs_a_module_cache() {
+        let host = SimHost::with_module_cache_capacity(16, crate::types::DEFAULT_PROTOCOL_VERSION)
+            .expect("protocol-compatible ledger entries");
+        let cache = host
+            .inner
+            .take_module_cache()
+            .expect("with_module_cache_capacity should still install a module cache");
+        host.inner
+            .set_module_cache(cache)
+            .expect("reinstalling module cache should succeed");
+    }
+
+    #[test]
+    fn test_module_cache_stats_starts_at_zero() {
+        let host = SimHost::new(None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
+        assert_eq!(host.module_cache_stats(), ModuleCacheStats { hits: 0, misses: 0 });
+    }
+
+    #[test]
+    fn test_check_cancellation_ok_by_default() {
+        let host = SimHost::new(None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
+        assert!(host.check_cancellation().is_ok());
+    }
+
+    #[test]
+    fn test_cancel_handle_cancels_a_shared_host() {
+        let host = SimHost::new(None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
+        let handle = host.cancel_handle();
+
+        assert!(host.check_cancellation().is_ok());
+        handle.cancel();
+        assert!(matches!(host.check_cancellation(), Err(SimError::Cancelled)));
+    }
+
+    #[test]
+    fn test_with_deadline_times_out_once_elapsed() {
+        let host = SimHost::new(None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION)
+            .expect("protocol-compatible ledger entries")
+            .with_deadline(Duration::from_millis(0));
+
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(matches!(host.check_cancellation(), Err(SimError::TimedOut)));
+    }
+
+    #[test]
+    fn test_with_deadline_ok_before_it_elapses() {
+        let host = SimHost::new(None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION)
+            .expect("protocol-compatible ledger entries")
+            .with_deadline(Duration::from_secs(60));
+
+        assert!(host.check_cancellation().is_ok());
+    }
+
     #[test]
     fn test_wipe_ledger_state_preserving_modules_without_cache() {
-        let mut host = SimHost::new(None, None, None);
+        let mut host = SimHost::new(None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
         let before = format!("{:?}", host.inner);
 
         host.wipe_ledger_state_preserving_modules()
@@ -175,7 +635,7 @@ mod tests {
 
     #[test]
     fn test_wipe_ledger_state_preserving_modules_keeps_module_cache() {
-        let mut host = SimHost::new(None, None, None);
+        let mut host = SimHost::new(None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
 
         let cache = ModuleCache::new(&host.inner).expect("module cache should initialize");
         host.inner
@@ -195,4 +655,149 @@ mod tests {
             .set_module_cache(transferred)
             .expect("reinstalling module cache should succeed");
     }
+
+    fn account_key(id: u8) -> LedgerKey {
+        use soroban_env_host::xdr::{AccountId, LedgerKeyAccount, PublicKey, Uint256};
+        LedgerKey::Account(LedgerKeyAccount {
+            account_id: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([id; 32]))),
+        })
+    }
+
+    fn account_entry(id: u8) -> LedgerEntry {
+        use soroban_env_host::xdr::{
+            AccountEntry, AccountId, LedgerEntryData, LedgerEntryExt, PublicKey, SequenceNumber,
+            Thresholds, Uint256,
+        };
+        LedgerEntry {
+            last_modified_ledger_seq: 1,
+            data: LedgerEntryData::Account(AccountEntry {
+                account_id: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([id; 32]))),
+                balance: 0,
+                seq_num: SequenceNumber(0),
+                num_sub_entries: 0,
+                inflation_dest: None,
+                flags: 0,
+                home_domain: Default::default(),
+                thresholds: Thresholds([1, 0, 0, 0]),
+                signers: Default::default(),
+                ext: Default::default(),
+            }),
+            ext: LedgerEntryExt::V0,
+        }
+    }
+
+    #[test]
+    fn test_discovered_footprint_is_surfaced_regardless_of_the_recording_flag() {
+        // `recording` no longer gates whether the footprint is readable —
+        // callers now always get back what was actually touched.
+        for recording in [false, true] {
+            let mut entries = HashMap::new();
+            entries.insert(account_key(9), account_entry(9));
+            entries.insert(account_key(7), account_entry(7));
+            let host = SimHost::with_ledger_entries(entries, recording, None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
+
+            let footprint = host
+                .discovered_footprint()
+                .expect("footprint should be readable right after construction");
+            assert!(footprint.read_only.is_empty());
+            assert_eq!(footprint.read_write.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_discovered_footprint_read_write_keys_come_back_sorted_and_deduplicated() {
+        let mut entries = HashMap::new();
+        entries.insert(account_key(9), account_entry(9));
+        entries.insert(account_key(7), account_entry(7));
+        entries.insert(account_key(3), account_entry(3));
+        let host = SimHost::with_ledger_entries(entries, false, None, None, None, crate::types::DEFAULT_PROTOCOL_VERSION).expect("protocol-compatible ledger entries");
+
+        let footprint = host
+            .discovered_footprint()
+            .expect("footprint should be readable right after construction");
+        let mut sorted = footprint.read_write.to_vec();
+        sorted.sort();
+        assert_eq!(
+            footprint.read_write.to_vec(),
+            sorted,
+            "OrdMap iteration should already yield keys in sorted order"
+        );
+    }
+
+    fn contract_data_entry(durability: ContractDataDurability) -> LedgerEntry {
+        use soroban_env_host::xdr::{
+            ContractDataEntry, ExtensionPoint, Hash, LedgerEntryExt, ScAddress, ScVal,
+        };
+        LedgerEntry {
+            last_modified_ledger_seq: 0,
+            data: LedgerEntryData::ContractData(ContractDataEntry {
+                ext: ExtensionPoint::V0,
+                contract: ScAddress::Contract(Hash([1u8; 32])),
+                key: ScVal::U32(1),
+                durability,
+                val: ScVal::U32(1),
+            }),
+            ext: LedgerEntryExt::V0,
+        }
+    }
+
+    #[test]
+    fn test_with_ledger_entries_rejects_a_temporary_entry_under_an_older_protocol() {
+        let mut entries = HashMap::new();
+        entries.insert(account_key(1), contract_data_entry(ContractDataDurability::Temporary));
+
+        let err = SimHost::with_ledger_entries(entries, false, None, None, None, 20)
+            .expect_err("protocol 20 predates state archival's Temporary durability");
+        assert_eq!(
+            err,
+            ProtocolCompatibilityError::EntryRequiresNewerProtocol {
+                required: 21,
+                configured: 20,
+            }
+        );
+    }
+
+    #[test]
+    fn test_with_ledger_entries_accepts_a_temporary_entry_under_a_new_enough_protocol() {
+        let mut entries = HashMap::new();
+        entries.insert(account_key(1), contract_data_entry(ContractDataDurability::Temporary));
+
+        SimHost::with_ledger_entries(entries, false, None, None, None, 21)
+            .expect("protocol 21 supports Temporary durability");
+    }
+
+    #[test]
+    fn test_with_ledger_entries_accepts_a_persistent_entry_under_any_supported_protocol() {
+        let mut entries = HashMap::new();
+        entries.insert(account_key(1), contract_data_entry(ContractDataDurability::Persistent));
+
+        SimHost::with_ledger_entries(entries, false, None, None, None, 20)
+            .expect("Persistent durability has no protocol floor above the base version");
+    }
+
+    #[test]
+    fn test_for_protocol_returns_the_exact_table_entry_when_one_exists() {
+        assert_eq!(
+            crate::types::ResourceCalibration::for_protocol(21),
+            crate::types::ResourceCalibration::for_protocol(21)
+        );
+        assert_ne!(
+            crate::types::ResourceCalibration::for_protocol(20).sha256_fixed,
+            crate::types::ResourceCalibration::for_protocol(22).sha256_fixed
+        );
+    }
+
+    #[test]
+    fn test_for_protocol_falls_back_to_the_nearest_known_version() {
+        // No table entry for 23 yet: nearest known is 22.
+        assert_eq!(
+            crate::types::ResourceCalibration::for_protocol(23),
+            crate::types::ResourceCalibration::for_protocol(22)
+        );
+        // Nothing below 20: nearest known is still 20.
+        assert_eq!(
+            crate::types::ResourceCalibration::for_protocol(5),
+            crate::types::ResourceCalibration::for_protocol(20)
+        );
+    }
 }
@@ -0,0 +1,396 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signed, verifiable snapshot bundles.
+//!
+//! [`LedgerSnapshot::from_base64_map`] and `restore_preamble` injection both
+//! take `(LedgerKey, LedgerEntry)` pairs from plain, unsigned JSON — fine
+//! for a snapshot captured locally, but not for one pulled over the network
+//! from a source that isn't the simulator itself. [`SnapshotBundle`] adds a
+//! TUF-style trust layer on top of the same base64 XDR encoding: a
+//! [`RootMetadata`] document lists which signer public keys are authorized
+//! to vouch for a bundle's contents and for how long, and a
+//! [`TargetsManifest`] — signed by one of those keys via the [`Signer`]
+//! trait — records each entry's expected SHA-256 hash and length. Producing
+//! a bundle is [`build`]; consuming one is [`BundleVerifier::verify`],
+//! which rejects a root that's been rolled back to an older version, an
+//! expired root or targets manifest, a signer the root doesn't authorize,
+//! or an entry whose bytes don't match what the manifest declared, before
+//! ever reconstructing a [`LedgerSnapshot`] from the payload.
+//!
+//! This only covers a single producer/consumer hop (no root-rotation
+//! chain-climbing, no multi-signer threshold) — see
+//! [`crate::hsm::trust_root`] for the fuller TUF client this is modeled on.
+
+use super::{decode_ledger_entry, decode_ledger_key, LedgerSnapshot, SnapshotError};
+use crate::hsm::verify::verify_with_public_key;
+use crate::hsm::{PublicKey, Signature, Signer, SignerError};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use soroban_env_host::xdr::{LedgerEntry, LedgerKey, Limits, WriteXdr};
+use std::collections::{BTreeMap, HashMap};
+
+/// One signer authorized to sign [`TargetsManifest`]s, and until when.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuthorizedSigner {
+    pub public_key: PublicKey,
+    /// RFC 3339 timestamp after which this signer is no longer authorized.
+    pub expires: String,
+}
+
+/// Root-of-trust document: the set of signer keys a [`TargetsManifest`] may
+/// be signed by, with a version counter [`BundleVerifier`] uses to reject a
+/// bundle carrying a root older than one it has already seen (e.g. a
+/// rollback reviving a since-revoked signer).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RootMetadata {
+    pub version: u32,
+    /// RFC 3339 timestamp the root itself expires at.
+    pub expires: String,
+    pub authorized_signers: Vec<AuthorizedSigner>,
+}
+
+/// Expected content hash/length of one bundled entry, the analogue of a TUF
+/// target file's `{hashes, length}` metadata, keyed in
+/// [`TargetsManifest::entries`] by the entry's base64 `LedgerKey` XDR.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EntryTarget {
+    pub sha256: String,
+    pub length: usize,
+}
+
+/// Signed manifest describing a bundle's contents: ledger-wide metadata
+/// plus each entry's expected hash/length. `entries` is a [`BTreeMap`]
+/// rather than a [`HashMap`] so that [`build`]/[`BundleVerifier::verify`]
+/// serialize it identically regardless of insertion order — the bytes
+/// actually signed/verified — without needing a separate canonicalization
+/// pass like [`crate::hsm::trust_root`]'s `canonicalize`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetsManifest {
+    pub version: u32,
+    /// RFC 3339 timestamp this manifest expires at.
+    pub expires: String,
+    pub protocol_version: u32,
+    pub sequence_number: u32,
+    pub entries: BTreeMap<String, EntryTarget>,
+}
+
+/// A complete package: a root of trust, a signed targets manifest, and the
+/// entry payload itself (base64 XDR, the same encoding
+/// [`LedgerSnapshot::from_base64_map`] and `restore_preamble` injection
+/// use).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotBundle {
+    pub root: RootMetadata,
+    pub targets: TargetsManifest,
+    /// Signature over the canonical JSON encoding of `targets`.
+    pub targets_signature: Signature,
+    /// Which key in `root.authorized_signers` produced `targets_signature`.
+    pub signer_public_key: PublicKey,
+    /// Base64 `LedgerKey` XDR -> base64 `LedgerEntry` XDR.
+    pub ledgers: HashMap<String, String>,
+}
+
+/// Errors from [`build`] or [`BundleVerifier::verify`].
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("root version went backwards: already trusted version {0}, bundle has {1}")]
+    StaleRoot(u32, u32),
+
+    #[error("{0} has expired (expires {1})")]
+    Expired(String, String),
+
+    #[error("signer is not among the root of trust's authorized signers")]
+    UnauthorizedSigner,
+
+    #[error("targets manifest signature did not verify: {0}")]
+    InvalidSignature(SignerError),
+
+    #[error("entry {0} is not listed in the targets manifest")]
+    UnlistedEntry(String),
+
+    #[error("entry {0} failed hash/length verification against the targets manifest")]
+    EntryMismatch(String),
+
+    #[error("invalid RFC 3339 timestamp {0}: {1}")]
+    InvalidTimestamp(String, String),
+
+    #[error(transparent)]
+    Snapshot(#[from] SnapshotError),
+}
+
+/// Mirrors [`crate::hsm::trust_root::check_not_expired`]: parse `expires` as
+/// RFC 3339 and compare against the current time, attributing a failure to
+/// `doc_name` in the returned error.
+fn check_not_expired(doc_name: &str, expires: &str) -> Result<(), BundleError> {
+    let expires_at = chrono::DateTime::parse_from_rfc3339(expires)
+        .map_err(|e| BundleError::InvalidTimestamp(expires.to_string(), e.to_string()))?;
+    if expires_at < chrono::Utc::now() {
+        return Err(BundleError::Expired(doc_name.to_string(), expires.to_string()));
+    }
+    Ok(())
+}
+
+/// Package `entries` into a [`SnapshotBundle`]: build a [`TargetsManifest`]
+/// recording each entry's SHA-256 hash and length, sign its canonical JSON
+/// encoding with `signer`, and bundle it alongside `root` and the base64
+/// XDR payload.
+pub async fn build(
+    signer: &dyn Signer,
+    root: RootMetadata,
+    targets_version: u32,
+    targets_expires: &str,
+    protocol_version: u32,
+    sequence_number: u32,
+    entries: &[(LedgerKey, LedgerEntry)],
+) -> Result<SnapshotBundle, BundleError> {
+    let mut manifest_entries = BTreeMap::new();
+    let mut ledgers = HashMap::new();
+
+    for (key, entry) in entries {
+        let key_xdr = key
+            .to_xdr(Limits::none())
+            .map_err(|e| BundleError::Snapshot(SnapshotError::XdrEncoding(format!("Failed to encode key: {e}"))))?;
+        let entry_xdr = entry
+            .to_xdr(Limits::none())
+            .map_err(|e| BundleError::Snapshot(SnapshotError::XdrEncoding(format!("Failed to encode entry: {e}"))))?;
+
+        let key_b64 = base64::engine::general_purpose::STANDARD.encode(&key_xdr);
+        let entry_b64 = base64::engine::general_purpose::STANDARD.encode(&entry_xdr);
+
+        manifest_entries.insert(
+            key_b64.clone(),
+            EntryTarget {
+                sha256: hex::encode(Sha256::digest(&entry_xdr)),
+                length: entry_xdr.len(),
+            },
+        );
+        ledgers.insert(key_b64, entry_b64);
+    }
+
+    let targets = TargetsManifest {
+        version: targets_version,
+        expires: targets_expires.to_string(),
+        protocol_version,
+        sequence_number,
+        entries: manifest_entries,
+    };
+
+    let canonical = serde_json::to_vec(&targets).expect("targets manifest always serializes");
+    let targets_signature = signer
+        .sign(&canonical)
+        .await
+        .map_err(BundleError::InvalidSignature)?;
+    let signer_public_key = signer
+        .public_key()
+        .await
+        .map_err(BundleError::InvalidSignature)?;
+
+    Ok(SnapshotBundle {
+        root,
+        targets,
+        targets_signature,
+        signer_public_key,
+        ledgers,
+    })
+}
+
+/// Verifies [`SnapshotBundle`]s, remembering the newest root version seen so
+/// far across calls so that a later bundle can't roll the trusted signer
+/// set back to an older one.
+#[derive(Debug, Default)]
+pub struct BundleVerifier {
+    last_root_version: Option<u32>,
+}
+
+impl BundleVerifier {
+    /// A verifier that hasn't seen any bundle yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Verify `bundle`'s root, signer authorization, targets signature, and
+    /// every entry's content hash/length, then reconstruct the
+    /// [`LedgerSnapshot`] it describes. Rejects (in order): a root older
+    /// than one already trusted, an expired root or targets manifest, a
+    /// signer the root doesn't authorize (or whose authorization expired),
+    /// an invalid targets signature, and any entry missing from or
+    /// mismatched against the targets manifest.
+    pub fn verify(&mut self, bundle: &SnapshotBundle) -> Result<LedgerSnapshot, BundleError> {
+        if let Some(last) = self.last_root_version {
+            if bundle.root.version < last {
+                return Err(BundleError::StaleRoot(last, bundle.root.version));
+            }
+        }
+        check_not_expired("root of trust", &bundle.root.expires)?;
+        check_not_expired("targets manifest", &bundle.targets.expires)?;
+
+        let authorized_signer = bundle
+            .root
+            .authorized_signers
+            .iter()
+            .find(|signer| signer.public_key == bundle.signer_public_key)
+            .ok_or(BundleError::UnauthorizedSigner)?;
+        check_not_expired("authorized signer", &authorized_signer.expires)?;
+
+        let canonical = serde_json::to_vec(&bundle.targets).expect("targets manifest always serializes");
+        verify_with_public_key(&canonical, &bundle.signer_public_key, &bundle.targets_signature)
+            .map_err(BundleError::InvalidSignature)?;
+
+        for (key_xdr, entry_xdr) in &bundle.ledgers {
+            let target = bundle
+                .targets
+                .entries
+                .get(key_xdr)
+                .ok_or_else(|| BundleError::UnlistedEntry(key_xdr.clone()))?;
+
+            let raw_entry = base64::engine::general_purpose::STANDARD
+                .decode(entry_xdr)
+                .map_err(|e| BundleError::Snapshot(SnapshotError::Base64Decode(format!("LedgerEntry: {e}"))))?;
+
+            if raw_entry.len() != target.length || hex::encode(Sha256::digest(&raw_entry)) != target.sha256 {
+                return Err(BundleError::EntryMismatch(key_xdr.clone()));
+            }
+
+            // Confirm the payload still parses as a well-formed key/entry
+            // pair, not just that its bytes match the manifest.
+            decode_ledger_key(key_xdr)?;
+            decode_ledger_entry(entry_xdr)?;
+        }
+
+        self.last_root_version = Some(bundle.root.version);
+
+        Ok(LedgerSnapshot::from_base64_map(&bundle.ledgers)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hsm::software::SoftwareSigner;
+    use soroban_env_host::xdr::{
+        ContractDataDurability, ContractDataEntry, ExtensionPoint, Hash, LedgerEntryData,
+        LedgerEntryExt, LedgerKeyContractData, ScAddress, ScVal,
+    };
+
+    fn sample_entry() -> (LedgerKey, LedgerEntry) {
+        let contract_id = Hash([7u8; 32]);
+        let key_val = ScVal::U32(1);
+        let key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::Contract(contract_id.clone()),
+            key: key_val.clone(),
+            durability: ContractDataDurability::Persistent,
+        });
+        let entry = LedgerEntry {
+            last_modified_ledger_seq: 1,
+            data: LedgerEntryData::ContractData(ContractDataEntry {
+                ext: ExtensionPoint::V0,
+                contract: ScAddress::Contract(contract_id),
+                key: key_val,
+                durability: ContractDataDurability::Persistent,
+                val: ScVal::U64(2),
+            }),
+            ext: LedgerEntryExt::V0,
+        };
+        (key, entry)
+    }
+
+    async fn signed_bundle(signer: &SoftwareSigner, root_version: u32) -> SnapshotBundle {
+        let public_key = signer.public_key().await.unwrap();
+        let root = RootMetadata {
+            version: root_version,
+            expires: "2999-01-01T00:00:00Z".to_string(),
+            authorized_signers: vec![AuthorizedSigner {
+                public_key,
+                expires: "2999-01-01T00:00:00Z".to_string(),
+            }],
+        };
+        build(signer, root, 1, "2999-01-01T00:00:00Z", 20, 100, &[sample_entry()])
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_verify_accepts_a_well_formed_bundle() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let bundle = signed_bundle(&signer, 1).await;
+
+        let mut verifier = BundleVerifier::new();
+        let snapshot = verifier.verify(&bundle).unwrap();
+        assert_eq!(snapshot.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_a_signer_not_in_the_root() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let mut bundle = signed_bundle(&signer, 1).await;
+
+        let (other_signer, _pem) = SoftwareSigner::generate().unwrap();
+        bundle.signer_public_key = other_signer.public_key().await.unwrap();
+
+        let mut verifier = BundleVerifier::new();
+        assert!(matches!(
+            verifier.verify(&bundle),
+            Err(BundleError::UnauthorizedSigner)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_a_tampered_targets_manifest() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let mut bundle = signed_bundle(&signer, 1).await;
+        bundle.targets.sequence_number += 1;
+
+        let mut verifier = BundleVerifier::new();
+        assert!(matches!(
+            verifier.verify(&bundle),
+            Err(BundleError::InvalidSignature(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_an_entry_tampered_after_signing() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let mut bundle = signed_bundle(&signer, 1).await;
+        let (key_xdr, entry_xdr) = bundle.ledgers.iter().next().map(|(k, v)| (k.clone(), v.clone())).unwrap();
+        bundle.ledgers.insert(key_xdr, format!("{entry_xdr}AA"));
+
+        let mut verifier = BundleVerifier::new();
+        assert!(matches!(
+            verifier.verify(&bundle),
+            Err(BundleError::EntryMismatch(_)) | Err(BundleError::Snapshot(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_a_rolled_back_root_version() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+
+        let mut verifier = BundleVerifier::new();
+        verifier.verify(&signed_bundle(&signer, 5).await).unwrap();
+
+        let err = verifier.verify(&signed_bundle(&signer, 3).await).unwrap_err();
+        assert!(matches!(err, BundleError::StaleRoot(5, 3)));
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_an_expired_root() {
+        let (signer, _pem) = SoftwareSigner::generate().unwrap();
+        let public_key = signer.public_key().await.unwrap();
+        let root = RootMetadata {
+            version: 1,
+            expires: "2000-01-01T00:00:00Z".to_string(),
+            authorized_signers: vec![AuthorizedSigner {
+                public_key,
+                expires: "2999-01-01T00:00:00Z".to_string(),
+            }],
+        };
+        let bundle = build(&signer, root, 1, "2999-01-01T00:00:00Z", 20, 100, &[sample_entry()])
+            .await
+            .unwrap();
+
+        let mut verifier = BundleVerifier::new();
+        assert!(matches!(verifier.verify(&bundle), Err(BundleError::Expired(_, _))));
+    }
+}
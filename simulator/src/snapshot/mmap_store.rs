@@ -0,0 +1,328 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Memory-mapped ledger snapshot store, for simulating against
+//! mainnet-sized state dumps too large to decode fully into a heap
+//! `HashMap` the way [`super::LedgerSnapshot`] does.
+//!
+//! The on-disk format is a small header followed by `capacity` fixed-size
+//! cells; each cell holds one length-prefixed, XDR-encoded
+//! `(LedgerKey, LedgerEntry)` pair, zero-padded to [`CELL_SIZE`].
+//! [`LedgerSnapshotStore::open`] mmaps the body and builds a small
+//! in-memory `key -> cell index` map plus an occupied-cells bitmap by
+//! scanning cell headers only; entry bodies are decoded lazily, on first
+//! [`LedgerSnapshotStore::get`], straight out of the memory map, and cached
+//! so a second lookup doesn't re-decode.
+
+use super::SnapshotError;
+use soroban_env_host::xdr::{LedgerEntry, LedgerKey, Limits, ReadXdr, WriteXdr};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write as _;
+
+/// Size of one fixed-size cell, in bytes. Must be large enough to hold the
+/// largest `(LedgerKey, LedgerEntry)` pair's length-prefixed XDR encoding;
+/// [`build`] returns `SnapshotError::XdrEncoding` for an entry that doesn't fit.
+pub const CELL_SIZE: usize = 8192;
+
+const HEADER_MAGIC: &[u8; 8] = b"ERSTLSS1";
+/// Magic (8 bytes) + cell count as little-endian `u64` (8 bytes).
+const HEADER_SIZE: usize = 16;
+
+/// Build a fixed-cell-size snapshot file from `entries`, one cell per entry.
+pub fn build(path: &str, entries: &HashMap<LedgerKey, LedgerEntry>) -> Result<(), SnapshotError> {
+    let mut file = File::create(path)
+        .map_err(|e| SnapshotError::StorageError(format!("failed to create {path}: {e}")))?;
+
+    file.write_all(HEADER_MAGIC)
+        .map_err(|e| SnapshotError::StorageError(format!("failed to write header: {e}")))?;
+    file.write_all(&(entries.len() as u64).to_le_bytes())
+        .map_err(|e| SnapshotError::StorageError(format!("failed to write header: {e}")))?;
+
+    for (key, entry) in entries {
+        let key_bytes = key
+            .to_xdr(Limits::none())
+            .map_err(|e| SnapshotError::XdrEncoding(format!("key: {e}")))?;
+        let entry_bytes = entry
+            .to_xdr(Limits::none())
+            .map_err(|e| SnapshotError::XdrEncoding(format!("entry: {e}")))?;
+
+        let mut cell = vec![0u8; CELL_SIZE];
+        let mut cursor = 0usize;
+        write_len_prefixed(&mut cell, &mut cursor, &key_bytes)?;
+        write_len_prefixed(&mut cell, &mut cursor, &entry_bytes)?;
+
+        file.write_all(&cell)
+            .map_err(|e| SnapshotError::StorageError(format!("failed to write cell: {e}")))?;
+    }
+
+    Ok(())
+}
+
+fn write_len_prefixed(
+    cell: &mut [u8],
+    cursor: &mut usize,
+    bytes: &[u8],
+) -> Result<(), SnapshotError> {
+    let needed = 4 + bytes.len();
+    if *cursor + needed > cell.len() {
+        return Err(SnapshotError::XdrEncoding(format!(
+            "entry does not fit in a {}-byte cell ({needed} bytes needed)",
+            cell.len()
+        )));
+    }
+    cell[*cursor..*cursor + 4].copy_from_slice(&(bytes.len() as u32).to_le_bytes());
+    *cursor += 4;
+    cell[*cursor..*cursor + bytes.len()].copy_from_slice(bytes);
+    *cursor += bytes.len();
+    Ok(())
+}
+
+fn read_len_prefixed(cell: &[u8], offset: usize) -> Option<&[u8]> {
+    if offset + 4 > cell.len() {
+        return None;
+    }
+    let len = u32::from_le_bytes(cell[offset..offset + 4].try_into().ok()?) as usize;
+    if len == 0 || offset + 4 + len > cell.len() {
+        return None;
+    }
+    Some(&cell[offset + 4..offset + 4 + len])
+}
+
+/// A memory-mapped, lazily-decoded ledger snapshot. Reads fault entries in
+/// from the memory map on first access instead of decoding the whole file
+/// up front, and keep only a `key -> cell index` map plus an
+/// occupied-cells bitmap on the heap, not the entries themselves.
+pub struct LedgerSnapshotStore {
+    mmap: memmap2::Mmap,
+    capacity: usize,
+    index: HashMap<Vec<u8>, usize>,
+    occupied: Vec<bool>,
+    cache: RefCell<HashMap<usize, LedgerEntry>>,
+}
+
+impl LedgerSnapshotStore {
+    /// Open a snapshot file written by [`build`], mmap its body, and build
+    /// the in-memory key index by scanning each cell's key once. Entry
+    /// bodies are left undecoded until [`Self::get`] is called for that key.
+    pub fn open(path: &str) -> Result<Self, SnapshotError> {
+        let file = File::open(path)
+            .map_err(|e| SnapshotError::StorageError(format!("failed to open {path}: {e}")))?;
+
+        let mmap = unsafe {
+            memmap2::Mmap::map(&file)
+                .map_err(|e| SnapshotError::StorageError(format!("failed to mmap {path}: {e}")))?
+        };
+
+        if mmap.len() < HEADER_SIZE || mmap[0..8] != *HEADER_MAGIC {
+            return Err(SnapshotError::StorageError(
+                "not a valid ledger snapshot file".to_string(),
+            ));
+        }
+        let capacity = u64::from_le_bytes(mmap[8..16].try_into().unwrap()) as usize;
+
+        let body_len = mmap.len() - HEADER_SIZE;
+        if body_len != capacity * CELL_SIZE {
+            return Err(SnapshotError::StorageError(format!(
+                "snapshot file size does not match its header: expected {} cell bytes, found {body_len}",
+                capacity * CELL_SIZE
+            )));
+        }
+
+        let mut index = HashMap::with_capacity(capacity);
+        let mut occupied = vec![false; capacity];
+        for (cell_idx, occ) in occupied.iter_mut().enumerate() {
+            let start = HEADER_SIZE + cell_idx * CELL_SIZE;
+            let cell = &mmap[start..start + CELL_SIZE];
+            if let Some(key_bytes) = read_len_prefixed(cell, 0) {
+                index.insert(key_bytes.to_vec(), cell_idx);
+                *occ = true;
+            }
+        }
+
+        Ok(Self {
+            mmap,
+            capacity,
+            index,
+            occupied,
+            cache: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Number of cells the store's backing file has room for (not all are
+    /// necessarily occupied).
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Look up `key`, faulting its entry in from the memory map (and
+    /// caching the decoded result) on first access. Asserts the resolved
+    /// cell index is within the store's capacity and marked occupied,
+    /// rather than silently reading past the intended data region.
+    pub fn get(&self, key: &LedgerKey) -> Result<Option<LedgerEntry>, SnapshotError> {
+        let key_bytes = key
+            .to_xdr(Limits::none())
+            .map_err(|e| SnapshotError::XdrEncoding(format!("key: {e}")))?;
+
+        let Some(&cell_idx) = self.index.get(&key_bytes) else {
+            return Ok(None);
+        };
+        assert!(
+            cell_idx < self.capacity,
+            "cell index {cell_idx} out of bounds for capacity {}",
+            self.capacity
+        );
+        assert!(
+            self.occupied[cell_idx],
+            "cell index {cell_idx} resolved from the index but not marked occupied"
+        );
+
+        if let Some(entry) = self.cache.borrow().get(&cell_idx) {
+            return Ok(Some(entry.clone()));
+        }
+
+        let start = HEADER_SIZE + cell_idx * CELL_SIZE;
+        let cell = &self.mmap[start..start + CELL_SIZE];
+        // `index` is built from `read_len_prefixed`, so the key prefix is
+        // already known to be well-formed, but the entry prefix that
+        // follows it hasn't been validated yet — a crafted or truncated
+        // cell could put `entry_header` at or past `CELL_SIZE`. Use the
+        // same bounds-checked reader here instead of indexing blindly, so a
+        // malformed cell returns `SnapshotError` rather than panicking.
+        let key_bytes = read_len_prefixed(cell, 0).ok_or_else(|| {
+            SnapshotError::StorageError(format!("cell {cell_idx} has a malformed key prefix"))
+        })?;
+        let entry_header = 4 + key_bytes.len();
+        let entry_bytes = read_len_prefixed(cell, entry_header).ok_or_else(|| {
+            SnapshotError::StorageError(format!("cell {cell_idx} has a malformed entry prefix"))
+        })?;
+
+        let entry = LedgerEntry::from_xdr(entry_bytes, Limits::none())
+            .map_err(|e| SnapshotError::XdrParse(format!("entry: {e}")))?;
+
+        self.cache.borrow_mut().insert(cell_idx, entry.clone());
+        Ok(Some(entry))
+    }
+
+    /// Decode and return every occupied cell as a `(LedgerKey, LedgerEntry)`
+    /// pair. Used by callers that need to pre-populate the host's `Storage`
+    /// map up front rather than fault entries in one at a time as the host
+    /// accesses them.
+    pub fn iter_occupied(&self) -> Result<Vec<(LedgerKey, LedgerEntry)>, SnapshotError> {
+        let mut out = Vec::with_capacity(self.index.len());
+        for key_bytes in self.index.keys() {
+            let key = LedgerKey::from_xdr(key_bytes.as_slice(), Limits::none())
+                .map_err(|e| SnapshotError::XdrParse(format!("key: {e}")))?;
+            if let Some(entry) = self.get(&key)? {
+                out.push((key, entry));
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{
+        AccountEntry, AccountId, LedgerEntryData, LedgerKey, LedgerKeyAccount, PublicKey,
+        SequenceNumber, Thresholds, Uint256,
+    };
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn temp_path(name: &str) -> String {
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir()
+            .join(format!("erst_ledger_snapshot_{name}_{id}.bin"))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn account_entry(seed: u8) -> (LedgerKey, LedgerEntry) {
+        let account_id = AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([seed; 32])));
+        let key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: account_id.clone(),
+        });
+        let entry = LedgerEntry {
+            last_modified_ledger_seq: 1,
+            data: LedgerEntryData::Account(AccountEntry {
+                account_id,
+                balance: seed as i64 * 1000,
+                seq_num: SequenceNumber(1),
+                num_sub_entries: 0,
+                inflation_dest: None,
+                flags: 0,
+                home_domain: Default::default(),
+                thresholds: Thresholds([1, 0, 0, 0]),
+                signers: Default::default(),
+                ext: Default::default(),
+            }),
+            ext: Default::default(),
+        };
+        (key, entry)
+    }
+
+    #[test]
+    fn round_trips_entries_through_build_and_open() {
+        let path = temp_path("round_trip");
+        let mut entries = HashMap::new();
+        for seed in 0..5u8 {
+            let (key, entry) = account_entry(seed);
+            entries.insert(key, entry);
+        }
+        build(&path, &entries).unwrap();
+
+        let store = LedgerSnapshotStore::open(&path).unwrap();
+        assert_eq!(store.capacity(), 5);
+
+        for (key, expected_entry) in &entries {
+            let found = store.get(key).unwrap().expect("entry should be found");
+            assert_eq!(found.last_modified_ledger_seq, expected_entry.last_modified_ledger_seq);
+        }
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn get_returns_none_for_a_key_never_written() {
+        let path = temp_path("missing_key");
+        let mut entries = HashMap::new();
+        let (key, entry) = account_entry(1);
+        entries.insert(key, entry);
+        build(&path, &entries).unwrap();
+
+        let store = LedgerSnapshotStore::open(&path).unwrap();
+        let (other_key, _) = account_entry(99);
+        assert!(store.get(&other_key).unwrap().is_none());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn open_rejects_a_file_with_the_wrong_magic() {
+        let path = temp_path("bad_magic");
+        std::fs::write(&path, [0u8; HEADER_SIZE]).unwrap();
+        assert!(LedgerSnapshotStore::open(&path).is_err());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn iter_occupied_recovers_every_entry() {
+        let path = temp_path("iter_occupied");
+        let mut entries = HashMap::new();
+        for seed in 0..3u8 {
+            let (key, entry) = account_entry(seed);
+            entries.insert(key, entry);
+        }
+        build(&path, &entries).unwrap();
+
+        let store = LedgerSnapshotStore::open(&path).unwrap();
+        let recovered = store.iter_occupied().unwrap();
+        assert_eq!(recovered.len(), entries.len());
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -53,11 +53,55 @@ impl SourceMapCache {
         Ok(Self { cache_dir })
     }
 
-    /// Gets the default cache directory (~/.erst/cache/sourcemaps)
+    /// Gets the default cache directory, trying candidates in priority
+    /// order and returning the first one that resolves. The directory
+    /// itself is not created here; callers that need it to exist (e.g.
+    /// [`Self::with_cache_dir`]) create it lazily.
     fn get_default_cache_dir() -> Result<PathBuf, String> {
-        let home_dir =
-            dirs::home_dir().ok_or_else(|| "Failed to determine home directory".to_string())?;
-        Ok(home_dir.join(".erst").join("cache").join(CACHE_DIR_NAME))
+        Self::cache_dir_candidates()
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                "Failed to determine a cache directory: set ERST_CACHE_DIR, XDG_CACHE_HOME \
+                 (or %LOCALAPPDATA% on Windows), or ensure a home directory is resolvable"
+                    .to_string()
+            })
+    }
+
+    /// Cache directory candidates in priority order:
+    ///
+    /// 1. `ERST_CACHE_DIR`, an explicit override.
+    /// 2. The platform's XDG-style cache directory: `XDG_CACHE_HOME` on
+    ///    Unix, `%LOCALAPPDATA%` on Windows, joined with `erst/sourcemaps`.
+    /// 3. The legacy `~/.erst/cache/sourcemaps` default.
+    ///
+    /// A candidate whose backing environment variable isn't set (or whose
+    /// home directory can't be determined) is simply skipped rather than
+    /// treated as an error — only running out of candidates entirely is.
+    fn cache_dir_candidates() -> Vec<PathBuf> {
+        let mut candidates = Vec::new();
+
+        if let Some(dir) = std::env::var_os("ERST_CACHE_DIR") {
+            candidates.push(PathBuf::from(dir));
+        }
+
+        if cfg!(windows) {
+            if let Some(local_app_data) = std::env::var_os("LOCALAPPDATA") {
+                candidates.push(
+                    PathBuf::from(local_app_data)
+                        .join("erst")
+                        .join(CACHE_DIR_NAME),
+                );
+            }
+        } else if let Some(xdg_cache_home) = std::env::var_os("XDG_CACHE_HOME") {
+            candidates.push(PathBuf::from(xdg_cache_home).join("erst").join(CACHE_DIR_NAME));
+        }
+
+        if let Some(home_dir) = dirs::home_dir() {
+            candidates.push(home_dir.join(".erst").join("cache").join(CACHE_DIR_NAME));
+        }
+
+        candidates
     }
 
     /// Computes SHA256 hash of WASM bytes
@@ -392,4 +436,66 @@ mod tests {
         assert_eq!(list.len(), 1);
         assert_eq!(list[0].wasm_hash, wasm_hash);
     }
+
+    /// Serializes access to the environment variables the candidate chain
+    /// reads, so tests that set/unset them can't race each other.
+    fn with_cache_env_vars<F: FnOnce()>(erst_cache_dir: Option<&str>, xdg_cache_home: Option<&str>, f: F) {
+        static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let saved_erst = std::env::var("ERST_CACHE_DIR");
+        let saved_xdg = std::env::var("XDG_CACHE_HOME");
+
+        match erst_cache_dir {
+            Some(v) => std::env::set_var("ERST_CACHE_DIR", v),
+            None => std::env::remove_var("ERST_CACHE_DIR"),
+        }
+        match xdg_cache_home {
+            Some(v) => std::env::set_var("XDG_CACHE_HOME", v),
+            None => std::env::remove_var("XDG_CACHE_HOME"),
+        }
+
+        f();
+
+        match saved_erst {
+            Ok(v) => std::env::set_var("ERST_CACHE_DIR", v),
+            Err(_) => std::env::remove_var("ERST_CACHE_DIR"),
+        }
+        match saved_xdg {
+            Ok(v) => std::env::set_var("XDG_CACHE_HOME", v),
+            Err(_) => std::env::remove_var("XDG_CACHE_HOME"),
+        }
+    }
+
+    #[test]
+    fn test_cache_dir_candidates_prefers_erst_cache_dir() {
+        with_cache_env_vars(Some("/tmp/custom-erst-cache"), Some("/tmp/xdg-cache"), || {
+            let candidates = SourceMapCache::cache_dir_candidates();
+            assert_eq!(candidates[0], PathBuf::from("/tmp/custom-erst-cache"));
+        });
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_cache_dir_candidates_falls_back_to_xdg_cache_home() {
+        with_cache_env_vars(None, Some("/tmp/xdg-cache"), || {
+            let candidates = SourceMapCache::cache_dir_candidates();
+            assert_eq!(
+                candidates[0],
+                PathBuf::from("/tmp/xdg-cache").join("erst").join(CACHE_DIR_NAME)
+            );
+        });
+    }
+
+    #[test]
+    fn test_cache_dir_candidates_falls_back_to_home_dir() {
+        with_cache_env_vars(None, None, || {
+            let candidates = SourceMapCache::cache_dir_candidates();
+            assert!(!candidates.is_empty());
+            assert!(candidates
+                .last()
+                .unwrap()
+                .ends_with(PathBuf::from(".erst").join("cache").join(CACHE_DIR_NAME)));
+        });
+    }
 }
@@ -11,10 +11,11 @@ use std::path::PathBuf;
 pub struct SourceMapper {
     has_symbols: bool,
     line_cache: Vec<CachedLineEntry>,
+    function_cache: Vec<CachedFunctionEntry>,
     git_repo: Option<GitRepository>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SourceLocation {
     pub file: String,
     pub line: u32,
@@ -31,6 +32,17 @@ struct CachedLineEntry {
     location: SourceLocation,
 }
 
+/// A `DW_TAG_subprogram`'s address range and (demangled-ish) name, used to
+/// attribute a call frame's WASM offset back to the Rust function that
+/// contains it, the same way [`CachedLineEntry`] attributes an offset back
+/// to a source line.
+#[derive(Debug, Clone)]
+struct CachedFunctionEntry {
+    start: u64,
+    end: Option<u64>,
+    name: String,
+}
+
 impl SourceMapper {
     /// Creates a new SourceMapper with caching enabled
     pub fn new(wasm_bytes: Vec<u8>) -> Self {
@@ -51,10 +63,16 @@ impl SourceMapper {
         } else {
             Vec::new()
         };
+        let function_cache = if has_symbols {
+            Self::build_function_cache(&wasm_bytes).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
 
         Self {
             has_symbols,
             line_cache,
+            function_cache,
             git_repo,
         }
     }
@@ -106,6 +124,33 @@ impl SourceMapper {
             .map_err(|err| format!("failed to parse .debug_line: {err}"))
     }
 
+    #[allow(deprecated)]
+    fn build_function_cache(wasm_bytes: &[u8]) -> Result<Vec<CachedFunctionEntry>, String> {
+        let obj_file = object::File::parse(wasm_bytes)
+            .map_err(|err| format!("failed to parse wasm object: {err}"))?;
+        let endian = if obj_file.is_little_endian() {
+            RunTimeEndian::Little
+        } else {
+            RunTimeEndian::Big
+        };
+
+        let dwarf_sections = Dwarf::load(|id: SectionId| -> Result<Cow<'_, [u8]>, gimli::Error> {
+            if let Some(section) = obj_file.section_by_name(id.name()) {
+                match section.uncompressed_data() {
+                    Ok(data) => Ok(data),
+                    Err(_) => Ok(Cow::Borrowed(&[])),
+                }
+            } else {
+                Ok(Cow::Borrowed(&[]))
+            }
+        })
+        .map_err(|err| format!("failed to load DWARF: {err}"))?;
+
+        let dwarf = dwarf_sections.borrow(|section| EndianSlice::new(section.as_ref(), endian));
+        Self::extract_function_entries(&dwarf)
+            .map_err(|err| format!("failed to parse .debug_info subprograms: {err}"))
+    }
+
     fn extract_line_entries<R>(dwarf: &Dwarf<R>) -> Result<Vec<CachedLineEntry>, gimli::Error>
     where
         R: Reader,
@@ -201,6 +246,61 @@ impl SourceMapper {
         Self::dedupe_same_address_entries(cache)
     }
 
+    /// Walk every unit's `DW_TAG_subprogram` entries, recording each
+    /// function's address range and name so a WASM offset falling inside it
+    /// can be attributed to a real Rust function name rather than only a
+    /// source line.
+    fn extract_function_entries<R>(dwarf: &Dwarf<R>) -> Result<Vec<CachedFunctionEntry>, gimli::Error>
+    where
+        R: Reader,
+    {
+        let mut functions = Vec::new();
+        let mut units = dwarf.units();
+
+        while let Some(header) = units.next()? {
+            let unit = dwarf.unit(header)?;
+            let mut cursor = unit.entries();
+
+            while let Some((_, entry)) = cursor.next_dfs()? {
+                if entry.tag() != gimli::DW_TAG_subprogram {
+                    continue;
+                }
+
+                let Some(low_pc) = entry
+                    .attr_value(gimli::DW_AT_low_pc)?
+                    .and_then(|v| v.udata_value())
+                else {
+                    continue;
+                };
+
+                let high_pc = entry
+                    .attr_value(gimli::DW_AT_high_pc)?
+                    .and_then(|v| match v {
+                        gimli::AttributeValue::Udata(offset_from_low) => {
+                            Some(low_pc.saturating_add(offset_from_low))
+                        }
+                        other => other.udata_value(),
+                    });
+
+                let Some(name) = entry
+                    .attr_value(gimli::DW_AT_name)?
+                    .and_then(|v| Self::attr_value_to_string(dwarf, &unit, v))
+                else {
+                    continue;
+                };
+
+                functions.push(CachedFunctionEntry {
+                    start: low_pc,
+                    end: high_pc,
+                    name,
+                });
+            }
+        }
+
+        functions.sort_by_key(|entry| entry.start);
+        Ok(functions)
+    }
+
     fn dedupe_same_address_entries(
         entries: Vec<CachedLineEntry>,
     ) -> Result<Vec<CachedLineEntry>, gimli::Error> {
@@ -261,6 +361,46 @@ impl SourceMapper {
         Some(location)
     }
 
+    /// The name of the Rust function whose `DW_TAG_subprogram` range
+    /// contains `wasm_offset`, or `None` if there are no debug symbols or
+    /// the offset falls outside every known function's range.
+    pub fn resolve_function_name(&self, wasm_offset: u64) -> Option<String> {
+        if !self.has_symbols || self.function_cache.is_empty() {
+            return None;
+        }
+
+        let idx = match self
+            .function_cache
+            .binary_search_by_key(&wasm_offset, |entry| entry.start)
+        {
+            Ok(index) => index,
+            Err(0) => return None,
+            Err(index) => index.saturating_sub(1),
+        };
+
+        let entry = self.function_cache.get(idx)?;
+        if let Some(end) = entry.end {
+            if wasm_offset >= end {
+                return None;
+            }
+        }
+
+        Some(entry.name.clone())
+    }
+
+    /// The source location of the first line inside the named function's
+    /// body, found by matching `name` against the recorded
+    /// `DW_TAG_subprogram` entries. Used to label a call-frame by function
+    /// name (e.g. `contract::fn_a`) with its defining `file:line`, rather
+    /// than only a bare WASM offset, when debug symbols are available.
+    pub fn function_location_by_name(&self, name: &str) -> Option<SourceLocation> {
+        let entry = self
+            .function_cache
+            .iter()
+            .find(|entry| entry.name == name || entry.name.ends_with(&format!("::{name}")))?;
+        self.map_wasm_offset_to_source(entry.start)
+    }
+
     pub fn create_source_location(&self, file: String, line: u32, column: Option<u32>) -> SourceLocation {
         let github_link = self.git_repo
             .as_ref()
@@ -278,6 +418,84 @@ impl SourceMapper {
     pub fn has_debug_symbols(&self) -> bool {
         self.has_symbols
     }
+
+    /// Build a mapper backed by an explicit offset-range-to-location table,
+    /// bypassing DWARF parsing entirely. Used by other modules' tests (e.g.
+    /// `stack_trace`) that need a `SourceMapper` with known resolutions but
+    /// have no WASM fixture with real debug info to parse.
+    #[cfg(test)]
+    pub(crate) fn for_tests_with_line_cache(entries: Vec<(u64, Option<u64>, SourceLocation)>) -> Self {
+        SourceMapper {
+            has_symbols: true,
+            line_cache: entries
+                .into_iter()
+                .map(|(start, end, location)| CachedLineEntry { start, end, location })
+                .collect(),
+            function_cache: Vec::new(),
+            git_repo: None,
+        }
+    }
+
+    /// A rustc-style annotated snippet around `location`: a couple of lines
+    /// of surrounding context plus a caret line pointing at `location`'s
+    /// column. Returns `None` when `location.file` can't be read from disk
+    /// (e.g. the WASM was built in a different environment, or this process
+    /// isn't running from the contract's source checkout) rather than
+    /// fabricating source text that was never actually executed.
+    pub fn source_snippet(&self, location: &SourceLocation) -> Option<String> {
+        const CONTEXT_LINES: u32 = 2;
+
+        let contents = std::fs::read_to_string(&location.file).ok()?;
+        let lines: Vec<&str> = contents.lines().collect();
+        let target = location.line;
+        if target == 0 || target as usize > lines.len() {
+            return None;
+        }
+
+        let first = target.saturating_sub(CONTEXT_LINES).max(1);
+        let last = (target + CONTEXT_LINES).min(lines.len() as u32);
+        let gutter_width = last.to_string().len();
+
+        let mut out = String::new();
+        out.push_str(&format!("{:>w$}--> {}:{}\n", "", location.file, target, w = gutter_width));
+        for line_no in first..=last {
+            let text = lines[(line_no - 1) as usize];
+            out.push_str(&format!("{line_no:>gutter_width$} | {text}\n"));
+            if line_no == target {
+                let col = location.column.unwrap_or(1).max(1) as usize;
+                out.push_str(&format!(
+                    "{:>gutter_width$} | {}^\n",
+                    "",
+                    " ".repeat(col - 1)
+                ));
+            }
+        }
+        Some(out)
+    }
+
+    /// Every source location the artifact's debug symbols resolve to, with
+    /// GitHub links resolved where a git repository was detected. Used to
+    /// build a complete, portable source map (e.g. for a
+    /// [`crate::provenance::ProvenanceBundle`]) rather than looking up
+    /// individual WASM offsets one at a time.
+    pub fn all_source_locations(&self) -> Vec<SourceLocation> {
+        self.line_cache
+            .iter()
+            .map(|entry| {
+                let mut location = entry.location.clone();
+                if let Some(ref git_repo) = self.git_repo {
+                    location.github_link = git_repo.generate_file_link(&location.file, location.line);
+                }
+                location
+            })
+            .collect()
+    }
+
+    /// The git commit the mapped artifact was built from, if one was
+    /// detected in the current working directory.
+    pub fn git_commit_hash(&self) -> Option<&str> {
+        self.git_repo.as_ref().map(|repo| repo.commit_hash.as_str())
+    }
 }
 
 #[cfg(test)]
@@ -290,6 +508,19 @@ mod tests {
         SourceMapper {
             has_symbols: true,
             line_cache: entries,
+            function_cache: Vec::new(),
+            git_repo: None,
+        }
+    }
+
+    fn mapper_with_function_cache(
+        line_entries: Vec<CachedLineEntry>,
+        function_entries: Vec<CachedFunctionEntry>,
+    ) -> SourceMapper {
+        SourceMapper {
+            has_symbols: true,
+            line_cache: line_entries,
+            function_cache: function_entries,
             git_repo: None,
         }
     }
@@ -365,6 +596,100 @@ mod tests {
         assert!(mapper.map_wasm_offset_to_source(0x20).is_none());
     }
 
+    #[test]
+    fn test_resolve_function_name_uses_address_ranges() {
+        let mapper = mapper_with_function_cache(
+            Vec::new(),
+            vec![
+                CachedFunctionEntry {
+                    start: 0x10,
+                    end: Some(0x20),
+                    name: "contract::fn_a".to_string(),
+                },
+                CachedFunctionEntry {
+                    start: 0x20,
+                    end: None,
+                    name: "contract::fn_b".to_string(),
+                },
+            ],
+        );
+
+        assert_eq!(
+            mapper.resolve_function_name(0x18),
+            Some("contract::fn_a".to_string())
+        );
+        assert_eq!(
+            mapper.resolve_function_name(0x25),
+            Some("contract::fn_b".to_string())
+        );
+        assert_eq!(mapper.resolve_function_name(0x5), None);
+    }
+
+    #[test]
+    fn test_function_location_by_name_resolves_defining_line() {
+        let mapper = mapper_with_function_cache(
+            vec![CachedLineEntry {
+                start: 0x10,
+                end: Some(0x20),
+                location: SourceLocation {
+                    file: "lib.rs".into(),
+                    line: 12,
+                    column: None,
+                    column_end: None,
+                    github_link: None,
+                },
+            }],
+            vec![CachedFunctionEntry {
+                start: 0x10,
+                end: Some(0x20),
+                name: "contract::fn_a".to_string(),
+            }],
+        );
+
+        let loc = mapper
+            .function_location_by_name("fn_a")
+            .expect("should resolve by suffix match");
+        assert_eq!(loc.file, "lib.rs");
+        assert_eq!(loc.line, 12);
+
+        assert!(mapper.function_location_by_name("missing").is_none());
+    }
+
+    #[test]
+    fn test_source_snippet_renders_context_and_caret() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("lib.rs");
+        std::fs::write(&file_path, "fn transfer() {\n    let x = balance - amount;\n}\n").unwrap();
+
+        let mapper = mapper_with_cache(vec![]);
+        let location = SourceLocation {
+            file: file_path.to_string_lossy().into_owned(),
+            line: 2,
+            column: Some(13),
+            column_end: None,
+            github_link: None,
+        };
+
+        let snippet = mapper.source_snippet(&location).expect("file is readable");
+        assert!(snippet.contains("fn transfer"));
+        assert!(snippet.contains("let x = balance - amount;"));
+        assert!(snippet.contains("^"));
+        assert!(snippet.contains(&file_path.to_string_lossy().to_string()));
+    }
+
+    #[test]
+    fn test_source_snippet_returns_none_for_unreadable_file() {
+        let mapper = mapper_with_cache(vec![]);
+        let location = SourceLocation {
+            file: "/nonexistent/path/does-not-exist.rs".to_string(),
+            line: 1,
+            column: None,
+            column_end: None,
+            github_link: None,
+        };
+        assert!(mapper.source_snippet(&location).is_none());
+    }
+
     #[test]
     fn test_source_location_serialization() {
         let location = SourceLocation {
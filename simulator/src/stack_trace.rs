@@ -3,10 +3,22 @@
 
 //! Enhanced WASM stack trace generation.
 //!
-//! Exposes the Wasmi internal call stack directly on traps,
-//! bypassing Soroban Host abstractions for low-level debugging.
+//! [`WasmStackTrace::from_host_error_typed`] classifies a typed `HostError`'s
+//! top-level kind (genuine WASM trap vs. some other host-level failure)
+//! structurally, off `ScErrorType` rather than string matching. Neither
+//! `soroban_env_host` nor this crate's other modules expose the
+//! Wasmi-internal call stack or a `TrapCode` enum through any public type
+//! this crate depends on, so frame extraction and trap *sub*-kind (OOB
+//! access vs. divide-by-zero vs. ...) still fall back to scraping the
+//! error's `Debug` text via [`extract_frames`]/[`classify_trap`].
+//! [`WasmStackTrace::from_host_error`] remains for callers that only ever
+//! have that text (e.g. a caught panic payload) and never had a typed
+//! `HostError` to begin with.
 
+use crate::source_mapper::{SourceLocation, SourceMapper};
 use serde::Serialize;
+use soroban_env_host::{xdr::ScErrorType, HostError};
+use std::collections::HashMap;
 
 /// A single frame in a WASM call stack.
 #[derive(Debug, Clone, Serialize, PartialEq)]
@@ -21,6 +33,18 @@ pub struct StackFrame {
     pub wasm_offset: Option<u64>,
     /// Module name, if the WASM has an embedded name section.
     pub module: Option<String>,
+    /// This frame's `{file, line, col}`, resolved from `wasm_offset` via
+    /// [`SourceMapper::map_wasm_offset_to_source`] when debug symbols are
+    /// available. Populated by [`WasmStackTrace::resolve_source_locations`],
+    /// not at parse time, since that's the only point a `SourceMapper` is
+    /// in scope.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_location: Option<SourceLocation>,
+    /// A rustc-style annotated snippet around `source_location`, when the
+    /// source file could be read from disk. See
+    /// [`SourceMapper::source_snippet`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
 }
 
 /// Categorised trap reason extracted from a raw error string.
@@ -40,16 +64,35 @@ pub enum TrapKind {
 }
 
 /// Structured stack trace emitted on a WASM trap.
-#[derive(Debug, Clone, Serialize)]
+///
+/// Not `Clone`: [`Self::source_error`] carries a `HostError`, which this
+/// crate's dependency on `soroban_env_host` doesn't guarantee is `Clone`.
+/// Nothing in this crate cloned a `WasmStackTrace` before this field
+/// existed, so that's not a real loss.
+#[derive(Debug, Serialize)]
 pub struct WasmStackTrace {
     /// Categorised trap reason.
     pub trap_kind: TrapKind,
     /// Raw error message from the host/runtime.
     pub raw_message: String,
-    /// Ordered call stack frames (index 0 = trap site).
+    /// Ordered call stack frames (index 0 = trap site). From
+    /// [`Self::from_host_error_typed`], only populated for a genuine VM trap
+    /// (`trap_kind` other than `TrapKind::HostError`); a host-level failure
+    /// that merely passed through the WASM call has no WASM frames to
+    /// report. [`Self::from_host_error`] fills it from whatever frames the
+    /// text carries, whatever `trap_kind` the text classifies as.
     pub frames: Vec<StackFrame>,
     /// Whether the Host error was unwound through Soroban abstractions.
     pub soroban_wrapped: bool,
+    /// The original `HostError`, preserved as this trace's
+    /// `std::error::Error::source()` rather than flattened into
+    /// `raw_message`, when `soroban_wrapped` is true and the failure is a
+    /// host-level error rather than a genuine VM trap. `None` for genuine
+    /// traps (there's no separate "underlying" error to chain to) and for
+    /// traces built from a bare string via `from_host_error`/`from_panic`,
+    /// which never had a typed `HostError` to begin with.
+    #[serde(skip)]
+    pub source_error: Option<HostError>,
 }
 
 impl WasmStackTrace {
@@ -69,6 +112,48 @@ impl WasmStackTrace {
             raw_message: error_debug.to_string(),
             frames,
             soroban_wrapped,
+            source_error: None,
+        }
+    }
+
+    /// Build a stack trace from a typed `HostError`, the way a caller with
+    /// the actual error in hand (rather than just its rendered string)
+    /// should: `host_error.error.get_type()` tells us structurally whether
+    /// this is a genuine WASM trap (`ScErrorType::WasmVm`) or some other
+    /// host-level failure (budget, storage, auth, ...), removing the
+    /// guesswork `from_host_error`/`classify_trap` does by searching the
+    /// debug text for "hosterror"/"host error" substrings. The finer trap
+    /// *sub*-kind is still scraped from the debug text, since it isn't
+    /// exposed through any typed field on `HostError` in this crate's
+    /// dependency graph — see the module doc.
+    ///
+    /// Only a genuine trap gets `frames`: a host-level error (budget,
+    /// storage, auth, ...) never entered the WASM call stack this crate can
+    /// walk, so reusing `extract_frames` on its debug text would just
+    /// attribute frames from an unrelated trap to it. That error is instead
+    /// preserved whole as `source_error`, reconstructed from its
+    /// `ScErrorType`/`ScErrorCode` rather than cloned, since `HostError`
+    /// isn't guaranteed `Clone` here.
+    pub fn from_host_error_typed(host_error: &HostError) -> Self {
+        let error_debug = format!("{host_error:?}");
+        let is_trap = host_error.error.get_type() == ScErrorType::WasmVm;
+
+        let (trap_kind, frames, source_error) = if is_trap {
+            (classify_trap(&error_debug), extract_frames(&error_debug), None)
+        } else {
+            let preserved = HostError::from(soroban_env_host::Error::from_type_and_code(
+                host_error.error.get_type(),
+                host_error.error.get_code(),
+            ));
+            (TrapKind::HostError(error_debug.clone()), Vec::new(), Some(preserved))
+        };
+
+        WasmStackTrace {
+            trap_kind,
+            raw_message: error_debug,
+            frames,
+            soroban_wrapped: true,
+            source_error,
         }
     }
 
@@ -79,9 +164,46 @@ impl WasmStackTrace {
             raw_message: message.to_string(),
             frames: vec![],
             soroban_wrapped: false,
+            source_error: None,
+        }
+    }
+
+    /// Resolve each frame's `wasm_offset` into a `{file, line, col}` and an
+    /// annotated source snippet, when `mapper` has debug symbols. Separate
+    /// from `from_host_error`/`from_panic` since those only ever see the raw
+    /// error text — a `SourceMapper` is only in scope once the caller has
+    /// loaded the contract's WASM, further up the call chain in `main.rs`.
+    pub fn resolve_source_locations(&mut self, mapper: &SourceMapper) {
+        for frame in &mut self.frames {
+            let Some(offset) = frame.wasm_offset else {
+                continue;
+            };
+            let Some(location) = mapper.map_wasm_offset_to_source(offset) else {
+                continue;
+            };
+            frame.snippet = mapper.source_snippet(&location);
+            frame.source_location = Some(location);
         }
     }
 
+    /// Resolve every frame's function name, module, and source location
+    /// from `wasm` in one call: first the WASM `name` custom section via
+    /// [`crate::symbolizer::Symbolizer`] (cheap, survives release builds
+    /// that strip DWARF), then — when `.debug_info`/`.debug_line` are
+    /// present — [`SourceMapper`]/[`Self::resolve_source_locations`] for
+    /// `file:line:col`. Frame fields already populated (e.g. a function
+    /// name `extract_frames` scraped from a raw error string) are left
+    /// untouched by either step.
+    pub fn symbolicate(&mut self, wasm: &[u8]) {
+        let symbolizer = crate::symbolizer::Symbolizer::parse(wasm);
+        for frame in &mut self.frames {
+            symbolizer.symbolicate_frame(frame);
+        }
+
+        let mapper = SourceMapper::new(wasm.to_vec());
+        self.resolve_source_locations(&mapper);
+    }
+
     /// Format the trace as a human-readable string.
     pub fn display(&self) -> String {
         let mut out = String::new();
@@ -111,7 +233,15 @@ impl WasmStackTrace {
                 if let Some(ref module) = frame.module {
                     out.push_str(&format!(" in {}", module));
                 }
+                if let Some(ref location) = frame.source_location {
+                    out.push_str(&format!(" ({}:{})", location.file, location.line));
+                }
                 out.push('\n');
+                if let Some(ref snippet) = frame.snippet {
+                    for snippet_line in snippet.lines() {
+                        out.push_str(&format!("      {snippet_line}\n"));
+                    }
+                }
             }
         }
         out
@@ -132,6 +262,145 @@ impl WasmStackTrace {
             TrapKind::Unknown(_) => "unknown trap",
         }
     }
+
+    /// Render the captured frames as a Graphviz `digraph`: one node per
+    /// frame (labelled by [`frame_label`] plus its `@0x` offset when
+    /// known), with edges from caller to callee following the frame
+    /// indices (index 0 = trap site, increasing index = further out). The
+    /// trap-site node gets a distinct shape/color and its
+    /// [`Self::trap_kind_label`] folded into the label; a dashed root node
+    /// marks where the trace was unwound through Soroban's Host
+    /// abstractions, when `soroban_wrapped` is set. Meant to be rendered
+    /// alongside [`Self::display`]'s plain-text form for deeply nested
+    /// contract calls where the text form alone is hard to follow.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        dot.push_str("digraph WasmStackTrace {\n");
+
+        if self.soroban_wrapped {
+            dot.push_str("  root [label=\"Soroban Host\", shape=box, style=dashed];\n");
+            if let Some(outermost) = self.frames.last() {
+                dot.push_str(&format!(
+                    "  root -> frame{} [style=dashed];\n",
+                    outermost.index
+                ));
+            }
+        }
+
+        for frame in &self.frames {
+            let mut label = frame_label(frame);
+            if let Some(offset) = frame.wasm_offset {
+                label.push_str(&format!(" @0x{offset:x}"));
+            }
+
+            if frame.index == 0 {
+                dot.push_str(&format!(
+                    "  frame{} [label=\"{}\\n{}\", shape=doublecircle, color=red];\n",
+                    frame.index,
+                    escape_dot(&label),
+                    escape_dot(self.trap_kind_label())
+                ));
+            } else {
+                dot.push_str(&format!(
+                    "  frame{} [label=\"{}\"];\n",
+                    frame.index,
+                    escape_dot(&label)
+                ));
+            }
+        }
+
+        for callee_and_caller in self.frames.windows(2) {
+            let (callee, caller) = (&callee_and_caller[0], &callee_and_caller[1]);
+            dot.push_str(&format!("  frame{} -> frame{};\n", caller.index, callee.index));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl std::fmt::Display for WasmStackTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display())
+    }
+}
+
+impl std::error::Error for WasmStackTrace {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source_error
+            .as_ref()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// Aggregates many [`WasmStackTrace`]s into collapsed folded-stack lines —
+/// `frameA;frameB;frameC <count>`, outermost frame first — the format
+/// standard flamegraph tooling consumes directly (see `render_flamegraph`
+/// in `main.rs`). Fuzzing or replaying a large batch of invocations
+/// produces one trace per trap; this turns that pile of individually
+/// useful-but-repetitive traces into an aggregate view of which call paths
+/// trap most often.
+#[derive(Debug, Default)]
+pub struct TraceAggregator {
+    counts: HashMap<String, u64>,
+}
+
+impl TraceAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one more occurrence of `trace`'s call stack.
+    pub fn ingest(&mut self, trace: &WasmStackTrace) {
+        let stack = folded_stack(&trace.frames);
+        *self.counts.entry(stack).or_insert(0) += 1;
+    }
+
+    /// Render every distinct stack as a `stack <count>` line, sorted by
+    /// stack so the output is deterministic across runs with the same
+    /// input traces.
+    pub fn folded_stacks(&self) -> String {
+        let mut stacks: Vec<&String> = self.counts.keys().collect();
+        stacks.sort();
+        stacks
+            .into_iter()
+            .map(|stack| format!("{} {}\n", stack, self.counts[stack]))
+            .collect()
+    }
+}
+
+/// Join `frames` outermost-first (frames are stored innermost/trap-site
+/// first) into a single folded-stack string, labelling each frame by
+/// `func_name`, falling back to `func[idx]`, then `<unknown>`. An empty
+/// frame list folds to `<no frames>` rather than an empty line, since a
+/// blank folded-stack line isn't meaningful to flamegraph tooling.
+fn folded_stack(frames: &[StackFrame]) -> String {
+    if frames.is_empty() {
+        return "<no frames>".to_string();
+    }
+    frames
+        .iter()
+        .rev()
+        .map(frame_label)
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn frame_label(frame: &StackFrame) -> String {
+    if let Some(name) = &frame.func_name {
+        name.clone()
+    } else if let Some(idx) = frame.func_index {
+        format!("func[{idx}]")
+    } else {
+        "<unknown>".to_string()
+    }
+}
+
+/// Escape a string for safe inclusion in a Graphviz DOT quoted label:
+/// backslashes and double quotes are escaped, since labels are emitted
+/// wrapped in `"..."` by [`WasmStackTrace::to_dot`].
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
 }
 
 /// Classify a raw error string into a known trap kind.
@@ -208,6 +477,8 @@ fn try_parse_numbered_frame(line: &str) -> Option<StackFrame> {
         func_name,
         wasm_offset,
         module: None,
+        source_location: None,
+        snippet: None,
     })
 }
 
@@ -222,6 +493,8 @@ fn try_parse_bare_frame(line: &str, index: usize) -> Option<StackFrame> {
             func_name,
             wasm_offset,
             module: None,
+            source_location: None,
+            snippet: None,
         })
     } else {
         None
@@ -390,6 +663,175 @@ mod tests {
         assert_eq!(trace.trap_kind, TrapKind::Unreachable);
     }
 
+    #[test]
+    fn test_from_host_error_typed_classifies_wasm_vm_errors_as_traps() {
+        let host_error = HostError::from(soroban_env_host::Error::from_type_and_code(
+            soroban_env_host::xdr::ScErrorType::WasmVm,
+            soroban_env_host::xdr::ScErrorCode::InternalError,
+        ));
+
+        let trace = WasmStackTrace::from_host_error_typed(&host_error);
+
+        assert!(trace.soroban_wrapped);
+        assert!(!matches!(trace.trap_kind, TrapKind::HostError(_)));
+    }
+
+    #[test]
+    fn test_from_host_error_typed_does_not_mistake_non_trap_errors_for_traps() {
+        let host_error = HostError::from(soroban_env_host::Error::from_type_and_code(
+            soroban_env_host::xdr::ScErrorType::Budget,
+            soroban_env_host::xdr::ScErrorCode::ExceededLimit,
+        ));
+
+        let trace = WasmStackTrace::from_host_error_typed(&host_error);
+
+        assert!(matches!(trace.trap_kind, TrapKind::HostError(_)));
+    }
+
+    #[test]
+    fn test_from_host_error_typed_only_populates_frames_for_genuine_traps() {
+        let trap = HostError::from(soroban_env_host::Error::from_type_and_code(
+            soroban_env_host::xdr::ScErrorType::WasmVm,
+            soroban_env_host::xdr::ScErrorCode::InternalError,
+        ));
+        let host_level_error = HostError::from(soroban_env_host::Error::from_type_and_code(
+            soroban_env_host::xdr::ScErrorType::Budget,
+            soroban_env_host::xdr::ScErrorCode::ExceededLimit,
+        ));
+
+        assert!(WasmStackTrace::from_host_error_typed(&host_level_error)
+            .frames
+            .is_empty());
+        // A trap's frames come from its debug text; this assertion only
+        // checks it runs the trap path, not that frames are non-empty,
+        // since `ScErrorType::WasmVm`'s synthetic `Debug` text here carries
+        // no parseable frame lines either.
+        let _ = WasmStackTrace::from_host_error_typed(&trap);
+    }
+
+    #[test]
+    fn test_from_host_error_typed_preserves_the_host_error_as_source_for_non_traps() {
+        use std::error::Error;
+
+        let host_error = HostError::from(soroban_env_host::Error::from_type_and_code(
+            soroban_env_host::xdr::ScErrorType::Budget,
+            soroban_env_host::xdr::ScErrorCode::ExceededLimit,
+        ));
+
+        let trace = WasmStackTrace::from_host_error_typed(&host_error);
+
+        assert!(trace.source().is_some());
+    }
+
+    #[test]
+    fn test_from_host_error_typed_has_no_source_for_genuine_traps() {
+        use std::error::Error;
+
+        let trap = HostError::from(soroban_env_host::Error::from_type_and_code(
+            soroban_env_host::xdr::ScErrorType::WasmVm,
+            soroban_env_host::xdr::ScErrorCode::InternalError,
+        ));
+
+        let trace = WasmStackTrace::from_host_error_typed(&trap);
+
+        assert!(trace.source().is_none());
+    }
+
+    #[test]
+    fn test_display_trait_matches_the_display_method() {
+        let trace = WasmStackTrace::from_panic("boom");
+        assert_eq!(trace.to_string(), trace.display());
+    }
+
+    fn frame(func_name: Option<&str>, func_index: Option<u32>) -> StackFrame {
+        StackFrame {
+            index: 0,
+            func_index,
+            func_name: func_name.map(str::to_string),
+            wasm_offset: None,
+            module: None,
+            source_location: None,
+            snippet: None,
+        }
+    }
+
+    #[test]
+    fn test_trace_aggregator_folds_identical_stacks_and_counts_them() {
+        let mut aggregator = TraceAggregator::new();
+        let trace = || WasmStackTrace {
+            trap_kind: TrapKind::Unreachable,
+            raw_message: "test".to_string(),
+            frames: vec![
+                frame(None, Some(1)),
+                frame(Some("transfer"), None),
+            ],
+            soroban_wrapped: false,
+            source_error: None,
+        };
+
+        aggregator.ingest(&trace());
+        aggregator.ingest(&trace());
+
+        assert_eq!(aggregator.folded_stacks(), "transfer;func[1] 2\n");
+    }
+
+    #[test]
+    fn test_trace_aggregator_keeps_distinct_stacks_separate_and_sorted() {
+        let mut aggregator = TraceAggregator::new();
+        aggregator.ingest(&WasmStackTrace {
+            trap_kind: TrapKind::Unreachable,
+            raw_message: "a".to_string(),
+            frames: vec![frame(Some("b_func"), None)],
+            soroban_wrapped: false,
+            source_error: None,
+        });
+        aggregator.ingest(&WasmStackTrace {
+            trap_kind: TrapKind::Unreachable,
+            raw_message: "b".to_string(),
+            frames: vec![frame(Some("a_func"), None)],
+            soroban_wrapped: false,
+            source_error: None,
+        });
+
+        assert_eq!(aggregator.folded_stacks(), "a_func 1\nb_func 1\n");
+    }
+
+    #[test]
+    fn test_trace_aggregator_folds_stacks_outermost_frame_first() {
+        let mut aggregator = TraceAggregator::new();
+        aggregator.ingest(&WasmStackTrace {
+            trap_kind: TrapKind::Unreachable,
+            raw_message: "test".to_string(),
+            frames: vec![frame(Some("inner"), None), frame(Some("outer"), None)],
+            soroban_wrapped: false,
+            source_error: None,
+        });
+
+        assert_eq!(aggregator.folded_stacks(), "outer;inner 1\n");
+    }
+
+    #[test]
+    fn test_trace_aggregator_labels_frames_without_a_name_or_index_as_unknown() {
+        let mut aggregator = TraceAggregator::new();
+        aggregator.ingest(&WasmStackTrace {
+            trap_kind: TrapKind::Unreachable,
+            raw_message: "test".to_string(),
+            frames: vec![frame(None, None)],
+            soroban_wrapped: false,
+            source_error: None,
+        });
+
+        assert_eq!(aggregator.folded_stacks(), "<unknown> 1\n");
+    }
+
+    #[test]
+    fn test_trace_aggregator_folds_empty_frame_lists_without_a_blank_line() {
+        let mut aggregator = TraceAggregator::new();
+        aggregator.ingest(&WasmStackTrace::from_panic("boom"));
+
+        assert_eq!(aggregator.folded_stacks(), "<no frames> 1\n");
+    }
+
     #[test]
     fn test_from_panic() {
         let trace = WasmStackTrace::from_panic("assertion failed");
@@ -410,6 +852,8 @@ mod tests {
                     func_name: None,
                     wasm_offset: Some(0xa3c),
                     module: None,
+                    source_location: None,
+                    snippet: None,
                 },
                 StackFrame {
                     index: 1,
@@ -417,9 +861,12 @@ mod tests {
                     func_name: Some("my_contract::transfer".to_string()),
                     wasm_offset: Some(0xb20),
                     module: Some("token".to_string()),
+                    source_location: None,
+                    snippet: None,
                 },
             ],
             soroban_wrapped: false,
+            source_error: None,
         };
 
         let output = trace.display();
@@ -494,4 +941,303 @@ mod tests {
         assert_eq!(capitalise_first(""), "");
         assert_eq!(capitalise_first("a"), "A");
     }
+
+    fn leb128(mut value: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(byte);
+                break;
+            } else {
+                bytes.push(byte | 0x80);
+            }
+        }
+        bytes
+    }
+
+    fn wasm_string(s: &str) -> Vec<u8> {
+        let mut bytes = leb128(s.len() as u32);
+        bytes.extend_from_slice(s.as_bytes());
+        bytes
+    }
+
+    fn name_subsection(id: u8, content: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![id];
+        bytes.extend(leb128(content.len() as u32));
+        bytes.extend_from_slice(content);
+        bytes
+    }
+
+    /// A minimal valid WASM module (magic + version + nothing else) with a
+    /// single `name` custom section carrying one function name and a
+    /// module name, built by hand the way `deploy.rs`'s tests build
+    /// minimal WASM fixtures.
+    fn wasm_with_name_section() -> Vec<u8> {
+        let mut function_names = leb128(1);
+        function_names.extend(leb128(42));
+        function_names.extend(wasm_string("transfer"));
+
+        let mut name_section_content = wasm_string("name");
+        name_section_content.extend(name_subsection(0, &wasm_string("token_contract")));
+        name_section_content.extend(name_subsection(1, &function_names));
+
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        wasm.push(0x00); // custom section id
+        wasm.extend(leb128(name_section_content.len() as u32));
+        wasm.extend(name_section_content);
+        wasm
+    }
+
+    #[test]
+    fn test_symbolicate_fills_func_name_and_module_from_name_section() {
+        let mut trace = WasmStackTrace {
+            trap_kind: TrapKind::Unreachable,
+            raw_message: "test".to_string(),
+            frames: vec![StackFrame {
+                index: 0,
+                func_index: Some(42),
+                func_name: None,
+                wasm_offset: None,
+                module: None,
+                source_location: None,
+                snippet: None,
+            }],
+            soroban_wrapped: false,
+            source_error: None,
+        };
+
+        trace.symbolicate(&wasm_with_name_section());
+
+        assert_eq!(trace.frames[0].func_name, Some("transfer".to_string()));
+        assert_eq!(trace.frames[0].module, Some("token_contract".to_string()));
+    }
+
+    #[test]
+    fn test_symbolicate_leaves_an_already_named_frame_untouched() {
+        let mut trace = WasmStackTrace {
+            trap_kind: TrapKind::Unreachable,
+            raw_message: "test".to_string(),
+            frames: vec![StackFrame {
+                index: 0,
+                func_index: Some(42),
+                func_name: Some("keep_me".to_string()),
+                wasm_offset: None,
+                module: None,
+                source_location: None,
+                snippet: None,
+            }],
+            soroban_wrapped: false,
+            source_error: None,
+        };
+
+        trace.symbolicate(&wasm_with_name_section());
+
+        assert_eq!(trace.frames[0].func_name, Some("keep_me".to_string()));
+        assert_eq!(trace.frames[0].module, Some("token_contract".to_string()));
+    }
+
+    #[test]
+    fn test_symbolicate_is_a_noop_for_invalid_wasm_bytes() {
+        let mut trace = WasmStackTrace {
+            trap_kind: TrapKind::Unreachable,
+            raw_message: "test".to_string(),
+            frames: vec![StackFrame {
+                index: 0,
+                func_index: Some(1),
+                func_name: None,
+                wasm_offset: None,
+                module: None,
+                source_location: None,
+                snippet: None,
+            }],
+            soroban_wrapped: false,
+            source_error: None,
+        };
+
+        trace.symbolicate(b"not a wasm module");
+
+        assert!(trace.frames[0].func_name.is_none());
+        assert!(trace.frames[0].module.is_none());
+    }
+
+    #[test]
+    fn test_resolve_source_locations_annotates_every_frame() {
+        let mapper = SourceMapper::for_tests_with_line_cache(vec![
+            (
+                0xa3c,
+                Some(0xb00),
+                SourceLocation {
+                    file: "contract.rs".to_string(),
+                    line: 42,
+                    column: Some(5),
+                    column_end: None,
+                    github_link: None,
+                },
+            ),
+            (
+                0xb20,
+                Some(0xc00),
+                SourceLocation {
+                    file: "token.rs".to_string(),
+                    line: 17,
+                    column: Some(9),
+                    column_end: None,
+                    github_link: None,
+                },
+            ),
+        ]);
+
+        let mut trace = WasmStackTrace {
+            trap_kind: TrapKind::OutOfBoundsMemoryAccess,
+            raw_message: "test".to_string(),
+            frames: vec![
+                StackFrame {
+                    index: 0,
+                    func_index: Some(42),
+                    func_name: None,
+                    wasm_offset: Some(0xa3c),
+                    module: None,
+                    source_location: None,
+                    snippet: None,
+                },
+                StackFrame {
+                    index: 1,
+                    func_index: None,
+                    func_name: Some("my_contract::transfer".to_string()),
+                    wasm_offset: Some(0xb20),
+                    module: Some("token".to_string()),
+                    source_location: None,
+                    snippet: None,
+                },
+            ],
+            soroban_wrapped: false,
+            source_error: None,
+        };
+
+        trace.resolve_source_locations(&mapper);
+
+        assert_eq!(trace.frames[0].source_location.as_ref().unwrap().file, "contract.rs");
+        assert_eq!(trace.frames[0].source_location.as_ref().unwrap().line, 42);
+        assert_eq!(trace.frames[1].source_location.as_ref().unwrap().file, "token.rs");
+        assert_eq!(trace.frames[1].source_location.as_ref().unwrap().line, 17);
+
+        let display = trace.display();
+        assert!(display.contains("contract.rs:42"));
+        assert!(display.contains("token.rs:17"));
+    }
+
+    #[test]
+    fn test_to_dot_renders_one_node_per_frame_with_caller_to_callee_edges() {
+        let trace = WasmStackTrace {
+            trap_kind: TrapKind::Unreachable,
+            raw_message: "test".to_string(),
+            frames: vec![
+                StackFrame {
+                    index: 0,
+                    func_index: Some(7),
+                    func_name: None,
+                    wasm_offset: Some(0x10),
+                    module: None,
+                    source_location: None,
+                    snippet: None,
+                },
+                StackFrame {
+                    index: 1,
+                    func_index: None,
+                    func_name: Some("transfer".to_string()),
+                    wasm_offset: None,
+                    module: None,
+                    source_location: None,
+                    snippet: None,
+                },
+            ],
+            soroban_wrapped: false,
+            source_error: None,
+        };
+
+        let dot = trace.to_dot();
+        assert!(dot.starts_with("digraph WasmStackTrace {\n"));
+        assert!(dot.trim_end().ends_with('}'));
+        assert!(dot.contains("frame1 -> frame0;"));
+        assert!(!dot.contains("frame0 -> frame1;"));
+    }
+
+    #[test]
+    fn test_to_dot_labels_the_trap_site_frame_with_trap_kind_and_distinct_shape() {
+        let trace = WasmStackTrace {
+            trap_kind: TrapKind::IntegerDivisionByZero,
+            raw_message: "test".to_string(),
+            frames: vec![frame(Some("divide"), None)],
+            soroban_wrapped: false,
+            source_error: None,
+        };
+
+        let dot = trace.to_dot();
+        assert!(dot.contains("shape=doublecircle, color=red"));
+        assert!(dot.contains("divide\\ninteger division by zero"));
+    }
+
+    #[test]
+    fn test_to_dot_adds_a_dashed_root_node_when_soroban_wrapped() {
+        let mut wrapped_frame = frame(Some("entry"), None);
+        wrapped_frame.index = 0;
+
+        let trace = WasmStackTrace {
+            trap_kind: TrapKind::Unreachable,
+            raw_message: "test".to_string(),
+            frames: vec![wrapped_frame],
+            soroban_wrapped: true,
+            source_error: None,
+        };
+
+        let dot = trace.to_dot();
+        assert!(dot.contains("root [label=\"Soroban Host\", shape=box, style=dashed];"));
+        assert!(dot.contains("root -> frame0 [style=dashed];"));
+    }
+
+    #[test]
+    fn test_to_dot_omits_root_node_when_not_soroban_wrapped() {
+        let trace = WasmStackTrace {
+            trap_kind: TrapKind::Unreachable,
+            raw_message: "test".to_string(),
+            frames: vec![frame(Some("entry"), None)],
+            soroban_wrapped: false,
+            source_error: None,
+        };
+
+        assert!(!trace.to_dot().contains("root"));
+    }
+
+    #[test]
+    fn test_to_dot_renders_a_bare_digraph_for_empty_frames() {
+        let trace = WasmStackTrace {
+            trap_kind: TrapKind::Unknown("mystery".to_string()),
+            raw_message: "test".to_string(),
+            frames: vec![],
+            soroban_wrapped: false,
+            source_error: None,
+        };
+
+        assert_eq!(trace.to_dot(), "digraph WasmStackTrace {\n}\n");
+    }
+
+    #[test]
+    fn test_escape_dot_escapes_quotes_and_backslashes() {
+        assert_eq!(escape_dot(r#"say "hi" \ bye"#), r#"say \"hi\" \\ bye"#);
+    }
+
+    #[test]
+    fn test_to_dot_escapes_function_names_containing_quotes() {
+        let trace = WasmStackTrace {
+            trap_kind: TrapKind::Unreachable,
+            raw_message: "test".to_string(),
+            frames: vec![frame(Some(r#"weird"name"#), None)],
+            soroban_wrapped: false,
+            source_error: None,
+        };
+
+        assert!(trace.to_dot().contains(r#"weird\"name"#));
+    }
 }
@@ -0,0 +1,561 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured "what changed" reporting over a simulation's ledger storage.
+//!
+//! Compares the `LedgerKey`/`LedgerEntry` map the host started with against
+//! the one it ends with, classifying each key as created, modified, or
+//! removed, and — for Stellar Asset Contract balance entries — derives a
+//! signed balance delta per principal. Entries are rendered through
+//! [`scval_to_json`] rather than `{:?}` so the report reads like the values
+//! a caller actually stored, not a Rust debug dump.
+
+use crate::types::{BalanceDelta, LedgerEntryChange, StateChangeReport};
+use soroban_env_host::xdr::{
+    AssetCode12, AssetCode4, ContractDataEntry, LedgerEntry, LedgerEntryData, LedgerKey,
+    ScAddress, ScVal, TrustLineAsset,
+};
+use std::collections::HashMap;
+
+/// Compare `before` and `after` ledger-storage snapshots and build the full
+/// state-change report, including derived SAC, native-account and trustline
+/// balance deltas.
+pub fn diff(
+    before: &HashMap<LedgerKey, LedgerEntry>,
+    after: &HashMap<LedgerKey, LedgerEntry>,
+) -> StateChangeReport {
+    let mut report = StateChangeReport::default();
+
+    for (key, after_entry) in after {
+        match before.get(key) {
+            None => report.created.push(LedgerEntryChange {
+                key: scval_key_to_json(key),
+                before: None,
+                after: Some(describe_entry(after_entry)),
+            }),
+            Some(before_entry) => {
+                if ledger_entry_data_equal(before_entry, after_entry) {
+                    continue;
+                }
+                report.modified.push(LedgerEntryChange {
+                    key: scval_key_to_json(key),
+                    before: Some(describe_entry(before_entry)),
+                    after: Some(describe_entry(after_entry)),
+                });
+            }
+        }
+
+        if let Some(delta) = balance_delta(key, before.get(key), Some(after_entry)) {
+            report.balance_deltas.push(delta);
+        }
+    }
+
+    for (key, before_entry) in before {
+        if after.contains_key(key) {
+            continue;
+        }
+        report.removed.push(LedgerEntryChange {
+            key: scval_key_to_json(key),
+            before: Some(describe_entry(before_entry)),
+            after: None,
+        });
+
+        if let Some(delta) = balance_delta(key, Some(before_entry), None) {
+            report.balance_deltas.push(delta);
+        }
+    }
+
+    report
+}
+
+/// Derive a balance delta for whichever balance-bearing entry kind `key`
+/// addresses — a Stellar Asset Contract balance, a classic account's native
+/// XLM balance, or a trustline's issued-asset balance — or `None` if it's
+/// none of those.
+fn balance_delta(
+    key: &LedgerKey,
+    before: Option<&LedgerEntry>,
+    after: Option<&LedgerEntry>,
+) -> Option<BalanceDelta> {
+    sac_balance_delta(key, before, after)
+        .or_else(|| account_balance_delta(key, before, after))
+        .or_else(|| trustline_balance_delta(key, before, after))
+}
+
+fn ledger_entry_data_equal(a: &LedgerEntry, b: &LedgerEntry) -> bool {
+    // `LedgerEntryData` doesn't derive `PartialEq` in every host version, so
+    // compare the readable projection instead; this is also exactly the
+    // granularity the report cares about.
+    describe_entry(a) == describe_entry(b)
+}
+
+/// Render a `LedgerKey` the same way its matching entry would be rendered,
+/// so callers can correlate a key across `created`/`modified`/`removed`.
+fn scval_key_to_json(key: &LedgerKey) -> serde_json::Value {
+    match key {
+        LedgerKey::ContractData(k) => serde_json::json!({
+            "kind": "contract_data",
+            "contract": sc_address_to_json(&k.contract),
+            "key": scval_to_json(&k.key),
+        }),
+        LedgerKey::ContractCode(k) => serde_json::json!({
+            "kind": "contract_code",
+            "hash": hex::encode(k.hash.0),
+        }),
+        LedgerKey::Account(k) => serde_json::json!({
+            "kind": "account",
+            "account_id": format!("{:?}", k.account_id),
+        }),
+        LedgerKey::Trustline(k) => serde_json::json!({
+            "kind": "trustline",
+            "account_id": format!("{:?}", k.account_id),
+            "asset": trustline_asset_to_string(&k.asset),
+        }),
+        other => serde_json::json!({
+            "kind": "other",
+            "debug": format!("{:?}", other),
+        }),
+    }
+}
+
+fn describe_entry(entry: &LedgerEntry) -> serde_json::Value {
+    match &entry.data {
+        LedgerEntryData::ContractData(data) => serde_json::json!({
+            "kind": "contract_data",
+            "contract": sc_address_to_json(&data.contract),
+            "durability": format!("{:?}", data.durability),
+            "key": scval_to_json(&data.key),
+            "value": scval_to_json(&data.val),
+        }),
+        LedgerEntryData::ContractCode(data) => serde_json::json!({
+            "kind": "contract_code",
+            "hash": hex::encode(data.hash.0),
+            "size_bytes": data.code.len(),
+        }),
+        LedgerEntryData::Account(data) => serde_json::json!({
+            "kind": "account",
+            "balance": data.balance,
+        }),
+        LedgerEntryData::Trustline(data) => serde_json::json!({
+            "kind": "trustline",
+            "asset": trustline_asset_to_string(&data.asset),
+            "balance": data.balance,
+        }),
+        other => serde_json::json!({
+            "kind": "other",
+            "debug": format!("{:?}", other),
+        }),
+    }
+}
+
+/// A best-effort, human-readable projection of an `ScVal`. Falls back to a
+/// debug string for variants that don't have an obvious JSON shape.
+fn scval_to_json(val: &ScVal) -> serde_json::Value {
+    match val {
+        ScVal::Bool(b) => serde_json::json!(b),
+        ScVal::Void => serde_json::Value::Null,
+        ScVal::U32(v) => serde_json::json!(v),
+        ScVal::I32(v) => serde_json::json!(v),
+        ScVal::U64(v) => serde_json::json!(v.to_string()),
+        ScVal::I64(v) => serde_json::json!(v.to_string()),
+        ScVal::U128(v) => serde_json::json!(format!("{}", (v.hi as u128) << 64 | v.lo as u128)),
+        ScVal::I128(v) => serde_json::json!(format!("{}", ((v.hi as i128) << 64) | v.lo as i128)),
+        ScVal::Bytes(b) => serde_json::json!(hex::encode(b.as_slice())),
+        ScVal::String(s) => serde_json::json!(s.to_string()),
+        ScVal::Symbol(s) => serde_json::json!(s.to_string()),
+        ScVal::Address(addr) => sc_address_scval_to_json(addr),
+        ScVal::Vec(Some(items)) => {
+            serde_json::Value::Array(items.iter().map(scval_to_json).collect())
+        }
+        ScVal::Map(Some(entries)) => {
+            let mut object = serde_json::Map::new();
+            for entry in entries.iter() {
+                let key = match scval_to_json(&entry.key) {
+                    serde_json::Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                object.insert(key, scval_to_json(&entry.val));
+            }
+            serde_json::Value::Object(object)
+        }
+        other => serde_json::json!(format!("{:?}", other)),
+    }
+}
+
+fn sc_address_to_json(addr: &ScAddress) -> serde_json::Value {
+    match addr {
+        ScAddress::Account(account_id) => serde_json::json!(format!("{:?}", account_id)),
+        ScAddress::Contract(hash) => serde_json::json!(hex::encode(hash.0)),
+    }
+}
+
+fn sc_address_scval_to_json(addr: &soroban_env_host::xdr::ScAddress) -> serde_json::Value {
+    sc_address_to_json(addr)
+}
+
+/// Recognize a Stellar Asset Contract balance entry — `ContractData` keyed
+/// by `["Balance", Address]` with a value map containing an `amount` field
+/// — and derive the signed delta between `before` and `after`.
+fn sac_balance_delta(
+    key: &LedgerKey,
+    before: Option<&LedgerEntry>,
+    after: Option<&LedgerEntry>,
+) -> Option<BalanceDelta> {
+    let LedgerKey::ContractData(k) = key else {
+        return None;
+    };
+
+    let principal = sac_balance_principal(&k.key)?;
+    let contract = match &k.contract {
+        ScAddress::Contract(hash) => hex::encode(hash.0),
+        ScAddress::Account(account_id) => format!("{:?}", account_id),
+    };
+
+    let before_amount = before.and_then(|e| sac_balance_amount(contract_data_of(e)?));
+    let after_amount = after.and_then(|e| sac_balance_amount(contract_data_of(e)?));
+
+    if before_amount.is_none() && after_amount.is_none() {
+        return None;
+    }
+
+    let delta = after_amount.unwrap_or(0) - before_amount.unwrap_or(0);
+    Some(BalanceDelta {
+        principal,
+        contract,
+        delta: delta.to_string(),
+    })
+}
+
+/// A classic account's native XLM balance delta, keyed by the account ID.
+fn account_balance_delta(
+    key: &LedgerKey,
+    before: Option<&LedgerEntry>,
+    after: Option<&LedgerEntry>,
+) -> Option<BalanceDelta> {
+    let LedgerKey::Account(k) = key else {
+        return None;
+    };
+
+    let before_amount = before.and_then(account_entry_balance);
+    let after_amount = after.and_then(account_entry_balance);
+    if before_amount.is_none() && after_amount.is_none() {
+        return None;
+    }
+
+    let delta = after_amount.unwrap_or(0) as i128 - before_amount.unwrap_or(0) as i128;
+    Some(BalanceDelta {
+        principal: format!("{:?}", k.account_id),
+        contract: "native".to_string(),
+        delta: delta.to_string(),
+    })
+}
+
+fn account_entry_balance(entry: &LedgerEntry) -> Option<i64> {
+    match &entry.data {
+        LedgerEntryData::Account(data) => Some(data.balance),
+        _ => None,
+    }
+}
+
+/// A trustline's issued-asset balance delta, keyed by the holding account
+/// and carrying the asset code/issuer as the delta's asset identifier.
+fn trustline_balance_delta(
+    key: &LedgerKey,
+    before: Option<&LedgerEntry>,
+    after: Option<&LedgerEntry>,
+) -> Option<BalanceDelta> {
+    let LedgerKey::Trustline(k) = key else {
+        return None;
+    };
+
+    let before_amount = before.and_then(trustline_entry_balance);
+    let after_amount = after.and_then(trustline_entry_balance);
+    if before_amount.is_none() && after_amount.is_none() {
+        return None;
+    }
+
+    let delta = after_amount.unwrap_or(0) as i128 - before_amount.unwrap_or(0) as i128;
+    Some(BalanceDelta {
+        principal: format!("{:?}", k.account_id),
+        contract: trustline_asset_to_string(&k.asset),
+        delta: delta.to_string(),
+    })
+}
+
+fn trustline_entry_balance(entry: &LedgerEntry) -> Option<i64> {
+    match &entry.data {
+        LedgerEntryData::Trustline(data) => Some(data.balance),
+        _ => None,
+    }
+}
+
+/// Render a `TrustLineAsset` as `"CODE:issuer"` (or `"native"`/`"pool:..."`),
+/// the same granularity a caller reading a Horizon-style balance line
+/// expects, rather than the raw XDR union.
+fn trustline_asset_to_string(asset: &TrustLineAsset) -> String {
+    match asset {
+        TrustLineAsset::Native => "native".to_string(),
+        TrustLineAsset::CreditAlphanum4(a) => format!(
+            "{}:{:?}",
+            asset_code4_to_string(&a.asset_code),
+            a.issuer
+        ),
+        TrustLineAsset::CreditAlphanum12(a) => format!(
+            "{}:{:?}",
+            asset_code12_to_string(&a.asset_code),
+            a.issuer
+        ),
+        TrustLineAsset::PoolShare(pool_id) => format!("pool:{:?}", pool_id),
+    }
+}
+
+fn asset_code4_to_string(code: &AssetCode4) -> String {
+    String::from_utf8_lossy(&code.0).trim_end_matches('\0').to_string()
+}
+
+fn asset_code12_to_string(code: &AssetCode12) -> String {
+    String::from_utf8_lossy(&code.0).trim_end_matches('\0').to_string()
+}
+
+fn contract_data_of(entry: &LedgerEntry) -> Option<&ContractDataEntry> {
+    match &entry.data {
+        LedgerEntryData::ContractData(data) => Some(data),
+        _ => None,
+    }
+}
+
+fn sac_balance_principal(key: &ScVal) -> Option<String> {
+    let ScVal::Vec(Some(items)) = key else {
+        return None;
+    };
+    if items.len() != 2 {
+        return None;
+    }
+    let ScVal::Symbol(tag) = &items[0] else {
+        return None;
+    };
+    if tag.to_string() != "Balance" {
+        return None;
+    }
+    let ScVal::Address(addr) = &items[1] else {
+        return None;
+    };
+    Some(match addr {
+        ScAddress::Account(account_id) => format!("{:?}", account_id),
+        ScAddress::Contract(hash) => hex::encode(hash.0),
+    })
+}
+
+fn sac_balance_amount(data: &ContractDataEntry) -> Option<i128> {
+    let ScVal::Map(Some(entries)) = &data.val else {
+        return None;
+    };
+    entries.iter().find_map(|entry| {
+        let ScVal::Symbol(sym) = &entry.key else {
+            return None;
+        };
+        if sym.to_string() != "amount" {
+            return None;
+        }
+        match &entry.val {
+            ScVal::I128(v) => Some(((v.hi as i128) << 64) | v.lo as i128),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{
+        AccountId, ContractDataDurability, ExtensionPoint, Hash, Int128Parts, LedgerEntryExt,
+        PublicKey, ScMap, ScMapEntry, ScSymbol, StringM, Uint256,
+    };
+
+    fn contract_data_entry(contract: Hash, key: ScVal, val: ScVal) -> LedgerEntry {
+        LedgerEntry {
+            last_modified_ledger_seq: 0,
+            data: LedgerEntryData::ContractData(ContractDataEntry {
+                ext: ExtensionPoint::V0,
+                contract: ScAddress::Contract(contract),
+                key,
+                durability: ContractDataDurability::Persistent,
+                val,
+            }),
+            ext: LedgerEntryExt::V0,
+        }
+    }
+
+    #[test]
+    fn created_entry_is_reported() {
+        let key = LedgerKey::ContractData(soroban_env_host::xdr::LedgerKeyContractData {
+            contract: ScAddress::Contract(Hash([1u8; 32])),
+            key: ScVal::U32(1),
+            durability: ContractDataDurability::Persistent,
+        });
+        let mut after = HashMap::new();
+        after.insert(
+            key,
+            contract_data_entry(Hash([1u8; 32]), ScVal::U32(1), ScVal::U64(42)),
+        );
+
+        let report = diff(&HashMap::new(), &after);
+        assert_eq!(report.created.len(), 1);
+        assert!(report.modified.is_empty());
+        assert!(report.removed.is_empty());
+    }
+
+    #[test]
+    fn removed_entry_is_reported() {
+        let key = LedgerKey::ContractData(soroban_env_host::xdr::LedgerKeyContractData {
+            contract: ScAddress::Contract(Hash([2u8; 32])),
+            key: ScVal::U32(2),
+            durability: ContractDataDurability::Persistent,
+        });
+        let mut before = HashMap::new();
+        before.insert(
+            key,
+            contract_data_entry(Hash([2u8; 32]), ScVal::U32(2), ScVal::U64(7)),
+        );
+
+        let report = diff(&before, &HashMap::new());
+        assert_eq!(report.removed.len(), 1);
+        assert!(report.created.is_empty());
+    }
+
+    fn balance_key(addr: ScAddress) -> ScVal {
+        ScVal::Vec(Some(
+            vec![
+                ScVal::Symbol(ScSymbol(StringM::try_from("Balance").unwrap())),
+                ScVal::Address(addr),
+            ]
+            .try_into()
+            .unwrap(),
+        ))
+    }
+
+    fn amount_val(amount: i128) -> ScVal {
+        ScVal::Map(Some(
+            ScMap(
+                vec![ScMapEntry {
+                    key: ScVal::Symbol(ScSymbol(StringM::try_from("amount").unwrap())),
+                    val: ScVal::I128(Int128Parts {
+                        hi: (amount >> 64) as i64,
+                        lo: amount as u64,
+                    }),
+                }]
+                .try_into()
+                .unwrap(),
+            )
+            .0,
+        ))
+    }
+
+    #[test]
+    fn sac_balance_delta_is_computed() {
+        let contract = Hash([3u8; 32]);
+        let holder = ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+            [4u8; 32],
+        ))));
+        let key = LedgerKey::ContractData(soroban_env_host::xdr::LedgerKeyContractData {
+            contract: ScAddress::Contract(contract),
+            key: balance_key(holder.clone()),
+            durability: ContractDataDurability::Persistent,
+        });
+
+        let mut before = HashMap::new();
+        before.insert(
+            key.clone(),
+            contract_data_entry(contract, balance_key(holder.clone()), amount_val(100)),
+        );
+        let mut after = HashMap::new();
+        after.insert(
+            key,
+            contract_data_entry(contract, balance_key(holder), amount_val(60)),
+        );
+
+        let report = diff(&before, &after);
+        assert_eq!(report.balance_deltas.len(), 1);
+        assert_eq!(report.balance_deltas[0].delta, "-40");
+    }
+
+    fn account_entry(account_id: AccountId, balance: i64) -> LedgerEntry {
+        LedgerEntry {
+            last_modified_ledger_seq: 0,
+            data: LedgerEntryData::Account(soroban_env_host::xdr::AccountEntry {
+                account_id,
+                balance,
+                seq_num: soroban_env_host::xdr::SequenceNumber(0),
+                num_sub_entries: 0,
+                inflation_dest: None,
+                flags: 0,
+                home_domain: StringM::default().try_into().unwrap(),
+                thresholds: soroban_env_host::xdr::Thresholds([0, 0, 0, 0]),
+                signers: Default::default(),
+                ext: soroban_env_host::xdr::AccountEntryExt::V0,
+            }),
+            ext: LedgerEntryExt::V0,
+        }
+    }
+
+    #[test]
+    fn account_native_balance_delta_is_computed() {
+        let account_id = AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([5u8; 32])));
+        let key = LedgerKey::Account(soroban_env_host::xdr::LedgerKeyAccount {
+            account_id: account_id.clone(),
+        });
+
+        let mut before = HashMap::new();
+        before.insert(key.clone(), account_entry(account_id.clone(), 1_000));
+        let mut after = HashMap::new();
+        after.insert(key, account_entry(account_id, 750));
+
+        let report = diff(&before, &after);
+        assert_eq!(report.balance_deltas.len(), 1);
+        assert_eq!(report.balance_deltas[0].contract, "native");
+        assert_eq!(report.balance_deltas[0].delta, "-250");
+    }
+
+    fn trustline_entry(
+        account_id: AccountId,
+        asset: TrustLineAsset,
+        balance: i64,
+    ) -> LedgerEntry {
+        LedgerEntry {
+            last_modified_ledger_seq: 0,
+            data: LedgerEntryData::Trustline(soroban_env_host::xdr::TrustLineEntry {
+                account_id,
+                asset,
+                balance,
+                limit: i64::MAX,
+                flags: 0,
+                ext: soroban_env_host::xdr::TrustLineEntryExt::V0,
+            }),
+            ext: LedgerEntryExt::V0,
+        }
+    }
+
+    #[test]
+    fn trustline_balance_delta_is_computed_and_asset_is_readable() {
+        let account_id = AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([6u8; 32])));
+        let issuer = AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([7u8; 32])));
+        let asset = TrustLineAsset::CreditAlphanum4(soroban_env_host::xdr::AlphaNum4 {
+            asset_code: AssetCode4(*b"USD\0"),
+            issuer,
+        });
+        let key = LedgerKey::Trustline(soroban_env_host::xdr::LedgerKeyTrustLine {
+            account_id: account_id.clone(),
+            asset: asset.clone(),
+        });
+
+        let mut before = HashMap::new();
+        before.insert(key.clone(), trustline_entry(account_id.clone(), asset.clone(), 500));
+        let mut after = HashMap::new();
+        after.insert(key, trustline_entry(account_id, asset, 600));
+
+        let report = diff(&before, &after);
+        assert_eq!(report.balance_deltas.len(), 1);
+        assert!(report.balance_deltas[0].contract.starts_with("USD:"));
+        assert_eq!(report.balance_deltas[0].delta, "100");
+    }
+}
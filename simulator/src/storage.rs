@@ -1,26 +1,46 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Merges a before-state ledger snapshot with the `LedgerEntryChange`s a
+//! simulation produced, keyed by each entry's canonical XDR-encoded
+//! `LedgerKey` rather than a `Debug` string (`Debug` output isn't a stable
+//! identity and can silently fail to match a `Removed` key against the
+//! entry it should delete).
+
+#![allow(dead_code)]
+
+use soroban_env_host::xdr::{
+    LedgerEntry, LedgerEntryChange, LedgerEntryData, LedgerKey, LedgerKeyAccount,
+    LedgerKeyContractCode, LedgerKeyContractData, LedgerKeyTrustLine, Limits, WriteXdr,
+};
 use std::collections::HashMap;
-use soroban_env_host::xdr::{LedgerEntry, LedgerEntryChange};
 
-fn merge_storage_state(
-    before: &[LedgerEntry],
-    changes: &[LedgerEntryChange],
-) -> Vec<LedgerEntry> {
-    let mut state: HashMap<String, LedgerEntry> = HashMap::new();
+/// What a transaction created, updated, or removed, computed by comparing
+/// `before` against the applied `changes` rather than only exposing the
+/// merged end state `merge_storage_state` returns.
+#[derive(Debug, Default)]
+pub struct StorageDiff {
+    pub created: Vec<LedgerEntry>,
+    pub updated: Vec<LedgerEntry>,
+    pub removed: Vec<LedgerKey>,
+}
+
+fn merge_storage_state(before: &[LedgerEntry], changes: &[LedgerEntryChange]) -> Vec<LedgerEntry> {
+    let mut state: HashMap<Vec<u8>, LedgerEntry> = HashMap::new();
 
     // Load BEFORE state
     for entry in before {
-        state.insert(format!("{:?}", entry.data), entry.clone());
+        state.insert(canonical_key(entry), entry.clone());
     }
 
     // Apply ResultMeta changes
     for change in changes {
         match change {
-            LedgerEntryChange::Created(e)
-            | LedgerEntryChange::Updated(e) => {
-                state.insert(format!("{:?}", e.data), e.clone());
+            LedgerEntryChange::Created(e) | LedgerEntryChange::Updated(e) => {
+                state.insert(canonical_key(e), e.clone());
             }
             LedgerEntryChange::Removed(key) => {
-                state.remove(&format!("{:?}", key));
+                state.remove(&canonical_key_bytes(key));
             }
             _ => {}
         }
@@ -28,3 +48,186 @@ fn merge_storage_state(
 
     state.into_values().collect()
 }
+
+/// Classify each `Created`/`Updated` change against whether its key
+/// already existed in `before`, rather than trusting the meta's own tag at
+/// face value. `Removed` keys are reported as given, without a check
+/// against `before`.
+pub fn storage_diff(before: &[LedgerEntry], changes: &[LedgerEntryChange]) -> StorageDiff {
+    let existed: std::collections::HashSet<Vec<u8>> =
+        before.iter().map(canonical_key).collect();
+
+    let mut diff = StorageDiff::default();
+    for change in changes {
+        match change {
+            LedgerEntryChange::Created(e) | LedgerEntryChange::Updated(e) => {
+                if existed.contains(&canonical_key(e)) {
+                    diff.updated.push(e.clone());
+                } else {
+                    diff.created.push(e.clone());
+                }
+            }
+            LedgerEntryChange::Removed(key) => diff.removed.push(key.clone()),
+            _ => {}
+        }
+    }
+
+    diff
+}
+
+/// Derive the `LedgerKey` that addresses `entry`, for the entry kinds this
+/// simulator's traffic actually touches (contract data/code, accounts,
+/// trustlines). Other kinds (offers, data entries, claimable balances,
+/// ...) fall back to `None` rather than guessing at XDR field layouts
+/// nothing else in this crate needs.
+fn ledger_key_for_entry(entry: &LedgerEntry) -> Option<LedgerKey> {
+    Some(match &entry.data {
+        LedgerEntryData::ContractData(data) => LedgerKey::ContractData(LedgerKeyContractData {
+            contract: data.contract.clone(),
+            key: data.key.clone(),
+            durability: data.durability,
+        }),
+        LedgerEntryData::ContractCode(data) => LedgerKey::ContractCode(LedgerKeyContractCode {
+            hash: data.hash.clone(),
+        }),
+        LedgerEntryData::Account(data) => LedgerKey::Account(LedgerKeyAccount {
+            account_id: data.account_id.clone(),
+        }),
+        LedgerEntryData::Trustline(data) => LedgerKey::Trustline(LedgerKeyTrustLine {
+            account_id: data.account_id.clone(),
+            asset: data.asset.clone(),
+        }),
+        _ => return None,
+    })
+}
+
+/// Key `entry` by the canonical XDR encoding of its derived `LedgerKey`
+/// when one exists, falling back to the XDR encoding of the entry's own
+/// data otherwise — still a stable, collision-resistant identity, just not
+/// a real `LedgerKey` for the handful of entry kinds
+/// [`ledger_key_for_entry`] doesn't cover.
+fn canonical_key(entry: &LedgerEntry) -> Vec<u8> {
+    match ledger_key_for_entry(entry) {
+        Some(key) => canonical_key_bytes(&key),
+        None => entry.data.to_xdr(Limits::none()).unwrap_or_default(),
+    }
+}
+
+fn canonical_key_bytes(key: &LedgerKey) -> Vec<u8> {
+    key.to_xdr(Limits::none()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{
+        AccountEntry, AccountEntryExt, AccountId, ContractDataDurability, ContractDataEntry,
+        ExtensionPoint, Hash, LedgerEntryExt, PublicKey, ScAddress, ScVal, SequenceNumber,
+        StringM, Thresholds, Uint256,
+    };
+
+    fn contract_data_entry(contract: Hash, key: ScVal, val: ScVal) -> LedgerEntry {
+        LedgerEntry {
+            last_modified_ledger_seq: 0,
+            data: LedgerEntryData::ContractData(ContractDataEntry {
+                ext: ExtensionPoint::V0,
+                contract: ScAddress::Contract(contract),
+                key,
+                durability: ContractDataDurability::Persistent,
+                val,
+            }),
+            ext: LedgerEntryExt::V0,
+        }
+    }
+
+    fn account_entry(account_id: AccountId, balance: i64) -> LedgerEntry {
+        LedgerEntry {
+            last_modified_ledger_seq: 0,
+            data: LedgerEntryData::Account(AccountEntry {
+                account_id,
+                balance,
+                seq_num: SequenceNumber(0),
+                num_sub_entries: 0,
+                inflation_dest: None,
+                flags: 0,
+                home_domain: StringM::default().try_into().unwrap(),
+                thresholds: Thresholds([0, 0, 0, 0]),
+                signers: Default::default(),
+                ext: AccountEntryExt::V0,
+            }),
+            ext: LedgerEntryExt::V0,
+        }
+    }
+
+    #[test]
+    fn test_merge_applies_created_and_updated_changes() {
+        let before = vec![contract_data_entry(Hash([1u8; 32]), ScVal::U32(1), ScVal::U64(1))];
+        let changes = vec![
+            LedgerEntryChange::Updated(contract_data_entry(
+                Hash([1u8; 32]),
+                ScVal::U32(1),
+                ScVal::U64(2),
+            )),
+            LedgerEntryChange::Created(contract_data_entry(
+                Hash([2u8; 32]),
+                ScVal::U32(2),
+                ScVal::U64(9),
+            )),
+        ];
+
+        let merged = merge_storage_state(&before, &changes);
+        assert_eq!(merged.len(), 2);
+        assert!(merged.iter().any(
+            |e| matches!(&e.data, LedgerEntryData::ContractData(d) if d.val == ScVal::U64(2))
+        ));
+        assert!(merged.iter().any(
+            |e| matches!(&e.data, LedgerEntryData::ContractData(d) if d.val == ScVal::U64(9))
+        ));
+    }
+
+    #[test]
+    fn test_merge_removes_entries_keyed_by_derived_ledger_key_not_debug_string() {
+        let entry = contract_data_entry(Hash([3u8; 32]), ScVal::U32(3), ScVal::U64(3));
+        let key = ledger_key_for_entry(&entry).unwrap();
+
+        let merged = merge_storage_state(&[entry], &[LedgerEntryChange::Removed(key)]);
+        assert!(merged.is_empty());
+    }
+
+    #[test]
+    fn test_storage_diff_classifies_created_updated_and_removed() {
+        let untouched = contract_data_entry(Hash([4u8; 32]), ScVal::U32(4), ScVal::U64(4));
+        let to_update = contract_data_entry(Hash([5u8; 32]), ScVal::U32(5), ScVal::U64(5));
+        let to_remove = contract_data_entry(Hash([6u8; 32]), ScVal::U32(6), ScVal::U64(6));
+        let before = vec![untouched, to_update.clone(), to_remove.clone()];
+
+        let changes = vec![
+            LedgerEntryChange::Updated(contract_data_entry(
+                Hash([5u8; 32]),
+                ScVal::U32(5),
+                ScVal::U64(50),
+            )),
+            LedgerEntryChange::Created(contract_data_entry(
+                Hash([7u8; 32]),
+                ScVal::U32(7),
+                ScVal::U64(7),
+            )),
+            LedgerEntryChange::Removed(ledger_key_for_entry(&to_remove).unwrap()),
+        ];
+
+        let diff = storage_diff(&before, &changes);
+        assert_eq!(diff.created.len(), 1);
+        assert_eq!(diff.updated.len(), 1);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0], ledger_key_for_entry(&to_remove).unwrap());
+    }
+
+    #[test]
+    fn test_ledger_key_for_entry_derives_the_matching_key_kind() {
+        let account_id = AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([8u8; 32])));
+        let entry = account_entry(account_id.clone(), 100);
+
+        let key = ledger_key_for_entry(&entry).expect("account entries derive a key");
+        assert_eq!(key, LedgerKey::Account(LedgerKeyAccount { account_id }));
+    }
+}
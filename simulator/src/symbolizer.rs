@@ -0,0 +1,237 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parses the WASM binary's `name` custom section (module name and
+//! per-function names) to fill in [`crate::stack_trace::StackFrame`] fields
+//! that DWARF alone can't provide: release builds often strip
+//! `.debug_info`/`.debug_line` but keep the much smaller name section, or
+//! only carry names for a subset of functions. See
+//! [`crate::source_mapper::SourceMapper`] for the DWARF side of
+//! symbolication (source lines, not just names).
+
+use object::{Object, ObjectSection};
+use std::collections::HashMap;
+
+const NAME_SUBSECTION_MODULE: u8 = 0;
+const NAME_SUBSECTION_FUNCTIONS: u8 = 1;
+
+/// Function-index-to-name and module-name data parsed from a WASM binary's
+/// `name` custom section.
+#[derive(Debug, Clone, Default)]
+pub struct Symbolizer {
+    module_name: Option<String>,
+    function_names: HashMap<u32, String>,
+}
+
+impl Symbolizer {
+    /// Parse `wasm_bytes`' `name` custom section, if present. Returns an
+    /// empty `Symbolizer` (nothing resolves) rather than an error when the
+    /// section is missing or malformed, matching `SourceMapper`'s
+    /// best-effort treatment of optional debug data.
+    pub fn parse(wasm_bytes: &[u8]) -> Self {
+        let Ok(obj_file) = object::File::parse(wasm_bytes) else {
+            return Self::default();
+        };
+        let Some(section) = obj_file.section_by_name("name") else {
+            return Self::default();
+        };
+        let Ok(data) = section.uncompressed_data() else {
+            return Self::default();
+        };
+
+        Self::parse_name_section(&data).unwrap_or_default()
+    }
+
+    /// The `name` custom section is a sequence of `(id: u8, size: LEB128
+    /// u32, content: [u8; size])` subsections. We only care about
+    /// subsection 0 (module name, a single WASM string) and subsection 1
+    /// (function names, a `namemap`: a LEB128-prefixed vector of
+    /// `(func_index: u32 LEB128, name: WASM string)` pairs); everything
+    /// else (locals, etc.) is skipped.
+    fn parse_name_section(data: &[u8]) -> Option<Self> {
+        let mut symbolizer = Symbolizer::default();
+        let mut offset = 0usize;
+
+        while offset < data.len() {
+            let subsection_id = *data.get(offset)?;
+            offset += 1;
+            let (size, consumed) = read_leb128_u32(data.get(offset..)?)?;
+            offset += consumed;
+            let end = offset.checked_add(size as usize)?;
+            let content = data.get(offset..end)?;
+
+            match subsection_id {
+                NAME_SUBSECTION_MODULE => {
+                    if let Some((name, _)) = read_wasm_string(content) {
+                        symbolizer.module_name = Some(name);
+                    }
+                }
+                NAME_SUBSECTION_FUNCTIONS => {
+                    symbolizer.function_names = read_name_map(content).unwrap_or_default();
+                }
+                _ => {}
+            }
+
+            offset = end;
+        }
+
+        Some(symbolizer)
+    }
+
+    pub fn module_name(&self) -> Option<&str> {
+        self.module_name.as_deref()
+    }
+
+    pub fn function_name(&self, func_index: u32) -> Option<&str> {
+        self.function_names.get(&func_index).map(String::as_str)
+    }
+
+    /// Fill in `frame.func_name`/`frame.module` from the parsed name
+    /// section, leaving anything already populated (e.g. a function name
+    /// scraped from a raw error string by
+    /// `crate::stack_trace::extract_frames`) untouched.
+    pub fn symbolicate_frame(&self, frame: &mut crate::stack_trace::StackFrame) {
+        if frame.func_name.is_none() {
+            if let Some(index) = frame.func_index {
+                frame.func_name = self.function_name(index).map(str::to_string);
+            }
+        }
+        if frame.module.is_none() {
+            frame.module = self.module_name.clone();
+        }
+    }
+}
+
+fn read_leb128_u32(data: &[u8]) -> Option<(u32, usize)> {
+    let mut result: u32 = 0;
+    let mut shift = 0u32;
+    for (i, &byte) in data.iter().enumerate() {
+        result |= ((byte & 0x7f) as u32).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some((result, i + 1));
+        }
+        shift += 7;
+        if shift >= 32 {
+            return None;
+        }
+    }
+    None
+}
+
+fn read_wasm_string(data: &[u8]) -> Option<(String, usize)> {
+    let (len, consumed) = read_leb128_u32(data)?;
+    let start = consumed;
+    let end = start.checked_add(len as usize)?;
+    let bytes = data.get(start..end)?;
+    Some((String::from_utf8_lossy(bytes).into_owned(), end))
+}
+
+fn read_name_map(data: &[u8]) -> Option<HashMap<u32, String>> {
+    let mut map = HashMap::new();
+    let (count, mut offset) = read_leb128_u32(data)?;
+    for _ in 0..count {
+        let (index, consumed) = read_leb128_u32(data.get(offset..)?)?;
+        offset += consumed;
+        let (name, consumed) = read_wasm_string(data.get(offset..)?)?;
+        offset += consumed;
+        map.insert(index, name);
+    }
+    Some(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leb128(mut value: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(byte);
+                break;
+            } else {
+                bytes.push(byte | 0x80);
+            }
+        }
+        bytes
+    }
+
+    fn wasm_string(s: &str) -> Vec<u8> {
+        let mut bytes = leb128(s.len() as u32);
+        bytes.extend_from_slice(s.as_bytes());
+        bytes
+    }
+
+    fn name_subsection(id: u8, content: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![id];
+        bytes.extend(leb128(content.len() as u32));
+        bytes.extend_from_slice(content);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_name_section_reads_module_and_function_names() {
+        let mut function_names_content = leb128(2); // count
+        function_names_content.extend(leb128(0));
+        function_names_content.extend(wasm_string("transfer"));
+        function_names_content.extend(leb128(5));
+        function_names_content.extend(wasm_string("mint"));
+
+        let mut data = Vec::new();
+        data.extend(name_subsection(
+            NAME_SUBSECTION_MODULE,
+            &wasm_string("my_contract"),
+        ));
+        data.extend(name_subsection(
+            NAME_SUBSECTION_FUNCTIONS,
+            &function_names_content,
+        ));
+
+        let symbolizer = Symbolizer::parse_name_section(&data).expect("valid section");
+        assert_eq!(symbolizer.module_name(), Some("my_contract"));
+        assert_eq!(symbolizer.function_name(0), Some("transfer"));
+        assert_eq!(symbolizer.function_name(5), Some("mint"));
+        assert_eq!(symbolizer.function_name(99), None);
+    }
+
+    #[test]
+    fn test_parse_returns_empty_for_non_wasm_bytes() {
+        let symbolizer = Symbolizer::parse(b"not a wasm module");
+        assert!(symbolizer.module_name().is_none());
+        assert!(symbolizer.function_name(0).is_none());
+    }
+
+    #[test]
+    fn test_symbolicate_frame_fills_missing_fields_only() {
+        let mut symbolizer = Symbolizer::default();
+        symbolizer.module_name = Some("token".to_string());
+        symbolizer.function_names.insert(42, "transfer".to_string());
+
+        let mut frame = crate::stack_trace::StackFrame {
+            index: 0,
+            func_index: Some(42),
+            func_name: None,
+            wasm_offset: None,
+            module: None,
+            source_location: None,
+            snippet: None,
+        };
+        symbolizer.symbolicate_frame(&mut frame);
+        assert_eq!(frame.func_name, Some("transfer".to_string()));
+        assert_eq!(frame.module, Some("token".to_string()));
+
+        let mut already_named = crate::stack_trace::StackFrame {
+            index: 1,
+            func_index: Some(42),
+            func_name: Some("keep_me".to_string()),
+            wasm_offset: None,
+            module: None,
+            source_location: None,
+            snippet: None,
+        };
+        symbolizer.symbolicate_frame(&mut already_named);
+        assert_eq!(already_named.func_name, Some("keep_me".to_string()));
+    }
+}
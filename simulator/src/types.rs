@@ -8,7 +8,7 @@ use crate::stack_trace::WasmStackTrace;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct SimulationRequest {
     pub envelope_xdr: String,
     pub result_meta_xdr: String,
@@ -28,20 +28,203 @@ pub struct SimulationRequest {
     #[serde(default)]
     pub coverage_lcov_path: Option<String>,
     pub resource_calibration: Option<ResourceCalibration>,
+    /// Protocol version to simulate against. Selects the coefficient set
+    /// [`ResourceCalibration::for_protocol`] returns when
+    /// `resource_calibration` isn't set, and is validated against the
+    /// loaded ledger entries (e.g. a `Temporary` `ContractData` entry needs
+    /// at least the protocol that introduced state archival). Defaults to
+    /// [`DEFAULT_PROTOCOL_VERSION`], the newest version this simulator is
+    /// calibrated for.
+    #[serde(default = "default_protocol_version")]
+    pub protocol_version: u32,
     /// Optional hard memory limit in bytes. If set, the simulator will panic
     /// when memory consumption exceeds this limit, simulating live network constraints.
     pub memory_limit: Option<u64>,
     #[serde(default)]
     pub restore_preamble: Option<serde_json::Value>,
+    /// Formerly required to opt in to footprint discovery; `SimulationResponse::footprint`
+    /// is now always populated from the `LedgerKey`s actually read or
+    /// written during execution, so this no longer has any effect. Kept so
+    /// existing callers that still send it deserialize without error.
+    #[serde(default)]
+    pub recording: bool,
+    /// Path to a memory-mapped ledger snapshot file produced by
+    /// [`crate::snapshot::mmap_store::build`]. When set, every entry it
+    /// contains is merged into the ledger state used for simulation, for
+    /// testing against mainnet-sized state dumps too large to pass inline
+    /// via `ledger_entries`. Entries given explicitly in `ledger_entries`
+    /// take precedence over entries from the snapshot.
+    #[serde(default)]
+    pub ledger_snapshot_path: Option<String>,
+    /// When set, simulate network congestion instead of pricing the
+    /// transaction at a single fixed `mock_gas_price`: draw
+    /// `sample_count` compute-unit prices from this distribution and
+    /// report the resulting fee at the p50/p90/p99 sampled prices as
+    /// `SimulationResponse::fee_market_report`.
+    #[serde(default)]
+    pub fee_market: Option<FeeMarketDistribution>,
+    /// Per-resource fee coefficients to use instead of the flat
+    /// `mock_base_fee`/`mock_gas_price` pricing, when the mocked fee check
+    /// is active. See [`crate::cost_schedule`].
+    #[serde(default)]
+    pub cost_schedule: Option<CostSchedule>,
+    /// When set, the transaction's source account is synthesized with
+    /// [`crate::snapshot::default_account_ledger_entry`] and injected before
+    /// execution if it isn't already present in `ledger_entries`, instead of
+    /// failing the simulation. Opt-in, matching sandbox networks that
+    /// materialize accounts on demand rather than requiring every source
+    /// account to be fully specified up front.
+    #[serde(default)]
+    pub auto_provision_accounts: Option<AccountAutoProvisionConfig>,
 }
 
+/// Starting balance/sequence number a synthesized default account is given
+/// by [`crate::snapshot::default_account_ledger_entry`]. See
+/// `SimulationRequest::auto_provision_accounts`.
 #[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AccountAutoProvisionConfig {
+    #[serde(default = "default_account_starting_balance")]
+    pub starting_balance: i64,
+    #[serde(default)]
+    pub starting_sequence: i64,
+}
+
+fn default_account_starting_balance() -> i64 {
+    100_000_000_000
+}
+
+/// Newest protocol version this simulator has a calibrated
+/// [`ResourceCalibration`] table entry for.
+pub const DEFAULT_PROTOCOL_VERSION: u32 = 22;
+
+fn default_protocol_version() -> u32 {
+    DEFAULT_PROTOCOL_VERSION
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct ResourceCalibration {
     pub sha256_fixed: u64,
     pub sha256_per_byte: u64,
     pub keccak256_fixed: u64,
     pub keccak256_per_byte: u64,
     pub ed25519_fixed: u64,
+    /// Cold `VmInstantiation` cost: parsing, validating, and translating a
+    /// contract's WASM the first time a given code hash is instantiated.
+    pub vm_instantiation_fixed: u64,
+    /// Cached `VmCachedInstantiation` cost: re-instantiating a module
+    /// `SimHost`'s installed `ModuleCache` already parsed and validated.
+    /// Much smaller than `vm_instantiation_fixed` — that's the whole point
+    /// of the cache — so metered CPU numbers track what a host with module
+    /// caching actually reports rather than paying cold cost on every call.
+    pub vm_cached_instantiation_fixed: u64,
+}
+
+impl ResourceCalibration {
+    /// The coefficient set calibrated for `version`, falling back to the
+    /// nearest protocol version [`PROTOCOL_CALIBRATIONS`] has figures for
+    /// when `version` isn't exactly one of them (e.g. simulating against a
+    /// brand-new protocol this table hasn't been updated for yet).
+    pub fn for_protocol(version: u32) -> Self {
+        PROTOCOL_CALIBRATIONS
+            .iter()
+            .min_by_key(|(table_version, _)| table_version.abs_diff(version))
+            .map(|(_, calibration)| calibration.clone())
+            .expect("PROTOCOL_CALIBRATIONS is never empty")
+    }
+}
+
+/// Hand-calibrated coefficients per protocol version. Real per-op costs
+/// shift across protocol upgrades (for example `VmCachedInstantiation`
+/// gained entirely different constant/linear CPU terms once module caching
+/// landed), so a single fixed model doesn't track a contract's metered
+/// cost accurately across versions. Covers the versions this simulator is
+/// exercised against; [`ResourceCalibration::for_protocol`] falls back to
+/// the nearest entry for anything else.
+const PROTOCOL_CALIBRATIONS: &[(u32, ResourceCalibration)] = &[
+    (
+        20,
+        ResourceCalibration {
+            sha256_fixed: 4_000,
+            sha256_per_byte: 30,
+            keccak256_fixed: 4_500,
+            keccak256_per_byte: 36,
+            ed25519_fixed: 8_000,
+            // Protocol 20 predates module caching — every instantiation is
+            // a cold one, so there's no cheaper cached path to calibrate.
+            vm_instantiation_fixed: 450_000,
+            vm_cached_instantiation_fixed: 450_000,
+        },
+    ),
+    (
+        21,
+        ResourceCalibration {
+            sha256_fixed: 3_800,
+            sha256_per_byte: 28,
+            keccak256_fixed: 4_200,
+            keccak256_per_byte: 33,
+            ed25519_fixed: 7_600,
+            vm_instantiation_fixed: 420_000,
+            vm_cached_instantiation_fixed: 18_000,
+        },
+    ),
+    (
+        22,
+        ResourceCalibration {
+            sha256_fixed: 3_600,
+            sha256_per_byte: 25,
+            keccak256_fixed: 3_900,
+            keccak256_per_byte: 30,
+            ed25519_fixed: 7_200,
+            vm_instantiation_fixed: 400_000,
+            vm_cached_instantiation_fixed: 15_000,
+        },
+    ),
+];
+
+/// A uniform distribution of compute-unit prices to sample from when
+/// modeling fee-market congestion (see [`crate::fee_market`]).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FeeMarketDistribution {
+    pub min_compute_unit_price: u64,
+    pub max_compute_unit_price: u64,
+    #[serde(default = "default_fee_market_sample_count")]
+    pub sample_count: u32,
+}
+
+fn default_fee_market_sample_count() -> u32 {
+    1_000
+}
+
+/// Per-resource fee pricing for the mocked fee check, overridable by
+/// callers who want to model protocol parameter changes (e.g. comparing
+/// mainnet vs. testnet pricing) rather than accepting the hard-coded
+/// conversion `mocked_required_fee_stroops` uses for `mock_gas_price`. All
+/// fields default to `0`, so a schedule that's never set contributes
+/// nothing to the required fee. See [`crate::cost_schedule`].
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct CostSchedule {
+    pub cpu_insn_price: u64,
+    pub memory_byte_price: u64,
+    pub per_operation_base_fee: u64,
+    pub storage_read_price: u64,
+    pub storage_write_price: u64,
+}
+
+/// One sampled compute-unit price and the fee it would produce for the
+/// simulated transaction's CPU usage.
+#[derive(Debug, Serialize, Clone)]
+pub struct FeeSamplePoint {
+    pub compute_unit_price: u64,
+    pub fee_stroops: u64,
+}
+
+/// Summary of [`crate::fee_market::simulate`]'s sampled fee distribution at
+/// the p50/p90/p99 sampled compute-unit prices.
+#[derive(Debug, Serialize, Clone)]
+pub struct FeeMarketReport {
+    pub p50: FeeSamplePoint,
+    pub p90: FeeSamplePoint,
+    pub p99: FeeSamplePoint,
 }
 
 #[derive(Debug, Serialize)]
@@ -58,9 +241,47 @@ pub struct SimulationResponse {
     pub diagnostic_events: Vec<DiagnosticEvent>,
     pub categorized_events: Vec<CategorizedEvent>,
     pub logs: Vec<String>,
+    /// CPU-weighted cost-attribution flamegraph: one flame per
+    /// `rootcall;subcall;<ContractCostType>` frame.
     pub flamegraph: Option<String>,
+    /// Same call-frame breakdown as `flamegraph`, weighted by memory bytes
+    /// instead of CPU instructions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_flamegraph: Option<String>,
     pub optimization_report: Option<OptimizationReport>,
     pub budget_usage: Option<BudgetUsage>,
+    /// Chronological host-function entry/exit trace, as opposed to
+    /// `budget_usage`'s single aggregate snapshot — one `Call`/`Return` pair
+    /// per invoked operation, with a nested pair per `ContractCostType` that
+    /// changed during it. Lets `erst explain` point at exactly which step
+    /// consumed a given slice of the budget, and gives the flamegraph real
+    /// per-frame weights instead of only the post-hoc aggregate deltas.
+    pub execution_trace: Vec<ExecutionTraceEvent>,
+    /// Sampled fee-under-congestion summary, populated when
+    /// `SimulationRequest::fee_market` was set. See [`crate::fee_market`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_market_report: Option<FeeMarketReport>,
+    /// The `CostSchedule` actually used to derive the mocked fee check's
+    /// required fee — `SimulationRequest::cost_schedule` if set, or the
+    /// all-zero default otherwise — so the "Mock fee check" log line is
+    /// reproducible under alternate cost assumptions. Only populated when
+    /// the mocked fee check ran at all (`mock_base_fee`, `mock_gas_price`,
+    /// or `cost_schedule` was set).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_cost_schedule: Option<CostSchedule>,
+    /// Base64-encoded `LedgerFootprint` XDR discovered during execution:
+    /// every `LedgerKey` the operations read or wrote, split into
+    /// `read_only`/`read_write` and deduplicated, so callers can build a
+    /// real transaction's footprint from what actually ran instead of
+    /// guessing it up front.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footprint: Option<String>,
+    /// Structured "what changed" report comparing ledger storage before and
+    /// after execution, including any balance deltas derived from Stellar
+    /// Asset Contract balance entries, accounts' native balances and
+    /// trustlines. Only populated on successful execution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_changes: Option<StateChangeReport>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_location: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -94,6 +315,58 @@ pub struct BudgetUsage {
     pub memory_limit: u64,
     pub cpu_usage_percent: f64,
     pub memory_usage_percent: f64,
+    /// Per-`ContractCostType` tracker readout (iterations, CPU, memory),
+    /// keyed by the cost type's name (e.g. `"WasmInsnExec"`,
+    /// `"VmInstantiation"`). Lets callers distinguish module instantiation
+    /// cost from guest execution cost rather than only seeing the
+    /// aggregate totals above.
+    pub cost_breakdown: HashMap<String, ContractCostEntry>,
+    /// CPU instructions spent parsing/instantiating contract WASM modules —
+    /// the `VmInstantiation` plus `VmCachedInstantiation` entries of
+    /// `cost_breakdown` — separate from the CPU spent actually running
+    /// them. Operations that repeatedly invoke the same contract pay full
+    /// cold-instantiation cost once and cheap `VmCachedInstantiation` hits
+    /// after that, so this total falls as a fraction of `cpu_instructions`
+    /// the more a transaction reuses a contract.
+    pub vm_instantiation_cpu: u64,
+    /// Same split as `vm_instantiation_cpu`, in memory bytes.
+    pub vm_instantiation_mem: u64,
+}
+
+/// Whether an [`ExecutionTraceEvent`] marks entering or leaving a frame.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceEventKind {
+    Call,
+    Return,
+}
+
+/// One entry/exit step of `SimulationResponse::execution_trace`. `depth` 0
+/// is the invoked operation itself; `depth` 1 is one of its
+/// `ContractCostType` charges, nested inside it the same way the flamegraph
+/// nests `rootcall;subcall;<CostType>`. `cumulative_*` are the budget's
+/// running totals at this point in the trace, and `delta_*` are this
+/// frame's own contribution — `0` on `Call` events, since the cost is only
+/// known once the frame returns.
+#[derive(Debug, Serialize, Clone)]
+pub struct ExecutionTraceEvent {
+    pub depth: u32,
+    pub kind: TraceEventKind,
+    pub label: String,
+    pub cumulative_cpu_insns: u64,
+    pub cumulative_memory_bytes: u64,
+    pub delta_cpu_insns: u64,
+    pub delta_memory_bytes: u64,
+}
+
+/// One `ContractCostType`'s budget tracker readout: how many times the
+/// model charged for that cost type, and the cumulative CPU/memory it
+/// attributed to it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContractCostEntry {
+    pub iterations: u64,
+    pub cpu_insns: u64,
+    pub memory_bytes: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -101,4 +374,40 @@ pub struct StructuredError {
     pub error_type: String,
     pub message: String,
     pub details: Option<String>,
+    /// A short, actionable next step for this error category (e.g.
+    /// "increase the CPU resource budget"), when there's an obvious one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+}
+
+/// One `LedgerEntry` that was created, modified, or removed during a
+/// simulation, decoded to readable JSON rather than a Rust debug string.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct LedgerEntryChange {
+    pub key: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<serde_json::Value>,
+}
+
+/// A signed token-balance delta derived from a Stellar Asset Contract
+/// balance entry, an account's native balance, or a trustline, keyed by the
+/// holder's principal. `contract` is the SAC's contract id, `"native"`, or
+/// the trustline's asset.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct BalanceDelta {
+    pub principal: String,
+    pub contract: String,
+    /// Signed decimal amount, e.g. `"-40"` or `"100"`.
+    pub delta: String,
+}
+
+/// The full "what changed" report produced by [`crate::state_diff::diff`].
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct StateChangeReport {
+    pub created: Vec<LedgerEntryChange>,
+    pub modified: Vec<LedgerEntryChange>,
+    pub removed: Vec<LedgerEntryChange>,
+    pub balance_deltas: Vec<BalanceDelta>,
 }
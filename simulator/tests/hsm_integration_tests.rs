@@ -229,7 +229,7 @@ mod tests {
         
         let config = Pkcs11SignerConfig::from_env().unwrap();
         assert_eq!(config.module_path, "/usr/lib/libykcs11.so");
-        assert_eq!(config.pin, "123456");
+        assert_eq!(config.pin, Some("123456".to_string()));
         
         // Test optional variables
         env::set_var("ERST_PKCS11_TOKEN_LABEL", "YubiKey");
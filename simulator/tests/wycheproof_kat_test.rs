@@ -0,0 +1,45 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+//! Known-answer tests driving the `Verifier` implementations against the
+//! hand-authored Wycheproof-style vectors under `tests/vectors/`.
+
+use crate::hsm::kat::{flatten, load_vectors, run_case};
+use crate::hsm::software::{Secp256k1SoftwareSigner, SoftwareSigner};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ed25519_wycheproof_vectors() {
+        let vectors = load_vectors("tests/vectors/wycheproof_ed25519.json").unwrap();
+        let cases = flatten(&vectors).unwrap();
+        assert!(!cases.is_empty());
+
+        let (verifier, _) = SoftwareSigner::generate().unwrap();
+        for case in &cases {
+            run_case(&verifier, case, "ed25519", false)
+                .await
+                .unwrap_or_else(|e| panic!("{}", e));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_secp256k1_wycheproof_vectors() {
+        let vectors = load_vectors("tests/vectors/wycheproof_secp256k1.json").unwrap();
+        let cases = flatten(&vectors).unwrap();
+        assert!(!cases.is_empty());
+
+        let (verifier, _) = Secp256k1SoftwareSigner::generate().unwrap();
+        for case in &cases {
+            // The "acceptable" case here is a malleable (non-canonical
+            // high-S) signature; this repo rejects those by default since
+            // the simulator treats signature verification as a trust
+            // boundary, not just a cryptographic check.
+            run_case(&verifier, case, "secp256k1", false)
+                .await
+                .unwrap_or_else(|e| panic!("{}", e));
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt;
+
+/// Top-level error type for the `erst` CLI services.
+#[derive(Debug)]
+pub enum AppError {
+    Network(String),
+    Io(String),
+    Serialization(String),
+    InvalidUrl(String),
+    NotFound(String),
+    Storage(String),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Network(msg) => write!(f, "network error: {msg}"),
+            AppError::Io(msg) => write!(f, "I/O error: {msg}"),
+            AppError::Serialization(msg) => write!(f, "serialization error: {msg}"),
+            AppError::InvalidUrl(msg) => write!(f, "invalid URL: {msg}"),
+            AppError::NotFound(msg) => write!(f, "not found: {msg}"),
+            AppError::Storage(msg) => write!(f, "storage error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Io(e.to_string())
+    }
+}
@@ -37,7 +37,13 @@
 //     let model: simulation-request.schema = serde_json::from_str(&json).unwrap();
 // }
 
-use serde::{Serialize, Deserialize};
+use base64::engine::general_purpose::{
+    STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD,
+};
+use base64::Engine as _;
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use soroban_env_host::xdr::{Limits, ReadXdr, TransactionEnvelope};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SimulationRequestSchema {
@@ -48,7 +54,75 @@ pub struct SimulationRequestSchema {
 
     version: String,
 
-    xdr: String,
+    xdr: Xdr,
+}
+
+/// A transaction envelope XDR, tolerant of the base64 variants seen from
+/// different Soroban tooling (standard padded, URL-safe, and unpadded
+/// forms, plus MIME-wrapped base64 with embedded line breaks).
+///
+/// Deserializing tries each encoding in a fixed order and accepts the
+/// first one that both decodes and parses as a well-formed
+/// `TransactionEnvelope`. Serializing always emits standard padded
+/// base64, so the representation is canonicalized on the way out.
+#[derive(Debug, Clone)]
+pub struct Xdr(Vec<u8>);
+
+impl Xdr {
+    /// The encodings attempted, in order, when decoding an `xdr` field.
+    const VARIANTS: &'static [&'static str] = &[
+        "BASE64",
+        "BASE64URL",
+        "BASE64_NOPAD",
+        "BASE64URL_NOPAD",
+        "BASE64_MIME",
+    ];
+
+    /// Parse a base64 string of unknown flavor into a validated envelope.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let mime_input: String = raw.chars().filter(|c| !c.is_whitespace()).collect();
+        let attempts: [Option<Vec<u8>>; 5] = [
+            STANDARD.decode(raw).ok(),
+            URL_SAFE.decode(raw).ok(),
+            STANDARD_NO_PAD.decode(raw).ok(),
+            URL_SAFE_NO_PAD.decode(raw).ok(),
+            STANDARD.decode(&mime_input).ok(),
+        ];
+
+        for bytes in attempts.into_iter().flatten() {
+            if TransactionEnvelope::from_xdr(bytes.clone(), Limits::none()).is_ok() {
+                return Ok(Self(bytes));
+            }
+        }
+
+        Err(format!(
+            "xdr field is not a valid transaction envelope in any known encoding (tried {})",
+            Self::VARIANTS.join(", ")
+        ))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for Xdr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        Xdr::parse(&raw).map_err(DeError::custom)
+    }
+}
+
+impl Serialize for Xdr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&STANDARD.encode(&self.0))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -0,0 +1,84 @@
+use crate::error::AppError;
+use crate::ipc::types::{SimulationRequestSchema, SimulationResponseSchema};
+use crate::services::identity::{verify_with_public_key, Identity};
+use crate::services::share::GitMetadata;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// A simulation request/response pair packaged with enough provenance
+/// (commit hash, timestamp) to be signed and later verified.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimulationBundle {
+    pub request: SimulationRequestSchema,
+    pub response: SimulationResponseSchema,
+    pub commit_hash: Option<String>,
+    pub timestamp: String,
+}
+
+/// A `SimulationBundle` plus the ed25519 signature and public key that
+/// attest to it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignedBundle {
+    pub bundle: SimulationBundle,
+    pub public_key_hex: String,
+    pub signature_hex: String,
+}
+
+impl SimulationBundle {
+    pub fn capture(
+        request: SimulationRequestSchema,
+        response: SimulationResponseSchema,
+        timestamp: String,
+    ) -> Self {
+        Self {
+            request,
+            response,
+            commit_hash: GitMetadata::detect().commit_hash,
+            timestamp,
+        }
+    }
+
+    /// Canonical JSON used for hashing/signing. `serde_json::Value`
+    /// serializes object keys in sorted order, so round-tripping through
+    /// it (rather than hashing the struct's natural field order) gives a
+    /// deterministic representation regardless of how the bundle was built.
+    fn canonical_json(&self) -> Result<String, AppError> {
+        let value =
+            serde_json::to_value(self).map_err(|e| AppError::Serialization(e.to_string()))?;
+        serde_json::to_string(&value).map_err(|e| AppError::Serialization(e.to_string()))
+    }
+
+    fn hash(&self) -> Result<[u8; 32], AppError> {
+        let canonical = self.canonical_json()?;
+        let mut hasher = Sha256::new();
+        hasher.update(canonical.as_bytes());
+        Ok(hasher.finalize().into())
+    }
+
+    /// Sign the canonical hash of this bundle with `identity`.
+    pub fn sign(self, identity: &Identity) -> Result<SignedBundle, AppError> {
+        let digest = self.hash()?;
+        let signature = identity.sign(&digest);
+        Ok(SignedBundle {
+            bundle: self,
+            public_key_hex: hex::encode(identity.public_key_bytes()),
+            signature_hex: hex::encode(signature.to_bytes()),
+        })
+    }
+}
+
+impl SignedBundle {
+    /// Recompute the canonical hash and check the signature, rejecting on
+    /// any field mutation.
+    pub fn verify(&self) -> Result<(), AppError> {
+        let digest = self.bundle.hash()?;
+        let public_key_bytes: [u8; 32] = hex::decode(&self.public_key_hex)
+            .map_err(|e| AppError::Serialization(format!("invalid public key hex: {e}")))?
+            .try_into()
+            .map_err(|_| AppError::Serialization("public key must be 32 bytes".to_string()))?;
+        let signature_bytes = hex::decode(&self.signature_hex)
+            .map_err(|e| AppError::Serialization(format!("invalid signature hex: {e}")))?;
+
+        verify_with_public_key(&public_key_bytes, &digest, &signature_bytes)
+    }
+}
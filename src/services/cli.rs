@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 pub struct Cli {
@@ -7,4 +7,53 @@ pub struct Cli {
 
     #[arg(long)]
     pub public: bool,
+
+    /// Destination for `--share`, as a URL. Supported schemes: `gist://`
+    /// (default), `file://`, and `https://`.
+    #[arg(long, default_value = "gist://")]
+    pub share_to: String,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Sign a simulation request/response pair with the local identity.
+    Sign {
+        /// Path to the SimulationRequestSchema JSON.
+        request_path: String,
+        /// Path to the SimulationResponseSchema JSON.
+        response_path: String,
+    },
+    /// Verify a previously signed bundle against its embedded public key.
+    Verify {
+        /// Path to the signed bundle JSON produced by `sign`.
+        bundle_path: String,
+    },
+    /// Inspect and compare locally recorded simulation runs.
+    ///
+    /// Requires the `sqlite-history` feature.
+    #[command(subcommand)]
+    #[cfg(feature = "sqlite-history")]
+    History(HistoryCommand),
+}
+
+#[derive(Subcommand, Debug)]
+#[cfg(feature = "sqlite-history")]
+pub enum HistoryCommand {
+    /// List every recorded run, most recent first.
+    List,
+    /// Re-run a previously recorded request against the current network.
+    Rerun {
+        /// `request_id` of a previously recorded run.
+        request_id: String,
+    },
+    /// Diff the most recently recorded responses for two request ids.
+    Diff {
+        /// `request_id` of the earlier run.
+        before: String,
+        /// `request_id` of the later run.
+        after: String,
+    },
 }
@@ -0,0 +1,218 @@
+//! Local history of past simulations, keyed by `request_id`, so a developer
+//! can re-run a stored request or diff how two recorded responses differ.
+//!
+//! Backed by SQLite under `~/.erst/history.sqlite3`. Gated behind the
+//! `sqlite-history` feature so the `rusqlite` dependency is only pulled in
+//! by users who opt into persistence.
+
+#![cfg(feature = "sqlite-history")]
+
+use crate::error::AppError;
+use crate::ipc::types::{SimulationRequestSchema, SimulationResponseSchema};
+use crate::services::share::GitMetadata;
+use rusqlite::{params, Connection};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+/// A single recorded simulation run.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub request_id: String,
+    pub xdr_hash: String,
+    pub network: String,
+    pub commit_hash: Option<String>,
+    pub request_json: String,
+    pub response_json: String,
+    pub recorded_at: String,
+}
+
+/// The observable differences between two recorded responses to the same
+/// (or differently-commit'd) request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseDiff {
+    pub fee_charged_before: Option<String>,
+    pub fee_charged_after: Option<String>,
+    pub error_code_before: Option<String>,
+    pub error_code_after: Option<String>,
+}
+
+impl ResponseDiff {
+    pub fn fee_changed(&self) -> bool {
+        self.fee_charged_before != self.fee_charged_after
+    }
+
+    pub fn error_changed(&self) -> bool {
+        self.error_code_before != self.error_code_after
+    }
+}
+
+pub struct HistoryStore {
+    conn: Connection,
+}
+
+impl HistoryStore {
+    fn db_path() -> PathBuf {
+        let mut path = std::env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("."));
+        path.push(".erst");
+        path.push("history.sqlite3");
+        path
+    }
+
+    /// Open the history database, creating the schema on first use.
+    pub fn open_or_create() -> Result<Self, AppError> {
+        let path = Self::db_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(&path).map_err(|e| AppError::Storage(e.to_string()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                request_id TEXT NOT NULL,
+                xdr_hash TEXT NOT NULL,
+                network TEXT NOT NULL,
+                commit_hash TEXT,
+                request_json TEXT NOT NULL,
+                response_json TEXT NOT NULL,
+                recorded_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS runs_request_id_idx ON runs (request_id);",
+        )
+        .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        Ok(Self { conn })
+    }
+
+    /// Record a request/response pair, keyed by the request's own
+    /// `request_id`, a hash of its `xdr` as re-encoded in standard base64,
+    /// its `network`, and the
+    /// currently checked-out commit hash (if any).
+    pub fn record(
+        &self,
+        request: &SimulationRequestSchema,
+        response: &SimulationResponseSchema,
+        recorded_at: &str,
+    ) -> Result<(), AppError> {
+        let request_value =
+            serde_json::to_value(request).map_err(|e| AppError::Serialization(e.to_string()))?;
+        let request_json = serde_json::to_string(&request_value)
+            .map_err(|e| AppError::Serialization(e.to_string()))?;
+        let response_json =
+            serde_json::to_string(response).map_err(|e| AppError::Serialization(e.to_string()))?;
+
+        let request_id = field_str(&request_value, "request_id");
+        let network = field_str(&request_value, "network");
+        let xdr = field_str(&request_value, "xdr");
+        let xdr_hash = hex::encode(Sha256::digest(xdr.as_bytes()));
+        let commit_hash = GitMetadata::detect().commit_hash;
+
+        self.conn
+            .execute(
+                "INSERT INTO runs
+                    (request_id, xdr_hash, network, commit_hash, request_json, response_json, recorded_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    request_id,
+                    xdr_hash,
+                    network,
+                    commit_hash,
+                    request_json,
+                    response_json,
+                    recorded_at
+                ],
+            )
+            .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// List every recorded run, most recent first.
+    pub fn list(&self) -> Result<Vec<HistoryEntry>, AppError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT request_id, xdr_hash, network, commit_hash, request_json, response_json, recorded_at
+                 FROM runs ORDER BY id DESC",
+            )
+            .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([], row_to_entry)
+            .map_err(|e| AppError::Storage(e.to_string()))?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| AppError::Storage(e.to_string()))
+    }
+
+    /// The most recently recorded run for `request_id`, if any.
+    pub fn latest(&self, request_id: &str) -> Result<Option<HistoryEntry>, AppError> {
+        self.conn
+            .query_row(
+                "SELECT request_id, xdr_hash, network, commit_hash, request_json, response_json, recorded_at
+                 FROM runs WHERE request_id = ?1 ORDER BY id DESC LIMIT 1",
+                params![request_id],
+                row_to_entry,
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(AppError::Storage(e.to_string())),
+            })
+    }
+
+    /// Diff the most recently recorded responses for two request ids (which
+    /// may be the same request re-run after a code change, or two distinct
+    /// requests run against the same contract).
+    pub fn diff(&self, request_id_before: &str, request_id_after: &str) -> Result<ResponseDiff, AppError> {
+        let before = self
+            .latest(request_id_before)?
+            .ok_or_else(|| AppError::NotFound(format!("no recorded run for {request_id_before}")))?;
+        let after = self
+            .latest(request_id_after)?
+            .ok_or_else(|| AppError::NotFound(format!("no recorded run for {request_id_after}")))?;
+
+        let before_value: Value = serde_json::from_str(&before.response_json)
+            .map_err(|e| AppError::Serialization(e.to_string()))?;
+        let after_value: Value = serde_json::from_str(&after.response_json)
+            .map_err(|e| AppError::Serialization(e.to_string()))?;
+
+        Ok(ResponseDiff {
+            fee_charged_before: optional_field_str(&before_value, &["result", "fee_charged"]),
+            fee_charged_after: optional_field_str(&after_value, &["result", "fee_charged"]),
+            error_code_before: optional_field_str(&before_value, &["error", "code"]),
+            error_code_after: optional_field_str(&after_value, &["error", "code"]),
+        })
+    }
+}
+
+fn row_to_entry(row: &rusqlite::Row<'_>) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        request_id: row.get(0)?,
+        xdr_hash: row.get(1)?,
+        network: row.get(2)?,
+        commit_hash: row.get(3)?,
+        request_json: row.get(4)?,
+        response_json: row.get(5)?,
+        recorded_at: row.get(6)?,
+    })
+}
+
+fn field_str(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn optional_field_str(value: &Value, path: &[&str]) -> Option<String> {
+    let mut current = value;
+    for key in path {
+        current = current.get(key)?;
+    }
+    current.as_str().map(str::to_string)
+}
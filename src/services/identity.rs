@@ -0,0 +1,74 @@
+use crate::error::AppError;
+use ed25519_dalek::pkcs8::{DecodePrivateKey, EncodePrivateKey};
+use ed25519_dalek::{Signature, Signer as EdSigner, SigningKey, Verifier as _, VerifyingKey};
+use std::fs;
+use std::path::PathBuf;
+
+/// A local ed25519 identity used to sign shared simulation bundles.
+///
+/// The keypair is stored under `~/.erst/identity/key.pem`, generated on
+/// first use so a reviewer can later confirm a posted result came from the
+/// same key.
+pub struct Identity {
+    signing_key: SigningKey,
+}
+
+impl Identity {
+    fn key_path() -> PathBuf {
+        let mut path = std::env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("."));
+        path.push(".erst");
+        path.push("identity");
+        path.push("key.pem");
+        path
+    }
+
+    /// Load the identity from disk, generating and persisting a new
+    /// keypair if none exists yet.
+    pub fn load_or_create() -> Result<Self, AppError> {
+        let path = Self::key_path();
+        if path.exists() {
+            let pem = fs::read_to_string(&path)?;
+            let signing_key = SigningKey::from_pkcs8_pem(&pem)
+                .map_err(|e| AppError::Serialization(format!("invalid identity key: {e}")))?;
+            return Ok(Self { signing_key });
+        }
+
+        let mut csprng = rand::rngs::OsRng;
+        let signing_key = SigningKey::generate(&mut csprng);
+        let pem = signing_key
+            .to_pkcs8_pem(Default::default())
+            .map_err(|e| AppError::Serialization(format!("failed to encode identity key: {e}")))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, pem.as_str())?;
+
+        Ok(Self { signing_key })
+    }
+
+    pub fn public_key_bytes(&self) -> [u8; 32] {
+        self.signing_key.verifying_key().to_bytes()
+    }
+
+    pub fn sign(&self, data: &[u8]) -> Signature {
+        self.signing_key.sign(data)
+    }
+}
+
+/// Verify a signature against raw ed25519 public key bytes.
+pub fn verify_with_public_key(
+    public_key_bytes: &[u8; 32],
+    data: &[u8],
+    signature_bytes: &[u8],
+) -> Result<(), AppError> {
+    let verifying_key = VerifyingKey::from_bytes(public_key_bytes)
+        .map_err(|e| AppError::Serialization(format!("invalid public key: {e}")))?;
+    let signature = Signature::from_slice(signature_bytes)
+        .map_err(|e| AppError::Serialization(format!("invalid signature: {e}")))?;
+    verifying_key
+        .verify(data, &signature)
+        .map_err(|_| AppError::Serialization("signature verification failed".to_string()))
+}
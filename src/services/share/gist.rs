@@ -1,18 +1,59 @@
+use super::uploader::{ShareBackend, Url};
+use crate::error::AppError;
+
+/// Uploads share payloads as GitHub Gists.
+///
+/// Whether the created gist is public or secret is decided once, at
+/// construction time, from the CLI's `--public` flag.
 pub struct GistUploader {
     token: String,
+    public: bool,
 }
 
 impl GistUploader {
-    pub fn new(token: String) -> Self {
-        Self { token }
+    pub fn new(token: String, public: bool) -> Self {
+        Self { token, public }
     }
 }
 
-impl TraceUploader for GistUploader {
-    fn upload(...) -> Result<String, AppError> {
-        // build request
-        // send HTTP
-        // parse URL
-        // return link
+impl ShareBackend for GistUploader {
+    fn put(&self, request_id: &str, payload: &str) -> Result<Url, AppError> {
+        let filename = format!("{request_id}.json");
+        let body = serde_json::json!({
+            "description": format!("erst simulation share: {request_id}"),
+            "public": self.public,
+            "files": { filename: { "content": payload } },
+        });
+
+        let response = ureq::post("https://api.github.com/gists")
+            .set("Authorization", &format!("token {}", self.token))
+            .set("User-Agent", "erst-cli")
+            .send_json(body)
+            .map_err(|e| AppError::Network(format!("gist upload failed: {e}")))?;
+
+        let json: serde_json::Value = response
+            .into_json()
+            .map_err(|e| AppError::Serialization(format!("gist response: {e}")))?;
+
+        json.get("html_url")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| AppError::Serialization("gist response missing html_url".to_string()))
+    }
+
+    fn get(&self, request_id: &str) -> Result<String, AppError> {
+        // GitHub's API has no way to look a gist up by description, so
+        // fetching one back requires the gist id rather than our
+        // request_id. Not supported yet; see `file://` or `https://`
+        // backends if round-tripping is needed.
+        Err(AppError::NotFound(format!(
+            "cannot fetch gist contents for {request_id} by request id alone"
+        )))
+    }
+
+    fn exists(&self, _request_id: &str) -> Result<bool, AppError> {
+        // Same limitation as `get`: a gist can't be found by request id, so
+        // this always reports `false`, even for a payload already uploaded.
+        Ok(false)
     }
 }
@@ -0,0 +1,49 @@
+use super::uploader::{ShareBackend, Url};
+use crate::error::AppError;
+
+/// Uploads share payloads to a user-supplied HTTP endpoint via PUT.
+///
+/// The endpoint is treated as a base URL; `request_id` is appended as the
+/// final path segment, e.g. `https://shares.example.com/` + `abc123` ->
+/// `https://shares.example.com/abc123`.
+pub struct HttpShareBackend {
+    endpoint: Url,
+}
+
+impl HttpShareBackend {
+    pub fn new(endpoint: Url) -> Self {
+        Self { endpoint }
+    }
+
+    fn url_for(&self, request_id: &str) -> Url {
+        format!("{}/{}", self.endpoint.trim_end_matches('/'), request_id)
+    }
+}
+
+impl ShareBackend for HttpShareBackend {
+    fn put(&self, request_id: &str, payload: &str) -> Result<Url, AppError> {
+        let url = self.url_for(request_id);
+        ureq::put(&url)
+            .set("Content-Type", "application/json")
+            .send_string(payload)
+            .map_err(|e| AppError::Network(format!("PUT {url} failed: {e}")))?;
+        Ok(url)
+    }
+
+    fn get(&self, request_id: &str) -> Result<String, AppError> {
+        let url = self.url_for(request_id);
+        ureq::get(&url)
+            .call()
+            .map_err(|e| AppError::Network(format!("GET {url} failed: {e}")))?
+            .into_string()
+            .map_err(|e| AppError::Serialization(format!("{url}: {e}")))
+    }
+
+    fn exists(&self, request_id: &str) -> Result<bool, AppError> {
+        match ureq::head(&self.url_for(request_id)).call() {
+            Ok(response) => Ok(response.status() < 400),
+            Err(ureq::Error::Status(code, _)) => Ok(code < 400),
+            Err(e) => Err(AppError::Network(e.to_string())),
+        }
+    }
+}
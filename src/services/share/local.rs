@@ -0,0 +1,48 @@
+use super::uploader::{ShareBackend, Url};
+use crate::error::AppError;
+use std::fs;
+use std::path::PathBuf;
+
+/// Writes share payloads under `~/.erst/shares/`.
+pub struct FileShareBackend {
+    root: PathBuf,
+}
+
+impl FileShareBackend {
+    pub fn new() -> Self {
+        let mut root = std::env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("."));
+        root.push(".erst");
+        root.push("shares");
+        Self { root }
+    }
+
+    fn path_for(&self, request_id: &str) -> PathBuf {
+        self.root.join(format!("{request_id}.json"))
+    }
+}
+
+impl Default for FileShareBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShareBackend for FileShareBackend {
+    fn put(&self, request_id: &str, payload: &str) -> Result<Url, AppError> {
+        fs::create_dir_all(&self.root)?;
+        let path = self.path_for(request_id);
+        fs::write(&path, payload)?;
+        Ok(format!("file://{}", path.display()))
+    }
+
+    fn get(&self, request_id: &str) -> Result<String, AppError> {
+        fs::read_to_string(self.path_for(request_id))
+            .map_err(|e| AppError::NotFound(format!("{request_id}: {e}")))
+    }
+
+    fn exists(&self, request_id: &str) -> Result<bool, AppError> {
+        Ok(self.path_for(request_id).exists())
+    }
+}
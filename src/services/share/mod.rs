@@ -0,0 +1,91 @@
+pub mod gist;
+pub mod http;
+pub mod local;
+pub mod uploader;
+
+use crate::error::AppError;
+use gist::GistUploader;
+use http::HttpShareBackend;
+use local::FileShareBackend;
+use std::process::Command;
+use uploader::{ShareBackend, Url};
+
+/// Build the `ShareBackend` named by `destination`'s URL scheme.
+///
+/// Supported schemes:
+/// - `gist://` - GitHub Gist, public or secret per `public`.
+/// - `file://` - local filesystem, under `~/.erst/shares/`.
+/// - `https://` / `http://` - PUT to the given endpoint.
+///
+/// New backends can be added here without touching any existing call site.
+pub fn resolve_backend(
+    destination: &str,
+    public: bool,
+    gist_token: Option<String>,
+) -> Result<Box<dyn ShareBackend>, AppError> {
+    if destination.starts_with("gist://") {
+        let token = gist_token.ok_or_else(|| {
+            AppError::InvalidUrl("gist:// sharing requires a GitHub token".to_string())
+        })?;
+        Ok(Box::new(GistUploader::new(token, public)))
+    } else if destination.starts_with("file://") {
+        Ok(Box::new(FileShareBackend::new()))
+    } else if destination.starts_with("https://") || destination.starts_with("http://") {
+        Ok(Box::new(HttpShareBackend::new(destination.to_string())))
+    } else {
+        Err(AppError::InvalidUrl(format!(
+            "unsupported share destination scheme: {destination}"
+        )))
+    }
+}
+
+/// Minimal git metadata attached to shared payloads: just enough to trace
+/// a shared result back to the repository and commit that produced it.
+#[derive(Debug, serde::Serialize)]
+pub struct GitMetadata {
+    pub remote_url: Option<String>,
+    pub commit_hash: Option<String>,
+}
+
+impl GitMetadata {
+    pub fn detect() -> Self {
+        Self {
+            remote_url: Self::run_git(&["config", "--get", "remote.origin.url"]),
+            commit_hash: Self::run_git(&["rev-parse", "HEAD"]),
+        }
+    }
+
+    fn run_git(args: &[&str]) -> Option<String> {
+        let output = Command::new("git").args(args).output().ok()?;
+        if output.status.success() {
+            Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            None
+        }
+    }
+}
+
+/// Serialize `response_json` alongside detected git metadata and upload it
+/// via the backend selected by `destination`'s scheme. Returns the URL the
+/// payload was uploaded to, ready to be printed for the caller.
+pub fn share(
+    request_id: &str,
+    response_json: &str,
+    destination: &str,
+    public: bool,
+    gist_token: Option<String>,
+) -> Result<Url, AppError> {
+    let response: serde_json::Value = serde_json::from_str(response_json)
+        .map_err(|e| AppError::Serialization(e.to_string()))?;
+
+    let payload = serde_json::json!({
+        "request_id": request_id,
+        "response": response,
+        "git": GitMetadata::detect(),
+    });
+    let payload = serde_json::to_string_pretty(&payload)
+        .map_err(|e| AppError::Serialization(e.to_string()))?;
+
+    let backend = resolve_backend(destination, public, gist_token)?;
+    backend.put(request_id, &payload)
+}
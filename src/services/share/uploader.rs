@@ -1,3 +1,20 @@
-pub trait TraceUploader {
-    fn upload(&self, content: &str, public: bool) -> Result<String, AppError>;
+use crate::error::AppError;
+
+/// URL a payload was uploaded to, or can be fetched from.
+pub type Url = String;
+
+/// A pluggable backend for uploading and retrieving shared simulation
+/// payloads. Implementations are selected at runtime by URL scheme (see
+/// `resolve_backend`), so a new backend can be added without touching any
+/// call site that already holds a `Box<dyn ShareBackend>`.
+pub trait ShareBackend {
+    /// Upload `payload` under `request_id`, returning the URL it can be
+    /// fetched back from.
+    fn put(&self, request_id: &str, payload: &str) -> Result<Url, AppError>;
+
+    /// Fetch a previously uploaded payload by request id.
+    fn get(&self, request_id: &str) -> Result<String, AppError>;
+
+    /// Check whether a payload for `request_id` has already been uploaded.
+    fn exists(&self, request_id: &str) -> Result<bool, AppError>;
 }